@@ -32,7 +32,7 @@ use crate::{
     control_plane::control_plane_state::ControlPlaneState,
     discover::monitor::{
         health::provider::HealthMonitorMap, metrics::EndpointMetricsRegistry,
-        rate_limit::RateLimitMonitorMap,
+        probe::ProbeResults, rate_limit::RateLimitMonitorMap,
     },
     error::{init::InitError, runtime::RuntimeError},
     logger::service::JawnClient,
@@ -40,7 +40,7 @@ use crate::{
     middleware::{
         auth::AuthService, cache::CacheLayer,
         rate_limit::service::Layer as RateLimitLayer,
-        response_headers::ResponseHeaderLayer,
+        response_headers::ResponseHeaderLayer, spend::Layer as SpendLayer,
     },
     router::meta::MetaRouter,
     types::provider::ProviderKeys,
@@ -105,6 +105,7 @@ pub type BoxedHyperServiceStack = BoxCloneService<
 /// 15. Mapper
 ///     - based on selected provider, map request body
 /// 16. `ProviderRegionBalancer`
+///     - for providers with regions, balance across regions by latency
 ///
 /// -- region specific middleware (none yet, just leaf service) --
 /// 17. Dispatcher
@@ -201,7 +202,11 @@ impl App {
 
         let meter = global::meter(SERVICE_NAME);
         let metrics = metrics::Metrics::new(&meter);
-        let endpoint_metrics = EndpointMetricsRegistry::default();
+        let endpoint_metrics = EndpointMetricsRegistry::new(&config.providers);
+        let probe_results = ProbeResults::new(
+            config.discover.monitor.probe.as_ref(),
+            &config.providers,
+        );
         let health_monitor = HealthMonitorMap::default();
         let rate_limit_monitor = RateLimitMonitorMap::default();
 
@@ -235,6 +240,7 @@ impl App {
             direct_proxy_api_keys,
             metrics,
             endpoint_metrics,
+            probe_results,
             health_monitors: health_monitor,
             rate_limit_monitors: rate_limit_monitor,
             rate_limit_senders: RwLock::new(HashMap::default()),
@@ -297,6 +303,7 @@ impl App {
                 app_state.clone(),
             )))
             .layer(RateLimitLayer::global(&app_state))
+            .layer(SpendLayer::global(&app_state)?)
             .layer(CacheLayer::global(&app_state))
             .layer(ErrorHandlerLayer::new(app_state.clone()))
             .layer(ResponseHeaderLayer::new(
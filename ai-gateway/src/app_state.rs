@@ -15,7 +15,7 @@ use crate::{
     control_plane::control_plane_state::ControlPlaneState,
     discover::monitor::{
         health::provider::HealthMonitorMap, metrics::EndpointMetricsRegistry,
-        rate_limit::RateLimitMonitorMap,
+        probe::ProbeResults, rate_limit::RateLimitMonitorMap,
     },
     error::{init::InitError, provider::ProviderError},
     logger::service::JawnClient,
@@ -25,7 +25,7 @@ use crate::{
         rate_limit::{
             RateLimitEvent, RateLimitEventReceivers, RateLimitEventSenders,
         },
-        router::RouterId,
+        router::{BalancerId, RouterId},
     },
 };
 
@@ -61,6 +61,9 @@ pub struct InnerAppState {
     /// Not used for OpenTelemetry, only used for the load balancer to be
     /// dynamically updated based on provider health and rate limits.
     pub endpoint_metrics: EndpointMetricsRegistry,
+    /// Results of active health probes, kept separately from the endpoint
+    /// metrics of live traffic.
+    pub probe_results: ProbeResults,
     pub health_monitors: HealthMonitorMap,
     pub rate_limit_monitors: RateLimitMonitorMap,
     pub rate_limit_senders: RateLimitEventSenders,
@@ -70,32 +73,34 @@ pub struct InnerAppState {
 impl AppState {
     pub async fn get_rate_limit_tx(
         &self,
-        router_id: &RouterId,
+        balancer_id: &BalancerId,
     ) -> Result<Sender<RateLimitEvent>, InitError> {
         let rate_limit_channels = self.0.rate_limit_senders.read().await;
         let rate_limit_tx =
-            rate_limit_channels.get(router_id).ok_or_else(|| {
-                InitError::RateLimitChannelsNotInitialized(router_id.clone())
+            rate_limit_channels.get(balancer_id).ok_or_else(|| {
+                InitError::RateLimitChannelsNotInitialized(
+                    balancer_id.router_id.clone(),
+                )
             })?;
         Ok(rate_limit_tx.clone())
     }
 
     pub async fn add_rate_limit_tx(
         &self,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         rate_limit_tx: Sender<RateLimitEvent>,
     ) {
         let mut rate_limit_channels = self.0.rate_limit_senders.write().await;
-        rate_limit_channels.insert(router_id, rate_limit_tx);
+        rate_limit_channels.insert(balancer_id, rate_limit_tx);
     }
 
     pub async fn add_rate_limit_rx(
         &self,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         rate_limit_rx: Receiver<RateLimitEvent>,
     ) {
         let mut rate_limit_channels = self.0.rate_limit_receivers.write().await;
-        rate_limit_channels.insert(router_id, rate_limit_rx);
+        rate_limit_channels.insert(balancer_id, rate_limit_rx);
     }
 
     pub async fn add_provider_keys_for_router(
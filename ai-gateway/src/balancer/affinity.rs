@@ -0,0 +1,189 @@
+//! Pins requests that share an affinity key, such as a session id, to the
+//! same provider.
+use std::task::{Context, Poll};
+
+use http::HeaderName;
+use indexmap::IndexMap;
+use rust_decimal::prelude::ToPrimitive;
+use sha2::{Digest, Sha256};
+use tower::Service;
+
+use crate::{
+    balancer::provider::{ProviderBalancer, ResponseFuture},
+    config::{affinity::AffinityConfig, balance::BalanceConfigInner},
+    discover::provider::DiscoveredProviders,
+    error::api::ApiError,
+    types::{
+        extensions::ExcludedProviders, provider::InferenceProvider,
+        request::Request,
+    },
+};
+
+/// Uses rendezvous hashing over the discovered providers, so when a provider
+/// is removed only the keys pinned to it are remapped, and they move back
+/// once it is healthy again.
+///
+/// Keys are pinned by passing every other provider to the inner balancer as
+/// [`ExcludedProviders`], so the inner balancer still tracks the pinned
+/// provider's load and waits for it to be ready. Requests without an
+/// affinity key are balanced as usual, as are keys whose providers are all
+/// excluded.
+#[derive(Debug)]
+pub struct AffinityBalance {
+    inner: ProviderBalancer,
+    discovered: DiscoveredProviders,
+    header: HeaderName,
+    /// Keys are only pinned to providers of the first tier with any
+    /// discovered providers, weighted as in the balance config.
+    tiers: Vec<IndexMap<InferenceProvider, f64>>,
+}
+
+impl AffinityBalance {
+    /// `discovered` must be populated by the inner balancer's discovery.
+    #[must_use]
+    pub fn new(
+        inner: ProviderBalancer,
+        discovered: DiscoveredProviders,
+        config: &AffinityConfig,
+        balance_config: &BalanceConfigInner,
+    ) -> Self {
+        let header = HeaderName::from_bytes(config.header.as_bytes())
+            .expect("affinity header is validated with the router config");
+        let tiers = match balance_config {
+            BalanceConfigInner::Weighted { providers } => vec![
+                providers
+                    .iter()
+                    .map(|target| {
+                        let weight = target.weight.to_f64().unwrap_or(0.0);
+                        (target.provider.clone(), weight)
+                    })
+                    .collect(),
+            ],
+            BalanceConfigInner::Priority { tiers } => tiers
+                .iter()
+                .map(|tier| {
+                    tier.iter()
+                        .map(|provider| (provider.clone(), 1.0))
+                        .collect()
+                })
+                .collect(),
+            BalanceConfigInner::Latency { providers }
+            | BalanceConfigInner::Cost { providers } => vec![
+                providers
+                    .iter()
+                    .map(|provider| (provider.clone(), 1.0))
+                    .collect(),
+            ],
+        };
+        Self {
+            inner,
+            discovered,
+            header,
+            tiers,
+        }
+    }
+}
+
+impl Service<Request> for AffinityBalance {
+    type Response = <ProviderBalancer as Service<Request>>::Response;
+    type Error = ApiError;
+    type Future = ResponseFuture;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // also drives discovery, which keeps the discovered providers fresh
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let Some(key) = req.headers().get(&self.header) else {
+            return self.inner.call(req);
+        };
+        let excluded = req.extensions().get::<ExcludedProviders>();
+        // excluded providers are treated like removed ones, so only the keys
+        // pinned to them are remapped
+        let others = self.discovered.with(|services| {
+            let is_candidate = |provider: &InferenceProvider| {
+                services.contains_key(provider)
+                    && excluded
+                        .is_none_or(|excluded| !excluded.contains(provider))
+            };
+            let tier = self.tiers.iter().find(|tier| {
+                tier.keys().any(|provider| is_candidate(provider))
+            })?;
+            let candidates =
+                tier.iter().filter(|(provider, _)| is_candidate(provider));
+            let provider = pick(key.as_bytes(), candidates)?;
+            tracing::trace!(provider = %provider, "pinned request to provider");
+            Some(
+                services
+                    .keys()
+                    .filter(|other| *other != provider)
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            )
+        });
+        // the pinned provider is still selected by the inner balancer, so that
+        // its load is tracked and it is only called once ready
+        if let Some(others) = others {
+            req.extensions_mut().insert(ExcludedProviders(others));
+        }
+        self.inner.call(req)
+    }
+}
+
+/// The provider with the highest weighted rendezvous score for `key`.
+fn pick<'a>(
+    key: &[u8],
+    candidates: impl Iterator<Item = (&'a InferenceProvider, &'a f64)>,
+) -> Option<&'a InferenceProvider> {
+    candidates
+        .filter(|(_, weight)| **weight > 0.0)
+        .map(|(provider, weight)| (provider, score(key, provider, *weight)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(provider, _)| provider)
+}
+
+/// A stable hash of the key and provider, mapped to `(0, 1)` and scaled so
+/// that each provider wins a share of keys proportional to its weight.
+fn score(key: &[u8], provider: &InferenceProvider, weight: f64) -> f64 {
+    let digest = Sha256::new()
+        .chain_update(key)
+        .chain_update(provider.as_ref().as_bytes())
+        .finalize();
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    // the top 53 bits fit in an f64 exactly
+    #[allow(clippy::cast_precision_loss)]
+    let hash =
+        ((u64::from_be_bytes(bytes) >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+    weight / -hash.ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removing_a_provider_only_remaps_its_keys() {
+        let all = IndexMap::from([
+            (InferenceProvider::OpenAI, 1.0),
+            (InferenceProvider::Anthropic, 1.0),
+            (InferenceProvider::GoogleGemini, 1.0),
+        ]);
+        let mut without_openai = all.clone();
+        without_openai.shift_remove(&InferenceProvider::OpenAI);
+
+        for session in 0..100 {
+            let key = format!("session-{session}");
+            let before = pick(key.as_bytes(), all.iter()).unwrap();
+            assert_eq!(before, pick(key.as_bytes(), all.iter()).unwrap());
+            let after = pick(key.as_bytes(), without_openai.iter()).unwrap();
+            if *before != InferenceProvider::OpenAI {
+                assert_eq!(before, after);
+            }
+        }
+    }
+}
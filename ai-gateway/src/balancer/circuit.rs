@@ -0,0 +1,109 @@
+//! Skips providers whose circuit wouldn't let a request through, when the
+//! circuit breaker health monitor is scoped to providers.
+use std::task::{Context, Poll};
+
+use tower::Service;
+
+use crate::{
+    app_state::AppState,
+    balancer::provider::{ProviderBalancer, ResponseFuture},
+    discover::provider::DiscoveredProviders,
+    endpoints::ApiEndpoint,
+    error::api::ApiError,
+    types::{
+        extensions::ExcludedProviders, provider::InferenceProvider,
+        request::Request,
+    },
+};
+
+/// The health monitor only removes providers with an open circuit from the
+/// balancer on its next check, and keeps half open ones in it until their
+/// trial requests complete. Until then, providers whose circuit is open or
+/// out of trial requests are passed to the inner balancer as
+/// [`ExcludedProviders`], rather than being selected only for the
+/// dispatcher to reject the request.
+#[derive(Debug)]
+pub struct CircuitBalance {
+    inner: ProviderBalancer,
+    discovered: DiscoveredProviders,
+    app_state: AppState,
+}
+
+impl CircuitBalance {
+    /// `discovered` must be populated by the inner balancer's discovery.
+    #[must_use]
+    pub fn new(
+        inner: ProviderBalancer,
+        discovered: DiscoveredProviders,
+        app_state: AppState,
+    ) -> Self {
+        Self {
+            inner,
+            discovered,
+            app_state,
+        }
+    }
+
+    /// Endpoints that the request can't be mapped to or that have no
+    /// metrics are left for the mapper and dispatcher to reject.
+    fn accepts_requests(
+        &self,
+        source_endpoint: &ApiEndpoint,
+        provider: &InferenceProvider,
+    ) -> bool {
+        let Ok(target_endpoint) =
+            ApiEndpoint::mapped(source_endpoint.clone(), provider)
+        else {
+            return true;
+        };
+        match self
+            .app_state
+            .0
+            .endpoint_metrics
+            .health_metrics(&target_endpoint)
+        {
+            Ok(metrics) => metrics
+                .accepts_requests(&self.app_state.config().discover.monitor),
+            Err(_) => true,
+        }
+    }
+}
+
+impl Service<Request> for CircuitBalance {
+    type Response = <ProviderBalancer as Service<Request>>::Response;
+    type Error = ApiError;
+    type Future = ResponseFuture;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // also drives discovery, which keeps the discovered providers fresh
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let Some(source_endpoint) =
+            req.extensions().get::<ApiEndpoint>().cloned()
+        else {
+            return self.inner.call(req);
+        };
+        let rejecting = self.discovered.with(|services| {
+            services
+                .keys()
+                .filter(|provider| {
+                    !self.accepts_requests(&source_endpoint, provider)
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+        if !rejecting.is_empty() {
+            tracing::debug!(
+                providers = ?rejecting,
+                "skipping providers whose circuit is not accepting requests"
+            );
+            req.extensions_mut().insert(ExcludedProviders(rejecting));
+        }
+        self.inner.call(req)
+    }
+}
@@ -0,0 +1,272 @@
+//! Routes each request to the cheapest provider able to serve its model.
+use std::{
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum_core::body::Body;
+use bytes::Bytes;
+use futures::{Stream, future::BoxFuture};
+use http_body_util::BodyExt;
+use indexmap::IndexMap;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use tower::{Service, ServiceExt, discover::Change};
+
+use crate::{
+    app_state::AppState,
+    config::router::RouterConfig,
+    discover::provider::{Key, discover::Discovery},
+    dispatcher::DispatcherService,
+    endpoints::EndpointType,
+    error::{api::ApiError, internal::InternalError, mapper::MapperError},
+    middleware::mapper::model::ModelMapper,
+    types::{
+        extensions::ExcludedProviders,
+        model_id::{ModelId, ModelName},
+        provider::InferenceProvider,
+        request::Request,
+        response::Response,
+        usage::estimate_tokens,
+    },
+};
+
+/// Assumed when the request doesn't cap the number of output tokens.
+const DEFAULT_OUTPUT_TOKENS: u64 = 512;
+
+#[derive(Debug)]
+pub struct CostBalance {
+    discover: Pin<Box<Discovery<Key>>>,
+    /// The healthy providers, in the order they were discovered.
+    services: Arc<IndexMap<InferenceProvider, DispatcherService>>,
+    app_state: AppState,
+    model_mapper: ModelMapper,
+    /// Models are mapped to those the providers offer for this endpoint.
+    endpoint_type: EndpointType,
+}
+
+impl CostBalance {
+    #[must_use]
+    pub fn new(
+        discover: Discovery<Key>,
+        app_state: AppState,
+        router_config: Arc<RouterConfig>,
+        endpoint_type: EndpointType,
+    ) -> Self {
+        let model_mapper =
+            ModelMapper::new_for_router(app_state.clone(), router_config);
+        Self {
+            discover: Box::pin(discover),
+            services: Arc::default(),
+            app_state,
+            model_mapper,
+            endpoint_type,
+        }
+    }
+
+    fn update_pending_from_discover(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Result<(), InternalError> {
+        loop {
+            match self.discover.as_mut().poll_next(cx) {
+                Poll::Pending => return Ok(()),
+                Poll::Ready(None) => {
+                    tracing::error!("provider discovery ended");
+                    return Err(InternalError::Internal);
+                }
+                Poll::Ready(Some(Ok(Change::Insert(key, service)))) => {
+                    tracing::trace!(provider = %key.provider, "inserting provider");
+                    Arc::make_mut(&mut self.services)
+                        .insert(key.provider, service);
+                }
+                Poll::Ready(Some(Ok(Change::Remove(key)))) => {
+                    tracing::trace!(provider = %key.provider, "removing provider");
+                    Arc::make_mut(&mut self.services)
+                        .shift_remove(&key.provider);
+                }
+                Poll::Ready(Some(Err(infallible))) => match infallible {},
+            }
+        }
+    }
+}
+
+impl Service<Request> for CostBalance {
+    type Response = Response;
+    type Error = ApiError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.update_pending_from_discover(cx)?;
+        if self.services.is_empty() {
+            // we'll be woken up by discovery once a provider is healthy again
+            return Poll::Pending;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let services = self.services.clone();
+        let app_state = self.app_state.clone();
+        let model_mapper = self.model_mapper.clone();
+        let endpoint_type = self.endpoint_type;
+        let excluded = req.extensions().get::<ExcludedProviders>().cloned();
+        Box::pin(async move {
+            // the body is buffered so that we can estimate the request's
+            // size and read its model
+            let (parts, body) = req.into_parts();
+            let body = body
+                .collect()
+                .await
+                .map_err(InternalError::CollectBodyError)?
+                .to_bytes();
+            let estimate = RequestEstimate::from_body(&body);
+            // excluded providers are only used if every provider is
+            let mut candidates = services
+                .keys()
+                .filter(|provider| {
+                    excluded
+                        .as_ref()
+                        .is_none_or(|excluded| !excluded.contains(provider))
+                })
+                .collect::<Vec<_>>();
+            if candidates.is_empty() {
+                candidates = services.keys().collect();
+            }
+            let source_model = estimate.model.as_ref().ok_or(
+                InternalError::MapperError(MapperError::InvalidRequest),
+            )?;
+            let Some(provider) = cheapest_provider(
+                &app_state,
+                &model_mapper,
+                endpoint_type,
+                candidates.iter().copied(),
+                source_model,
+                &estimate,
+            ) else {
+                let provider =
+                    candidates.first().ok_or(InternalError::Internal)?;
+                return Err(InternalError::MapperError(
+                    MapperError::NoModelMapping(
+                        (*provider).clone(),
+                        ModelName::from_model(source_model)
+                            .as_ref()
+                            .to_string(),
+                    ),
+                )
+                .into());
+            };
+            let service = services
+                .get(&provider)
+                .cloned()
+                .ok_or(InternalError::Internal)?;
+
+            let req = Request::from_parts(parts, Body::from(body));
+            // the dispatcher converts its errors into responses
+            match service.oneshot(req).await {
+                Ok(response) => Ok(response),
+                Err(infallible) => match infallible {},
+            }
+        })
+    }
+}
+
+/// The fields of a request body needed to estimate its cost, common to the
+/// chat completions, Responses and Messages APIs.
+#[derive(Debug, Default, Deserialize)]
+struct RequestFields {
+    model: Option<String>,
+    max_tokens: Option<u64>,
+    max_completion_tokens: Option<u64>,
+    max_output_tokens: Option<u64>,
+}
+
+#[derive(Debug)]
+struct RequestEstimate {
+    model: Option<ModelId>,
+    input_tokens: u64,
+    output_tokens: u64,
+}
+
+impl RequestEstimate {
+    fn from_body(body: &Bytes) -> Self {
+        let fields: RequestFields =
+            serde_json::from_slice(body).unwrap_or_default();
+        let model = fields
+            .model
+            .as_deref()
+            .and_then(|model| ModelId::from_str(model).ok());
+        let output_tokens = fields
+            .max_completion_tokens
+            .or(fields.max_tokens)
+            .or(fields.max_output_tokens)
+            .unwrap_or(DEFAULT_OUTPUT_TOKENS);
+        Self {
+            model,
+            input_tokens: estimate_tokens(body.len()),
+            output_tokens,
+        }
+    }
+}
+
+/// Providers without a price for the mapped model are never chosen, since
+/// we can't tell whether they're cheaper, so this is `None` if no provider
+/// has one.
+fn cheapest_provider<'a>(
+    app_state: &AppState,
+    model_mapper: &ModelMapper,
+    endpoint_type: EndpointType,
+    providers: impl Iterator<Item = &'a InferenceProvider>,
+    source_model: &ModelId,
+    estimate: &RequestEstimate,
+) -> Option<InferenceProvider> {
+    let providers_config = &app_state.0.config.providers;
+    let mut cheapest: Option<(InferenceProvider, Decimal)> = None;
+    for provider in providers {
+        let Ok(target_model) = model_mapper.map_model_for_endpoint(
+            source_model,
+            provider,
+            endpoint_type,
+        ) else {
+            continue;
+        };
+        let Some(price) = providers_config
+            .get(provider)
+            .and_then(|c| c.pricing.get(&ModelName::from_model(&target_model)))
+        else {
+            continue;
+        };
+        let cost = price.cost(estimate.input_tokens, estimate.output_tokens);
+        tracing::trace!(provider = %provider, model = %target_model, cost = %cost, "estimated request cost");
+        if cheapest.as_ref().is_none_or(|(_, lowest)| cost < *lowest) {
+            cheapest = Some((provider.clone(), cost));
+        }
+    }
+    cheapest.map(|(provider, _)| provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_tokens_default_when_uncapped() {
+        let body = Bytes::from_static(
+            br#"{"model":"openai/gpt-4o-mini","messages":[]}"#,
+        );
+        let estimate = RequestEstimate::from_body(&body);
+        assert_eq!(estimate.output_tokens, DEFAULT_OUTPUT_TOKENS);
+        assert_eq!(estimate.input_tokens, estimate_tokens(body.len()));
+        assert!(estimate.model.is_some());
+
+        let body = Bytes::from_static(
+            br#"{"model":"openai/gpt-4o-mini","messages":[],"max_tokens":64}"#,
+        );
+        assert_eq!(RequestEstimate::from_body(&body).output_tokens, 64);
+    }
+}
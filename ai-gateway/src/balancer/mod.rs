@@ -1 +1,8 @@
+pub mod affinity;
+pub mod circuit;
+pub mod cost;
+pub mod model;
+pub mod model_health;
+pub mod priority;
 pub mod provider;
+pub mod region;
@@ -0,0 +1,146 @@
+//! Routes requests for models with a model level balance config to that
+//! model's balancer, falling back to the endpoint level balancer.
+use std::{
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum_core::body::Body;
+use compact_str::CompactString;
+use futures::future::BoxFuture;
+use http_body_util::BodyExt;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use tower::{Service, ServiceExt, buffer::Buffer};
+
+use crate::{
+    app::BUFFER_SIZE,
+    balancer::provider::ProviderBalancer,
+    config::router::RouterConfig,
+    error::{api::ApiError, internal::InternalError},
+    types::{
+        extensions::MapperContext,
+        model_id::{ModelId, ModelName},
+        request::Request,
+        response::Response,
+    },
+};
+
+type BufferedBalancer =
+    Buffer<Request, <ProviderBalancer as Service<Request>>::Future>;
+
+/// Each balancer is buffered separately so that a model group without any
+/// healthy providers doesn't hold up requests for other models.
+#[derive(Debug, Clone)]
+pub struct ModelBalancer {
+    endpoint: BufferedBalancer,
+    models: Arc<IndexMap<CompactString, BufferedBalancer>>,
+    router_config: Arc<RouterConfig>,
+}
+
+impl ModelBalancer {
+    #[must_use]
+    pub fn new(
+        endpoint: ProviderBalancer,
+        models: IndexMap<CompactString, ProviderBalancer>,
+        router_config: Arc<RouterConfig>,
+    ) -> Self {
+        let models = models
+            .into_iter()
+            .map(|(group, balancer)| {
+                (group, Buffer::new(balancer, BUFFER_SIZE))
+            })
+            .collect();
+        Self {
+            endpoint: Buffer::new(endpoint, BUFFER_SIZE),
+            models: Arc::new(models),
+            router_config,
+        }
+    }
+}
+
+impl Service<Request> for ModelBalancer {
+    type Response = Response;
+    type Error = ApiError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // readiness is driven per request once we know which balancer to use
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let this = self.clone();
+        Box::pin(async move {
+            // the body is buffered so that we can read its model
+            let (mut parts, body) = req.into_parts();
+            let body = body
+                .collect()
+                .await
+                .map_err(InternalError::CollectBodyError)?
+                .to_bytes();
+            let mapper_ctx = source_mapper_context(&body);
+            let group = model_group(&this.router_config, &mapper_ctx);
+            let mut balancer = match group {
+                Some(group) => {
+                    tracing::trace!(model_group = %group, "using model balancer");
+                    this.models
+                        .get(group)
+                        .cloned()
+                        .ok_or(InternalError::Internal)?
+                }
+                None => this.endpoint.clone(),
+            };
+            parts.extensions.insert(mapper_ctx);
+
+            let req = Request::from_parts(parts, Body::from(body));
+            balancer
+                .ready()
+                .await
+                .map_err(ApiError::from_buffer_error)?
+                .call(req)
+                .await
+                .map_err(ApiError::from_buffer_error)
+        })
+    }
+}
+
+/// The fields of a request body that identify its model, common to the
+/// chat completions, Responses and Messages APIs.
+#[derive(Debug, Default, Deserialize)]
+struct RequestFields {
+    model: Option<String>,
+    stream: Option<bool>,
+}
+
+/// The model group whose balancer a request is sent to, if its model has a
+/// model level balance config.
+pub(crate) fn model_group<'a>(
+    router_config: &'a RouterConfig,
+    mapper_ctx: &MapperContext,
+) -> Option<&'a CompactString> {
+    mapper_ctx.model.as_ref().and_then(|model| {
+        router_config
+            .model_load_balance
+            .group_for(&ModelName::from_model(model))
+    })
+}
+
+/// The context of a request before it has been mapped for a provider.
+///
+/// The mapper replaces this with the context of the mapped request.
+pub(crate) fn source_mapper_context(body: &[u8]) -> MapperContext {
+    let fields: RequestFields =
+        serde_json::from_slice(body).unwrap_or_default();
+    MapperContext {
+        is_stream: fields.stream.unwrap_or_default(),
+        model: fields
+            .model
+            .as_deref()
+            .and_then(|model| ModelId::from_str(model).ok()),
+    }
+}
@@ -0,0 +1,120 @@
+//! Skips providers whose mapping of a request's model is unhealthy, when
+//! health is scoped to models.
+use std::task::{Context, Poll};
+
+use tower::Service;
+
+use crate::{
+    app_state::AppState,
+    balancer::provider::{ProviderBalancer, ResponseFuture},
+    discover::provider::DiscoveredProviders,
+    endpoints::EndpointType,
+    error::api::ApiError,
+    middleware::mapper::model::ModelMapper,
+    types::{
+        extensions::{ExcludedProviders, MapperContext},
+        model_id::{ModelId, ModelName},
+        provider::InferenceProvider,
+        request::Request,
+    },
+};
+
+/// Providers that are unhealthy for a request's model are passed to the
+/// inner balancer as [`ExcludedProviders`], so the configured strategy picks
+/// among the others.
+///
+/// Relies on the [`ModelBalancer`] to read the request's model.
+///
+/// [`ModelBalancer`]: crate::balancer::model::ModelBalancer
+#[derive(Debug)]
+pub struct ModelHealthBalance {
+    inner: ProviderBalancer,
+    discovered: DiscoveredProviders,
+    app_state: AppState,
+    model_mapper: ModelMapper,
+    endpoint_type: EndpointType,
+}
+
+impl ModelHealthBalance {
+    /// `discovered` must be populated by the inner balancer's discovery.
+    #[must_use]
+    pub fn new(
+        inner: ProviderBalancer,
+        discovered: DiscoveredProviders,
+        app_state: AppState,
+        model_mapper: ModelMapper,
+        endpoint_type: EndpointType,
+    ) -> Self {
+        Self {
+            inner,
+            discovered,
+            app_state,
+            model_mapper,
+            endpoint_type,
+        }
+    }
+
+    /// Providers that the model can't be mapped to are left for the mapper
+    /// to reject.
+    fn is_healthy(
+        &self,
+        model: &ModelId,
+        provider: &InferenceProvider,
+    ) -> bool {
+        let Ok(target_model) = self.model_mapper.map_model_for_endpoint(
+            model,
+            provider,
+            self.endpoint_type,
+        ) else {
+            return true;
+        };
+        self.app_state
+            .0
+            .endpoint_metrics
+            .model_health_metrics(
+                provider,
+                &ModelName::from_model(&target_model),
+            )
+            .is_healthy(&self.app_state.config().discover.monitor)
+    }
+}
+
+impl Service<Request> for ModelHealthBalance {
+    type Response = <ProviderBalancer as Service<Request>>::Response;
+    type Error = ApiError;
+    type Future = ResponseFuture;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        // also drives discovery, which keeps the discovered providers fresh
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request) -> Self::Future {
+        let Some(model) = req
+            .extensions()
+            .get::<MapperContext>()
+            .and_then(|ctx| ctx.model.clone())
+        else {
+            return self.inner.call(req);
+        };
+        let unhealthy = self.discovered.with(|services| {
+            services
+                .keys()
+                .filter(|provider| !self.is_healthy(&model, provider))
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+        if !unhealthy.is_empty() {
+            tracing::debug!(
+                model = %model,
+                providers = ?unhealthy,
+                "skipping providers that are unhealthy for model"
+            );
+            req.extensions_mut().insert(ExcludedProviders(unhealthy));
+        }
+        self.inner.call(req)
+    }
+}
@@ -0,0 +1,193 @@
+//! Sends traffic to the first tier of providers that has a healthy provider.
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{
+    Stream, TryFutureExt,
+    future::{Either, MapErr},
+};
+use indexmap::IndexMap;
+use tower::{Service, ServiceExt, discover::Change, util::Oneshot};
+
+use crate::{
+    discover::provider::{Key, discover::Discovery},
+    dispatcher::DispatcherService,
+    error::{api::ApiError, internal::InternalError},
+    types::{
+        extensions::ExcludedProviders, provider::InferenceProvider,
+        request::Request,
+    },
+};
+
+type DispatcherFuture = <DispatcherService as Service<Request>>::Future;
+/// Requests are sent to the provider driven to readiness, or to another one
+/// if it's excluded for the request.
+type SelectedFuture =
+    Either<DispatcherFuture, Oneshot<DispatcherService, Request>>;
+
+#[derive(Debug)]
+pub struct PriorityBalance {
+    discover: Pin<Box<Discovery<Key>>>,
+    tiers: Vec<Vec<InferenceProvider>>,
+    /// The healthy providers, i.e. those not removed by the health or rate
+    /// limit monitors.
+    services: IndexMap<InferenceProvider, DispatcherService>,
+    /// Providers within a tier are used in turn.
+    next: usize,
+    /// The provider that was last driven to readiness.
+    ready: Option<InferenceProvider>,
+}
+
+impl PriorityBalance {
+    #[must_use]
+    pub fn new(
+        discover: Discovery<Key>,
+        tiers: Vec<Vec<InferenceProvider>>,
+    ) -> Self {
+        Self {
+            discover: Box::pin(discover),
+            tiers,
+            services: IndexMap::new(),
+            next: 0,
+            ready: None,
+        }
+    }
+
+    fn update_pending_from_discover(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Result<(), InternalError> {
+        loop {
+            match self.discover.as_mut().poll_next(cx) {
+                Poll::Pending => return Ok(()),
+                Poll::Ready(None) => {
+                    tracing::error!("provider discovery ended");
+                    return Err(InternalError::Internal);
+                }
+                Poll::Ready(Some(Ok(Change::Insert(key, service)))) => {
+                    tracing::trace!(
+                        provider = %key.provider,
+                        "inserting provider"
+                    );
+                    self.services.insert(key.provider, service);
+                }
+                Poll::Ready(Some(Ok(Change::Remove(key)))) => {
+                    tracing::trace!(
+                        provider = %key.provider,
+                        "removing provider"
+                    );
+                    self.services.shift_remove(&key.provider);
+                    if self.ready.as_ref() == Some(&key.provider) {
+                        self.ready = None;
+                    }
+                }
+                Poll::Ready(Some(Err(infallible))) => match infallible {},
+            }
+        }
+    }
+
+    /// The next provider of the highest priority tier with any healthy
+    /// providers that aren't excluded.
+    fn select(
+        &mut self,
+        excluded: Option<&ExcludedProviders>,
+    ) -> Option<InferenceProvider> {
+        let tier = self.tiers.iter().find_map(|tier| {
+            let healthy = tier
+                .iter()
+                .filter(|provider| {
+                    self.services.contains_key(*provider)
+                        && excluded
+                            .is_none_or(|excluded| !excluded.contains(provider))
+                })
+                .collect::<Vec<_>>();
+            (!healthy.is_empty()).then_some(healthy)
+        })?;
+        let provider = tier[self.next % tier.len()].clone();
+        self.next = self.next.wrapping_add(1);
+        Some(provider)
+    }
+
+    fn call_ready(
+        &mut self,
+        provider: &InferenceProvider,
+        req: Request,
+    ) -> SelectedFuture {
+        tracing::trace!(provider = %provider, "selected priority provider");
+        Either::Left(
+            self.services
+                .get_mut(provider)
+                .expect("ready provider was removed")
+                .call(req),
+        )
+    }
+}
+
+impl Service<Request> for PriorityBalance {
+    type Response = <DispatcherService as Service<Request>>::Response;
+    type Error = ApiError;
+    type Future = MapErr<SelectedFuture, fn(Infallible) -> ApiError>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.update_pending_from_discover(cx)?;
+        if self.ready.is_none() {
+            // we'll be woken up by discovery once a provider is healthy again
+            let Some(provider) = self.select(None) else {
+                return Poll::Pending;
+            };
+            self.ready = Some(provider);
+        }
+        let Some(service) = self
+            .ready
+            .as_ref()
+            .and_then(|provider| self.services.get_mut(provider))
+        else {
+            return Poll::Pending;
+        };
+        match service.poll_ready(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(Ok(())),
+            Poll::Ready(Err(infallible)) => match infallible {},
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let provider = self.ready.take().expect("called before ready");
+        let excluded = req.extensions().get::<ExcludedProviders>().cloned();
+        let future = match excluded {
+            Some(excluded) if excluded.contains(&provider) => {
+                match self.select(Some(&excluded)) {
+                    Some(other) => {
+                        tracing::trace!(
+                            provider = %other,
+                            "selected priority provider instead of excluded \
+                             one"
+                        );
+                        // the excluded provider stays ready for the next
+                        // request
+                        self.ready = Some(provider);
+                        let service = self
+                            .services
+                            .get(&other)
+                            .cloned()
+                            .expect("selected provider is discovered");
+                        Either::Right(service.oneshot(req))
+                    }
+                    None => self.call_ready(&provider, req),
+                }
+            }
+            _ => self.call_ready(&provider, req),
+        };
+        future.map_err(unreachable as fn(Infallible) -> ApiError)
+    }
+}
+
+fn unreachable(infallible: Infallible) -> ApiError {
+    match infallible {}
+}
@@ -3,82 +3,196 @@ use std::{
     task::{Context, Poll},
 };
 
-use futures::Future;
+use futures::{Future, future::BoxFuture};
 use pin_project_lite::pin_project;
 use tokio::sync::mpsc::channel;
-use tower::{Service, balance::p2c::Balance, load::PeakEwmaDiscover};
-use weighted_balance::{balance::WeightedBalance, weight::WeightedDiscover};
+use tower::{Service, load::PeakEwmaDiscover};
+use weighted_balance::{
+    balance::WeightedBalance, p2c::P2cBalance, weight::WeightedDiscover,
+};
 
 use crate::{
     app_state::AppState,
-    config::{balance::BalanceConfigInner, router::RouterConfig},
+    balancer::{
+        affinity::AffinityBalance, circuit::CircuitBalance, cost::CostBalance,
+        model_health::ModelHealthBalance, priority::PriorityBalance,
+    },
+    config::{
+        balance::BalanceConfigInner,
+        monitor::{HealthMonitorConfig, HealthScope},
+        router::RouterConfig,
+    },
     discover::{
-        provider::{Key, discover, factory::DiscoverFactory},
+        provider::{
+            DiscoveredProviders, HasProvider, Key, discover,
+            factory::DiscoverFactory,
+        },
         weighted::WeightedKey,
     },
     error::{api::ApiError, init::InitError, internal::InternalError},
-    types::{request::Request, response::Response, router::RouterId},
+    middleware::mapper::model::ModelMapper,
+    types::{
+        extensions::ExcludedProviders, provider::InferenceProvider,
+        request::Request, response::Response, router::BalancerId,
+    },
 };
 
 const CHANNEL_CAPACITY: usize = 16;
 
 #[derive(Debug)]
 pub enum ProviderBalancer {
-    PeakEwma(Balance<PeakEwmaDiscover<discover::Discovery<Key>>, Request>),
+    PeakEwma(P2cBalance<PeakEwmaDiscover<discover::Discovery<Key>>, Request>),
     Weighted(
         WeightedBalance<
             WeightedDiscover<discover::Discovery<WeightedKey>>,
             Request,
         >,
     ),
+    Cost(CostBalance),
+    Priority(PriorityBalance),
+    /// Pins requests by an affinity key, see [`AffinityBalance`].
+    Affinity(Box<AffinityBalance>),
+    /// Skips providers that are unhealthy for the request's model, see
+    /// [`ModelHealthBalance`].
+    ModelHealth(Box<ModelHealthBalance>),
+    /// Skips providers whose circuit isn't accepting requests, see
+    /// [`CircuitBalance`].
+    Circuit(Box<CircuitBalance>),
 }
 
 impl ProviderBalancer {
+    /// The providers the balancer discovers are mirrored into `discovered`,
+    /// if given.
     pub async fn new(
         app_state: AppState,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         balance_config: &BalanceConfigInner,
+        discovered: Option<DiscoveredProviders>,
     ) -> Result<ProviderBalancer, InitError> {
-        match balance_config {
+        let monitor = &app_state.config().discover.monitor;
+        let model_scoped = monitor.scope == HealthScope::Model;
+        // model scoped health already covers the circuits of each model
+        let circuit_scoped = !model_scoped
+            && matches!(
+                monitor.health,
+                HealthMonitorConfig::CircuitBreaker { .. }
+            );
+        let discovered = discovered.or_else(|| {
+            (router_config.affinity.is_some() || model_scoped || circuit_scoped)
+                .then(DiscoveredProviders::default)
+        });
+        let endpoint_type = balancer_id.endpoint_type;
+        let balancer = match balance_config {
             BalanceConfigInner::Weighted { .. } => {
-                Self::weighted(app_state, router_id, router_config).await
+                Self::weighted(
+                    app_state.clone(),
+                    balancer_id,
+                    router_config.clone(),
+                    discovered.clone(),
+                )
+                .await?
             }
             BalanceConfigInner::Latency { .. } => {
-                Self::peak_ewma(app_state, router_id, router_config).await
+                Self::peak_ewma(
+                    app_state.clone(),
+                    balancer_id,
+                    router_config.clone(),
+                    discovered.clone(),
+                )
+                .await?
+            }
+            BalanceConfigInner::Cost { .. } => {
+                Self::cost(
+                    app_state.clone(),
+                    balancer_id,
+                    router_config.clone(),
+                    discovered.clone(),
+                )
+                .await?
+            }
+            BalanceConfigInner::Priority { tiers } => {
+                let tiers = tiers
+                    .into_iter()
+                    .map(|tier| tier.into_iter().cloned().collect())
+                    .collect();
+                Self::priority(
+                    app_state.clone(),
+                    balancer_id,
+                    router_config.clone(),
+                    tiers,
+                    discovered.clone(),
+                )
+                .await?
             }
+        };
+
+        let Some(discovered) = discovered else {
+            return Ok(balancer);
+        };
+        let balancer = match &router_config.affinity {
+            Some(affinity) => {
+                ProviderBalancer::Affinity(Box::new(AffinityBalance::new(
+                    balancer,
+                    discovered.clone(),
+                    affinity,
+                    balance_config,
+                )))
+            }
+            None => balancer,
+        };
+        if circuit_scoped {
+            return Ok(ProviderBalancer::Circuit(Box::new(
+                CircuitBalance::new(balancer, discovered, app_state),
+            )));
         }
+        if !model_scoped {
+            return Ok(balancer);
+        }
+        let model_mapper =
+            ModelMapper::new_for_router(app_state.clone(), router_config);
+        Ok(ProviderBalancer::ModelHealth(Box::new(
+            ModelHealthBalance::new(
+                balancer,
+                discovered,
+                app_state,
+                model_mapper,
+                endpoint_type,
+            ),
+        )))
     }
 
     async fn weighted(
         app_state: AppState,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
+        discovered: Option<DiscoveredProviders>,
     ) -> Result<ProviderBalancer, InitError> {
         tracing::debug!("Creating weighted balancer");
         let (change_tx, change_rx) = channel(CHANNEL_CAPACITY);
         let (rate_limit_tx, rate_limit_rx) = channel(CHANNEL_CAPACITY);
         let discover_factory = DiscoverFactory::new(
             app_state.clone(),
-            router_id.clone(),
+            balancer_id.clone(),
             router_config.clone(),
+            discovered,
         );
         app_state
             .add_weighted_router_health_monitor(
-                router_id.clone(),
+                balancer_id.clone(),
                 router_config.clone(),
                 change_tx.clone(),
             )
             .await;
         app_state
-            .add_rate_limit_tx(router_id.clone(), rate_limit_tx)
+            .add_rate_limit_tx(balancer_id.clone(), rate_limit_tx)
             .await;
         app_state
-            .add_rate_limit_rx(router_id.clone(), rate_limit_rx)
+            .add_rate_limit_rx(balancer_id.clone(), rate_limit_rx)
             .await;
         app_state
             .add_weighted_router_rate_limit_monitor(
-                router_id.clone(),
+                balancer_id,
                 router_config,
                 change_tx,
             )
@@ -93,44 +207,121 @@ impl ProviderBalancer {
 
     async fn peak_ewma(
         app_state: AppState,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
+        discovered: Option<DiscoveredProviders>,
     ) -> Result<ProviderBalancer, InitError> {
         tracing::debug!("Creating peak ewma p2c balancer");
         let (change_tx, change_rx) = channel(CHANNEL_CAPACITY);
         let (rate_limit_tx, rate_limit_rx) = channel(CHANNEL_CAPACITY);
-        let discover_factory = DiscoverFactory::new(
+        let mut discover_factory = DiscoverFactory::new(
             app_state.clone(),
-            router_id.clone(),
+            balancer_id.clone(),
             router_config.clone(),
+            discovered,
         );
         app_state
             .add_p2c_router_health_monitor(
-                router_id.clone(),
+                balancer_id.clone(),
                 router_config.clone(),
                 change_tx.clone(),
             )
             .await;
         app_state
-            .add_rate_limit_tx(router_id.clone(), rate_limit_tx)
+            .add_rate_limit_tx(balancer_id.clone(), rate_limit_tx)
             .await;
         app_state
-            .add_rate_limit_rx(router_id.clone(), rate_limit_rx)
+            .add_rate_limit_rx(balancer_id.clone(), rate_limit_rx)
             .await;
         app_state
             .add_p2c_router_rate_limit_monitor(
-                router_id.clone(),
+                balancer_id,
                 router_config,
                 change_tx,
             )
             .await;
-        let mut balance_factory =
-            tower::balance::p2c::MakeBalance::new(discover_factory);
-        let balance = balance_factory.call(change_rx).await?;
-        let provider_balancer = ProviderBalancer::PeakEwma(balance);
+        let discover = discover_factory.call(change_rx).await?;
+        let provider_balancer =
+            ProviderBalancer::PeakEwma(P2cBalance::new(discover));
 
         Ok(provider_balancer)
     }
+
+    async fn cost(
+        app_state: AppState,
+        balancer_id: BalancerId,
+        router_config: Arc<RouterConfig>,
+        discovered: Option<DiscoveredProviders>,
+    ) -> Result<ProviderBalancer, InitError> {
+        tracing::debug!("Creating cost balancer");
+        let discovery =
+            Self::monitored_discovery(&app_state, &balancer_id, &router_config)
+                .await?
+                .observed_by(discovered);
+        let balance = CostBalance::new(
+            discovery,
+            app_state,
+            router_config,
+            balancer_id.endpoint_type,
+        );
+
+        Ok(ProviderBalancer::Cost(balance))
+    }
+
+    async fn priority(
+        app_state: AppState,
+        balancer_id: BalancerId,
+        router_config: Arc<RouterConfig>,
+        tiers: Vec<Vec<InferenceProvider>>,
+        discovered: Option<DiscoveredProviders>,
+    ) -> Result<ProviderBalancer, InitError> {
+        tracing::debug!("Creating priority balancer");
+        let discovery =
+            Self::monitored_discovery(&app_state, &balancer_id, &router_config)
+                .await?
+                .observed_by(discovered);
+        let balance = PriorityBalance::new(discovery, tiers);
+
+        Ok(ProviderBalancer::Priority(balance))
+    }
+
+    /// Providers are discovered and monitored like they are for latency
+    /// based balancing, since they're keyed by provider alone.
+    async fn monitored_discovery(
+        app_state: &AppState,
+        balancer_id: &BalancerId,
+        router_config: &Arc<RouterConfig>,
+    ) -> Result<discover::Discovery<Key>, InitError> {
+        let (change_tx, change_rx) = channel(CHANNEL_CAPACITY);
+        let (rate_limit_tx, rate_limit_rx) = channel(CHANNEL_CAPACITY);
+        app_state
+            .add_p2c_router_health_monitor(
+                balancer_id.clone(),
+                router_config.clone(),
+                change_tx.clone(),
+            )
+            .await;
+        app_state
+            .add_rate_limit_tx(balancer_id.clone(), rate_limit_tx)
+            .await;
+        app_state
+            .add_rate_limit_rx(balancer_id.clone(), rate_limit_rx)
+            .await;
+        app_state
+            .add_p2c_router_rate_limit_monitor(
+                balancer_id.clone(),
+                router_config.clone(),
+                change_tx,
+            )
+            .await;
+        discover::Discovery::new(
+            app_state,
+            balancer_id,
+            router_config,
+            change_rx,
+        )
+        .await
+    }
 }
 
 impl tower::Service<Request> for ProviderBalancer {
@@ -144,22 +335,51 @@ impl tower::Service<Request> for ProviderBalancer {
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Result<(), Self::Error>> {
         match self {
-            ProviderBalancer::PeakEwma(inner) => inner.poll_ready(cx),
-            ProviderBalancer::Weighted(inner) => inner.poll_ready(cx),
+            ProviderBalancer::PeakEwma(inner) => inner
+                .poll_ready(cx)
+                .map_err(InternalError::PollReadyError)
+                .map_err(Into::into),
+            ProviderBalancer::Weighted(inner) => inner
+                .poll_ready(cx)
+                .map_err(InternalError::PollReadyError)
+                .map_err(Into::into),
+            ProviderBalancer::Cost(inner) => inner.poll_ready(cx),
+            ProviderBalancer::Priority(inner) => inner.poll_ready(cx),
+            ProviderBalancer::Affinity(inner) => inner.poll_ready(cx),
+            ProviderBalancer::ModelHealth(inner) => inner.poll_ready(cx),
+            ProviderBalancer::Circuit(inner) => inner.poll_ready(cx),
         }
-        .map_err(InternalError::PollReadyError)
-        .map_err(Into::into)
     }
 
     fn call(&mut self, req: Request) -> Self::Future {
         tracing::trace!("ProviderBalancer");
+        let excluded = req.extensions().get::<ExcludedProviders>().cloned();
         match self {
             ProviderBalancer::PeakEwma(inner) => ResponseFuture::PeakEwma {
-                future: inner.call(req),
+                future: match excluded {
+                    Some(excluded) => inner.call_excluding(req, |key| {
+                        excluded.contains(key.provider())
+                    }),
+                    None => inner.call(req),
+                },
             },
             ProviderBalancer::Weighted(inner) => ResponseFuture::Weighted {
+                future: match excluded {
+                    Some(excluded) => inner.call_excluding(req, |key| {
+                        excluded.contains(key.provider())
+                    }),
+                    None => inner.call(req),
+                },
+            },
+            ProviderBalancer::Cost(inner) => ResponseFuture::Cost {
                 future: inner.call(req),
             },
+            ProviderBalancer::Priority(inner) => ResponseFuture::Priority {
+                future: inner.call(req),
+            },
+            ProviderBalancer::Affinity(inner) => inner.call(req),
+            ProviderBalancer::ModelHealth(inner) => inner.call(req),
+            ProviderBalancer::Circuit(inner) => inner.call(req),
         }
     }
 }
@@ -170,7 +390,7 @@ pin_project! {
         PeakEwma {
             #[pin]
             future: <
-                Balance<PeakEwmaDiscover<discover::Discovery<Key>>, Request> as tower::Service<
+                P2cBalance<PeakEwmaDiscover<discover::Discovery<Key>>, Request> as tower::Service<
                     Request,
                 >
             >::Future,
@@ -183,6 +403,14 @@ pin_project! {
                 >
             >::Future,
         },
+        Cost {
+            #[pin]
+            future: BoxFuture<'static, Result<Response, ApiError>>,
+        },
+        Priority {
+            #[pin]
+            future: <PriorityBalance as tower::Service<Request>>::Future,
+        },
     }
 }
 
@@ -208,6 +436,8 @@ impl Future for ResponseFuture {
                 ))),
                 Poll::Pending => Poll::Pending,
             },
+            EnumProj::Cost { future } => future.poll(cx),
+            EnumProj::Priority { future } => future.poll(cx),
         }
     }
 }
@@ -0,0 +1,242 @@
+//! Balances requests to a provider across its regional deployments.
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use rand::Rng;
+use tokio::time::Instant;
+use tower::Service;
+
+use crate::{
+    app_state::AppState,
+    config::monitor::MonitorConfig,
+    discover::monitor::metrics::EndpointMetrics,
+    dispatcher::service::{Dispatcher, DispatcherFuture},
+    error::api::ApiError,
+    types::{provider::Region, request::Request},
+};
+
+/// Balances requests to a provider across its regions by latency.
+///
+/// Regions are picked using the power of two choices over their peak EWMA
+/// latency, skipping regions that are unhealthy by the health monitor's
+/// config, whether that's their error ratio, an open circuit, or a breached
+/// latency SLO. Providers without regions are dispatched to directly.
+#[derive(Debug, Clone)]
+pub struct ProviderRegionBalancer {
+    app_state: AppState,
+    /// Used when the provider has no regions.
+    dispatcher: Dispatcher,
+    regions: Arc<[RegionDispatcher]>,
+}
+
+#[derive(Debug, Clone)]
+struct RegionDispatcher {
+    region: Region,
+    dispatcher: Dispatcher,
+    stats: Arc<RegionStats>,
+}
+
+impl ProviderRegionBalancer {
+    pub(crate) fn new(
+        app_state: AppState,
+        dispatcher: Dispatcher,
+        regions: Vec<(Region, Dispatcher)>,
+    ) -> Self {
+        let default_rtt = app_state.config().discover.default_rtt;
+        let regions = regions
+            .into_iter()
+            .map(|(region, dispatcher)| RegionDispatcher {
+                region,
+                dispatcher,
+                stats: Arc::new(RegionStats::new(default_rtt)),
+            })
+            .collect();
+        Self {
+            app_state,
+            dispatcher,
+            regions,
+        }
+    }
+
+    fn pick_region(&self, monitor: &MonitorConfig) -> &RegionDispatcher {
+        let healthy = self
+            .regions
+            .iter()
+            .filter(|region| region.stats.is_healthy(monitor))
+            .collect::<Vec<_>>();
+        // if every region is unhealthy the request still has to go somewhere
+        let candidates = if healthy.is_empty() {
+            self.regions.iter().collect()
+        } else {
+            healthy
+        };
+        if let [only] = candidates.as_slice() {
+            return only;
+        }
+
+        let mut rng = rand::rng();
+        let first = rng.random_range(0..candidates.len());
+        let mut second = rng.random_range(0..candidates.len() - 1);
+        if second >= first {
+            second += 1;
+        }
+        let (first, second) = (candidates[first], candidates[second]);
+        if first.stats.rtt() <= second.stats.rtt() {
+            first
+        } else {
+            second
+        }
+    }
+}
+
+impl Service<Request> for ProviderRegionBalancer {
+    type Response = http::Response<crate::types::body::Body>;
+    type Error = ApiError;
+    type Future = DispatcherFuture;
+
+    fn poll_ready(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        if self.regions.is_empty() {
+            return self.dispatcher.call(req);
+        }
+        let config = self.app_state.config();
+        let RegionDispatcher {
+            region,
+            mut dispatcher,
+            stats,
+        } = self.pick_region(&config.discover.monitor).clone();
+        tracing::trace!(region = %region, "selected region");
+        // counts the trial requests of half open circuits, which are still
+        // sent to if every region is unhealthy
+        stats.metrics.try_acquire_permit(&config.discover.monitor);
+        let decay = config.discover.discover_decay;
+        let start = Instant::now();
+        Box::pin(async move {
+            let result = dispatcher.call(req).await;
+            stats.record(&result, start.elapsed(), decay);
+            result
+        })
+    }
+}
+
+/// Latency and health metrics of a single region.
+#[derive(Debug)]
+struct RegionStats {
+    /// Time until the response headers, or the first event for streams.
+    rtt: Mutex<PeakEwma>,
+    /// Checked against the health monitor's config like the metrics of
+    /// provider endpoints, but only used to pick regions.
+    metrics: EndpointMetrics,
+}
+
+impl RegionStats {
+    fn new(default_rtt: Duration) -> Self {
+        Self {
+            rtt: Mutex::new(PeakEwma::new(default_rtt)),
+            metrics: EndpointMetrics::default(),
+        }
+    }
+
+    fn rtt(&self) -> f64 {
+        self.rtt
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .estimate
+    }
+
+    fn is_healthy(&self, monitor: &MonitorConfig) -> bool {
+        self.metrics.is_healthy(monitor)
+    }
+
+    fn record(
+        &self,
+        result: &Result<http::Response<crate::types::body::Body>, ApiError>,
+        rtt: Duration,
+        decay: Duration,
+    ) {
+        self.metrics.incr_req_count();
+        let failed = match result {
+            Ok(response) => response.status().is_server_error(),
+            Err(_) => true,
+        };
+        if failed {
+            self.metrics.incr_remote_internal_error_count();
+        } else {
+            self.metrics.record_success();
+        }
+        self.metrics.record_latency(rtt);
+        self.rtt
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .update(rtt, decay);
+    }
+}
+
+/// An exponentially weighted moving average that immediately follows
+/// latency spikes and then decays back down, as in
+/// [`tower::load::PeakEwma`].
+#[derive(Debug)]
+struct PeakEwma {
+    /// In nanoseconds.
+    estimate: f64,
+    updated_at: Instant,
+}
+
+impl PeakEwma {
+    fn new(default_rtt: Duration) -> Self {
+        Self {
+            estimate: nanos(default_rtt),
+            updated_at: Instant::now(),
+        }
+    }
+
+    fn update(&mut self, rtt: Duration, decay: Duration) {
+        let now = Instant::now();
+        let rtt = nanos(rtt);
+        if rtt > self.estimate {
+            self.estimate = rtt;
+        } else {
+            let elapsed = nanos(now.saturating_duration_since(self.updated_at));
+            let weight = (-elapsed / nanos(decay).max(1.0)).exp();
+            self.estimate = self.estimate * weight + rtt * (1.0 - weight);
+        }
+        self.updated_at = now;
+    }
+}
+
+fn nanos(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peak_ewma_follows_spikes_and_decays() {
+        let mut ewma = PeakEwma::new(Duration::from_millis(100));
+
+        ewma.update(Duration::from_millis(500), Duration::from_secs(10));
+        assert!(
+            (ewma.estimate - nanos(Duration::from_millis(500))).abs() < 1.0
+        );
+
+        // lower samples are averaged in rather than taken as is
+        ewma.update(Duration::from_millis(100), Duration::from_secs(10));
+        assert!(ewma.estimate > nanos(Duration::from_millis(400)));
+
+        // and win out once the decay period has passed
+        std::thread::sleep(Duration::from_millis(5));
+        ewma.update(Duration::from_millis(100), Duration::from_nanos(1));
+        assert!(ewma.estimate < nanos(Duration::from_millis(101)));
+    }
+}
@@ -0,0 +1,20 @@
+use compact_str::CompactString;
+use serde::{Deserialize, Serialize};
+
+/// Pins requests that share the value of a header, such as a session or user
+/// id, to the same provider for as long as that provider stays healthy.
+///
+/// Requests without the header are balanced as usual.
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct AffinityConfig {
+    pub header: CompactString,
+}
+
+impl Default for AffinityConfig {
+    fn default() -> Self {
+        Self {
+            header: CompactString::const_new("helicone-session-id"),
+        }
+    }
+}
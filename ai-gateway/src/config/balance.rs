@@ -1,12 +1,16 @@
 use std::collections::HashMap;
 
+use compact_str::CompactString;
 use derive_more::{AsRef, From};
-use indexmap::IndexSet;
-use nonempty_collections::{NESet, nes};
+use indexmap::{IndexMap, IndexSet};
+use nonempty_collections::{NESet, NEVec, nes};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use crate::{endpoints::EndpointType, types::provider::InferenceProvider};
+use crate::{
+    endpoints::EndpointType,
+    types::{model_id::ModelName, provider::InferenceProvider},
+};
 
 /// A registry of balance configs for each endpoint type,
 /// since a separate load balancer is used for each endpoint type.
@@ -111,8 +115,22 @@ impl BalanceConfig {
 #[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
 #[serde(rename_all = "kebab-case", tag = "strategy")]
 pub enum BalanceConfigInner {
-    Weighted { providers: NESet<BalanceTarget> },
-    Latency { providers: NESet<InferenceProvider> },
+    Weighted {
+        providers: NESet<BalanceTarget>,
+    },
+    Latency {
+        providers: NESet<InferenceProvider>,
+    },
+    /// Routes each request to the cheapest healthy provider for the mapped
+    /// model, based on the `pricing` in the provider config.
+    Cost {
+        providers: NESet<InferenceProvider>,
+    },
+    /// Only sends traffic to a tier while every provider in the tiers
+    /// before it is unhealthy or rate limited.
+    Priority {
+        tiers: NEVec<NESet<InferenceProvider>>,
+    },
 }
 
 impl BalanceConfigInner {
@@ -122,7 +140,87 @@ impl BalanceConfigInner {
             Self::Weighted { providers } => {
                 providers.iter().map(|t| t.provider.clone()).collect()
             }
-            Self::Latency { providers } => providers.iter().cloned().collect(),
+            Self::Latency { providers } | Self::Cost { providers } => {
+                providers.iter().cloned().collect()
+            }
+            Self::Priority { tiers } => {
+                tiers.into_iter().flatten().cloned().collect()
+            }
+        }
+    }
+
+    /// The providers ordered from most to least preferred.
+    ///
+    /// For weighted balancing this is by descending weight and for priority
+    /// balancing it is the tier order. Latency and cost based balancing have
+    /// no static preference so the order is unspecified.
+    #[must_use]
+    pub fn providers_by_preference(&self) -> IndexSet<InferenceProvider> {
+        match self {
+            Self::Weighted { providers } => {
+                let mut targets = providers.iter().collect::<Vec<_>>();
+                targets.sort_by(|a, b| b.weight.cmp(&a.weight));
+                targets.into_iter().map(|t| t.provider.clone()).collect()
+            }
+            Self::Latency { .. }
+            | Self::Cost { .. }
+            | Self::Priority { .. } => self.providers(),
+        }
+    }
+}
+
+/// Balance configs for requests to specific models, keyed by the name of a
+/// model or of a group of models.
+///
+/// Each entry gets its own load balancer for every endpoint type in the
+/// router's [`BalanceConfig`], and requests for models without an entry are
+/// sent to the endpoint level balancer.
+#[derive(
+    Debug, Default, Clone, Deserialize, Serialize, Eq, PartialEq, AsRef, From,
+)]
+pub struct ModelBalanceConfig(pub IndexMap<CompactString, ModelBalanceGroup>);
+
+impl ModelBalanceConfig {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
+    pub fn providers(&self) -> IndexSet<InferenceProvider> {
+        self.0
+            .values()
+            .flat_map(|group| group.balance.providers())
+            .collect()
+    }
+
+    /// The name of the group that balances requests for `model`, if any.
+    #[must_use]
+    pub fn group_for(&self, model: &ModelName<'_>) -> Option<&CompactString> {
+        self.0
+            .iter()
+            .find(|(name, group)| group.contains(name, model))
+            .map(|(name, _)| name)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModelBalanceGroup {
+    /// The models balanced by this group. If empty, the group's name is
+    /// used as the model name.
+    #[serde(default, skip_serializing_if = "IndexSet::is_empty")]
+    pub models: IndexSet<ModelName<'static>>,
+    #[serde(flatten)]
+    pub balance: BalanceConfigInner,
+}
+
+impl ModelBalanceGroup {
+    fn contains(&self, name: &str, model: &ModelName<'_>) -> bool {
+        if self.models.is_empty() {
+            model.as_ref() == name
+        } else {
+            self.models.contains(model)
         }
     }
 }
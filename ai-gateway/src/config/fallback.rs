@@ -0,0 +1,107 @@
+use indexmap::IndexSet;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::balance::BalanceConfigInner, types::provider::InferenceProvider,
+};
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct FallbackConfig {
+    pub enabled: bool,
+    /// The order in which providers are tried after the provider selected
+    /// by the load balancer fails.
+    ///
+    /// Providers that are not part of an endpoint's balance config are
+    /// ignored, and balanced providers missing from this list are tried
+    /// last. If empty, the order is inferred from the balance config.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<InferenceProvider>,
+    /// Also fail over streams that break partway through. The stream is
+    /// restarted on the next provider if nothing has been sent to the client
+    /// yet, and otherwise ended with an `error` event.
+    pub mid_stream: bool,
+}
+
+impl FallbackConfig {
+    /// The providers to fall back to for the given balance config, in the
+    /// order they should be tried.
+    #[must_use]
+    pub fn providers(
+        &self,
+        balance_config: &BalanceConfigInner,
+    ) -> IndexSet<InferenceProvider> {
+        let balanced = balance_config.providers_by_preference();
+        self.order
+            .iter()
+            .filter(|provider| balanced.contains(*provider))
+            .cloned()
+            .chain(balanced.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nonempty_collections::nes;
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::config::balance::BalanceTarget;
+
+    fn weighted() -> BalanceConfigInner {
+        BalanceConfigInner::Weighted {
+            providers: nes![
+                BalanceTarget {
+                    provider: InferenceProvider::OpenAI,
+                    weight: Decimal::try_from(0.25).unwrap(),
+                },
+                BalanceTarget {
+                    provider: InferenceProvider::Anthropic,
+                    weight: Decimal::try_from(0.5).unwrap(),
+                },
+                BalanceTarget {
+                    provider: InferenceProvider::GoogleGemini,
+                    weight: Decimal::try_from(0.25).unwrap(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn order_is_inferred_from_weights() {
+        let config = FallbackConfig {
+            enabled: true,
+            order: Vec::new(),
+            mid_stream: false,
+        };
+        let providers = config.providers(&weighted());
+        assert_eq!(providers.first(), Some(&InferenceProvider::Anthropic));
+        assert_eq!(providers.len(), 3);
+    }
+
+    #[test]
+    fn configured_order_takes_precedence() {
+        let config = FallbackConfig {
+            enabled: true,
+            order: vec![
+                InferenceProvider::GoogleGemini,
+                InferenceProvider::Ollama,
+                InferenceProvider::OpenAI,
+            ],
+            mid_stream: false,
+        };
+        let providers = config
+            .providers(&weighted())
+            .into_iter()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            providers,
+            vec![
+                InferenceProvider::GoogleGemini,
+                InferenceProvider::OpenAI,
+                InferenceProvider::Anthropic,
+            ]
+        );
+    }
+}
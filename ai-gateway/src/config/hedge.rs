@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Sends a second request to a different provider when the first hasn't
+/// responded in time, using whichever response arrives first.
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct HedgeConfig {
+    pub enabled: bool,
+    /// Requests are hedged once they have taken longer than this percentile
+    /// of the router's recent time to first byte.
+    pub percentile: Decimal,
+    /// Used until the router has seen enough requests to estimate the
+    /// percentile.
+    #[serde(with = "humantime_serde")]
+    pub default_delay: Duration,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            percentile: Decimal::new(95, 2),
+            default_delay: Duration::from_secs(2),
+        }
+    }
+}
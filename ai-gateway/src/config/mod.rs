@@ -1,12 +1,16 @@
+pub mod affinity;
 pub mod balance;
 pub mod cache;
 pub mod database;
 pub mod discover;
 pub mod dispatcher;
+pub mod fallback;
+pub mod hedge;
 pub mod helicone;
 pub mod minio;
 pub mod model_mapping;
 pub mod monitor;
+pub mod probe;
 pub mod providers;
 pub mod rate_limit;
 pub mod redis;
@@ -14,6 +18,7 @@ pub mod response_headers;
 pub mod retry;
 pub mod router;
 pub mod server;
+pub mod spend;
 pub mod validation;
 use std::path::PathBuf;
 
@@ -62,6 +67,8 @@ pub struct MiddlewareConfig {
     pub cache: Option<self::cache::CacheConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub rate_limit: Option<self::rate_limit::GlobalRateLimitConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spend: Option<self::spend::SpendConfig>,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -160,6 +167,7 @@ impl Config {
                 return Err(InitError::InvalidRouterId(router_id.to_string()));
             }
         }
+        self.providers.validate()?;
         self.validate_model_mappings()?;
         Ok(())
     }
@@ -178,6 +186,7 @@ impl crate::tests::TestDefault for Config {
             rate_limit: Some(
                 self::rate_limit::GlobalRateLimitConfig::test_default(),
             ),
+            spend: None,
         };
         Config {
             telemetry,
@@ -6,12 +6,23 @@ use rust_decimal::{
 };
 use serde::{Deserialize, Serialize};
 
+use super::probe::ProbeConfig;
+
 const DEFAULT_ERROR_THRESHOLD: f64 = 0.15;
+/// Only error ratio monitors have a grace period.
+const DEFAULT_GRACE_PERIOD: GracePeriod =
+    GracePeriod::Requests { min_requests: 20 };
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields, default, rename_all = "kebab-case")]
 pub struct MonitorConfig {
     pub health: HealthMonitorConfig,
+    /// Whether health is tracked for each provider or for each of a
+    /// provider's models.
+    pub scope: HealthScope,
+    /// Active health probes, disabled if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probe: Option<ProbeConfig>,
 }
 
 impl MonitorConfig {
@@ -21,6 +32,8 @@ impl MonitorConfig {
             HealthMonitorConfig::ErrorRatio { ratio, .. } => {
                 ratio.to_f64().unwrap_or(DEFAULT_ERROR_THRESHOLD)
             }
+            HealthMonitorConfig::CircuitBreaker { .. }
+            | HealthMonitorConfig::LatencySlo { .. } => DEFAULT_ERROR_THRESHOLD,
         }
     }
 
@@ -30,13 +43,17 @@ impl MonitorConfig {
             HealthMonitorConfig::ErrorRatio { grace_period, .. } => {
                 grace_period
             }
+            HealthMonitorConfig::CircuitBreaker { .. }
+            | HealthMonitorConfig::LatencySlo { .. } => &DEFAULT_GRACE_PERIOD,
         }
     }
 
     #[must_use]
     pub fn health_interval(&self) -> Duration {
         match &self.health {
-            HealthMonitorConfig::ErrorRatio { interval, .. } => *interval,
+            HealthMonitorConfig::ErrorRatio { interval, .. }
+            | HealthMonitorConfig::CircuitBreaker { interval, .. }
+            | HealthMonitorConfig::LatencySlo { interval, .. } => *interval,
         }
     }
 }
@@ -63,6 +80,63 @@ pub enum HealthMonitorConfig {
         #[serde(default = "default_grace_period")]
         grace_period: GracePeriod,
     },
+    /// Removes a provider as soon as it fails a number of requests in a row,
+    /// regardless of how much traffic it gets.
+    CircuitBreaker {
+        /// The number of consecutive 5xx errors that will open the circuit
+        /// and remove the provider from the load balancer.
+        #[serde(default = "default_failure_threshold")]
+        failure_threshold: u32,
+        /// How long the circuit stays open before the provider is added back
+        /// to the load balancer for trial requests.
+        #[serde(default = "default_open_duration", with = "humantime_serde")]
+        open_duration: Duration,
+        /// The number of trial requests sent while the circuit is half open.
+        /// If they all succeed the circuit is closed, and if any fail it is
+        /// opened again.
+        #[serde(default = "default_half_open_requests")]
+        half_open_requests: u32,
+        /// Interval to check if providers have changed health status.
+        #[serde(default = "default_health_interval", with = "humantime_serde")]
+        interval: Duration,
+    },
+    /// Removes a provider that is consistently slower to respond than the
+    /// SLO, measured to the first event for streams.
+    ///
+    /// Once removed, a provider no longer gets any samples and is added back
+    /// when its slow samples have left the window.
+    LatencySlo {
+        /// The latency percentile, in `[0, 1]`, compared to the SLO.
+        #[serde(default = "default_latency_percentile")]
+        percentile: Decimal,
+        /// The latency that the percentile must stay under.
+        #[serde(default = "default_slo", with = "humantime_serde")]
+        slo: Duration,
+        /// The window over which to measure the latency percentile.
+        #[serde(default = "default_window", with = "humantime_serde")]
+        window: Duration,
+        /// How long the SLO must be breached for before the provider is
+        /// marked as unhealthy.
+        #[serde(default = "default_sustained_for", with = "humantime_serde")]
+        sustained_for: Duration,
+        /// Interval to check if providers have changed health status.
+        #[serde(default = "default_health_interval", with = "humantime_serde")]
+        interval: Duration,
+    },
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum HealthScope {
+    /// Unhealthy providers are removed from the load balancer.
+    #[default]
+    Provider,
+    /// Only the unhealthy models of a provider are skipped by the load
+    /// balancer, so that one broken model mapping doesn't take out the
+    /// whole provider.
+    Model,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -76,7 +150,31 @@ pub enum GracePeriod {
 }
 
 fn default_grace_period() -> GracePeriod {
-    GracePeriod::Requests { min_requests: 20 }
+    DEFAULT_GRACE_PERIOD
+}
+
+fn default_failure_threshold() -> u32 {
+    5
+}
+
+fn default_open_duration() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_half_open_requests() -> u32 {
+    3
+}
+
+fn default_latency_percentile() -> Decimal {
+    Decimal::from_f64(0.95).unwrap()
+}
+
+fn default_slo() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_sustained_for() -> Duration {
+    Duration::from_secs(30)
 }
 
 fn default_health_interval() -> Duration {
@@ -125,6 +223,8 @@ impl crate::tests::TestDefault for MonitorConfig {
     fn test_default() -> Self {
         Self {
             health: HealthMonitorConfig::test_default(),
+            scope: HealthScope::Provider,
+            probe: None,
         }
     }
 }
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::types::provider::InferenceProvider;
+
+/// Periodically sends a cheap request to each configured provider, so that
+/// outages and recoveries are noticed even without live traffic.
+///
+/// A provider is only considered healthy by the health monitor if its probes
+/// are succeeding as well. Since unhealthy providers get no traffic, a probe
+/// that succeeds after a provider's last request also brings it back when
+/// its traffic was unhealthy.
+///
+/// Providers with regions are probed in each region, and are healthy while
+/// the probes of any region are.
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ProbeConfig {
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+    /// The number of consecutive failed probes after which a provider is
+    /// considered unhealthy.
+    pub failure_threshold: u32,
+    /// Providers without a probe request are not probed.
+    pub providers: IndexMap<InferenceProvider, ProbeRequest>,
+}
+
+impl Default for ProbeConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(10),
+            failure_threshold: 2,
+            providers: IndexMap::new(),
+        }
+    }
+}
+
+/// Sent as a `POST` with a JSON body if `body` is set, and as a `GET`
+/// otherwise.
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ProbeRequest {
+    /// Joined with the provider's base url in the same way as request paths,
+    /// e.g. `/v1/models`.
+    pub path: String,
+    /// e.g. a chat completion request with `max_tokens` set to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
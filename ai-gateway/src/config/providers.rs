@@ -1,13 +1,22 @@
 use derive_more::{AsRef, Deref, DerefMut};
 use indexmap::{IndexMap, IndexSet};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::types::{model_id::ModelName, provider::InferenceProvider};
+use crate::{
+    endpoints::EndpointType,
+    error::init::InitError,
+    types::{
+        model_id::ModelName,
+        provider::{InferenceProvider, Region},
+    },
+};
 
 const PROVIDERS_YAML: &str =
     include_str!("../../config/embedded/providers.yaml");
 pub(crate) const DEFAULT_ANTHROPIC_VERSION: &str = "2023-06-01";
+pub(crate) const DEFAULT_AZURE_API_VERSION: &str = "2024-10-21";
 
 /// Global configuration for providers, shared across all routers.
 ///
@@ -18,9 +27,101 @@ pub struct GlobalProviderConfig {
     /// NOTE: In the future we can delete the `model` field and
     /// instead load the models from the provider's respective APIs
     pub models: IndexSet<ModelName<'static>>,
+    /// Embedding models are listed separately since they can only be
+    /// mapped to other embedding models.
+    #[serde(default, skip_serializing_if = "IndexSet::is_empty")]
+    pub embedding_models: IndexSet<ModelName<'static>>,
+    /// Likewise, image models can only be mapped to other image models.
+    #[serde(default, skip_serializing_if = "IndexSet::is_empty")]
+    pub image_models: IndexSet<ModelName<'static>>,
+    /// Transcription and text-to-speech models.
+    #[serde(default, skip_serializing_if = "IndexSet::is_empty")]
+    pub audio_models: IndexSet<ModelName<'static>>,
+    /// Required for providers configured by name, since we can only route
+    /// to them if they serve a known API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flavor: Option<ProviderFlavor>,
+    /// Azure `OpenAI` deployment names keyed by model. Models without an
+    /// entry are assumed to be deployed under their own name.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub deployments: IndexMap<ModelName<'static>, String>,
+    /// Model prices, used by the `cost` load balancing strategy.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub pricing: IndexMap<ModelName<'static>, ModelPrice>,
     pub base_url: Url,
+    /// The API version, sent as a header to Anthropic and as the
+    /// `api-version` query parameter to Azure `OpenAI`.
     #[serde(default)]
     pub version: Option<String>,
+    /// Overrides the `token_uri` of the Vertex AI service account key used
+    /// to mint access tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_url: Option<Url>,
+    /// Regional deployments of the provider. When set, requests to the
+    /// provider are balanced across its regions by latency instead of being
+    /// sent to `base_url`.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub regions: IndexMap<Region, RegionConfig>,
+}
+
+/// A regional deployment of a provider.
+///
+/// Keys for a region are read from the provider's environment variables
+/// suffixed with the region name, e.g. `AZURE_API_KEY_EASTUS2`, falling back
+/// to the provider's key.
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct RegionConfig {
+    pub base_url: Url,
+}
+
+/// The price of a model in USD per million tokens.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModelPrice {
+    pub input: Decimal,
+    pub output: Decimal,
+}
+
+impl ModelPrice {
+    /// The estimated price of a request in USD.
+    #[must_use]
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> Decimal {
+        (self.input * Decimal::from(input_tokens)
+            + self.output * Decimal::from(output_tokens))
+            / Decimal::from(1_000_000)
+    }
+}
+
+/// The API served by a provider configured by name.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProviderFlavor {
+    /// Serves the `OpenAI` API under its base url, e.g. Groq, Together or
+    /// vLLM.
+    #[serde(rename = "openai-compatible")]
+    OpenAICompatible,
+}
+
+impl GlobalProviderConfig {
+    #[must_use]
+    pub fn is_openai_compatible(&self) -> bool {
+        self.flavor == Some(ProviderFlavor::OpenAICompatible)
+    }
+
+    /// The models offered by the provider for the given endpoint type.
+    #[must_use]
+    pub fn models_for(
+        &self,
+        endpoint_type: EndpointType,
+    ) -> &IndexSet<ModelName<'static>> {
+        match endpoint_type {
+            EndpointType::Embeddings => &self.embedding_models,
+            EndpointType::Image => &self.image_models,
+            EndpointType::Audio => &self.audio_models,
+            EndpointType::Chat => &self.models,
+        }
+    }
 }
 
 /// Map of *ALL* supported providers.
@@ -43,6 +144,20 @@ impl FromIterator<(InferenceProvider, GlobalProviderConfig)>
     }
 }
 
+impl ProvidersConfig {
+    /// Providers configured by name must declare a supported flavor.
+    pub fn validate(&self) -> Result<(), InitError> {
+        for (provider, config) in self.iter() {
+            if matches!(provider, InferenceProvider::Named(_))
+                && !config.is_openai_compatible()
+            {
+                return Err(InitError::ProviderNotSupported(provider.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Default for ProvidersConfig {
     fn default() -> Self {
         serde_yml::from_str(PROVIDERS_YAML).expect("Always valid if tests pass")
@@ -58,4 +173,22 @@ mod tests {
         let _default_config = ProvidersConfig::default();
         // just want to make sure we don't panic...
     }
+
+    #[test]
+    fn named_providers_require_openai_compatible_flavor() {
+        let yaml = r"
+groq:
+  models:
+    - llama-3.1-8b-instant
+  base-url: https://api.groq.com/openai/v1
+";
+        let mut providers: ProvidersConfig = serde_yml::from_str(yaml).unwrap();
+        assert!(providers.validate().is_err());
+
+        providers
+            .get_mut(&InferenceProvider::Named("groq".into()))
+            .unwrap()
+            .flavor = Some(ProviderFlavor::OpenAICompatible);
+        assert!(providers.validate().is_ok());
+    }
 }
@@ -35,8 +35,7 @@ impl GlobalRateLimitConfig {
 }
 
 fn limiter_config(limits: Option<&LimitsConfig>) -> Option<RateLimiterConfig> {
-    if let Some(limits) = limits {
-        let gcra = &limits.per_api_key;
+    if let Some(gcra) = limits.and_then(|limits| limits.per_api_key.as_ref()) {
         let per_cell_duration = gcra
             .refill_frequency
             .checked_div(gcra.capacity.into())
@@ -125,14 +124,39 @@ pub fn store_enabled_for_test_redis() -> RateLimitStore {
 #[derive(Debug, Default, Clone, Deserialize, Serialize, Eq, PartialEq)]
 #[serde(deny_unknown_fields, rename_all = "kebab-case")]
 pub struct LimitsConfig {
-    pub per_api_key: GcraConfig,
+    /// Limits the requests of each API key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_api_key: Option<GcraConfig>,
+    /// Limits the tokens, rather than the requests, used by each API key.
+    ///
+    /// The capacity is in tokens, so a capacity of `100000` with a refill
+    /// frequency of `1m` allows 100k tokens per minute. Prompt tokens are
+    /// estimated and reserved before the request is sent, and reconciled
+    /// with the `usage` reported in the response once it completes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_api_key_tokens: Option<GcraConfig>,
+    /// Limits the requests of all API keys in an organization.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_org: Option<GcraConfig>,
+    /// Limits the requests for each model across all API keys, whichever
+    /// provider the request names it by.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_model: Option<GcraConfig>,
+    /// Limits the requests of all API keys together, for the router or, in
+    /// the global config, for the whole gateway.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_router_total: Option<GcraConfig>,
 }
 
 #[cfg(feature = "testing")]
 impl crate::tests::TestDefault for LimitsConfig {
     fn test_default() -> Self {
         Self {
-            per_api_key: GcraConfig::test_default(),
+            per_api_key: Some(GcraConfig::test_default()),
+            per_api_key_tokens: None,
+            per_org: None,
+            per_model: None,
+            per_router_total: None,
         }
     }
 }
@@ -1,11 +1,18 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
 use derive_more::{AsMut, AsRef};
+use indexmap::IndexSet;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    balance::{BalanceConfig, BalanceConfigInner},
+    affinity::AffinityConfig,
+    balance::{BalanceConfig, BalanceConfigInner, ModelBalanceConfig},
+    fallback::FallbackConfig,
+    hedge::HedgeConfig,
     model_mapping::ModelMappingConfig,
     rate_limit::LimitsConfig,
     retry::RetryConfig,
@@ -13,7 +20,9 @@ use super::{
 use crate::{
     config::{cache::CacheConfig, rate_limit::RateLimitStore},
     error::init::InitError,
-    types::router::RouterId,
+    types::{
+        model_id::ModelName, provider::InferenceProvider, router::RouterId,
+    },
 };
 
 #[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq, AsRef, AsMut)]
@@ -46,19 +55,34 @@ impl std::ops::Deref for RouterConfigs {
 #[serde(default, rename_all = "kebab-case")]
 pub struct RouterConfig {
     pub load_balance: BalanceConfig,
+    #[serde(skip_serializing_if = "ModelBalanceConfig::is_empty")]
+    pub model_load_balance: ModelBalanceConfig,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model_mappings: Option<ModelMappingConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache: Option<CacheConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retries: Option<RetryConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<FallbackConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hedge: Option<HedgeConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub affinity: Option<AffinityConfig>,
     #[serde(skip_serializing_if = "RouterRateLimitConfig::is_disabled")]
     pub rate_limit: RouterRateLimitConfig,
 }
 
 impl RouterConfig {
     pub fn validate(&self) -> Result<(), InitError> {
-        for balance_config in self.load_balance.0.values() {
+        let model_balance_configs = self
+            .model_load_balance
+            .0
+            .values()
+            .map(|group| &group.balance);
+        for balance_config in
+            self.load_balance.0.values().chain(model_balance_configs)
+        {
             match balance_config {
                 BalanceConfigInner::Weighted { providers } => {
                     let total =
@@ -69,13 +93,57 @@ impl RouterConfig {
                         ));
                     }
                 }
-                BalanceConfigInner::Latency { .. } => {}
+                BalanceConfigInner::Priority { tiers } => {
+                    let mut seen = HashSet::new();
+                    for provider in tiers.into_iter().flatten() {
+                        if !seen.insert(provider) {
+                            return Err(InitError::InvalidPriorityBalancer(
+                                provider.clone(),
+                            ));
+                        }
+                    }
+                }
+                BalanceConfigInner::Latency { .. }
+                | BalanceConfigInner::Cost { .. } => {}
+            }
+        }
+
+        let mut seen_models = HashSet::new();
+        for (name, group) in &self.model_load_balance.0 {
+            let group_models = if group.models.is_empty() {
+                vec![ModelName::borrowed(name.as_str())]
+            } else {
+                group.models.iter().cloned().collect()
+            };
+            for model in group_models {
+                if !seen_models.insert(model.clone()) {
+                    return Err(InitError::InvalidModelBalancer(
+                        model.to_string(),
+                    ));
+                }
+            }
+        }
+
+        if let Some(affinity) = &self.affinity {
+            if http::HeaderName::from_str(&affinity.header).is_err() {
+                return Err(InitError::InvalidAffinityHeader(
+                    affinity.header.to_string(),
+                ));
             }
         }
 
         Ok(())
     }
 
+    /// All providers the router may balance requests across, at either the
+    /// endpoint or model level.
+    #[must_use]
+    pub fn providers(&self) -> IndexSet<InferenceProvider> {
+        let mut providers = self.load_balance.providers();
+        providers.extend(self.model_load_balance.providers());
+        providers
+    }
+
     #[must_use]
     pub fn model_mappings(&self) -> Option<&ModelMappingConfig> {
         self.model_mappings.as_ref()
@@ -123,7 +191,11 @@ impl crate::tests::TestDefault for RouterConfigs {
                         ],
                     },
                 )])),
+                model_load_balance: ModelBalanceConfig::default(),
                 retries: None,
+                fallback: None,
+                hedge: None,
+                affinity: None,
                 rate_limit: RouterRateLimitConfig::default(),
             },
         )]))
@@ -158,7 +230,11 @@ mod tests {
             model_mappings: None,
             cache: Some(cache),
             load_balance: balance,
+            model_load_balance: ModelBalanceConfig::default(),
             retries: Some(retries),
+            fallback: None,
+            hedge: None,
+            affinity: None,
             rate_limit: RouterRateLimitConfig::default(),
         }
     }
@@ -172,6 +248,42 @@ mod tests {
         assert_eq!(config, deserialized);
     }
 
+    #[test]
+    fn providers_may_only_appear_in_one_priority_tier() {
+        let yaml = r"
+load-balance:
+  chat:
+    strategy: priority
+    tiers:
+      - [openai]
+      - [anthropic, openai]
+";
+        let config = serde_yml::from_str::<RouterConfig>(yaml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(InitError::InvalidPriorityBalancer(_))
+        ));
+    }
+
+    #[test]
+    fn models_may_only_appear_in_one_model_balance_group() {
+        let yaml = r"
+model-load-balance:
+  claude-sonnet-4:
+    strategy: latency
+    providers: [anthropic, bedrock]
+  sonnet:
+    models: [claude-3-7-sonnet, claude-sonnet-4]
+    strategy: latency
+    providers: [anthropic]
+";
+        let config = serde_yml::from_str::<RouterConfig>(yaml).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(InitError::InvalidModelBalancer(model)) if model == "claude-sonnet-4"
+        ));
+    }
+
     #[test]
     fn router_configs_round_trip() {
         let config = RouterConfigs::default();
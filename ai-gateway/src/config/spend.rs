@@ -0,0 +1,88 @@
+use std::{fmt, time::Duration};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Budgets in USD for the spend of each API key or org, after which requests
+/// are rejected until the budget's period has passed.
+///
+/// Spend is computed from the token usage in responses and the `pricing` of
+/// the provider that served them, so requests to models without a price and
+/// responses served from the cache are free. It is accrued in the
+/// `rate-limit-store`.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SpendConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_api_key: Option<BudgetConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_org: Option<BudgetConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct BudgetConfig {
+    /// The budget in USD.
+    pub limit: Decimal,
+    pub period: BudgetPeriod,
+    /// The fraction of the budget, e.g. `0.8`, after which responses carry
+    /// an `x-budget-warning` header.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub soft_limit: Option<Decimal>,
+}
+
+/// Calendar periods are in UTC.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum BudgetPeriod {
+    Daily,
+    Monthly,
+    /// The spend over the trailing duration, e.g. `24h`.
+    Rolling(#[serde(with = "humantime_serde")] Duration),
+}
+
+impl fmt::Display for BudgetPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Daily => write!(f, "daily"),
+            Self::Monthly => write!(f, "monthly"),
+            Self::Rolling(window) => {
+                write!(
+                    f,
+                    "rolling {}",
+                    humantime_serde::re::humantime::format_duration(*window)
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_periods_round_trip() {
+        let yaml = r"
+per-api-key:
+  limit: 10
+  period: daily
+per-org:
+  limit: 250.5
+  period:
+    rolling: 7days
+  soft-limit: 0.8
+";
+        let config: SpendConfig = serde_yml::from_str(yaml).unwrap();
+        let per_org = config.per_org.as_ref().unwrap();
+        assert_eq!(
+            per_org.period,
+            BudgetPeriod::Rolling(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+        assert_eq!(per_org.period.to_string(), "rolling 7days");
+        let serialized = serde_yml::to_string(&config).unwrap();
+        let round_tripped: SpendConfig =
+            serde_yml::from_str(&serialized).unwrap();
+        assert_eq!(config, round_tripped);
+    }
+}
@@ -45,7 +45,7 @@ impl Config {
         // Validate each router
         for (router_id, router_config) in self.routers.as_ref() {
             // Get all providers this router might use
-            let router_providers = router_config.load_balance.providers();
+            let router_providers = router_config.providers();
 
             // Validate each provider exists in global config
             for provider in &router_providers {
@@ -59,27 +59,38 @@ impl Config {
                 }
             }
 
-            let all_models_offered_by_configured_providers: IndexSet<
-                ModelName,
-            > = router_providers
-                .iter()
-                .flat_map(|provider| &self.providers[provider].models)
-                .cloned()
-                .collect();
-
-            // For each provider this router might route to
-            for target_provider in &router_providers {
-                let target_provider_config = &self.providers[target_provider];
-
-                for source_model in &all_models_offered_by_configured_providers
-                {
-                    self.can_map_model(
-                        source_model,
-                        target_provider.clone(),
-                        &target_provider_config.models,
-                        router_id,
-                        router_config,
-                    )?;
+            // Models are only mapped between providers balanced for the same
+            // endpoint type
+            for (endpoint_type, balance_config) in
+                router_config.load_balance.as_ref()
+            {
+                let balanced_providers = balance_config.providers();
+                let all_models_offered_by_configured_providers: IndexSet<
+                    ModelName,
+                > = balanced_providers
+                    .iter()
+                    .flat_map(|provider| {
+                        self.providers[provider].models_for(*endpoint_type)
+                    })
+                    .cloned()
+                    .collect();
+
+                // For each provider this router might route to
+                for target_provider in &balanced_providers {
+                    let target_models = self.providers[target_provider]
+                        .models_for(*endpoint_type);
+
+                    for source_model in
+                        &all_models_offered_by_configured_providers
+                    {
+                        self.can_map_model(
+                            source_model,
+                            target_provider.clone(),
+                            target_models,
+                            router_id,
+                            router_config,
+                        )?;
+                    }
                 }
             }
         }
@@ -131,8 +142,17 @@ impl Config {
     fn validate_mapping_models_exist(
         &self,
     ) -> Result<(), ModelMappingValidationError> {
-        let all_provider_models: IndexSet<&ModelName> =
-            self.providers.values().flat_map(|p| &p.models).collect();
+        let all_provider_models: IndexSet<&ModelName> = self
+            .providers
+            .values()
+            .flat_map(|p| {
+                p.models
+                    .iter()
+                    .chain(&p.embedding_models)
+                    .chain(&p.image_models)
+                    .chain(&p.audio_models)
+            })
+            .collect();
 
         // Check default mappings
         for (source_model, target_models) in self.default_model_mapping.as_ref()
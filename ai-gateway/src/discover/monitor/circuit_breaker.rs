@@ -0,0 +1,242 @@
+//! A consecutive failure circuit breaker for a single provider endpoint.
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// Request outcomes are recorded as they happen, while the circuit is only
+/// opened or moved to half open when the health monitor checks it, which is
+/// also when the provider is removed from or added back to the load
+/// balancer.
+///
+/// Requests must acquire a permit with [`CircuitBreaker::try_acquire`]
+/// before they are dispatched, which limits a half open circuit to its trial
+/// requests however many requests the load balancer sends it. Load balancers
+/// check [`CircuitBreaker::accepts`] to avoid selecting providers that would
+/// be refused a permit.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Circuit {
+    Closed,
+    Open { since: Instant },
+    HalfOpen { since: Instant },
+}
+
+#[derive(Debug)]
+struct State {
+    circuit: Circuit,
+    consecutive_failures: u32,
+    /// Trial requests sent since the circuit became half open.
+    trial_requests: u32,
+    /// Trial requests that succeeded since the circuit became half open.
+    trial_successes: u32,
+}
+
+/// The parameters of [`CircuitBreaker::check`], from the health monitor
+/// config.
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerParams {
+    pub failure_threshold: u32,
+    pub open_duration: Duration,
+    pub half_open_requests: u32,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(State {
+                circuit: Circuit::Closed,
+                consecutive_failures: 0,
+                trial_requests: 0,
+                trial_successes: 0,
+            }),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns whether a request may be dispatched, which isn't the case
+    /// while the circuit is open, or once `half_open_requests` trial
+    /// requests have been dispatched while it is half open.
+    pub fn try_acquire(&self, half_open_requests: u32) -> bool {
+        let mut state = self.state();
+        match state.circuit {
+            Circuit::Closed => true,
+            Circuit::Open { .. } => false,
+            Circuit::HalfOpen { .. } => {
+                if state.trial_requests >= half_open_requests {
+                    return false;
+                }
+                state.trial_requests += 1;
+                true
+            }
+        }
+    }
+
+    /// Returns whether [`CircuitBreaker::try_acquire`] would currently grant
+    /// a permit, without taking one.
+    #[must_use]
+    pub fn accepts(&self, half_open_requests: u32) -> bool {
+        let state = self.state();
+        match state.circuit {
+            Circuit::Closed => true,
+            Circuit::Open { .. } => false,
+            Circuit::HalfOpen { .. } => {
+                state.trial_requests < half_open_requests
+            }
+        }
+    }
+
+    pub fn on_success(&self) {
+        let mut state = self.state();
+        state.consecutive_failures = 0;
+        if matches!(state.circuit, Circuit::HalfOpen { .. }) {
+            state.trial_successes += 1;
+        }
+    }
+
+    pub fn on_failure(&self) {
+        let mut state = self.state();
+        state.consecutive_failures += 1;
+        if matches!(state.circuit, Circuit::HalfOpen { .. }) {
+            tracing::info!("trial request failed, opening circuit");
+            state.circuit = Circuit::Open {
+                since: Instant::now(),
+            };
+        }
+    }
+
+    /// Moves the circuit to its next state if it is due to, and returns
+    /// whether the provider should be in the load balancer.
+    ///
+    /// Half open circuits are kept in the load balancer until all of their
+    /// trial requests have been sent, and then removed until those requests
+    /// complete. If they haven't completed within the open duration, the
+    /// circuit is opened again.
+    pub fn check(&self, params: &CircuitBreakerParams) -> bool {
+        let mut state = self.state();
+        let now = Instant::now();
+        match state.circuit {
+            Circuit::Closed => {
+                if state.consecutive_failures < params.failure_threshold {
+                    return true;
+                }
+                tracing::info!(
+                    consecutive_failures = state.consecutive_failures,
+                    "failure threshold reached, opening circuit"
+                );
+                state.circuit = Circuit::Open { since: now };
+                false
+            }
+            Circuit::Open { since } => {
+                if now.duration_since(since) < params.open_duration {
+                    return false;
+                }
+                tracing::info!("circuit half open, sending trial requests");
+                state.circuit = Circuit::HalfOpen { since: now };
+                state.trial_requests = 0;
+                state.trial_successes = 0;
+                true
+            }
+            Circuit::HalfOpen { since } => {
+                if state.trial_successes >= params.half_open_requests {
+                    tracing::info!("trial requests succeeded, closing circuit");
+                    state.circuit = Circuit::Closed;
+                    state.consecutive_failures = 0;
+                    true
+                } else if state.trial_requests < params.half_open_requests {
+                    true
+                } else if now.duration_since(since) < params.open_duration {
+                    false
+                } else {
+                    tracing::info!(
+                        "trial requests did not complete, opening circuit"
+                    );
+                    state.circuit = Circuit::Open { since: now };
+                    false
+                }
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn circuit(&self) -> Circuit {
+        self.state().circuit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: CircuitBreakerParams = CircuitBreakerParams {
+        failure_threshold: 3,
+        open_duration: Duration::ZERO,
+        half_open_requests: 2,
+    };
+
+    #[test]
+    fn opens_on_consecutive_failures_and_closes_after_trials() {
+        let breaker = CircuitBreaker::default();
+        breaker.on_failure();
+        breaker.on_failure();
+        breaker.on_success();
+        breaker.on_failure();
+        breaker.on_failure();
+        assert!(breaker.check(&PARAMS));
+        assert!(breaker.try_acquire(PARAMS.half_open_requests));
+
+        breaker.on_failure();
+        assert!(!breaker.check(&PARAMS));
+        assert!(matches!(breaker.circuit(), Circuit::Open { .. }));
+        assert!(!breaker.try_acquire(PARAMS.half_open_requests));
+
+        // the open duration has passed, so the circuit is half open and only
+        // lets its trial requests through
+        assert!(breaker.check(&PARAMS));
+        assert!(matches!(breaker.circuit(), Circuit::HalfOpen { .. }));
+        assert!(breaker.try_acquire(PARAMS.half_open_requests));
+        assert!(breaker.accepts(PARAMS.half_open_requests));
+        assert!(breaker.try_acquire(PARAMS.half_open_requests));
+        assert!(!breaker.accepts(PARAMS.half_open_requests));
+        assert!(!breaker.try_acquire(PARAMS.half_open_requests));
+
+        breaker.on_success();
+        breaker.on_success();
+        assert!(breaker.check(&PARAMS));
+        assert_eq!(breaker.circuit(), Circuit::Closed);
+        assert!(breaker.try_acquire(PARAMS.half_open_requests));
+        assert!(breaker.try_acquire(PARAMS.half_open_requests));
+        assert!(breaker.try_acquire(PARAMS.half_open_requests));
+    }
+
+    #[test]
+    fn failed_trial_reopens_circuit() {
+        let breaker = CircuitBreaker::default();
+        for _ in 0..PARAMS.failure_threshold {
+            breaker.on_failure();
+        }
+        assert!(!breaker.check(&PARAMS));
+        assert!(breaker.check(&PARAMS));
+        assert!(matches!(breaker.circuit(), Circuit::HalfOpen { .. }));
+
+        assert!(breaker.try_acquire(PARAMS.half_open_requests));
+        breaker.on_failure();
+        assert!(matches!(breaker.circuit(), Circuit::Open { .. }));
+        assert!(!breaker.try_acquire(PARAMS.half_open_requests));
+
+        // the next half open period gets a fresh set of trial requests
+        assert!(breaker.check(&PARAMS));
+        assert!(breaker.try_acquire(PARAMS.half_open_requests));
+        assert!(breaker.try_acquire(PARAMS.half_open_requests));
+        assert!(!breaker.try_acquire(PARAMS.half_open_requests));
+    }
+}
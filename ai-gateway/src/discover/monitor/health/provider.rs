@@ -17,7 +17,7 @@ use weighted_balance::weight::Weight;
 use crate::{
     app_state::AppState,
     config::{
-        balance::BalanceConfigInner, monitor::GracePeriod, router::RouterConfig,
+        balance::BalanceConfigInner, monitor::HealthScope, router::RouterConfig,
     },
     discover::{provider::Key, weighted::WeightedKey},
     dispatcher::{Dispatcher, DispatcherService},
@@ -26,11 +26,11 @@ use crate::{
         internal::InternalError,
         runtime::{self, RuntimeError},
     },
-    types::{provider::InferenceProvider, router::RouterId},
+    types::{provider::InferenceProvider, router::BalancerId},
 };
 
 pub type HealthMonitorMap =
-    Arc<RwLock<HashMap<RouterId, ProviderHealthMonitor>>>;
+    Arc<RwLock<HashMap<BalancerId, ProviderHealthMonitor>>>;
 
 #[derive(Debug, Clone)]
 pub enum ProviderHealthMonitor {
@@ -41,13 +41,13 @@ pub enum ProviderHealthMonitor {
 impl ProviderHealthMonitor {
     fn weighted(
         tx: Sender<Change<WeightedKey, DispatcherService>>,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         app_state: AppState,
     ) -> Self {
         Self::Weighted(ProviderMonitorInner::new(
             tx,
-            router_id,
+            balancer_id,
             router_config,
             app_state,
         ))
@@ -55,13 +55,13 @@ impl ProviderHealthMonitor {
 
     fn p2c(
         tx: Sender<Change<Key, DispatcherService>>,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         app_state: AppState,
     ) -> Self {
         Self::P2C(ProviderMonitorInner::new(
             tx,
-            router_id,
+            balancer_id,
             router_config,
             app_state,
         ))
@@ -80,8 +80,11 @@ impl ProviderHealthMonitor {
 async fn check_weighted_monitor(
     inner: &mut ProviderMonitorInner<WeightedKey>,
 ) -> Result<(), runtime::RuntimeError> {
+    let monitored_endpoint_type = inner.balancer_id.endpoint_type;
     for (endpoint_type, balance_config) in
-        inner.router_config.load_balance.as_ref()
+        inner.router_config.load_balance.as_ref().iter().filter(
+            |(endpoint_type, _)| **endpoint_type == monitored_endpoint_type,
+        )
     {
         match balance_config {
             BalanceConfigInner::Weighted { providers } => {
@@ -115,7 +118,7 @@ async fn check_weighted_monitor(
 
                         let service = Dispatcher::new(
                             inner.app_state.clone(),
-                            &inner.router_id,
+                            &inner.balancer_id,
                             &inner.router_config,
                             provider.clone(),
                         )
@@ -147,7 +150,9 @@ async fn check_weighted_monitor(
                     }
                 }
             }
-            BalanceConfigInner::Latency { .. } => {
+            BalanceConfigInner::Latency { .. }
+            | BalanceConfigInner::Cost { .. }
+            | BalanceConfigInner::Priority { .. } => {
                 tracing::error!("P2C entries in a weighted monitor");
                 return Err(InternalError::Internal.into());
             }
@@ -160,12 +165,19 @@ async fn check_weighted_monitor(
 async fn check_p2c_monitor(
     inner: &mut ProviderMonitorInner<Key>,
 ) -> Result<(), runtime::RuntimeError> {
+    let monitored_endpoint_type = inner.balancer_id.endpoint_type;
     for (endpoint_type, balance_config) in
-        inner.router_config.load_balance.as_ref()
+        inner.router_config.load_balance.as_ref().iter().filter(
+            |(endpoint_type, _)| **endpoint_type == monitored_endpoint_type,
+        )
     {
         match balance_config {
-            BalanceConfigInner::Latency { providers } => {
-                for provider in providers {
+            // cost and priority based balancing discover providers the same
+            // way
+            BalanceConfigInner::Latency { .. }
+            | BalanceConfigInner::Cost { .. }
+            | BalanceConfigInner::Priority { .. } => {
+                for provider in &balance_config.providers() {
                     let key = Key::new(provider.clone(), *endpoint_type);
                     let is_healthy = inner.check_health(provider)?;
                     let was_unhealthy = inner.unhealthy_keys.contains(&key);
@@ -184,7 +196,7 @@ async fn check_p2c_monitor(
 
                         let service = Dispatcher::new(
                             inner.app_state.clone(),
-                            &inner.router_id,
+                            &inner.balancer_id,
                             &inner.router_config,
                             provider.clone(),
                         )
@@ -231,7 +243,7 @@ async fn check_p2c_monitor(
 #[derive(Debug, Clone)]
 pub struct ProviderMonitorInner<K> {
     tx: Sender<Change<K, DispatcherService>>,
-    router_id: RouterId,
+    balancer_id: BalancerId,
     router_config: Arc<RouterConfig>,
     app_state: AppState,
     unhealthy_keys: HashSet<K>,
@@ -240,13 +252,13 @@ pub struct ProviderMonitorInner<K> {
 impl<K> ProviderMonitorInner<K> {
     fn new(
         tx: Sender<Change<K, DispatcherService>>,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         app_state: AppState,
     ) -> Self {
         Self {
             tx,
-            router_id,
+            balancer_id,
             router_config,
             app_state,
             unhealthy_keys: HashSet::default(),
@@ -257,30 +269,54 @@ impl<K> ProviderMonitorInner<K> {
         &self,
         provider: &InferenceProvider,
     ) -> Result<bool, InternalError> {
-        let provider_endpoints = provider.endpoints();
+        let traffic_healthy = self.check_traffic_health(provider)?;
+        let Some(probe) = &self.app_state.config().discover.monitor.probe
+        else {
+            return Ok(traffic_healthy);
+        };
+        let probe_results = &self.app_state.0.probe_results;
+        if !probe_results.is_healthy(provider, probe.failure_threshold) {
+            return Ok(false);
+        }
+        // unhealthy providers get no traffic, so their traffic metrics only
+        // recover once they age out. A probe that passed since the last
+        // request is more recent, so it brings the provider back
+        let last_request_at =
+            self.app_state.0.endpoint_metrics.last_request_at(provider);
+        Ok(traffic_healthy
+            || probe_results.passed_since(provider, last_request_at))
+    }
+
+    /// Health as measured from live traffic.
+    fn check_traffic_health(
+        &self,
+        provider: &InferenceProvider,
+    ) -> Result<bool, InternalError> {
         let config = self.app_state.config();
-        let grace_period = config.discover.monitor.grace_period();
+        if config.discover.monitor.scope == HealthScope::Model {
+            // unhealthy models are skipped by the balancer, so providers are
+            // only removed once none of their models are healthy
+            return Ok(self
+                .app_state
+                .0
+                .endpoint_metrics
+                .is_provider_healthy_for_models(
+                    provider,
+                    &config.discover.monitor,
+                ));
+        }
         let mut all_healthy = true;
-        for endpoint in provider_endpoints {
-            let endpoint_metrics =
-                self.app_state.0.endpoint_metrics.health_metrics(endpoint)?;
-            let requests = endpoint_metrics.request_count.total();
-            match grace_period {
-                GracePeriod::Requests { min_requests } => {
-                    if requests < *min_requests {
-                        continue;
-                    }
-                }
-            }
-
-            let errors = endpoint_metrics.remote_internal_error_count.total();
-            let error_ratio = f64::from(errors) / f64::from(requests);
-
-            if error_ratio > config.discover.monitor.error_threshold() {
-                all_healthy = false;
-            }
+        for endpoint in provider.endpoints() {
+            let endpoint_metrics = self
+                .app_state
+                .0
+                .endpoint_metrics
+                .health_metrics(&endpoint)?;
+            // every endpoint is checked since checks can change the state
+            // of circuit breakers and latency SLO breaches
+            all_healthy &=
+                endpoint_metrics.is_healthy(&config.discover.monitor);
         }
-
         Ok(all_healthy)
     }
 }
@@ -307,12 +343,12 @@ impl HealthMonitor {
             interval.tick().await;
             let mut monitors = self.app_state.0.health_monitors.write().await;
             let mut check_futures = Vec::new();
-            for (router_id, monitor) in monitors.iter_mut() {
-                let span = tracing::info_span!("health_monitor", router_id = ?router_id);
+            for (balancer_id, monitor) in monitors.iter_mut() {
+                let span = tracing::info_span!("health_monitor", router_id = ?balancer_id.router_id, endpoint_type = ?balancer_id.endpoint_type, model_group = ?balancer_id.model_group);
                 let check_future = async move {
                     let result = monitor.check_monitor().await;
                     if let Err(e) = &result {
-                        error!(router_id = ?router_id, error = ?e, "Provider health monitor check failed");
+                        error!(balancer_id = ?balancer_id, error = ?e, "Provider health monitor check failed");
                     }
                     result
                 }.instrument(span);
@@ -354,15 +390,15 @@ impl meltdown::Service for HealthMonitor {
 impl AppState {
     pub async fn add_weighted_router_health_monitor(
         &self,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         tx: Sender<Change<WeightedKey, DispatcherService>>,
     ) {
         self.0.health_monitors.write().await.insert(
-            router_id.clone(),
+            balancer_id.clone(),
             ProviderHealthMonitor::weighted(
                 tx,
-                router_id,
+                balancer_id,
                 router_config,
                 self.clone(),
             ),
@@ -371,15 +407,15 @@ impl AppState {
 
     pub async fn add_p2c_router_health_monitor(
         &self,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         tx: Sender<Change<Key, DispatcherService>>,
     ) {
         self.0.health_monitors.write().await.insert(
-            router_id.clone(),
+            balancer_id.clone(),
             ProviderHealthMonitor::p2c(
                 tx,
-                router_id,
+                balancer_id,
                 router_config,
                 self.clone(),
             ),
@@ -1,13 +1,30 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{Arc, Mutex, PoisonError, RwLock},
+    time::{Duration, Instant},
+};
 
+use rust_decimal::prelude::ToPrimitive;
 use rustc_hash::FxHashMap as HashMap;
-use strum::IntoEnumIterator;
 
 use crate::{
-    endpoints::ApiEndpoint, error::internal::InternalError,
-    metrics::RollingCounter, types::provider::InferenceProvider,
+    config::{
+        monitor::{GracePeriod, HealthMonitorConfig, MonitorConfig},
+        providers::ProvidersConfig,
+    },
+    discover::monitor::circuit_breaker::{
+        Circuit, CircuitBreaker, CircuitBreakerParams,
+    },
+    endpoints::ApiEndpoint,
+    error::internal::InternalError,
+    metrics::{RollingCounter, tfft::TfftWindow},
+    types::{model_id::ModelName, provider::InferenceProvider},
 };
 
+type ModelKey = (InferenceProvider, ModelName<'static>);
+
+/// How many recent latency samples are kept for each endpoint.
+const LATENCY_SAMPLES: usize = 1000;
+
 /// We use this to track metrics for monitoring provider health.
 ///
 /// We do this separately from the OpenTelemetry metrics because a) they
@@ -16,23 +33,18 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct EndpointMetricsRegistry {
     endpoint_health_metrics: Arc<HashMap<ApiEndpoint, EndpointMetrics>>,
+    /// Only tracked when health is scoped to models, created as each
+    /// provider and model pair is first used.
+    model_health_metrics: Arc<RwLock<HashMap<ModelKey, Arc<EndpointMetrics>>>>,
 }
 
 impl EndpointMetricsRegistry {
-    pub fn health_metrics(
-        &self,
-        api_endpoint: ApiEndpoint,
-    ) -> Result<&EndpointMetrics, InternalError> {
-        self.endpoint_health_metrics
-            .get(&api_endpoint)
-            .ok_or(InternalError::MetricsNotConfigured(api_endpoint))
-    }
-}
-
-impl Default for EndpointMetricsRegistry {
-    fn default() -> Self {
+    /// Tracks the endpoints of every configured provider, including those
+    /// configured by name.
+    #[must_use]
+    pub fn new(providers_config: &ProvidersConfig) -> Self {
         let mut endpoint_health_metrics = HashMap::default();
-        for provider in InferenceProvider::iter() {
+        for provider in providers_config.keys() {
             for endpoint in provider.endpoints() {
                 endpoint_health_metrics
                     .insert(endpoint, EndpointMetrics::default());
@@ -40,16 +52,136 @@ impl Default for EndpointMetricsRegistry {
         }
         Self {
             endpoint_health_metrics: Arc::new(endpoint_health_metrics),
+            model_health_metrics: Arc::default(),
+        }
+    }
+
+    pub fn health_metrics(
+        &self,
+        api_endpoint: &ApiEndpoint,
+    ) -> Result<&EndpointMetrics, InternalError> {
+        self.endpoint_health_metrics
+            .get(api_endpoint)
+            .ok_or_else(|| {
+                InternalError::MetricsNotConfigured(api_endpoint.clone())
+            })
+    }
+
+    /// The health metrics of a provider's model, as named by the provider.
+    #[must_use]
+    pub fn model_health_metrics(
+        &self,
+        provider: &InferenceProvider,
+        model: &ModelName<'_>,
+    ) -> Arc<EndpointMetrics> {
+        let key = (provider.clone(), ModelName::owned(model.to_string()));
+        if let Some(metrics) = self
+            .model_health_metrics
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+        {
+            return metrics.clone();
+        }
+        self.model_health_metrics
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(key)
+            .or_default()
+            .clone()
+    }
+
+    /// When a request was last sent to any of the provider's endpoints or
+    /// models, if one has been since the gateway started.
+    #[must_use]
+    pub fn last_request_at(
+        &self,
+        provider: &InferenceProvider,
+    ) -> Option<Instant> {
+        let endpoints = provider
+            .endpoints()
+            .into_iter()
+            .filter_map(|endpoint| {
+                self.endpoint_health_metrics
+                    .get(&endpoint)?
+                    .last_request_at()
+            })
+            .max();
+        let models = self
+            .model_health_metrics
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|((model_provider, _), _)| model_provider == provider)
+            .filter_map(|(_, metrics)| metrics.last_request_at())
+            .max();
+        endpoints.max(models)
+    }
+
+    /// Whether a provider is healthy when health is scoped to models, which
+    /// is unless it has an unhealthy model and none of its models that are
+    /// getting traffic are healthy.
+    #[must_use]
+    pub fn is_provider_healthy_for_models(
+        &self,
+        provider: &InferenceProvider,
+        monitor: &MonitorConfig,
+    ) -> bool {
+        let models = self
+            .model_health_metrics
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|((model_provider, _), _)| model_provider == provider)
+            .map(|(_, metrics)| metrics.clone())
+            .collect::<Vec<_>>();
+        let mut any_unhealthy = false;
+        let mut any_healthy_in_use = false;
+        // every model is checked since checks can change the state of
+        // circuit breakers and latency SLO breaches
+        for metrics in models {
+            if metrics.is_healthy(monitor) {
+                any_healthy_in_use |= metrics.request_count.total() > 0;
+            } else {
+                any_unhealthy = true;
+            }
         }
+        !any_unhealthy || any_healthy_in_use
+    }
+}
+
+impl Default for EndpointMetricsRegistry {
+    fn default() -> Self {
+        Self::new(&ProvidersConfig::default())
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct EndpointMetrics {
     /// total request count
     pub(crate) request_count: RollingCounter,
     /// Count of upstream remote internal errors
     pub(crate) remote_internal_error_count: RollingCounter,
+    /// Only used by the circuit breaker health monitor
+    pub(crate) circuit_breaker: CircuitBreaker,
+    /// Time to the response headers, or the first event for streams
+    pub(crate) latency: TfftWindow,
+    /// When the latency SLO started being breached, if it currently is
+    slo_breached_since: Mutex<Option<Instant>>,
+    last_request_at: Mutex<Option<Instant>>,
+}
+
+impl Default for EndpointMetrics {
+    fn default() -> Self {
+        Self {
+            request_count: RollingCounter::default(),
+            remote_internal_error_count: RollingCounter::default(),
+            circuit_breaker: CircuitBreaker::default(),
+            latency: TfftWindow::new(LATENCY_SAMPLES),
+            slo_breached_since: Mutex::new(None),
+            last_request_at: Mutex::new(None),
+        }
+    }
 }
 
 impl EndpointMetrics {
@@ -58,15 +190,137 @@ impl EndpointMetrics {
         Self {
             request_count: RollingCounter::new(window, buckets),
             remote_internal_error_count: RollingCounter::new(window, buckets),
+            circuit_breaker: CircuitBreaker::default(),
+            latency: TfftWindow::new(LATENCY_SAMPLES),
+            slo_breached_since: Mutex::new(None),
+            last_request_at: Mutex::new(None),
         }
     }
 
     pub fn incr_req_count(&self) {
         self.request_count.incr();
+        *self
+            .last_request_at
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(Instant::now());
+    }
+
+    #[must_use]
+    pub fn last_request_at(&self) -> Option<Instant> {
+        *self
+            .last_request_at
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether a request may be dispatched, which is only limited by the
+    /// circuit breaker health monitor.
+    pub fn try_acquire_permit(&self, monitor: &MonitorConfig) -> bool {
+        match &monitor.health {
+            HealthMonitorConfig::CircuitBreaker {
+                half_open_requests, ..
+            } => self.circuit_breaker.try_acquire(*half_open_requests),
+            HealthMonitorConfig::ErrorRatio { .. }
+            | HealthMonitorConfig::LatencySlo { .. } => true,
+        }
+    }
+
+    /// Whether [`EndpointMetrics::try_acquire_permit`] would currently
+    /// succeed, so that load balancers can skip endpoints that would reject
+    /// the request.
+    #[must_use]
+    pub fn accepts_requests(&self, monitor: &MonitorConfig) -> bool {
+        match &monitor.health {
+            HealthMonitorConfig::CircuitBreaker {
+                half_open_requests, ..
+            } => self.circuit_breaker.accepts(*half_open_requests),
+            HealthMonitorConfig::ErrorRatio { .. }
+            | HealthMonitorConfig::LatencySlo { .. } => true,
+        }
+    }
+
+    /// The state of the circuit breaker, which only changes with the
+    /// circuit breaker health monitor.
+    #[must_use]
+    pub fn circuit(&self) -> Circuit {
+        self.circuit_breaker.circuit()
     }
 
     pub fn incr_remote_internal_error_count(&self) {
         self.remote_internal_error_count.incr();
+        self.circuit_breaker.on_failure();
+    }
+
+    /// Records a response that wasn't a remote internal error.
+    pub fn record_success(&self) {
+        self.circuit_breaker.on_success();
+    }
+
+    pub fn record_latency(&self, latency: Duration) {
+        self.latency.record(latency);
+    }
+
+    /// Whether these metrics are healthy according to the health monitor.
+    pub fn is_healthy(&self, monitor: &MonitorConfig) -> bool {
+        match &monitor.health {
+            HealthMonitorConfig::ErrorRatio { .. } => {
+                let requests = self.request_count.total();
+                match monitor.grace_period() {
+                    GracePeriod::Requests { min_requests } => {
+                        if requests == 0 || requests < *min_requests {
+                            return true;
+                        }
+                    }
+                }
+                let errors = self.remote_internal_error_count.total();
+                let error_ratio = f64::from(errors) / f64::from(requests);
+                error_ratio <= monitor.error_threshold()
+            }
+            HealthMonitorConfig::CircuitBreaker {
+                failure_threshold,
+                open_duration,
+                half_open_requests,
+                ..
+            } => self.circuit_breaker.check(&CircuitBreakerParams {
+                failure_threshold: *failure_threshold,
+                open_duration: *open_duration,
+                half_open_requests: *half_open_requests,
+            }),
+            HealthMonitorConfig::LatencySlo {
+                percentile,
+                slo,
+                window,
+                sustained_for,
+                ..
+            } => !self.is_slo_breached(
+                percentile.to_f64().unwrap_or(1.0),
+                *slo,
+                *window,
+                *sustained_for,
+            ),
+        }
+    }
+
+    /// Whether the latency percentile over the window has been above the SLO
+    /// for at least `sustained_for`.
+    pub fn is_slo_breached(
+        &self,
+        percentile: f64,
+        slo: Duration,
+        window: Duration,
+        sustained_for: Duration,
+    ) -> bool {
+        let mut breached_since = self
+            .slo_breached_since
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let latency = self.latency.percentile_within(percentile, Some(window));
+        if latency.is_none_or(|latency| latency <= slo) {
+            *breached_since = None;
+            return false;
+        }
+        let since = breached_since.get_or_insert_with(Instant::now);
+        since.elapsed() >= sustained_for
     }
 
     pub fn incr_for_stream_error(
@@ -104,3 +358,101 @@ impl EndpointMetrics {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_health_is_tracked_per_provider_and_model() {
+        let registry = EndpointMetricsRegistry::default();
+        let model = ModelName::borrowed("claude-3-5-haiku");
+        let bedrock =
+            registry.model_health_metrics(&InferenceProvider::Bedrock, &model);
+        bedrock.incr_req_count();
+        bedrock.incr_remote_internal_error_count();
+
+        let same =
+            registry.model_health_metrics(&InferenceProvider::Bedrock, &model);
+        assert!(Arc::ptr_eq(&bedrock, &same));
+        let anthropic = registry
+            .model_health_metrics(&InferenceProvider::Anthropic, &model);
+        assert_eq!(anthropic.remote_internal_error_count.total(), 0);
+
+        let monitor = MonitorConfig {
+            health: HealthMonitorConfig::CircuitBreaker {
+                failure_threshold: 1,
+                open_duration: Duration::from_secs(60),
+                half_open_requests: 1,
+                interval: Duration::from_secs(1),
+            },
+            ..Default::default()
+        };
+        assert!(!bedrock.is_healthy(&monitor));
+        assert!(anthropic.is_healthy(&monitor));
+    }
+
+    #[test]
+    fn provider_is_unhealthy_once_no_used_model_is_healthy() {
+        let registry = EndpointMetricsRegistry::default();
+        let monitor = MonitorConfig {
+            health: HealthMonitorConfig::CircuitBreaker {
+                failure_threshold: 1,
+                open_duration: Duration::from_secs(60),
+                half_open_requests: 1,
+                interval: Duration::from_secs(1),
+            },
+            ..Default::default()
+        };
+        let provider = InferenceProvider::Bedrock;
+        assert!(registry.is_provider_healthy_for_models(&provider, &monitor));
+
+        let haiku = registry
+            .model_health_metrics(&provider, &ModelName::borrowed("haiku"));
+        haiku.incr_req_count();
+        haiku.incr_remote_internal_error_count();
+        // a model without traffic doesn't keep the provider healthy
+        let _sonnet = registry
+            .model_health_metrics(&provider, &ModelName::borrowed("sonnet"));
+        assert!(!registry.is_provider_healthy_for_models(&provider, &monitor));
+
+        let opus = registry
+            .model_health_metrics(&provider, &ModelName::borrowed("opus"));
+        opus.incr_req_count();
+        opus.record_success();
+        assert!(registry.is_provider_healthy_for_models(&provider, &monitor));
+        assert!(registry.is_provider_healthy_for_models(
+            &InferenceProvider::Anthropic,
+            &monitor
+        ));
+    }
+
+    #[test]
+    fn slo_breach_must_be_sustained() {
+        let metrics = EndpointMetrics::default();
+        let slo = Duration::from_millis(100);
+        let window = Duration::from_secs(60);
+        let sustained_for = Duration::from_millis(50);
+        let record = |millis, count| {
+            for _ in 0..count {
+                metrics.record_latency(Duration::from_millis(millis));
+            }
+        };
+
+        record(500, 20);
+        assert!(!metrics.is_slo_breached(0.5, slo, window, sustained_for));
+        // recovering before the breach is sustained resets it
+        record(10, 40);
+        assert!(!metrics.is_slo_breached(0.5, slo, window, sustained_for));
+        std::thread::sleep(sustained_for);
+        assert!(!metrics.is_slo_breached(0.5, slo, window, sustained_for));
+
+        record(500, 100);
+        assert!(!metrics.is_slo_breached(0.5, slo, window, sustained_for));
+        std::thread::sleep(sustained_for);
+        assert!(metrics.is_slo_breached(0.5, slo, window, sustained_for));
+
+        record(10, 500);
+        assert!(!metrics.is_slo_breached(0.5, slo, window, sustained_for));
+    }
+}
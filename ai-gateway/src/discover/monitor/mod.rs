@@ -1,3 +1,5 @@
+pub mod circuit_breaker;
 pub mod health;
 pub mod metrics;
+pub mod probe;
 pub mod rate_limit;
@@ -0,0 +1,441 @@
+//! Actively probe providers, so that outages and recoveries are noticed even
+//! when a router has no live traffic.
+use std::{
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use bytes::Bytes;
+use futures::future::{self, BoxFuture};
+use meltdown::Token;
+use opentelemetry::KeyValue;
+use rustc_hash::FxHashMap as HashMap;
+use tokio::time::{self, Instant};
+use tracing::{debug, error, trace};
+use url::Url;
+
+use crate::{
+    app_state::AppState,
+    config::{probe::ProbeConfig, providers::ProvidersConfig},
+    dispatcher::{
+        client::{Client, ProviderClient},
+        service::relative_base_url,
+    },
+    error::{
+        api::ApiError,
+        init::InitError,
+        internal::InternalError,
+        runtime::{self, RuntimeError},
+    },
+    types::provider::{InferenceProvider, Region},
+    utils::host_header,
+};
+
+/// A provider, or one of its regions if it is deployed in several.
+type ProbeTarget = (InferenceProvider, Option<Region>);
+
+/// The results of the most recent probes of each provider.
+///
+/// Kept separately from the [`EndpointMetrics`] of live traffic, so that
+/// probes don't affect the request metrics used by the health monitor.
+///
+/// [`EndpointMetrics`]: crate::discover::monitor::metrics::EndpointMetrics
+#[derive(Debug, Clone, Default)]
+pub struct ProbeResults {
+    targets: Arc<HashMap<ProbeTarget, ProbeState>>,
+}
+
+#[derive(Debug, Default)]
+struct ProbeState {
+    consecutive_failures: AtomicU32,
+    /// When the most recent probe succeeded, if it did.
+    passed_at: Mutex<Option<std::time::Instant>>,
+}
+
+impl ProbeState {
+    fn passed_at(&self) -> Option<std::time::Instant> {
+        *self
+            .passed_at
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl ProbeResults {
+    /// Providers with regions are probed in each of them.
+    #[must_use]
+    pub fn new(
+        config: Option<&ProbeConfig>,
+        providers_config: &ProvidersConfig,
+    ) -> Self {
+        let targets = config
+            .into_iter()
+            .flat_map(|config| config.providers.keys())
+            .flat_map(|provider| probe_targets(provider, providers_config))
+            .map(|target| (target, ProbeState::default()))
+            .collect();
+        Self {
+            targets: Arc::new(targets),
+        }
+    }
+
+    fn record(
+        &self,
+        provider: &InferenceProvider,
+        region: Option<&Region>,
+        success: bool,
+    ) {
+        let Some(state) =
+            self.targets.get(&(provider.clone(), region.cloned()))
+        else {
+            return;
+        };
+        let mut passed_at = state
+            .passed_at
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if success {
+            state.consecutive_failures.store(0, Ordering::Relaxed);
+            *passed_at = Some(std::time::Instant::now());
+        } else {
+            state.consecutive_failures.fetch_add(1, Ordering::Relaxed);
+            *passed_at = None;
+        }
+    }
+
+    fn states(
+        &self,
+        provider: &InferenceProvider,
+    ) -> impl Iterator<Item = &ProbeState> {
+        self.targets
+            .iter()
+            .filter(move |((target, _), _)| target == provider)
+            .map(|(_, state)| state)
+    }
+
+    /// Providers that aren't probed are always healthy, and providers probed
+    /// in several regions are healthy while any of them is.
+    #[must_use]
+    pub fn is_healthy(
+        &self,
+        provider: &InferenceProvider,
+        failure_threshold: u32,
+    ) -> bool {
+        let mut probed = false;
+        for state in self.states(provider) {
+            if state.consecutive_failures.load(Ordering::Relaxed)
+                < failure_threshold
+            {
+                return true;
+            }
+            probed = true;
+        }
+        !probed
+    }
+
+    /// Whether the most recent probe of the provider, or of any of its
+    /// regions, succeeded after `since`.
+    #[must_use]
+    pub fn passed_since(
+        &self,
+        provider: &InferenceProvider,
+        since: Option<std::time::Instant>,
+    ) -> bool {
+        self.states(provider).any(|state| {
+            state
+                .passed_at()
+                .is_some_and(|passed_at| since.is_none_or(|at| passed_at > at))
+        })
+    }
+}
+
+/// Requests to providers with regions are only sent to their regions, so
+/// those are probed instead of the provider's base url.
+fn probe_targets(
+    provider: &InferenceProvider,
+    providers_config: &ProvidersConfig,
+) -> Vec<ProbeTarget> {
+    let regions = providers_config
+        .get(provider)
+        .map(|config| config.regions.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    if regions.is_empty() {
+        return vec![(provider.clone(), None)];
+    }
+    regions
+        .into_iter()
+        .map(|region| (provider.clone(), Some(region)))
+        .collect()
+}
+
+#[derive(Debug)]
+struct Probe {
+    provider: InferenceProvider,
+    region: Option<Region>,
+    client: Client,
+    url: Url,
+    body: Option<Bytes>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Prober {
+    app_state: AppState,
+    probes: Arc<[Probe]>,
+}
+
+impl Prober {
+    /// Returns `None` if probes aren't configured.
+    pub fn new(app_state: AppState) -> Result<Option<Self>, InitError> {
+        let config = app_state.config();
+        let Some(probe_config) = &config.discover.monitor.probe else {
+            return Ok(None);
+        };
+        let mut probes = Vec::with_capacity(probe_config.providers.len());
+        for (provider, request) in &probe_config.providers {
+            let provider_config =
+                config.providers.get(provider).ok_or_else(|| {
+                    InitError::InvalidProbe(
+                        provider.clone(),
+                        "provider is not configured".to_string(),
+                    )
+                })?;
+            let body = request
+                .body
+                .as_ref()
+                .map(|body| Bytes::from(body.to_string()));
+            let provider_key = if provider_config.regions.is_empty() {
+                None
+            } else {
+                app_state.get_provider_api_key_for_direct_proxy(provider)?
+            };
+            for (_, region) in probe_targets(provider, &config.providers) {
+                // regions are probed with the same url and key as requests
+                // dispatched to them
+                let (base_url, client) = match &region {
+                    Some(region) => (
+                        provider_config.regions[region].base_url.clone(),
+                        Client::new_for_region(
+                            &app_state,
+                            provider.clone(),
+                            region,
+                            provider_key.as_ref(),
+                        )?,
+                    ),
+                    None => (
+                        provider_config.base_url.clone(),
+                        Client::new_for_direct_proxy(
+                            &app_state,
+                            provider.clone(),
+                        )?,
+                    ),
+                };
+                let base_url = relative_base_url(provider, base_url);
+                let mut url = base_url.join(&request.path).map_err(|e| {
+                    InitError::InvalidProbe(provider.clone(), e.to_string())
+                })?;
+                client.set_query_params(&mut url);
+                probes.push(Probe {
+                    provider: provider.clone(),
+                    region,
+                    client,
+                    url,
+                    body: body.clone(),
+                });
+            }
+        }
+        Ok(Some(Self {
+            app_state: app_state.clone(),
+            probes: probes.into(),
+        }))
+    }
+
+    pub async fn run_forever(self) -> Result<(), runtime::RuntimeError> {
+        let Some(config) =
+            self.app_state.config().discover.monitor.probe.clone()
+        else {
+            return Ok(());
+        };
+        tracing::info!(probes = self.probes.len(), "starting provider probes");
+        let mut interval = time::interval(config.interval);
+        loop {
+            interval.tick().await;
+            future::join_all(
+                self.probes
+                    .iter()
+                    .map(|probe| self.probe(probe, config.timeout)),
+            )
+            .await;
+        }
+    }
+
+    async fn probe(&self, probe: &Probe, timeout: Duration) {
+        let start = Instant::now();
+        let success = match self.send(probe, timeout).await {
+            Ok(status) if status.is_success() => true,
+            Ok(status) => {
+                debug!(
+                    provider = %probe.provider,
+                    region = ?probe.region,
+                    status = %status,
+                    "probe failed"
+                );
+                false
+            }
+            Err(e) => {
+                debug!(
+                    provider = %probe.provider,
+                    region = ?probe.region,
+                    error = %e,
+                    "probe failed"
+                );
+                false
+            }
+        };
+        trace!(
+            provider = %probe.provider,
+            region = ?probe.region,
+            success,
+            "probed provider"
+        );
+        self.app_state.0.probe_results.record(
+            &probe.provider,
+            probe.region.as_ref(),
+            success,
+        );
+
+        let metrics = &self.app_state.0.metrics;
+        let provider = KeyValue::new("provider", probe.provider.to_string());
+        metrics.probe_count.add(
+            1,
+            &[
+                provider.clone(),
+                KeyValue::new(
+                    "status",
+                    if success { "success" } else { "failure" },
+                ),
+            ],
+        );
+        #[allow(clippy::cast_precision_loss)]
+        metrics
+            .probe_duration
+            .record(start.elapsed().as_millis() as f64, &[provider]);
+    }
+
+    async fn send(
+        &self,
+        probe: &Probe,
+        timeout: Duration,
+    ) -> Result<http::StatusCode, ApiError> {
+        let client = probe.client.as_ref();
+        let request_builder = match &probe.body {
+            Some(body) => client
+                .post(probe.url.clone())
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(body.clone()),
+            None => client.get(probe.url.clone()),
+        }
+        .timeout(timeout);
+        let request_builder = if probe.region.is_some() {
+            // clients set the host of the provider's base url by default
+            request_builder.header(http::header::HOST, host_header(&probe.url))
+        } else {
+            request_builder
+        };
+        let request_builder = probe
+            .client
+            .extract_and_sign_aws_headers(
+                request_builder,
+                probe.body.as_ref().unwrap_or(&Bytes::new()),
+            )
+            .inspect_err(|e| {
+                error!(
+                    provider = %probe.provider,
+                    error = %e,
+                    "failed to sign probe"
+                );
+            })?;
+        let request_builder = probe
+            .client
+            .authenticate(request_builder)
+            .await
+            .inspect_err(|e| {
+                error!(
+                    provider = %probe.provider,
+                    error = %e,
+                    "failed to authenticate probe"
+                );
+            })?;
+        let response = request_builder
+            .send()
+            .await
+            .map_err(InternalError::ReqwestError)?;
+        Ok(response.status())
+    }
+}
+
+impl meltdown::Service for Prober {
+    type Future = BoxFuture<'static, Result<(), RuntimeError>>;
+
+    fn run(self, mut token: Token) -> Self::Future {
+        Box::pin(async move {
+            tokio::select! {
+                result = self.run_forever() => {
+                    if let Err(e) = result {
+                        error!(name = "provider-prober-task", error = ?e, "Prober encountered error, shutting down");
+                    } else {
+                        debug!(name = "provider-prober-task", "Prober shut down successfully");
+                    }
+                    token.trigger();
+                }
+                () = &mut token => {
+                    debug!(name = "provider-prober-task", "task shut down successfully");
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::probe::ProbeRequest;
+
+    #[test]
+    fn unhealthy_after_consecutive_failures() {
+        let config = ProbeConfig {
+            providers: [(
+                InferenceProvider::OpenAI,
+                ProbeRequest {
+                    path: "/v1/models".to_string(),
+                    body: None,
+                },
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        let results =
+            ProbeResults::new(Some(&config), &ProvidersConfig::default());
+        let openai = InferenceProvider::OpenAI;
+
+        results.record(&openai, None, false);
+        assert!(results.is_healthy(&openai, 2));
+        results.record(&openai, None, false);
+        assert!(!results.is_healthy(&openai, 2));
+        assert!(!results.passed_since(&openai, None));
+        results.record(&openai, None, true);
+        assert!(results.is_healthy(&openai, 2));
+        assert!(results.passed_since(&openai, None));
+        assert!(
+            !results.passed_since(&openai, Some(std::time::Instant::now()))
+        );
+
+        // providers that aren't probed are always healthy
+        results.record(&InferenceProvider::Anthropic, None, false);
+        assert!(results.is_healthy(&InferenceProvider::Anthropic, 0));
+    }
+}
@@ -29,7 +29,7 @@ use crate::{
     error::{init::InitError, internal::InternalError, runtime::RuntimeError},
     types::{
         rate_limit::{ProviderRestore, RateLimitEvent},
-        router::RouterId,
+        router::BalancerId,
     },
 };
 
@@ -46,7 +46,7 @@ const RATE_LIMIT_MONITOR_INTERVAL: Duration = Duration::from_secs(2);
 const RATE_LIMIT_MONITOR_INTERVAL: Duration = Duration::from_millis(100);
 
 pub type RateLimitMonitorMap =
-    Arc<RwLock<HashMap<RouterId, ProviderRateLimitMonitor>>>;
+    Arc<RwLock<HashMap<BalancerId, ProviderRateLimitMonitor>>>;
 
 #[derive(Debug)]
 pub enum ProviderRateLimitMonitor {
@@ -57,13 +57,13 @@ pub enum ProviderRateLimitMonitor {
 impl ProviderRateLimitMonitor {
     fn weighted(
         tx: Sender<Change<WeightedKey, DispatcherService>>,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         app_state: AppState,
     ) -> Self {
         Self::Weighted(ProviderMonitorInner::new(
             tx,
-            router_id,
+            balancer_id,
             router_config,
             app_state,
         ))
@@ -71,13 +71,13 @@ impl ProviderRateLimitMonitor {
 
     fn p2c(
         tx: Sender<Change<Key, DispatcherService>>,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         app_state: AppState,
     ) -> Self {
         Self::P2C(ProviderMonitorInner::new(
             tx,
-            router_id,
+            balancer_id,
             router_config,
             app_state,
         ))
@@ -89,7 +89,7 @@ impl ProviderRateLimitMonitor {
 #[derive(Debug)]
 pub struct ProviderMonitorInner<K> {
     tx: Sender<Change<K, DispatcherService>>,
-    router_id: RouterId,
+    balancer_id: BalancerId,
     router_config: Arc<RouterConfig>,
     app_state: AppState,
 }
@@ -97,13 +97,13 @@ pub struct ProviderMonitorInner<K> {
 impl<K> ProviderMonitorInner<K> {
     fn new(
         tx: Sender<Change<K, DispatcherService>>,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         app_state: AppState,
     ) -> Self {
         Self {
             tx,
-            router_id,
+            balancer_id,
             router_config,
             app_state,
         }
@@ -111,7 +111,7 @@ impl<K> ProviderMonitorInner<K> {
 }
 
 impl ProviderMonitorInner<Key> {
-    fn create_key_for_endpoint(api_endpoint: ApiEndpoint) -> Key {
+    fn create_key_for_endpoint(api_endpoint: &ApiEndpoint) -> Key {
         let provider = api_endpoint.provider();
         let endpoint_type = api_endpoint.endpoint_type();
         Key::new(provider, endpoint_type)
@@ -121,7 +121,7 @@ impl ProviderMonitorInner<Key> {
         self,
         mut rx: Receiver<RateLimitEvent>,
     ) -> Result<(), RuntimeError> {
-        info!(router_id = ?self.router_id, "starting rate limit monitor for latency strategy LB");
+        info!(balancer_id = ?self.balancer_id, "starting rate limit monitor for latency strategy LB");
 
         let mut rate_limited_providers: HashSet<Key> = HashSet::default();
         let mut pending_restores: FuturesUnordered<ProviderRestore<Key>> =
@@ -131,7 +131,7 @@ impl ProviderMonitorInner<Key> {
             tokio::select! {
                 // Handle incoming rate limit events
                 Some(event) = rx.recv() => {
-                    let key = Self::create_key_for_endpoint(event.api_endpoint);
+                    let key = Self::create_key_for_endpoint(&event.api_endpoint);
                     if rate_limited_providers.contains(&key) {
                         info!(
                             provider = ?event.api_endpoint.provider(),
@@ -142,7 +142,7 @@ impl ProviderMonitorInner<Key> {
                         debug!(
                             provider = ?event.api_endpoint.provider(),
                             api_endpoint = ?event.api_endpoint,
-                            router_id = ?self.router_id,
+                            balancer_id = ?self.balancer_id,
                             "Removing rate-limited provider from P2C balancer"
                         );
 
@@ -157,7 +157,7 @@ impl ProviderMonitorInner<Key> {
 
                         let restore = ProviderRestore {
                             key: Some(key.clone()),
-                            api_endpoint: event.api_endpoint,
+                            api_endpoint: event.api_endpoint.clone(),
                             timer: tokio::time::sleep(duration),
                         };
                         pending_restores.push(restore);
@@ -180,7 +180,7 @@ impl ProviderMonitorInner<Key> {
 
                     let service = Dispatcher::new(
                         self.app_state.clone(),
-                        &self.router_id,
+                        &self.balancer_id,
                         &self.router_config,
                         api_endpoint.provider(),
                     )
@@ -190,13 +190,13 @@ impl ProviderMonitorInner<Key> {
                                 error = ?e,
                                 provider = ?api_endpoint.provider(),
                                 api_endpoint = ?api_endpoint,
-                                router_id = ?self.router_id,
+                                balancer_id = ?self.balancer_id,
                                 "Failed to create dispatcher for recovered provider"
                             );
                         })?;
 
                     self.tx.send(Change::Insert(key.clone(), service)).await.map_err(|e| {
-                        error!(error = ?e, router_id = ?self.router_id, "Failed to send insert event for recovered provider");
+                        error!(error = ?e, balancer_id = ?self.balancer_id, "Failed to send insert event for recovered provider");
                         RuntimeError::ChannelSendFailed
                     })?;
                     rate_limited_providers.remove(&key);
@@ -215,7 +215,7 @@ impl ProviderMonitorInner<Key> {
 impl ProviderMonitorInner<WeightedKey> {
     fn create_key_for_endpoint(
         &self,
-        api_endpoint: ApiEndpoint,
+        api_endpoint: &ApiEndpoint,
     ) -> Result<WeightedKey, InternalError> {
         let provider = api_endpoint.provider();
         let endpoint_type = api_endpoint.endpoint_type();
@@ -224,7 +224,7 @@ impl ProviderMonitorInner<WeightedKey> {
             self.router_config.load_balance.0.get(&endpoint_type)
         else {
             tracing::error!(
-                router_id = ?self.router_id,
+                balancer_id = ?self.balancer_id,
                 endpoint_type = ?endpoint_type,
                 "No balance config found for endpoint type"
             );
@@ -249,14 +249,18 @@ impl ProviderMonitorInner<WeightedKey> {
                     }
                 }
                 tracing::error!(
-                    router_id = ?self.router_id,
+                    balancer_id = ?self.balancer_id,
                     endpoint_type = ?endpoint_type,
                     api_endpoint = ?api_endpoint,
                     "No target found for API endpoint in balance config"
                 );
                 Err(InternalError::Internal)
             }
-            BalanceConfigInner::Latency { .. } => Err(InternalError::Internal),
+            BalanceConfigInner::Latency { .. }
+            | BalanceConfigInner::Cost { .. }
+            | BalanceConfigInner::Priority { .. } => {
+                Err(InternalError::Internal)
+            }
         }
     }
 
@@ -264,7 +268,7 @@ impl ProviderMonitorInner<WeightedKey> {
         self,
         mut rx: Receiver<RateLimitEvent>,
     ) -> Result<(), RuntimeError> {
-        info!(router_id = ?self.router_id, "starting rate limit monitor for weighted strategy LB");
+        info!(balancer_id = ?self.balancer_id, "starting rate limit monitor for weighted strategy LB");
 
         let mut rate_limited_providers: HashMap<WeightedKey, Instant> =
             HashMap::default();
@@ -276,12 +280,12 @@ impl ProviderMonitorInner<WeightedKey> {
             tokio::select! {
                 // Handle incoming rate limit events
                 Some(event) = rx.recv() => {
-                    let key = self.create_key_for_endpoint(event.api_endpoint)?;
+                    let key = self.create_key_for_endpoint(&event.api_endpoint)?;
                     if let std::collections::hash_map::Entry::Vacant(e) = rate_limited_providers.entry(key.clone()) {
                         debug!(
                             provider = ?event.api_endpoint.provider(),
                             api_endpoint = ?event.api_endpoint,
-                            router_id = ?self.router_id,
+                            balancer_id = ?self.balancer_id,
                             "Removing rate-limited provider from Weighted balancer"
                         );
 
@@ -297,7 +301,7 @@ impl ProviderMonitorInner<WeightedKey> {
                             provider = ?event.api_endpoint.provider(),
                             endpoint_type = ?event.api_endpoint.endpoint_type(),
                             api_endpoint = ?event.api_endpoint,
-                            router_id = ?self.router_id,
+                            balancer_id = ?self.balancer_id,
                             duration_secs = duration.as_secs(),
                             "Scheduled provider re-addition"
                         );
@@ -322,13 +326,13 @@ impl ProviderMonitorInner<WeightedKey> {
                         provider = ?api_endpoint.provider(),
                         endpoint = ?api_endpoint.endpoint_type(),
                         api_endpoint = ?api_endpoint,
-                        router_id = ?self.router_id,
+                        balancer_id = ?self.balancer_id,
                         "Re-adding provider to Weighted balancer after rate limit expired"
                     );
 
                     let service = Dispatcher::new(
                         self.app_state.clone(),
-                        &self.router_id,
+                        &self.balancer_id,
                         &self.router_config,
                         api_endpoint.provider(),
                     )
@@ -338,14 +342,14 @@ impl ProviderMonitorInner<WeightedKey> {
                             error = ?e,
                             provider = ?api_endpoint.provider(),
                             api_endpoint = ?api_endpoint,
-                            router_id = ?self.router_id,
+                            balancer_id = ?self.balancer_id,
                             "Failed to create dispatcher for recovered provider"
                         );
                     })?;
                     self.tx.send(Change::Insert(key.clone(), service))
                         .await
                         .map_err(|e| {
-                            error!(error = ?e, router_id = ?self.router_id, "Failed to send insert event for recovered provider");
+                            error!(error = ?e, balancer_id = ?self.balancer_id, "Failed to send insert event for recovered provider");
                             RuntimeError::ChannelSendFailed
                         })?;
                     rate_limited_providers.remove(&key);
@@ -401,8 +405,8 @@ impl RateLimitMonitor {
                 _ = interval.tick() => {
                     // Check for new routers
                     let mut monitors = app_state.0.rate_limit_monitors.write().await;
-                    for (router_id, monitor) in monitors.drain() {
-                        let rx = app_state.remove_rate_limit_receiver(&router_id).await?;
+                    for (balancer_id, monitor) in monitors.drain() {
+                        let rx = app_state.remove_rate_limit_receiver(&balancer_id).await?;
                         match monitor {
                             ProviderRateLimitMonitor::Weighted(inner) => {
                                 self.tasks.spawn(inner.monitor(rx));
@@ -444,15 +448,15 @@ impl meltdown::Service for RateLimitMonitor {
 impl AppState {
     pub async fn add_weighted_router_rate_limit_monitor(
         &self,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         tx: Sender<Change<WeightedKey, DispatcherService>>,
     ) {
         self.0.rate_limit_monitors.write().await.insert(
-            router_id.clone(),
+            balancer_id.clone(),
             ProviderRateLimitMonitor::weighted(
                 tx,
-                router_id,
+                balancer_id,
                 router_config,
                 self.clone(),
             ),
@@ -461,15 +465,15 @@ impl AppState {
 
     pub async fn add_p2c_router_rate_limit_monitor(
         &self,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
         tx: Sender<Change<Key, DispatcherService>>,
     ) {
         self.0.rate_limit_monitors.write().await.insert(
-            router_id.clone(),
+            balancer_id.clone(),
             ProviderRateLimitMonitor::p2c(
                 tx,
-                router_id,
+                balancer_id,
                 router_config,
                 self.clone(),
             ),
@@ -478,14 +482,18 @@ impl AppState {
 
     pub async fn remove_rate_limit_receiver(
         &self,
-        router_id: &RouterId,
+        balancer_id: &BalancerId,
     ) -> Result<Receiver<RateLimitEvent>, InitError> {
-        let Some(rx) =
-            self.0.rate_limit_receivers.write().await.remove(router_id)
+        let Some(rx) = self
+            .0
+            .rate_limit_receivers
+            .write()
+            .await
+            .remove(balancer_id)
         else {
-            warn!(router_id = ?router_id, "No rate limit receiver found for router");
+            warn!(balancer_id = ?balancer_id, "No rate limit receiver found for router");
             return Err(InitError::RateLimitChannelsNotInitialized(
-                router_id.clone(),
+                balancer_id.router_id.clone(),
             ));
         };
         Ok(rx)
@@ -20,7 +20,7 @@ use crate::{
     discover::{provider::Key, weighted::WeightedKey},
     dispatcher::{Dispatcher, DispatcherService},
     error::init::InitError,
-    types::router::RouterId,
+    types::router::BalancerId,
 };
 
 pin_project! {
@@ -52,27 +52,28 @@ pin_project! {
 impl ConfigDiscovery<Key> {
     pub async fn new(
         app_state: &AppState,
-        router_id: &RouterId,
+        balancer_id: &BalancerId,
         router_config: &Arc<RouterConfig>,
         rx: Receiver<Change<Key, DispatcherService>>,
     ) -> Result<Self, InitError> {
+        let endpoint_type = balancer_id.endpoint_type;
         let events = ReceiverStream::new(rx);
         let mut service_map: HashMap<Key, DispatcherService> = HashMap::new();
-        for (endpoint_type, balance_config) in
-            router_config.load_balance.as_ref()
-        {
-            let providers = balance_config.providers();
-            for provider in providers {
-                let key = Key::new(provider, *endpoint_type);
-                let dispatcher = Dispatcher::new(
-                    app_state.clone(),
-                    router_id,
-                    router_config,
-                    key.provider.clone(),
-                )
-                .await?;
-                service_map.insert(key, dispatcher);
-            }
+        let balance_config = router_config
+            .load_balance
+            .as_ref()
+            .get(&endpoint_type)
+            .ok_or(InitError::BalanceConfigNotFound(endpoint_type))?;
+        for provider in balance_config.providers() {
+            let key = Key::new(provider, endpoint_type);
+            let dispatcher = Dispatcher::new(
+                app_state.clone(),
+                balancer_id,
+                router_config,
+                key.provider.clone(),
+            )
+            .await?;
+            service_map.insert(key, dispatcher);
         }
 
         tracing::debug!("Created config provider discovery");
@@ -86,42 +87,46 @@ impl ConfigDiscovery<Key> {
 impl ConfigDiscovery<WeightedKey> {
     pub async fn new_weighted(
         app_state: &AppState,
-        router_id: &RouterId,
+        balancer_id: &BalancerId,
         router_config: &Arc<RouterConfig>,
         rx: Receiver<Change<WeightedKey, DispatcherService>>,
     ) -> Result<Self, InitError> {
+        let endpoint_type = balancer_id.endpoint_type;
         let mut service_map = HashMap::new();
-        for (endpoint_type, balance_config) in
-            router_config.load_balance.as_ref()
-        {
-            let weighted_balance_targets = match balance_config {
-                BalanceConfigInner::Weighted { providers } => providers,
-                BalanceConfigInner::Latency { .. } => {
-                    return Err(InitError::InvalidWeightedBalancer(
-                        "P2C balancer not supported for weighted discovery"
-                            .to_string(),
-                    ));
-                }
-            };
-            for target in weighted_balance_targets {
-                let weight =
-                    Weight::from(target.weight.to_f64().ok_or_else(|| {
-                        InitError::InvalidWeight(target.provider.clone())
-                    })?);
-                let key = WeightedKey::new(
-                    target.provider.clone(),
-                    *endpoint_type,
-                    weight,
-                );
-                let dispatcher = Dispatcher::new(
-                    app_state.clone(),
-                    router_id,
-                    router_config,
-                    key.provider.clone(),
-                )
-                .await?;
-                service_map.insert(key, dispatcher);
+        let balance_config = router_config
+            .load_balance
+            .as_ref()
+            .get(&endpoint_type)
+            .ok_or(InitError::BalanceConfigNotFound(endpoint_type))?;
+        let weighted_balance_targets = match balance_config {
+            BalanceConfigInner::Weighted { providers } => providers,
+            BalanceConfigInner::Latency { .. }
+            | BalanceConfigInner::Cost { .. }
+            | BalanceConfigInner::Priority { .. } => {
+                return Err(InitError::InvalidWeightedBalancer(
+                    "P2C balancer not supported for weighted discovery"
+                        .to_string(),
+                ));
             }
+        };
+        for target in weighted_balance_targets {
+            let weight =
+                Weight::from(target.weight.to_f64().ok_or_else(|| {
+                    InitError::InvalidWeight(target.provider.clone())
+                })?);
+            let key = WeightedKey::new(
+                target.provider.clone(),
+                endpoint_type,
+                weight,
+            );
+            let dispatcher = Dispatcher::new(
+                app_state.clone(),
+                balancer_id,
+                router_config,
+                key.provider.clone(),
+            )
+            .await?;
+            service_map.insert(key, dispatcher);
         }
         let events = ReceiverStream::new(rx);
 
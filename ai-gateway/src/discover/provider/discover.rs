@@ -6,7 +6,7 @@ use std::{
     task::{Context, Poll},
 };
 
-use futures::Stream;
+use futures::{Stream, ready};
 use pin_project_lite::pin_project;
 use tokio::sync::mpsc::Receiver;
 use tower::discover::Change;
@@ -15,12 +15,14 @@ use crate::{
     app_state::AppState,
     config::router::RouterConfig,
     discover::{
-        provider::{Key, config::ConfigDiscovery},
+        provider::{
+            DiscoveredProviders, HasProvider, Key, config::ConfigDiscovery,
+        },
         weighted::WeightedKey,
     },
     dispatcher::DispatcherService,
     error::init::InitError,
-    types::{discover::DiscoverMode, router::RouterId},
+    types::{discover::DiscoverMode, router::BalancerId},
 };
 
 pin_project! {
@@ -30,14 +32,25 @@ pin_project! {
     pub enum Discovery<K> {
         Config {
             #[pin] inner: ConfigDiscovery<K>,
+            discovered: Option<DiscoveredProviders>,
         },
     }
 }
 
+impl<K> Discovery<K> {
+    /// Mirrors discovered providers into `discovered`, if given.
+    #[must_use]
+    pub fn observed_by(self, discovered: Option<DiscoveredProviders>) -> Self {
+        match self {
+            Self::Config { inner, .. } => Self::Config { inner, discovered },
+        }
+    }
+}
+
 impl Discovery<Key> {
     pub async fn new(
         app_state: &AppState,
-        router_id: &RouterId,
+        balancer_id: &BalancerId,
         router_config: &Arc<RouterConfig>,
         rx: Receiver<Change<Key, DispatcherService>>,
     ) -> Result<Self, InitError> {
@@ -45,11 +58,12 @@ impl Discovery<Key> {
             DiscoverMode::Config => Ok(Self::Config {
                 inner: ConfigDiscovery::new(
                     app_state,
-                    router_id,
+                    balancer_id,
                     router_config,
                     rx,
                 )
                 .await?,
+                discovered: None,
             }),
         }
     }
@@ -58,7 +72,7 @@ impl Discovery<Key> {
 impl Discovery<WeightedKey> {
     pub async fn new_weighted(
         app_state: &AppState,
-        router_id: &RouterId,
+        balancer_id: &BalancerId,
         router_config: &Arc<RouterConfig>,
         rx: Receiver<Change<WeightedKey, DispatcherService>>,
     ) -> Result<Self, InitError> {
@@ -66,11 +80,12 @@ impl Discovery<WeightedKey> {
             DiscoverMode::Config => Ok(Self::Config {
                 inner: ConfigDiscovery::new_weighted(
                     app_state,
-                    router_id,
+                    balancer_id,
                     router_config,
                     rx,
                 )
                 .await?,
+                discovered: None,
             }),
         }
     }
@@ -78,7 +93,7 @@ impl Discovery<WeightedKey> {
 
 impl<K> Stream for Discovery<K>
 where
-    K: Hash + Eq + Clone + std::fmt::Debug,
+    K: Hash + Eq + Clone + std::fmt::Debug + HasProvider,
 {
     type Item = Result<Change<K, DispatcherService>, Infallible>;
 
@@ -87,8 +102,14 @@ where
         ctx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
         match self.project() {
-            DiscoveryProj::Config { inner } => {
-                inner.poll_next(ctx).map(|p| p.map(Result::Ok))
+            DiscoveryProj::Config { inner, discovered } => {
+                let change = ready!(inner.poll_next(ctx));
+                if let (Some(discovered), Some(change)) =
+                    (discovered.as_ref(), change.as_ref())
+                {
+                    discovered.apply(change);
+                }
+                Poll::Ready(change.map(Result::Ok))
             }
         }
     }
@@ -14,30 +14,33 @@ use tower::{
 use crate::{
     app_state::AppState,
     config::router::RouterConfig,
-    discover::provider::{Key, discover::Discovery},
+    discover::provider::{DiscoveredProviders, Key, discover::Discovery},
     dispatcher::DispatcherService,
     error::init::InitError,
-    types::router::RouterId,
+    types::router::BalancerId,
 };
 
 #[derive(Debug)]
 pub struct DiscoverFactory {
     pub(crate) app_state: AppState,
-    pub(crate) router_id: RouterId,
+    pub(crate) balancer_id: BalancerId,
     pub(crate) router_config: Arc<RouterConfig>,
+    pub(crate) discovered: Option<DiscoveredProviders>,
 }
 
 impl DiscoverFactory {
     #[must_use]
     pub fn new(
         app_state: AppState,
-        router_id: RouterId,
+        balancer_id: BalancerId,
         router_config: Arc<RouterConfig>,
+        discovered: Option<DiscoveredProviders>,
     ) -> Self {
         Self {
             app_state,
-            router_id,
+            balancer_id,
             router_config,
+            discovered,
         }
     }
 }
@@ -59,12 +62,14 @@ impl Service<Receiver<Change<Key, DispatcherService>>> for DiscoverFactory {
         rx: Receiver<Change<Key, DispatcherService>>,
     ) -> Self::Future {
         let app_state = self.app_state.clone();
-        let router_id = self.router_id.clone();
+        let balancer_id = self.balancer_id.clone();
         let router_config = self.router_config.clone();
+        let discovered = self.discovered.clone();
         Box::pin(async move {
             let discovery =
-                Discovery::new(&app_state, &router_id, &router_config, rx)
-                    .await?;
+                Discovery::new(&app_state, &balancer_id, &router_config, rx)
+                    .await?
+                    .observed_by(discovered);
             let discovery = PeakEwmaDiscover::new(
                 discovery,
                 app_state.0.config.discover.default_rtt,
@@ -2,7 +2,21 @@ pub mod config;
 pub mod discover;
 pub mod factory;
 
-use crate::{endpoints::EndpointType, types::provider::InferenceProvider};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use compact_str::CompactString;
+use indexmap::IndexMap;
+use tower::discover::Change;
+
+use crate::{
+    config::router::RouterConfig, dispatcher::DispatcherService,
+    endpoints::EndpointType, types::provider::InferenceProvider,
+};
+
+/// Discovery keys that identify a provider.
+pub trait HasProvider {
+    fn provider(&self) -> &InferenceProvider;
+}
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Key {
@@ -22,3 +36,79 @@ impl Key {
         }
     }
 }
+
+impl HasProvider for Key {
+    fn provider(&self) -> &InferenceProvider {
+        &self.provider
+    }
+}
+
+/// The providers currently discovered by a balancer, shared with anything
+/// that needs to send requests to a specific one of them.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveredProviders(
+    Arc<Mutex<IndexMap<InferenceProvider, DispatcherService>>>,
+);
+
+impl DiscoveredProviders {
+    pub(crate) fn apply<K: HasProvider>(
+        &self,
+        change: &Change<K, DispatcherService>,
+    ) {
+        let mut services =
+            self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match change {
+            Change::Insert(key, service) => {
+                services.insert(key.provider().clone(), service.clone());
+            }
+            Change::Remove(key) => {
+                services.shift_remove(key.provider());
+            }
+        }
+    }
+
+    /// Runs `f` with the currently discovered providers.
+    pub fn with<T>(
+        &self,
+        f: impl FnOnce(&IndexMap<InferenceProvider, DispatcherService>) -> T,
+    ) -> T {
+        f(&self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// The providers discovered by each balancer of one of a router's endpoint
+/// types: the endpoint level balancer and the balancer of each model group.
+#[derive(Debug, Clone, Default)]
+pub struct BalancerProviders {
+    endpoint: DiscoveredProviders,
+    models: IndexMap<CompactString, DiscoveredProviders>,
+}
+
+impl BalancerProviders {
+    #[must_use]
+    pub fn new(router_config: &RouterConfig) -> Self {
+        let models = router_config
+            .model_load_balance
+            .as_ref()
+            .keys()
+            .map(|group| (group.clone(), DiscoveredProviders::default()))
+            .collect();
+        Self {
+            endpoint: DiscoveredProviders::default(),
+            models,
+        }
+    }
+
+    /// Where the balancer of the model `group`, or the endpoint level
+    /// balancer if `None`, mirrors its discovered providers.
+    #[must_use]
+    pub fn get(
+        &self,
+        group: Option<&CompactString>,
+    ) -> Option<&DiscoveredProviders> {
+        match group {
+            Some(group) => self.models.get(group),
+            None => Some(&self.endpoint),
+        }
+    }
+}
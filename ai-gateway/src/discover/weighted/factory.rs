@@ -33,16 +33,18 @@ impl Service<Receiver<Change<WeightedKey, DispatcherService>>>
         rx: Receiver<Change<WeightedKey, DispatcherService>>,
     ) -> Self::Future {
         let app_state = self.app_state.clone();
-        let router_id = self.router_id.clone();
+        let balancer_id = self.balancer_id.clone();
         let router_config = self.router_config.clone();
+        let discovered = self.discovered.clone();
         Box::pin(async move {
             let discovery = Discovery::new_weighted(
                 &app_state,
-                &router_id,
+                &balancer_id,
                 &router_config,
                 rx,
             )
-            .await?;
+            .await?
+            .observed_by(discovered);
             let discovery = WeightedDiscover::new(discovery);
             Ok(discovery)
         })
@@ -1,6 +1,9 @@
 use weighted_balance::weight::{HasWeight, Weight};
 
-use crate::{endpoints::EndpointType, types::provider::InferenceProvider};
+use crate::{
+    discover::provider::HasProvider, endpoints::EndpointType,
+    types::provider::InferenceProvider,
+};
 
 pub mod factory;
 
@@ -26,6 +29,12 @@ impl WeightedKey {
     }
 }
 
+impl HasProvider for WeightedKey {
+    fn provider(&self) -> &InferenceProvider {
+        &self.provider
+    }
+}
+
 impl HasWeight for WeightedKey {
     fn weight(&self) -> Weight {
         self.weight
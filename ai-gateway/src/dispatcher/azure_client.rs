@@ -0,0 +1,76 @@
+use http::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::ClientBuilder;
+use url::Url;
+
+use crate::{
+    app_state::AppState,
+    config::providers::DEFAULT_AZURE_API_VERSION,
+    error::{init::InitError, provider::ProviderError},
+    types::provider::{InferenceProvider, ProviderKey},
+    utils::host_header,
+};
+
+const API_VERSION_QUERY_PARAM: &str = "api-version";
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    pub(super) inner: reqwest::Client,
+    pub(super) api_version: String,
+}
+
+impl Client {
+    pub fn new(
+        app_state: &AppState,
+        client_builder: ClientBuilder,
+        provider_key: Option<&ProviderKey>,
+    ) -> Result<Self, InitError> {
+        let provider_config = app_state
+            .0
+            .config
+            .providers
+            .get(&InferenceProvider::Azure)
+            .ok_or(ProviderError::ProviderNotConfigured(
+                InferenceProvider::Azure,
+            ))?;
+
+        let base_url = provider_config.base_url.clone();
+        let api_version = provider_config
+            .version
+            .clone()
+            .unwrap_or_else(|| DEFAULT_AZURE_API_VERSION.to_string());
+
+        let mut default_headers = HeaderMap::new();
+        if let Some(ProviderKey::Secret(key)) = provider_key {
+            default_headers.insert(
+                HeaderName::from_static("api-key"),
+                HeaderValue::from_str(key.expose()).unwrap(),
+            );
+        }
+        default_headers.insert(http::header::HOST, host_header(&base_url));
+        default_headers.insert(
+            http::header::CONTENT_TYPE,
+            HeaderValue::from_str(mime::APPLICATION_JSON.essence_str())
+                .unwrap(),
+        );
+
+        let inner = client_builder
+            .default_headers(default_headers)
+            .build()
+            .map_err(InitError::CreateReqwestClient)?;
+        Ok(Self { inner, api_version })
+    }
+
+    /// Azure requires the API version as a query parameter, unless the
+    /// client already provided one.
+    pub(super) fn set_api_version(&self, target_url: &mut Url) {
+        if target_url
+            .query_pairs()
+            .any(|(key, _)| key == API_VERSION_QUERY_PARAM)
+        {
+            return;
+        }
+        target_url
+            .query_pairs_mut()
+            .append_pair(API_VERSION_QUERY_PARAM, &self.api_version);
+    }
+}
@@ -7,14 +7,19 @@ use tracing::{Instrument, info_span};
 
 use crate::{
     app_state::AppState,
+    config::providers::GlobalProviderConfig,
     discover::monitor::metrics::EndpointMetricsRegistry,
     dispatcher::{
         SSEStream, anthropic_client::Client as AnthropicClient,
+        azure_client::Client as AzureClient,
         bedrock_client::Client as BedrockClient,
+        cohere_client::Client as CohereClient,
         google_gemini_client::Client as GoogleGeminiClient,
+        mistral_client::Client as MistralClient,
         ollama_client::Client as OllamaClient,
         openai_client::Client as OpenAIClient,
         service::record_stream_err_metrics,
+        vertex_client::Client as VertexClient,
     },
     endpoints::ApiEndpoint,
     error::{
@@ -22,7 +27,7 @@ use crate::{
         stream::StreamError,
     },
     types::{
-        provider::{InferenceProvider, ProviderKey},
+        provider::{InferenceProvider, ProviderKey, Region},
         router::RouterId,
     },
 };
@@ -56,6 +61,10 @@ pub enum Client {
     GoogleGemini(GoogleGeminiClient),
     Ollama(OllamaClient),
     Bedrock(BedrockClient),
+    Azure(AzureClient),
+    Mistral(MistralClient),
+    Cohere(CohereClient),
+    GoogleVertex(VertexClient),
 }
 
 impl Client {
@@ -91,6 +100,7 @@ impl Client {
         match inference_provider {
             InferenceProvider::OpenAI => Ok(Self::OpenAI(OpenAIClient::new(
                 app_state,
+                &inference_provider,
                 base_client,
                 api_key,
             )?)),
@@ -106,13 +116,65 @@ impl Client {
             InferenceProvider::Ollama => {
                 Ok(Self::Ollama(OllamaClient::new(app_state, base_client)?))
             }
-            // will be implemented in future PR
+            InferenceProvider::Azure => Ok(Self::Azure(AzureClient::new(
+                app_state,
+                base_client,
+                api_key,
+            )?)),
+            InferenceProvider::Mistral => Ok(Self::Mistral(
+                MistralClient::new(app_state, base_client, api_key)?,
+            )),
+            InferenceProvider::Cohere => Ok(Self::Cohere(CohereClient::new(
+                app_state,
+                base_client,
+                api_key,
+            )?)),
+            InferenceProvider::GoogleVertex => Ok(Self::GoogleVertex(
+                VertexClient::new(app_state, base_client, api_key)?,
+            )),
             InferenceProvider::Named(_) => {
-                Err(InitError::ProviderNotSupported(inference_provider))
+                let openai_compatible = app_state
+                    .0
+                    .config
+                    .providers
+                    .get(&inference_provider)
+                    .is_some_and(GlobalProviderConfig::is_openai_compatible);
+                if !openai_compatible {
+                    return Err(InitError::ProviderNotSupported(
+                        inference_provider,
+                    ));
+                }
+                Ok(Self::OpenAI(OpenAIClient::new(
+                    app_state,
+                    &inference_provider,
+                    base_client,
+                    api_key,
+                )?))
             }
         }
     }
 
+    /// Adds any query parameters the provider requires on every request.
+    pub(crate) fn set_query_params(&self, target_url: &mut url::Url) {
+        if let Client::Azure(inner) = self {
+            inner.set_api_version(target_url);
+        }
+    }
+
+    /// Adds credentials that can't be set as default headers, such as
+    /// short-lived access tokens.
+    pub(crate) async fn authenticate(
+        &self,
+        request_builder: RequestBuilder,
+    ) -> Result<RequestBuilder, ApiError> {
+        match self {
+            Client::GoogleVertex(inner) => {
+                inner.authenticate(request_builder).await
+            }
+            _ => Ok(request_builder),
+        }
+    }
+
     pub(crate) async fn new_for_router(
         app_state: &AppState,
         inference_provider: InferenceProvider,
@@ -128,6 +190,23 @@ impl Client {
         Self::new_inner(app_state, inference_provider, api_key.as_ref())
     }
 
+    /// A client for one of the provider's regions, using the region's own
+    /// key if one is set and the provider's key otherwise.
+    pub(crate) fn new_for_region(
+        app_state: &AppState,
+        inference_provider: InferenceProvider,
+        region: &Region,
+        provider_key: Option<&ProviderKey>,
+    ) -> Result<Self, InitError> {
+        let region_key =
+            ProviderKey::from_env_for_region(&inference_provider, region);
+        Self::new_inner(
+            app_state,
+            inference_provider,
+            region_key.as_ref().or(provider_key),
+        )
+    }
+
     pub(crate) fn new_for_direct_proxy(
         app_state: &AppState,
         inference_provider: InferenceProvider,
@@ -165,6 +244,10 @@ impl AsRef<reqwest::Client> for Client {
             Client::GoogleGemini(client) => &client.0,
             Client::Ollama(client) => &client.0,
             Client::Bedrock(client) => &client.inner,
+            Client::Azure(client) => &client.inner,
+            Client::Mistral(client) => &client.0,
+            Client::Cohere(client) => &client.0,
+            Client::GoogleVertex(client) => &client.inner,
         }
     }
 }
@@ -193,7 +276,7 @@ pub(super) async fn sse_stream(
             let stream_error = StreamError::StreamError(Box::new(e));
             record_stream_err_metrics(
                 &stream_error,
-                api_endpoint,
+                api_endpoint.as_ref(),
                 metrics_registry,
             );
             return Err(stream_error);
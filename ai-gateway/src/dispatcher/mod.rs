@@ -1,11 +1,15 @@
 pub mod anthropic_client;
+mod azure_client;
 mod bedrock_client;
 pub mod client;
+mod cohere_client;
 mod extensions;
 pub mod google_gemini_client;
+mod mistral_client;
 pub mod ollama_client;
 pub mod openai_client;
 pub mod service;
+mod vertex_client;
 
 use std::pin::Pin;
 
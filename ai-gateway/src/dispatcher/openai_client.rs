@@ -12,8 +12,11 @@ use crate::{
 pub struct Client(pub(super) reqwest::Client);
 
 impl Client {
+    /// Also used for providers configured by name, since they serve the
+    /// `OpenAI` API.
     pub fn new(
         app_state: &AppState,
+        inference_provider: &InferenceProvider,
         client_builder: ClientBuilder,
         provider_key: Option<&ProviderKey>,
     ) -> Result<Self, InitError> {
@@ -21,10 +24,10 @@ impl Client {
             .0
             .config
             .providers
-            .get(&InferenceProvider::OpenAI)
-            .ok_or(ProviderError::ProviderNotConfigured(
-                InferenceProvider::OpenAI,
-            ))?
+            .get(inference_provider)
+            .ok_or_else(|| {
+                ProviderError::ProviderNotConfigured(inference_provider.clone())
+            })?
             .base_url
             .clone();
 
@@ -17,11 +17,13 @@ use tokio::{
 };
 use tower::{Service, ServiceBuilder};
 use tracing::{Instrument, info_span};
+use url::Url;
 
 use crate::{
     app_state::AppState,
-    config::router::RouterConfig,
-    discover::monitor::metrics::EndpointMetricsRegistry,
+    balancer::region::ProviderRegionBalancer,
+    config::{monitor::HealthScope, router::RouterConfig},
+    discover::monitor::metrics::{EndpointMetrics, EndpointMetricsRegistry},
     dispatcher::{
         client::{Client, ProviderClient},
         extensions::ExtensionsCopier,
@@ -40,20 +42,26 @@ use crate::{
     types::{
         body::BodyReader,
         extensions::{MapperContext, RequestContext},
-        provider::InferenceProvider,
+        model_id::ModelName,
+        multipart::MultipartForm,
+        provider::{InferenceProvider, ProviderKey, Region},
         rate_limit::RateLimitEvent,
         request::Request,
-        router::RouterId,
+        router::{BalancerId, RouterId},
+    },
+    utils::{
+        handle_error::{ErrorHandler, ErrorHandlerLayer},
+        host_header,
     },
-    utils::handle_error::{ErrorHandler, ErrorHandlerLayer},
 };
 
 pub type DispatcherFuture = BoxFuture<
     'static,
     Result<http::Response<crate::types::body::Body>, ApiError>,
 >;
-pub type DispatcherService =
-    AddExtensions<ErrorHandler<crate::middleware::mapper::Service<Dispatcher>>>;
+pub type DispatcherService = AddExtensions<
+    ErrorHandler<crate::middleware::mapper::Service<ProviderRegionBalancer>>,
+>;
 pub type DispatcherServiceWithoutMapper =
     AddExtensions<ErrorHandler<Dispatcher>>;
 
@@ -65,26 +73,35 @@ pub struct Dispatcher {
     provider: InferenceProvider,
     /// Is `Some` for load balanced routers, `None` for direct proxies.
     rate_limit_tx: Option<Sender<RateLimitEvent>>,
+    /// Is `Some` for providers deployed in multiple regions.
+    region: Option<Region>,
 }
 
 impl Dispatcher {
     pub async fn new(
         app_state: AppState,
-        router_id: &RouterId,
+        balancer_id: &BalancerId,
         router_config: &Arc<RouterConfig>,
         provider: InferenceProvider,
     ) -> Result<DispatcherService, InitError> {
+        let router_id = &balancer_id.router_id;
         let client =
             Client::new_for_router(&app_state, provider.clone(), router_id)
                 .await?;
-        let rate_limit_tx = app_state.get_rate_limit_tx(router_id).await?;
+        let rate_limit_tx = app_state.get_rate_limit_tx(balancer_id).await?;
 
         let dispatcher = Self {
             client,
             app_state: app_state.clone(),
             provider: provider.clone(),
             rate_limit_tx: Some(rate_limit_tx),
+            region: None,
         };
+        let provider_key = app_state
+            .get_provider_api_key_for_router(router_id, &provider)
+            .await?;
+        let region_balancer =
+            dispatcher.region_balancer(provider_key.as_ref())?;
         let model_mapper = ModelMapper::new_for_router(
             app_state.clone(),
             router_config.clone(),
@@ -102,7 +119,7 @@ impl Dispatcher {
             .layer(crate::middleware::mapper::Layer::new(converter_registry))
             // other middleware: rate limiting, logging, etc, etc
             // will be added here as well
-            .service(dispatcher))
+            .service(region_balancer))
     }
 
     pub fn new_direct_proxy(
@@ -117,7 +134,12 @@ impl Dispatcher {
             app_state: app_state.clone(),
             provider: provider.clone(),
             rate_limit_tx: None,
+            region: None,
         };
+        let provider_key =
+            app_state.get_provider_api_key_for_direct_proxy(&provider)?;
+        let region_balancer =
+            dispatcher.region_balancer(provider_key.as_ref())?;
         let model_mapper = ModelMapper::new(app_state.clone());
         let converter_registry = EndpointConverterRegistry::new(&model_mapper);
 
@@ -132,7 +154,7 @@ impl Dispatcher {
             .layer(crate::middleware::mapper::Layer::new(converter_registry))
             // other middleware: rate limiting, logging, etc, etc
             // will be added here as well
-            .service(dispatcher))
+            .service(region_balancer))
     }
 
     pub fn new_without_mapper(
@@ -146,6 +168,7 @@ impl Dispatcher {
             app_state: app_state.clone(),
             provider: provider.clone(),
             rate_limit_tx: None,
+            region: None,
         };
 
         let extensions_layer = AddExtensionsLayer::builder()
@@ -160,6 +183,37 @@ impl Dispatcher {
             // will be added here as well
             .service(dispatcher))
     }
+
+    /// Balances across a dispatcher for each of the provider's regions, each
+    /// with its own client, or just this dispatcher if there are none.
+    fn region_balancer(
+        self,
+        provider_key: Option<&ProviderKey>,
+    ) -> Result<ProviderRegionBalancer, InitError> {
+        let config = self.app_state.config();
+        let mut regions = Vec::new();
+        if let Some(provider_config) = config.providers.get(&self.provider) {
+            for region in provider_config.regions.keys() {
+                let client = Client::new_for_region(
+                    &self.app_state,
+                    self.provider.clone(),
+                    region,
+                    provider_key,
+                )?;
+                let dispatcher = Self {
+                    client,
+                    region: Some(region.clone()),
+                    ..self.clone()
+                };
+                regions.push((region.clone(), dispatcher));
+            }
+        }
+        Ok(ProviderRegionBalancer::new(
+            self.app_state.clone(),
+            self,
+            regions,
+        ))
+    }
 }
 
 impl Service<Request> for Dispatcher {
@@ -199,14 +253,33 @@ impl Dispatcher {
             .remove::<Arc<RequestContext>>()
             .ok_or(InternalError::ExtensionNotFound("RequestContext"))?;
         let auth_ctx = req_ctx.auth_context.as_ref();
-        let api_endpoint = req.extensions().get::<ApiEndpoint>().copied();
+        let api_endpoint = req.extensions().get::<ApiEndpoint>().cloned();
         let target_provider = &self.provider;
         let config = self.app_state.config();
+        let model_metrics = mapper_ctx
+            .model
+            .as_ref()
+            .filter(|_| config.discover.monitor.scope == HealthScope::Model)
+            .map(|model| {
+                self.app_state.0.endpoint_metrics.model_health_metrics(
+                    target_provider,
+                    &ModelName::from_model(model),
+                )
+            });
         let provider_config =
             config.providers.get(target_provider).ok_or_else(|| {
                 InternalError::ProviderNotConfigured(target_provider.clone())
             })?;
-        let base_url = provider_config.base_url.clone();
+        let region_config = self
+            .region
+            .as_ref()
+            .and_then(|region| provider_config.regions.get(region));
+        let base_url = relative_base_url(
+            target_provider,
+            region_config
+                .map_or(&provider_config.base_url, |region| &region.base_url)
+                .clone(),
+        );
         {
             let h = req.headers_mut();
             h.remove(http::header::HOST);
@@ -255,9 +328,10 @@ impl Dispatcher {
                 Utc::now()
             });
 
-        let target_url = base_url
+        let mut target_url = base_url
             .join(extracted_path_and_query.as_str())
             .expect("PathAndQuery joined with valid url will always succeed");
+        self.client.set_query_params(&mut target_url);
         // TODO: could change request type of dispatcher to
         // http::Request<reqwest::Body>
         // to avoid collecting the body twice
@@ -268,18 +342,47 @@ impl Dispatcher {
             .map_err(|e| InternalError::RequestBodyError(Box::new(e)))?
             .to_bytes();
 
-        let request_builder = self
+        let mut request_builder = self
             .client
             .as_ref()
             .request(method.clone(), target_url.clone())
             .headers(headers.clone());
+        if region_config.is_some() {
+            // clients set the host of the provider's base url by default
+            request_builder = request_builder
+                .header(http::header::HOST, host_header(&base_url));
+        }
 
         let request_builder = self
             .client
             .extract_and_sign_aws_headers(request_builder, &req_body_bytes)?;
+        let request_builder = self.client.authenticate(request_builder).await?;
+
+        // while a circuit is half open only its trial requests are sent.
+        // Balancers skip circuits that are out of trial requests, so the rest
+        // are only failed, like they would be by the provider, if another
+        // request took the last permit since this provider was selected
+        let permitted = match (&model_metrics, &api_endpoint) {
+            (Some(model_metrics), _) => {
+                model_metrics.try_acquire_permit(&config.discover.monitor)
+            }
+            (None, Some(api_endpoint)) => self
+                .app_state
+                .0
+                .endpoint_metrics
+                .health_metrics(api_endpoint)?
+                .try_acquire_permit(&config.discover.monitor),
+            (None, None) => true,
+        };
+        if !permitted {
+            return Err(
+                InternalError::CircuitOpen(target_provider.clone()).into()
+            );
+        }
 
+        let dispatched_at = Instant::now();
         let metrics_for_stream = self.app_state.0.endpoint_metrics.clone();
-        if let Some(api_endpoint) = api_endpoint {
+        if let Some(api_endpoint) = &api_endpoint {
             let endpoint_metrics = self
                 .app_state
                 .0
@@ -287,6 +390,9 @@ impl Dispatcher {
                 .health_metrics(api_endpoint)?;
             endpoint_metrics.incr_req_count();
         }
+        if let Some(model_metrics) = &model_metrics {
+            model_metrics.incr_req_count();
+        }
 
         let (mut client_response, response_body_for_logger, tfft_rx): (
             http::Response<crate::types::body::Body>,
@@ -297,15 +403,23 @@ impl Dispatcher {
             Self::dispatch_stream(
                 request_builder,
                 req_body_bytes.clone(),
-                api_endpoint,
+                api_endpoint.clone(),
                 metrics_for_stream,
+                model_metrics.clone(),
             )
             .await?
         } else {
             tracing::debug!(method = %method, target_url = %target_url, "dispatching sync request");
             self.dispatch_sync(request_builder, req_body_bytes.clone())
                 .instrument(info_span!("dispatch_sync"))
-                .await?
+                .await
+                .inspect_err(|e| {
+                    self.record_dispatch_error(
+                        e,
+                        api_endpoint.as_ref(),
+                        model_metrics.as_deref(),
+                    );
+                })?
         };
         let provider_request_id = {
             let headers = client_response.headers_mut();
@@ -313,6 +427,16 @@ impl Dispatcher {
             headers.remove("x-request-id")
         };
         tracing::debug!(provider_req_id = ?provider_request_id, status = %client_response.status(), "received response");
+        if let Some(api_endpoint) = &api_endpoint {
+            self.app_state
+                .0
+                .endpoint_metrics
+                .health_metrics(api_endpoint)?
+                .record_latency(dispatched_at.elapsed());
+        }
+        if let Some(model_metrics) = &model_metrics {
+            model_metrics.record_latency(dispatched_at.elapsed());
+        }
         let extensions_copier = ExtensionsCopier::builder()
             .inference_provider(inference_provider)
             .router_id(router_id)
@@ -322,10 +446,15 @@ impl Dispatcher {
             .build();
         extensions_copier.copy_extensions(client_response.extensions_mut());
         client_response.extensions_mut().insert(mapper_ctx.clone());
-        client_response.extensions_mut().insert(api_endpoint);
+        client_response
+            .extensions_mut()
+            .insert(api_endpoint.clone());
         client_response
             .extensions_mut()
             .insert(extracted_path_and_query);
+        if let Some(region) = &self.region {
+            client_response.extensions_mut().insert(region.clone());
+        }
 
         if self.app_state.config().helicone.is_observability_enabled() {
             let auth_ctx = req_ctx
@@ -333,6 +462,13 @@ impl Dispatcher {
                 .clone()
                 .ok_or(InternalError::ExtensionNotFound("AuthContext"))?;
 
+            // multipart uploads are logged as JSON without the file contents
+            let logged_req_body =
+                MultipartForm::new(&headers, req_body_bytes.clone())
+                    .map_or(req_body_bytes, |form| {
+                        Bytes::from(form.to_json().to_string())
+                    });
+            let binary_response = is_binary_response(client_response.headers());
             let response_logger = LoggerService::builder()
                 .app_state(self.app_state.clone())
                 .auth_ctx(auth_ctx)
@@ -340,7 +476,8 @@ impl Dispatcher {
                 .start_instant(start_instant)
                 .target_url(target_url)
                 .request_headers(headers)
-                .request_body(req_body_bytes)
+                .request_body(logged_req_body)
+                .binary_response(binary_response)
                 .response_status(client_response.status())
                 .response_body(response_body_for_logger)
                 .provider(target_provider.clone())
@@ -370,6 +507,7 @@ impl Dispatcher {
             );
             let path = target_url.path().to_string();
             let provider_string = target_provider.to_string();
+            let region = self.region.clone();
             tokio::spawn(
                 async move {
                     let tfft_future = TFFTFuture::new(start_instant, tfft_rx);
@@ -377,11 +515,14 @@ impl Dispatcher {
                     let (_response_body, tfft_duration) = tokio::join!(collect_future, tfft_future);
                     if let Ok(tfft_duration) = tfft_duration {
                         tracing::trace!(tfft_duration = ?tfft_duration, "tfft_duration");
-                        let attributes = [
+                        let mut attributes = vec![
                             KeyValue::new("provider", provider_string),
                             KeyValue::new("model", model),
                             KeyValue::new("path", path),
                         ];
+                        if let Some(region) = region {
+                            attributes.push(KeyValue::new("region", region.to_string()));
+                        }
                         #[allow(clippy::cast_precision_loss)]
                         app_state.0.metrics.tfft_duration.record(tfft_duration.as_millis() as f64, &attributes);
                     } else { tracing::error!("Failed to get TFFT signal") }
@@ -390,8 +531,16 @@ impl Dispatcher {
             );
         }
 
+        if let Some(model_metrics) = &model_metrics {
+            if is_model_failure(client_response.status()) {
+                model_metrics.incr_remote_internal_error_count();
+            } else if client_response.status() != StatusCode::TOO_MANY_REQUESTS
+            {
+                model_metrics.record_success();
+            }
+        }
         if client_response.status().is_server_error() {
-            if let Some(api_endpoint) = api_endpoint {
+            if let Some(api_endpoint) = &api_endpoint {
                 let endpoint_metrics = self
                     .app_state
                     .0
@@ -419,16 +568,57 @@ impl Dispatcher {
                     }
                 }
             }
+        } else if let Some(api_endpoint) = &api_endpoint {
+            self.app_state
+                .0
+                .endpoint_metrics
+                .health_metrics(api_endpoint)?
+                .record_success();
         }
 
         Ok(client_response)
     }
 
+    /// Requests that failed before a response was received, e.g. because the
+    /// connection was refused or timed out, count against the provider's
+    /// health like server errors do.
+    ///
+    /// Streams record these when their first event is read instead.
+    fn record_dispatch_error(
+        &self,
+        error: &ApiError,
+        api_endpoint: Option<&ApiEndpoint>,
+        model_metrics: Option<&EndpointMetrics>,
+    ) {
+        if !matches!(error, ApiError::Internal(InternalError::ReqwestError(_)))
+        {
+            return;
+        }
+        tracing::warn!(provider = ?self.provider, error = %error, "failed to dispatch request");
+        if let Some(api_endpoint) = api_endpoint {
+            match self
+                .app_state
+                .0
+                .endpoint_metrics
+                .health_metrics(api_endpoint)
+            {
+                Ok(metrics) => metrics.incr_remote_internal_error_count(),
+                Err(e) => {
+                    tracing::error!(error = %e, "failed to record dispatch error");
+                }
+            }
+        }
+        if let Some(model_metrics) = model_metrics {
+            model_metrics.incr_remote_internal_error_count();
+        }
+    }
+
     async fn dispatch_stream(
         request_builder: RequestBuilder,
         req_body_bytes: Bytes,
         api_endpoint: Option<ApiEndpoint>,
         metrics_registry: EndpointMetricsRegistry,
+        model_metrics: Option<Arc<EndpointMetrics>>,
     ) -> Result<
         (
             http::Response<crate::types::body::Body>,
@@ -440,17 +630,29 @@ impl Dispatcher {
         let response_stream = Client::sse_stream(
             request_builder,
             req_body_bytes,
-            api_endpoint,
+            api_endpoint.clone(),
             &metrics_registry,
         )
-        .await?
+        .await
+        .inspect_err(|e| {
+            if let ApiError::StreamError(error) = e {
+                record_model_stream_err_metrics(
+                    error,
+                    model_metrics.as_deref(),
+                );
+            }
+        })?
         .map_err(move |e| {
             if let ApiError::StreamError(error) = &e {
                 record_stream_err_metrics(
                     error,
-                    api_endpoint,
+                    api_endpoint.as_ref(),
                     &(metrics_registry.clone()),
                 );
+                record_model_stream_err_metrics(
+                    error,
+                    model_metrics.as_deref(),
+                );
             }
             e
         });
@@ -550,6 +752,19 @@ fn extract_retry_after(headers: &HeaderMap) -> Option<u64> {
     None
 }
 
+fn is_binary_response(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<mime::Mime>().ok())
+    else {
+        return false;
+    };
+    content_type.type_() == mime::AUDIO
+        || (content_type.type_() == mime::APPLICATION
+            && content_type.subtype() == mime::OCTET_STREAM)
+}
+
 fn stream_response_headers() -> HeaderMap {
     HeaderMap::from_iter([
         (
@@ -567,9 +782,47 @@ fn stream_response_headers() -> HeaderMap {
     ])
 }
 
+/// Models that a provider no longer serves are reported as client errors,
+/// but should still count against the model's health.
+fn is_model_failure(status: StatusCode) -> bool {
+    status.is_server_error()
+        || status == StatusCode::NOT_FOUND
+        || status == StatusCode::GONE
+}
+
+fn record_model_stream_err_metrics(
+    error: &StreamError,
+    model_metrics: Option<&EndpointMetrics>,
+) {
+    if let (Some(model_metrics), StreamError::StreamError(boxed_error)) =
+        (model_metrics, error)
+    {
+        model_metrics.incr_for_stream_error(boxed_error);
+    }
+}
+
+/// The base url that request paths are joined with.
+pub(crate) fn relative_base_url(
+    provider: &InferenceProvider,
+    mut base_url: Url,
+) -> Url {
+    if matches!(
+        provider,
+        InferenceProvider::Named(_) | InferenceProvider::GoogleVertex
+    ) && !base_url.path().ends_with('/')
+    {
+        // paths are relative for providers configured by name and for
+        // Vertex AI, so that base urls may include a prefix such as
+        // `/openai/v1` or the project and location
+        let path = format!("{}/", base_url.path());
+        base_url.set_path(&path);
+    }
+    base_url
+}
+
 pub(super) fn record_stream_err_metrics(
     error: &StreamError,
-    api_endpoint: Option<ApiEndpoint>,
+    api_endpoint: Option<&ApiEndpoint>,
     metrics_registry: &EndpointMetricsRegistry,
 ) {
     if let Some(api_endpoint) = api_endpoint {
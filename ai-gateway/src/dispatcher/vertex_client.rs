@@ -0,0 +1,241 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use http::{HeaderMap, HeaderValue};
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::ClientBuilder;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use url::Url;
+
+use crate::{
+    app_state::AppState,
+    error::{
+        api::ApiError, auth::AuthError, init::InitError,
+        internal::InternalError, provider::ProviderError,
+    },
+    types::{
+        provider::{InferenceProvider, ProviderKey},
+        secret::Secret,
+    },
+    utils::host_header,
+};
+
+const SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const JWT_BEARER_GRANT_TYPE: &str =
+    "urn:ietf:params:oauth:grant-type:jwt-bearer";
+/// Lifetime of the signed assertion; Google caps this at one hour.
+const ASSERTION_LIFETIME: Duration = Duration::from_secs(3600);
+/// Tokens are refreshed this long before they expire so that in-flight
+/// requests don't race the expiry.
+const REFRESH_MARGIN: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    pub(super) inner: reqwest::Client,
+    token_provider: Option<Arc<TokenProvider>>,
+}
+
+impl Client {
+    pub fn new(
+        app_state: &AppState,
+        client_builder: ClientBuilder,
+        provider_key: Option<&ProviderKey>,
+    ) -> Result<Self, InitError> {
+        let provider_config = app_state
+            .0
+            .config
+            .providers
+            .get(&InferenceProvider::GoogleVertex)
+            .ok_or(ProviderError::ProviderNotConfigured(
+                InferenceProvider::GoogleVertex,
+            ))?;
+
+        let base_url = provider_config.base_url.clone();
+
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert(http::header::HOST, host_header(&base_url));
+        default_headers.insert(
+            http::header::CONTENT_TYPE,
+            HeaderValue::from_str(mime::APPLICATION_JSON.essence_str())
+                .unwrap(),
+        );
+
+        let inner = client_builder
+            .default_headers(default_headers)
+            .build()
+            .map_err(InitError::CreateReqwestClient)?;
+
+        let token_provider = match provider_key {
+            Some(ProviderKey::Secret(key)) => Some(Arc::new(
+                TokenProvider::new(key, provider_config.token_url.clone())?,
+            )),
+            _ => None,
+        };
+
+        Ok(Self {
+            inner,
+            token_provider,
+        })
+    }
+
+    /// Adds an `OAuth` access token minted from the service account key.
+    pub async fn authenticate(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder, ApiError> {
+        let token_provider = self
+            .token_provider
+            .as_ref()
+            .ok_or(ApiError::Authentication(AuthError::InvalidCredentials))?;
+        let access_token = token_provider.access_token(&self.inner).await?;
+        Ok(request_builder.bearer_auth(access_token.expose()))
+    }
+}
+
+/// The fields of a service account JSON key we need to mint tokens.
+#[derive(Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    #[serde(default)]
+    private_key_id: Option<String>,
+    token_uri: Url,
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+struct AccessToken {
+    token: Secret<String>,
+    expires_at: SystemTime,
+}
+
+/// Signs JWTs with the service account key and exchanges them for access
+/// tokens, which are cached until shortly before they expire.
+struct TokenProvider {
+    client_email: String,
+    key_id: Option<String>,
+    encoding_key: EncodingKey,
+    token_url: Url,
+    cached: RwLock<Option<AccessToken>>,
+}
+
+impl std::fmt::Debug for TokenProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenProvider")
+            .field("client_email", &self.client_email)
+            .field("token_url", &self.token_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenProvider {
+    fn new(
+        key: &Secret<String>,
+        token_url: Option<Url>,
+    ) -> Result<Self, InitError> {
+        let key: ServiceAccountKey = serde_json::from_str(key.expose())
+            .map_err(|e| InitError::InvalidServiceAccountKey(e.to_string()))?;
+        let encoding_key = EncodingKey::from_rsa_pem(
+            key.private_key.as_bytes(),
+        )
+        .map_err(|e| InitError::InvalidServiceAccountKey(e.to_string()))?;
+        Ok(Self {
+            client_email: key.client_email,
+            key_id: key.private_key_id,
+            encoding_key,
+            token_url: token_url.unwrap_or(key.token_uri),
+            cached: RwLock::new(None),
+        })
+    }
+
+    async fn access_token(
+        &self,
+        client: &reqwest::Client,
+    ) -> Result<Secret<String>, InternalError> {
+        if let Some(token) = self.cached.read().await.as_ref()
+            && is_fresh(token)
+        {
+            return Ok(token.token.clone());
+        }
+
+        let mut cached = self.cached.write().await;
+        // another request may have refreshed the token while we waited
+        if let Some(token) = cached.as_ref()
+            && is_fresh(token)
+        {
+            return Ok(token.token.clone());
+        }
+        let token = self.fetch(client).await?;
+        let secret = token.token.clone();
+        *cached = Some(token);
+        Ok(secret)
+    }
+
+    async fn fetch(
+        &self,
+        client: &reqwest::Client,
+    ) -> Result<AccessToken, InternalError> {
+        let now = SystemTime::now();
+        let iat = now
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| InternalError::VertexTokenError(e.to_string()))?
+            .as_secs();
+        let claims = Claims {
+            iss: &self.client_email,
+            scope: SCOPE,
+            aud: self.token_url.as_str(),
+            iat,
+            exp: iat + ASSERTION_LIFETIME.as_secs(),
+        };
+        let header = Header {
+            kid: self.key_id.clone(),
+            ..Header::new(Algorithm::RS256)
+        };
+        let assertion =
+            jsonwebtoken::encode(&header, &claims, &self.encoding_key)
+                .map_err(|e| InternalError::VertexTokenError(e.to_string()))?;
+
+        let response = client
+            .post(self.token_url.clone())
+            // the client's default host header targets the inference API
+            .header(http::header::HOST, host_header(&self.token_url))
+            .form(&[
+                ("grant_type", JWT_BEARER_GRANT_TYPE),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| InternalError::VertexTokenError(e.to_string()))?;
+        let token: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| InternalError::VertexTokenError(e.to_string()))?;
+
+        tracing::debug!(expires_in = token.expires_in, "minted vertex token");
+        Ok(AccessToken {
+            token: Secret::from(token.access_token),
+            expires_at: now + Duration::from_secs(token.expires_in),
+        })
+    }
+}
+
+fn is_fresh(token: &AccessToken) -> bool {
+    SystemTime::now() + REFRESH_MARGIN < token.expires_at
+}
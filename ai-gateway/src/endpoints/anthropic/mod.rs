@@ -1,7 +1,8 @@
 pub mod messages;
 
-use super::{Endpoint, EndpointType};
+use super::{Endpoint, EndpointRoute, EndpointType};
 pub use crate::endpoints::anthropic::messages::Messages;
+use crate::error::invalid_req::InvalidRequestError;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
 pub enum Anthropic {
@@ -28,3 +29,16 @@ impl Anthropic {
         }
     }
 }
+
+impl TryFrom<&EndpointRoute> for Anthropic {
+    type Error = InvalidRequestError;
+
+    fn try_from(endpoint: &EndpointRoute) -> Result<Self, Self::Error> {
+        match endpoint {
+            EndpointRoute::Messages => Ok(Self::Messages(Messages)),
+            _ => Err(InvalidRequestError::UnsupportedEndpoint(
+                endpoint.path().to_string(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use async_openai::types::{
+    CreateChatCompletionRequest, CreateChatCompletionResponse,
+    CreateChatCompletionStreamResponse,
+};
+
+use crate::endpoints::Endpoint;
+
+/// Azure `OpenAI` accepts the same payloads as `OpenAI`, the model is instead
+/// selected by the deployment in the path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ChatCompletions;
+
+impl Endpoint for ChatCompletions {
+    const PATH: &'static str =
+        "/openai/deployments/{deployment}/chat/completions";
+    type RequestBody = CreateChatCompletionRequest;
+    type ResponseBody = CreateChatCompletionResponse;
+    type StreamResponseBody = CreateChatCompletionStreamResponse;
+    type ErrorResponseBody = async_openai::error::WrappedError;
+}
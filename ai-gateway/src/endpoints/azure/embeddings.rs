@@ -0,0 +1,15 @@
+use async_openai::types::{CreateEmbeddingRequest, CreateEmbeddingResponse};
+
+use crate::endpoints::Endpoint;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Embeddings;
+
+impl Endpoint for Embeddings {
+    const PATH: &'static str = "/openai/deployments/{deployment}/embeddings";
+    type RequestBody = CreateEmbeddingRequest;
+    type ResponseBody = CreateEmbeddingResponse;
+    // embeddings are never streamed
+    type StreamResponseBody = CreateEmbeddingResponse;
+    type ErrorResponseBody = async_openai::error::WrappedError;
+}
@@ -0,0 +1,48 @@
+pub(crate) mod chat_completions;
+pub(crate) mod embeddings;
+
+use super::EndpointType;
+pub(crate) use crate::endpoints::azure::{
+    chat_completions::ChatCompletions, embeddings::Embeddings,
+};
+use crate::types::model_id::ModelId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
+pub enum Azure {
+    ChatCompletions(ChatCompletions),
+    Embeddings(Embeddings),
+}
+
+impl Azure {
+    /// Requests are routed by deployment name, which the mapper sets as the
+    /// request's model.
+    #[must_use]
+    pub fn path(self, deployment: &ModelId) -> String {
+        match self {
+            Self::ChatCompletions(_) => {
+                format!("/openai/deployments/{deployment}/chat/completions")
+            }
+            Self::Embeddings(_) => {
+                format!("/openai/deployments/{deployment}/embeddings")
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn chat_completions() -> Self {
+        Self::ChatCompletions(ChatCompletions)
+    }
+
+    #[must_use]
+    pub fn embeddings() -> Self {
+        Self::Embeddings(Embeddings)
+    }
+
+    #[must_use]
+    pub fn endpoint_type(self) -> EndpointType {
+        match self {
+            Self::ChatCompletions(_) => EndpointType::Chat,
+            Self::Embeddings(_) => EndpointType::Embeddings,
+        }
+    }
+}
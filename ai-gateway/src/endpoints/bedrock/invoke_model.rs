@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::{AiRequest, Endpoint},
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+/// `InvokeModel` for the Titan and Cohere embedding models.
+///
+/// Unlike `Converse`, the request and response schemas are specific to the
+/// model family, so the body is chosen based on the target model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct InvokeModel;
+
+impl Endpoint for InvokeModel {
+    const PATH: &'static str = "/model/{model_id}/invoke";
+    type RequestBody = InvokeModelEmbeddingRequest;
+    type ResponseBody = InvokeModelEmbeddingResponse;
+    // embeddings are never streamed
+    type StreamResponseBody = InvokeModelEmbeddingResponse;
+    type ErrorResponseBody = InvokeModelError;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InvokeModelEmbeddingRequest {
+    /// The model id is part of the request path rather than the body.
+    #[serde(skip)]
+    pub model_id: String,
+    #[serde(flatten)]
+    pub body: InvokeModelEmbeddingBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum InvokeModelEmbeddingBody {
+    Titan(TitanEmbeddingRequest),
+    Cohere(CohereEmbeddingRequest),
+}
+
+// https://docs.aws.amazon.com/bedrock/latest/userguide/model-parameters-titan-embed-text.html
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TitanEmbeddingRequest {
+    pub input_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+}
+
+// https://docs.aws.amazon.com/bedrock/latest/userguide/model-parameters-embed.html
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereEmbeddingRequest {
+    pub texts: Vec<String>,
+    pub input_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum InvokeModelEmbeddingResponse {
+    Titan(TitanEmbeddingResponse),
+    Cohere(CohereEmbeddingResponse),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TitanEmbeddingResponse {
+    pub embedding: Vec<f32>,
+    pub input_text_token_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereEmbeddingResponse {
+    pub id: String,
+    pub embeddings: Vec<Vec<f32>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InvokeModelError {
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl AiRequest for InvokeModelEmbeddingRequest {
+    fn is_stream(&self) -> bool {
+        false
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(
+            &InferenceProvider::Bedrock,
+            &self.model_id,
+        )
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::{AiRequest, Endpoint, bedrock::invoke_model::InvokeModelError},
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+/// `InvokeModel` for the Titan Image Generator and Stability image models.
+///
+/// Like the embedding models, the request schema is specific to the model
+/// family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct InvokeModelImage;
+
+impl Endpoint for InvokeModelImage {
+    const PATH: &'static str = "/model/{model_id}/invoke";
+    type RequestBody = InvokeModelImageRequest;
+    type ResponseBody = InvokeModelImageResponse;
+    // image generations are never streamed
+    type StreamResponseBody = InvokeModelImageResponse;
+    type ErrorResponseBody = InvokeModelError;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InvokeModelImageRequest {
+    /// The model id is part of the request path rather than the body.
+    #[serde(skip)]
+    pub model_id: String,
+    #[serde(flatten)]
+    pub body: InvokeModelImageBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum InvokeModelImageBody {
+    Titan(TitanImageRequest),
+    Stability(StabilityImageRequest),
+}
+
+// https://docs.aws.amazon.com/bedrock/latest/userguide/model-parameters-titan-image.html
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TitanImageRequest {
+    /// Always `TEXT_IMAGE`, since we only support text-to-image generation.
+    pub task_type: String,
+    pub text_to_image_params: TitanTextToImageParams,
+    pub image_generation_config: TitanImageGenerationConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TitanTextToImageParams {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TitanImageGenerationConfig {
+    pub number_of_images: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Either `standard` or `premium`.
+    pub quality: String,
+}
+
+// https://docs.aws.amazon.com/bedrock/latest/userguide/model-parameters-diffusion-3-text-image.html
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StabilityImageRequest {
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<String>,
+    pub output_format: String,
+}
+
+/// Both Titan and Stability respond with base64 encoded images.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InvokeModelImageResponse {
+    pub images: Vec<String>,
+}
+
+impl AiRequest for InvokeModelImageRequest {
+    fn is_stream(&self) -> bool {
+        false
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(
+            &InferenceProvider::Bedrock,
+            &self.model_id,
+        )
+    }
+}
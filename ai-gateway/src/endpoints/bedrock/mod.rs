@@ -1,12 +1,19 @@
 pub(crate) mod converse;
+pub(crate) mod invoke_model;
+pub(crate) mod invoke_model_image;
 
 use super::EndpointType;
-pub(crate) use crate::endpoints::bedrock::converse::Converse;
+pub(crate) use crate::endpoints::bedrock::{
+    converse::Converse, invoke_model::InvokeModel,
+    invoke_model_image::InvokeModelImage,
+};
 use crate::types::model_id::ModelId;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
 pub enum Bedrock {
     Converse(Converse),
+    InvokeModel(InvokeModel),
+    InvokeModelImage(InvokeModelImage),
 }
 
 impl Bedrock {
@@ -20,6 +27,9 @@ impl Bedrock {
                     format!("/model/{model_id}/converse")
                 }
             }
+            Self::InvokeModel(_) | Self::InvokeModelImage(_) => {
+                format!("/model/{model_id}/invoke")
+            }
         }
     }
 
@@ -28,10 +38,22 @@ impl Bedrock {
         Self::Converse(Converse)
     }
 
+    #[must_use]
+    pub fn invoke_model() -> Self {
+        Self::InvokeModel(InvokeModel)
+    }
+
+    #[must_use]
+    pub fn invoke_model_image() -> Self {
+        Self::InvokeModelImage(InvokeModelImage)
+    }
+
     #[must_use]
     pub fn endpoint_type(self) -> EndpointType {
         match self {
             Self::Converse(_) => EndpointType::Chat,
+            Self::InvokeModel(_) => EndpointType::Embeddings,
+            Self::InvokeModelImage(_) => EndpointType::Image,
         }
     }
 }
@@ -0,0 +1,336 @@
+use async_openai::types::ChatCompletionMessageToolCall;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::{AiRequest, Endpoint},
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Chat;
+
+impl Endpoint for Chat {
+    // https://docs.cohere.com/reference/chat
+    const PATH: &'static str = "/v2/chat";
+    type RequestBody = CohereChatRequest;
+    type ResponseBody = CohereChatResponse;
+    type StreamResponseBody = CohereStreamEvent;
+    type ErrorResponseBody = CohereError;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereChatRequest {
+    pub model: String,
+    pub messages: Vec<CohereMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<CohereTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<CohereToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict_tools: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<CohereResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+}
+
+impl AiRequest for CohereChatRequest {
+    fn is_stream(&self) -> bool {
+        self.stream.unwrap_or(false)
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(&InferenceProvider::Cohere, &self.model)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "role", rename_all = "lowercase")]
+pub enum CohereMessage {
+    System {
+        content: CohereContent,
+    },
+    User {
+        content: CohereContent,
+    },
+    Assistant {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        content: Option<CohereContent>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_plan: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_calls: Option<Vec<ChatCompletionMessageToolCall>>,
+    },
+    Tool {
+        tool_call_id: String,
+        content: CohereContent,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum CohereContent {
+    Text(String),
+    Blocks(Vec<CohereContentBlock>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CohereContentBlock {
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: CohereImageUrl,
+    },
+    /// Tool results sent as documents can be cited in the response.
+    Document {
+        document: CohereDocument,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereImageUrl {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereTool {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub function: CohereFunction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereFunction {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<serde_json::Value>,
+}
+
+/// Tools are chosen automatically unless a tool choice is given.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CohereToolChoice {
+    Required,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CohereResponseFormat {
+    Text,
+    JsonObject {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        json_schema: Option<serde_json::Value>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereChatResponse {
+    pub id: String,
+    pub finish_reason: CohereFinishReason,
+    pub message: CohereResponseMessage,
+    #[serde(default)]
+    pub usage: Option<CohereUsage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CohereResponseMessage {
+    #[serde(default)]
+    pub content: Vec<CohereResponseContent>,
+    #[serde(default)]
+    pub tool_plan: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<ChatCompletionMessageToolCall>>,
+    #[serde(default)]
+    pub citations: Option<Vec<CohereCitation>>,
+}
+
+/// Reasoning models also respond with `thinking` content, which has no
+/// `text`. Content in stream deltas has no `type`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CohereResponseContent {
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereCitation {
+    #[serde(default)]
+    pub start: u32,
+    #[serde(default)]
+    pub end: u32,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default)]
+    pub sources: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CohereFinishReason {
+    Complete,
+    StopSequence,
+    MaxTokens,
+    ToolCall,
+    Error,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CohereUsage {
+    #[serde(default)]
+    pub billed_units: Option<CohereTokens>,
+    #[serde(default)]
+    pub tokens: Option<CohereTokens>,
+}
+
+impl CohereUsage {
+    /// Prefers the tokens consumed by the model over the billed tokens,
+    /// which exclude tokens added by Cohere such as the system preamble.
+    #[must_use]
+    pub fn tokens(&self) -> CohereTokens {
+        self.tokens
+            .clone()
+            .or_else(|| self.billed_units.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CohereTokens {
+    #[serde(default)]
+    pub input_tokens: u32,
+    #[serde(default)]
+    pub output_tokens: u32,
+}
+
+/// Stream events, tagged by their `type`. Content, tool calls and citations
+/// each have start, delta and end events.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum CohereStreamEvent {
+    MessageStart {
+        #[serde(default)]
+        id: Option<String>,
+    },
+    ContentStart {
+        #[serde(default)]
+        index: u32,
+        #[serde(default)]
+        delta: CohereStreamDelta,
+    },
+    ContentDelta {
+        #[serde(default)]
+        index: u32,
+        #[serde(default)]
+        delta: CohereStreamDelta,
+    },
+    ToolPlanDelta {
+        #[serde(default)]
+        delta: CohereStreamDelta,
+    },
+    ToolCallStart {
+        #[serde(default)]
+        index: u32,
+        #[serde(default)]
+        delta: CohereStreamDelta,
+    },
+    ToolCallDelta {
+        #[serde(default)]
+        index: u32,
+        #[serde(default)]
+        delta: CohereStreamDelta,
+    },
+    CitationStart {
+        #[serde(default)]
+        index: u32,
+        #[serde(default)]
+        delta: CohereStreamDelta,
+    },
+    MessageEnd {
+        #[serde(default)]
+        delta: CohereMessageEndDelta,
+    },
+    /// `content-end`, `tool-call-end`, `citation-end` and `debug` events
+    /// carry nothing we need.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CohereStreamDelta {
+    #[serde(default)]
+    pub message: CohereStreamMessage,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CohereStreamMessage {
+    #[serde(default)]
+    pub content: Option<CohereResponseContent>,
+    #[serde(default)]
+    pub tool_plan: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<CohereStreamToolCall>,
+    #[serde(default)]
+    pub citations: Option<CohereCitation>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CohereStreamToolCall {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub function: Option<CohereStreamFunctionCall>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CohereStreamFunctionCall {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CohereMessageEndDelta {
+    #[serde(default)]
+    pub finish_reason: Option<CohereFinishReason>,
+    #[serde(default)]
+    pub usage: Option<CohereUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereError {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
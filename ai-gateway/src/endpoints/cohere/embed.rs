@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::{
+        AiRequest, Endpoint,
+        cohere::chat::{CohereError, CohereTokens},
+    },
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Embed;
+
+impl Endpoint for Embed {
+    // https://docs.cohere.com/reference/embed
+    const PATH: &'static str = "/v2/embed";
+    type RequestBody = CohereEmbedRequest;
+    type ResponseBody = CohereEmbedResponse;
+    // embeddings are never streamed
+    type StreamResponseBody = CohereEmbedResponse;
+    type ErrorResponseBody = CohereError;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereEmbedRequest {
+    pub model: String,
+    pub texts: Vec<String>,
+    pub input_type: CohereInputType,
+    pub embedding_types: Vec<CohereEmbeddingType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dimension: Option<u32>,
+}
+
+/// Required by Cohere's v3 and later embedding models.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CohereInputType {
+    SearchDocument,
+    SearchQuery,
+    Classification,
+    Clustering,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CohereEmbeddingType {
+    Float,
+}
+
+impl AiRequest for CohereEmbedRequest {
+    fn is_stream(&self) -> bool {
+        false
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(&InferenceProvider::Cohere, &self.model)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CohereEmbedResponse {
+    pub id: String,
+    pub embeddings: CohereEmbeddings,
+    #[serde(default)]
+    pub meta: Option<CohereEmbedMeta>,
+}
+
+/// Embeddings keyed by the requested embedding types.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CohereEmbeddings {
+    #[serde(default)]
+    pub float: Vec<Vec<f32>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CohereEmbedMeta {
+    #[serde(default)]
+    pub billed_units: Option<CohereTokens>,
+}
@@ -0,0 +1,40 @@
+pub(crate) mod chat;
+pub(crate) mod embed;
+
+use super::EndpointType;
+use crate::endpoints::Endpoint;
+pub(crate) use crate::endpoints::cohere::{chat::Chat, embed::Embed};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
+pub enum Cohere {
+    Chat(Chat),
+    Embed(Embed),
+}
+
+impl Cohere {
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Chat(_) => Chat::PATH,
+            Self::Embed(_) => Embed::PATH,
+        }
+    }
+
+    #[must_use]
+    pub fn chat() -> Self {
+        Self::Chat(Chat)
+    }
+
+    #[must_use]
+    pub fn embed() -> Self {
+        Self::Embed(Embed)
+    }
+
+    #[must_use]
+    pub fn endpoint_type(&self) -> EndpointType {
+        match self {
+            Self::Chat(_) => EndpointType::Chat,
+            Self::Embed(_) => EndpointType::Embeddings,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use async_openai::types::{CreateEmbeddingRequest, CreateEmbeddingResponse};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::AiRequest,
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EmbedContent;
+
+impl crate::endpoints::Endpoint for EmbedContent {
+    // https://ai.google.dev/gemini-api/docs/openai#embeddings
+    const PATH: &'static str = "/v1beta/openai/embeddings";
+    type RequestBody = CreateEmbeddingRequestGemini;
+    type ResponseBody = CreateEmbeddingResponse;
+    // embeddings are never streamed
+    type StreamResponseBody = CreateEmbeddingResponse;
+    type ErrorResponseBody = async_openai::error::WrappedError;
+}
+
+#[derive(Clone, Serialize, Debug, Deserialize, PartialEq)]
+pub struct CreateEmbeddingRequestGemini(pub(crate) CreateEmbeddingRequest);
+
+impl AiRequest for CreateEmbeddingRequestGemini {
+    fn is_stream(&self) -> bool {
+        false
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(
+            &InferenceProvider::GoogleGemini,
+            &self.0.model,
+        )
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::{
+        AiRequest,
+        openai::image_generations::{CreateImageRequest, ImagesResponse},
+    },
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ImageGenerations;
+
+impl crate::endpoints::Endpoint for ImageGenerations {
+    // https://ai.google.dev/gemini-api/docs/openai#image-generation
+    const PATH: &'static str = "/v1beta/openai/images/generations";
+    type RequestBody = CreateImageRequestGemini;
+    type ResponseBody = ImagesResponse;
+    // image generations are never streamed
+    type StreamResponseBody = ImagesResponse;
+    type ErrorResponseBody = async_openai::error::WrappedError;
+}
+
+#[derive(Clone, Serialize, Default, Debug, Deserialize, PartialEq)]
+pub struct CreateImageRequestGemini(pub(crate) CreateImageRequest);
+
+impl AiRequest for CreateImageRequestGemini {
+    fn is_stream(&self) -> bool {
+        false
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(
+            &InferenceProvider::GoogleGemini,
+            &self.0.model,
+        )
+    }
+}
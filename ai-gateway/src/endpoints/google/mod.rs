@@ -1,11 +1,18 @@
+pub(crate) mod embed_content;
 pub(crate) mod generate_contents;
+pub(crate) mod image_generations;
 
 use super::{Endpoint, EndpointType};
-pub(crate) use crate::endpoints::google::generate_contents::GenerateContents;
+pub(crate) use crate::endpoints::google::{
+    embed_content::EmbedContent, generate_contents::GenerateContents,
+    image_generations::ImageGenerations,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
 pub enum Google {
     GenerateContents(GenerateContents),
+    EmbedContent(EmbedContent),
+    ImageGenerations(ImageGenerations),
 }
 
 impl Google {
@@ -13,6 +20,8 @@ impl Google {
     pub fn path(&self) -> &str {
         match self {
             Self::GenerateContents(_) => GenerateContents::PATH,
+            Self::EmbedContent(_) => EmbedContent::PATH,
+            Self::ImageGenerations(_) => ImageGenerations::PATH,
         }
     }
 
@@ -21,10 +30,22 @@ impl Google {
         Self::GenerateContents(GenerateContents)
     }
 
+    #[must_use]
+    pub fn embed_content() -> Self {
+        Self::EmbedContent(EmbedContent)
+    }
+
+    #[must_use]
+    pub fn image_generations() -> Self {
+        Self::ImageGenerations(ImageGenerations)
+    }
+
     #[must_use]
     pub fn endpoint_type(&self) -> EndpointType {
         match self {
             Self::GenerateContents(_) => EndpointType::Chat,
+            Self::EmbedContent(_) => EndpointType::Embeddings,
+            Self::ImageGenerations(_) => EndpointType::Image,
         }
     }
 }
@@ -37,6 +58,10 @@ impl TryFrom<&str> for Google {
             GenerateContents::PATH => {
                 Ok(Self::GenerateContents(GenerateContents))
             }
+            EmbedContent::PATH => Ok(Self::EmbedContent(EmbedContent)),
+            ImageGenerations::PATH => {
+                Ok(Self::ImageGenerations(ImageGenerations))
+            }
             path => {
                 tracing::warn!(path = %path, "unsupported Google path");
                 Err(crate::error::invalid_req::InvalidRequestError::NotFound(
@@ -1,6 +1,10 @@
-use crate::endpoints::{
-    anthropic::Anthropic, bedrock::Bedrock, google::Google, ollama::Ollama,
-    openai::OpenAI,
+use crate::{
+    endpoints::{
+        anthropic::Anthropic, azure::Azure, bedrock::Bedrock, cohere::Cohere,
+        google::Google, mistral::Mistral, ollama::Ollama, openai::OpenAI,
+        vertex::Vertex,
+    },
+    error::invalid_req::InvalidRequestError,
 };
 
 impl From<Anthropic> for OpenAI {
@@ -11,10 +15,23 @@ impl From<Anthropic> for OpenAI {
     }
 }
 
-impl From<OpenAI> for Anthropic {
-    fn from(value: OpenAI) -> Self {
+impl TryFrom<OpenAI> for Anthropic {
+    type Error = InvalidRequestError;
+
+    fn try_from(value: OpenAI) -> Result<Self, Self::Error> {
         match value {
-            OpenAI::ChatCompletions(_) => Self::messages(),
+            OpenAI::ChatCompletions(_) | OpenAI::Responses(_) => {
+                Ok(Self::messages())
+            }
+            OpenAI::Embeddings(_)
+            | OpenAI::ImageGenerations(_)
+            | OpenAI::AudioTranscriptions(_)
+            | OpenAI::AudioSpeech(_) => {
+                Err(InvalidRequestError::UnsupportedEndpoint(format!(
+                    "{} is not supported by anthropic",
+                    value.path()
+                )))
+            }
         }
     }
 }
@@ -23,22 +40,49 @@ impl From<Google> for OpenAI {
     fn from(value: Google) -> Self {
         match value {
             Google::GenerateContents(_) => Self::chat_completions(),
+            Google::EmbedContent(_) => Self::embeddings(),
+            Google::ImageGenerations(_) => Self::image_generations(),
         }
     }
 }
 
-impl From<OpenAI> for Google {
-    fn from(value: OpenAI) -> Self {
+impl TryFrom<OpenAI> for Google {
+    type Error = InvalidRequestError;
+
+    fn try_from(value: OpenAI) -> Result<Self, Self::Error> {
         match value {
-            OpenAI::ChatCompletions(_) => Self::generate_contents(),
+            OpenAI::ChatCompletions(_) | OpenAI::Responses(_) => {
+                Ok(Self::generate_contents())
+            }
+            OpenAI::Embeddings(_) => Ok(Self::embed_content()),
+            OpenAI::ImageGenerations(_) => Ok(Self::image_generations()),
+            OpenAI::AudioTranscriptions(_) | OpenAI::AudioSpeech(_) => {
+                Err(InvalidRequestError::UnsupportedEndpoint(format!(
+                    "{} is not supported by gemini",
+                    value.path()
+                )))
+            }
         }
     }
 }
 
-impl From<OpenAI> for Ollama {
-    fn from(value: OpenAI) -> Self {
+impl TryFrom<OpenAI> for Ollama {
+    type Error = InvalidRequestError;
+
+    fn try_from(value: OpenAI) -> Result<Self, Self::Error> {
         match value {
-            OpenAI::ChatCompletions(_) => Self::chat_completions(),
+            OpenAI::ChatCompletions(_) | OpenAI::Responses(_) => {
+                Ok(Self::chat_completions())
+            }
+            OpenAI::Embeddings(_) => Ok(Self::embed()),
+            OpenAI::ImageGenerations(_)
+            | OpenAI::AudioTranscriptions(_)
+            | OpenAI::AudioSpeech(_) => {
+                Err(InvalidRequestError::UnsupportedEndpoint(format!(
+                    "{} is not supported by ollama",
+                    value.path()
+                )))
+            }
         }
     }
 }
@@ -47,13 +91,111 @@ impl From<Ollama> for OpenAI {
     fn from(value: Ollama) -> Self {
         match value {
             Ollama::ChatCompletions(_) => Self::chat_completions(),
+            Ollama::Embed(_) => Self::embeddings(),
+        }
+    }
+}
+
+impl TryFrom<OpenAI> for Bedrock {
+    type Error = InvalidRequestError;
+
+    fn try_from(value: OpenAI) -> Result<Self, Self::Error> {
+        match value {
+            OpenAI::ChatCompletions(_) | OpenAI::Responses(_) => {
+                Ok(Self::converse())
+            }
+            OpenAI::Embeddings(_) => Ok(Self::invoke_model()),
+            OpenAI::ImageGenerations(_) => Ok(Self::invoke_model_image()),
+            OpenAI::AudioTranscriptions(_) | OpenAI::AudioSpeech(_) => {
+                Err(InvalidRequestError::UnsupportedEndpoint(format!(
+                    "{} is not supported by bedrock",
+                    value.path()
+                )))
+            }
+        }
+    }
+}
+
+impl TryFrom<OpenAI> for Azure {
+    type Error = InvalidRequestError;
+
+    fn try_from(value: OpenAI) -> Result<Self, Self::Error> {
+        match value {
+            OpenAI::ChatCompletions(_) | OpenAI::Responses(_) => {
+                Ok(Self::chat_completions())
+            }
+            OpenAI::Embeddings(_) => Ok(Self::embeddings()),
+            OpenAI::ImageGenerations(_)
+            | OpenAI::AudioTranscriptions(_)
+            | OpenAI::AudioSpeech(_) => {
+                Err(InvalidRequestError::UnsupportedEndpoint(format!(
+                    "{} is not supported by azure",
+                    value.path()
+                )))
+            }
         }
     }
 }
-impl From<OpenAI> for Bedrock {
-    fn from(value: OpenAI) -> Self {
+
+impl TryFrom<OpenAI> for Mistral {
+    type Error = InvalidRequestError;
+
+    fn try_from(value: OpenAI) -> Result<Self, Self::Error> {
+        match value {
+            OpenAI::ChatCompletions(_) | OpenAI::Responses(_) => {
+                Ok(Self::chat_completions())
+            }
+            OpenAI::Embeddings(_) => Ok(Self::embeddings()),
+            OpenAI::ImageGenerations(_)
+            | OpenAI::AudioTranscriptions(_)
+            | OpenAI::AudioSpeech(_) => {
+                Err(InvalidRequestError::UnsupportedEndpoint(format!(
+                    "{} is not supported by mistral",
+                    value.path()
+                )))
+            }
+        }
+    }
+}
+
+impl TryFrom<OpenAI> for Cohere {
+    type Error = InvalidRequestError;
+
+    fn try_from(value: OpenAI) -> Result<Self, Self::Error> {
+        match value {
+            OpenAI::ChatCompletions(_) | OpenAI::Responses(_) => {
+                Ok(Self::chat())
+            }
+            OpenAI::Embeddings(_) => Ok(Self::embed()),
+            OpenAI::ImageGenerations(_)
+            | OpenAI::AudioTranscriptions(_)
+            | OpenAI::AudioSpeech(_) => {
+                Err(InvalidRequestError::UnsupportedEndpoint(format!(
+                    "{} is not supported by cohere",
+                    value.path()
+                )))
+            }
+        }
+    }
+}
+
+impl TryFrom<OpenAI> for Vertex {
+    type Error = InvalidRequestError;
+
+    fn try_from(value: OpenAI) -> Result<Self, Self::Error> {
         match value {
-            OpenAI::ChatCompletions(_) => Self::converse(),
+            OpenAI::ChatCompletions(_) | OpenAI::Responses(_) => {
+                Ok(Self::chat_completions())
+            }
+            OpenAI::Embeddings(_)
+            | OpenAI::ImageGenerations(_)
+            | OpenAI::AudioTranscriptions(_)
+            | OpenAI::AudioSpeech(_) => {
+                Err(InvalidRequestError::UnsupportedEndpoint(format!(
+                    "{} is not supported by vertex",
+                    value.path()
+                )))
+            }
         }
     }
 }
@@ -0,0 +1,217 @@
+use async_openai::types::{
+    ChatCompletionRequestMessage, ChatCompletionTool,
+    ChatCompletionToolChoiceOption, ResponseFormat, Stop,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::{AiRequest, Endpoint},
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ChatCompletions;
+
+impl Endpoint for ChatCompletions {
+    // https://docs.mistral.ai/api/#tag/chat
+    const PATH: &'static str = "/v1/chat/completions";
+    type RequestBody = MistralChatCompletionRequest;
+    type ResponseBody = MistralChatCompletionResponse;
+    type StreamResponseBody = MistralChatCompletionChunk;
+    type ErrorResponseBody = MistralError;
+}
+
+/// Mistral rejects parameters it doesn't know about, so only the subset of
+/// `OpenAI` chat completion parameters it supports is sent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatCompletionRequestMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Stop>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random_seed: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<ChatCompletionTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ChatCompletionToolChoiceOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u8>,
+}
+
+impl AiRequest for MistralChatCompletionRequest {
+    fn is_stream(&self) -> bool {
+        self.stream.unwrap_or(false)
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(&InferenceProvider::Mistral, &self.model)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralChatCompletionResponse {
+    pub id: String,
+    #[serde(default)]
+    pub created: u32,
+    pub model: String,
+    pub choices: Vec<MistralChoice>,
+    #[serde(default)]
+    pub usage: Option<MistralUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralChoice {
+    pub index: u32,
+    pub message: MistralMessage,
+    #[serde(default)]
+    pub finish_reason: Option<MistralFinishReason>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralChatCompletionChunk {
+    pub id: String,
+    #[serde(default)]
+    pub created: u32,
+    pub model: String,
+    pub choices: Vec<MistralStreamChoice>,
+    #[serde(default)]
+    pub usage: Option<MistralUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralStreamChoice {
+    pub index: u32,
+    pub delta: MistralMessage,
+    #[serde(default)]
+    pub finish_reason: Option<MistralFinishReason>,
+}
+
+/// Used for both response messages and stream deltas.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MistralMessage {
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub content: Option<MistralContent>,
+    #[serde(default)]
+    pub tool_calls: Option<Vec<MistralToolCall>>,
+}
+
+/// Reasoning models respond with a list of chunks rather than a string.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum MistralContent {
+    Text(String),
+    Chunks(Vec<MistralContentChunk>),
+}
+
+impl MistralContent {
+    /// Joins the text chunks, dropping any other chunks such as thinking.
+    #[must_use]
+    pub fn into_text(self) -> String {
+        match self {
+            Self::Text(text) => text,
+            Self::Chunks(chunks) => {
+                chunks.into_iter().filter_map(|chunk| chunk.text).collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralContentChunk {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Unlike `OpenAI`, the `type` and `index` of a tool call may be omitted and
+/// the arguments may be sent as an object rather than a string.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralToolCall {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub index: Option<u32>,
+    pub function: MistralFunctionCall,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralFunctionCall {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub arguments: Option<serde_json::Value>,
+}
+
+impl MistralFunctionCall {
+    #[must_use]
+    pub fn arguments_string(&self) -> Option<String> {
+        match &self.arguments {
+            Some(serde_json::Value::String(arguments)) => {
+                Some(arguments.clone())
+            }
+            Some(arguments) => Some(arguments.to_string()),
+            None => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MistralFinishReason {
+    Stop,
+    Length,
+    ModelLength,
+    Error,
+    ToolCalls,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MistralUsage {
+    #[serde(default)]
+    pub prompt_tokens: u32,
+    #[serde(default)]
+    pub completion_tokens: u32,
+    #[serde(default)]
+    pub total_tokens: u32,
+}
+
+/// Validation errors have a list of `detail`s rather than a `message`, and
+/// other errors may nest the message in an object.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralError {
+    #[serde(default)]
+    pub message: Option<serde_json::Value>,
+    #[serde(default)]
+    pub detail: Option<serde_json::Value>,
+}
+
+impl MistralError {
+    #[must_use]
+    pub fn into_message(self) -> Option<String> {
+        match self.message.or(self.detail)? {
+            serde_json::Value::String(message) => Some(message),
+            message => Some(message.to_string()),
+        }
+    }
+}
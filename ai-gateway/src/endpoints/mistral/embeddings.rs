@@ -0,0 +1,39 @@
+use async_openai::types::CreateEmbeddingResponse;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::{AiRequest, Endpoint, mistral::chat_completions::MistralError},
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Embeddings;
+
+impl Endpoint for Embeddings {
+    // https://docs.mistral.ai/api/#tag/embeddings
+    const PATH: &'static str = "/v1/embeddings";
+    type RequestBody = MistralEmbeddingRequest;
+    type ResponseBody = CreateEmbeddingResponse;
+    // embeddings are never streamed
+    type StreamResponseBody = CreateEmbeddingResponse;
+    type ErrorResponseBody = MistralError;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MistralEmbeddingRequest {
+    pub model: String,
+    pub input: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_dimension: Option<u32>,
+}
+
+impl AiRequest for MistralEmbeddingRequest {
+    fn is_stream(&self) -> bool {
+        false
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(&InferenceProvider::Mistral, &self.model)
+    }
+}
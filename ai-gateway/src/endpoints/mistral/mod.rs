@@ -0,0 +1,42 @@
+pub(crate) mod chat_completions;
+pub(crate) mod embeddings;
+
+use super::EndpointType;
+use crate::endpoints::Endpoint;
+pub(crate) use crate::endpoints::mistral::{
+    chat_completions::ChatCompletions, embeddings::Embeddings,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
+pub enum Mistral {
+    ChatCompletions(ChatCompletions),
+    Embeddings(Embeddings),
+}
+
+impl Mistral {
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::ChatCompletions(_) => ChatCompletions::PATH,
+            Self::Embeddings(_) => Embeddings::PATH,
+        }
+    }
+
+    #[must_use]
+    pub fn chat_completions() -> Self {
+        Self::ChatCompletions(ChatCompletions)
+    }
+
+    #[must_use]
+    pub fn embeddings() -> Self {
+        Self::Embeddings(Embeddings)
+    }
+
+    #[must_use]
+    pub fn endpoint_type(&self) -> EndpointType {
+        match self {
+            Self::ChatCompletions(_) => EndpointType::Chat,
+            Self::Embeddings(_) => EndpointType::Embeddings,
+        }
+    }
+}
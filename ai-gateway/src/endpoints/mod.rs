@@ -1,16 +1,21 @@
 pub mod anthropic;
+pub mod azure;
 pub(crate) mod bedrock;
+pub mod cohere;
 pub mod google;
 pub mod mappings;
+pub mod mistral;
 pub mod ollama;
 pub mod openai;
+pub mod vertex;
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
     endpoints::{
-        anthropic::Anthropic, bedrock::Bedrock, google::Google, ollama::Ollama,
-        openai::OpenAI,
+        anthropic::Anthropic, azure::Azure, bedrock::Bedrock, cohere::Cohere,
+        google::Google, mistral::Mistral, ollama::Ollama, openai::OpenAI,
+        vertex::Vertex,
     },
     error::{
         internal::InternalError, invalid_req::InvalidRequestError,
@@ -55,6 +60,12 @@ macro_rules! define_endpoints {
 
 define_endpoints! {
     (ChatCompletions, "/chat/completions"),
+    (Embeddings, "/embeddings"),
+    (ImageGenerations, "/images/generations"),
+    (AudioTranscriptions, "/audio/transcriptions"),
+    (AudioSpeech, "/audio/speech"),
+    (Responses, "/responses"),
+    (Messages, "/v1/messages"),
 }
 
 pub trait AiRequest {
@@ -62,20 +73,34 @@ pub trait AiRequest {
     fn model(&self) -> Result<ModelId, MapperError>;
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ApiEndpoint {
     OpenAI(OpenAI),
+    /// An `OpenAI` endpoint served by a provider configured by name.
+    OpenAICompatible {
+        provider: InferenceProvider,
+        openai_endpoint: OpenAI,
+    },
     Anthropic(Anthropic),
     Google(Google),
     Ollama(Ollama),
     Bedrock(Bedrock),
+    Azure(Azure),
+    Mistral(Mistral),
+    Cohere(Cohere),
+    Vertex(Vertex),
 }
 
 impl ApiEndpoint {
     #[must_use]
     pub fn new(path: &str) -> Option<Self> {
         let endpoint_route = EndpointRoute::from_path(path)?;
-        Some(Self::OpenAI(OpenAI::try_from(&endpoint_route).ok()?))
+        OpenAI::try_from(&endpoint_route)
+            .map(Self::OpenAI)
+            .or_else(|_| {
+                Anthropic::try_from(&endpoint_route).map(Self::Anthropic)
+            })
+            .ok()
     }
 
     pub fn mapped(
@@ -84,26 +109,66 @@ impl ApiEndpoint {
     ) -> Result<Self, InvalidRequestError> {
         match (source_endpoint, target_provider) {
             (Self::OpenAI(source), InferenceProvider::Anthropic) => {
-                Ok(Self::Anthropic(Anthropic::from(source)))
+                Ok(Self::Anthropic(Anthropic::try_from(source)?))
             }
             (Self::OpenAI(source), InferenceProvider::OpenAI) => {
                 Ok(Self::OpenAI(source))
             }
             (Self::OpenAI(source), InferenceProvider::GoogleGemini) => {
-                Ok(Self::Google(Google::from(source)))
+                Ok(Self::Google(Google::try_from(source)?))
             }
             (Self::OpenAI(source), InferenceProvider::Ollama) => {
-                Ok(Self::Ollama(Ollama::from(source)))
+                Ok(Self::Ollama(Ollama::try_from(source)?))
             }
             (Self::OpenAI(source), InferenceProvider::Bedrock) => {
-                Ok(Self::Bedrock(Bedrock::from(source)))
+                Ok(Self::Bedrock(Bedrock::try_from(source)?))
+            }
+            (Self::OpenAI(source), InferenceProvider::Azure) => {
+                Ok(Self::Azure(Azure::try_from(source)?))
+            }
+            (Self::OpenAI(source), InferenceProvider::Mistral) => {
+                Ok(Self::Mistral(Mistral::try_from(source)?))
+            }
+            (Self::OpenAI(source), InferenceProvider::Cohere) => {
+                Ok(Self::Cohere(Cohere::try_from(source)?))
+            }
+            (Self::OpenAI(source), InferenceProvider::GoogleVertex) => {
+                Ok(Self::Vertex(Vertex::try_from(source)?))
+            }
+            (Self::OpenAI(source), InferenceProvider::Named(_)) => {
+                // compatible providers rarely serve the Responses API
+                let openai_endpoint = match source {
+                    OpenAI::Responses(_) => OpenAI::chat_completions(),
+                    source => source,
+                };
+                Ok(Self::OpenAICompatible {
+                    provider: target_provider.clone(),
+                    openai_endpoint,
+                })
             }
-            _ => {
-                // Full support for named providers is coming in a future PR
-                Err(InvalidRequestError::UnsupportedProvider(
-                    target_provider.clone(),
-                ))
+            (Self::Anthropic(source), InferenceProvider::Anthropic) => {
+                Ok(Self::Anthropic(source))
             }
+            // other providers are reached via their chat endpoints, which are
+            // the ones mapped from `OpenAI` chat completions
+            (
+                Self::Anthropic(source),
+                InferenceProvider::OpenAI
+                | InferenceProvider::GoogleGemini
+                | InferenceProvider::Ollama
+                | InferenceProvider::Bedrock
+                | InferenceProvider::Azure
+                | InferenceProvider::Mistral
+                | InferenceProvider::Cohere
+                | InferenceProvider::GoogleVertex
+                | InferenceProvider::Named(_),
+            ) => Self::mapped(
+                Self::OpenAI(OpenAI::from(source)),
+                target_provider,
+            ),
+            _ => Err(InvalidRequestError::UnsupportedProvider(
+                target_provider.clone(),
+            )),
         }
     }
 
@@ -111,10 +176,15 @@ impl ApiEndpoint {
     pub fn provider(&self) -> InferenceProvider {
         match self {
             Self::OpenAI(_) => InferenceProvider::OpenAI,
+            Self::OpenAICompatible { provider, .. } => provider.clone(),
             Self::Anthropic(_) => InferenceProvider::Anthropic,
             Self::Google(_) => InferenceProvider::GoogleGemini,
             Self::Ollama(_) => InferenceProvider::Ollama,
             Self::Bedrock(_) => InferenceProvider::Bedrock,
+            Self::Azure(_) => InferenceProvider::Azure,
+            Self::Mistral(_) => InferenceProvider::Mistral,
+            Self::Cohere(_) => InferenceProvider::Cohere,
+            Self::Vertex(_) => InferenceProvider::GoogleVertex,
         }
     }
 
@@ -125,6 +195,14 @@ impl ApiEndpoint {
     ) -> Result<String, InternalError> {
         match self {
             Self::OpenAI(openai) => Ok(openai.path().to_string()),
+            // relative to the base url, which usually includes the API
+            // version, e.g. `https://api.groq.com/openai/v1`
+            Self::OpenAICompatible {
+                openai_endpoint, ..
+            } => Ok(openai_endpoint
+                .path()
+                .trim_start_matches("/v1/")
+                .to_string()),
             Self::Anthropic(anthropic) => Ok(anthropic.path().to_string()),
             Self::Google(google) => Ok(google.path().to_string()),
             Self::Ollama(ollama) => Ok(ollama.path().to_string()),
@@ -136,6 +214,19 @@ impl ApiEndpoint {
                     Err(InternalError::Internal)
                 }
             }
+            Self::Azure(azure) => {
+                if let Some(model_id) = model_id {
+                    Ok(azure.path(model_id))
+                } else {
+                    tracing::error!("Azure path requires model id");
+                    Err(InternalError::Internal)
+                }
+            }
+            Self::Mistral(mistral) => Ok(mistral.path().to_string()),
+            Self::Cohere(cohere) => Ok(cohere.path().to_string()),
+            // relative to the base url, which includes the project and
+            // location
+            Self::Vertex(vertex) => Ok(vertex.path().to_string()),
         }
     }
 
@@ -143,10 +234,17 @@ impl ApiEndpoint {
     pub fn endpoint_type(&self) -> EndpointType {
         match self {
             Self::OpenAI(openai) => openai.endpoint_type(),
+            Self::OpenAICompatible {
+                openai_endpoint, ..
+            } => openai_endpoint.endpoint_type(),
             Self::Anthropic(anthropic) => anthropic.endpoint_type(),
             Self::Google(google) => google.endpoint_type(),
             Self::Ollama(ollama) => ollama.endpoint_type(),
             Self::Bedrock(bedrock) => bedrock.endpoint_type(),
+            Self::Azure(azure) => azure.endpoint_type(),
+            Self::Mistral(mistral) => mistral.endpoint_type(),
+            Self::Cohere(cohere) => cohere.endpoint_type(),
+            Self::Vertex(vertex) => vertex.endpoint_type(),
         }
     }
 }
@@ -155,6 +253,7 @@ impl ApiEndpoint {
 #[serde(rename_all = "kebab-case")]
 pub enum EndpointType {
     Chat,
+    Embeddings,
     Image,
     Audio,
 }
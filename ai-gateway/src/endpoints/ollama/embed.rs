@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::{AiRequest, Endpoint},
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Embed;
+
+impl Endpoint for Embed {
+    // https://github.com/ollama/ollama/blob/main/docs/api.md#generate-embeddings
+    const PATH: &'static str = "/api/embed";
+    type RequestBody = EmbedRequest;
+    type ResponseBody = EmbedResponse;
+    // embeddings are never streamed
+    type StreamResponseBody = EmbedResponse;
+    type ErrorResponseBody = OllamaError;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmbedRequest {
+    pub model: String,
+    pub input: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmbedResponse {
+    pub model: String,
+    pub embeddings: Vec<Vec<f32>>,
+    #[serde(default)]
+    pub prompt_eval_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OllamaError {
+    pub error: String,
+}
+
+impl AiRequest for EmbedRequest {
+    fn is_stream(&self) -> bool {
+        false
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(&InferenceProvider::Ollama, &self.model)
+    }
+}
@@ -1,14 +1,19 @@
 pub mod chat_completions;
+pub mod embed;
 
 use super::EndpointType;
 use crate::{
-    endpoints::{Endpoint, ollama::chat_completions::ChatCompletions},
+    endpoints::{
+        Endpoint,
+        ollama::{chat_completions::ChatCompletions, embed::Embed},
+    },
     error::invalid_req::InvalidRequestError,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
 pub enum Ollama {
     ChatCompletions(ChatCompletions),
+    Embed(Embed),
 }
 
 impl Ollama {
@@ -16,6 +21,7 @@ impl Ollama {
     pub fn path(&self) -> &str {
         match self {
             Self::ChatCompletions(_) => ChatCompletions::PATH,
+            Self::Embed(_) => Embed::PATH,
         }
     }
 
@@ -24,10 +30,16 @@ impl Ollama {
         Self::ChatCompletions(ChatCompletions)
     }
 
+    #[must_use]
+    pub fn embed() -> Self {
+        Self::Embed(Embed)
+    }
+
     #[must_use]
     pub fn endpoint_type(&self) -> EndpointType {
         match self {
             Self::ChatCompletions(_) => EndpointType::Chat,
+            Self::Embed(_) => EndpointType::Embeddings,
         }
     }
 }
@@ -38,6 +50,7 @@ impl TryFrom<&str> for Ollama {
     fn try_from(path: &str) -> Result<Self, Self::Error> {
         match path {
             ChatCompletions::PATH => Ok(Self::ChatCompletions(ChatCompletions)),
+            Embed::PATH => Ok(Self::Embed(Embed)),
             path => {
                 tracing::debug!(path = %path, "unsupported ollama path");
                 Err(InvalidRequestError::NotFound(path.to_string()))
@@ -0,0 +1,18 @@
+use bytes::Bytes;
+
+use crate::endpoints::Endpoint;
+
+/// Speech responses are binary audio, streamed back to the client as they
+/// are generated, so bodies are forwarded as is by the
+/// [`PassthroughConverter`](crate::middleware::mapper::passthrough::PassthroughConverter)
+/// rather than being deserialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct AudioSpeech;
+
+impl Endpoint for AudioSpeech {
+    const PATH: &'static str = "/v1/audio/speech";
+    type RequestBody = Bytes;
+    type ResponseBody = Bytes;
+    type StreamResponseBody = Bytes;
+    type ErrorResponseBody = async_openai::error::WrappedError;
+}
@@ -0,0 +1,20 @@
+use bytes::Bytes;
+
+use crate::endpoints::Endpoint;
+
+/// Transcription requests are `multipart/form-data` uploads, so bodies are
+/// forwarded as is by the
+/// [`PassthroughConverter`](crate::middleware::mapper::passthrough::PassthroughConverter)
+/// rather than being deserialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct AudioTranscriptions;
+
+impl Endpoint for AudioTranscriptions {
+    const PATH: &'static str = "/v1/audio/transcriptions";
+    type RequestBody = Bytes;
+    // depending on the `response_format`, this is either JSON or plain text
+    // (`text`, `srt`, `vtt`)
+    type ResponseBody = Bytes;
+    type StreamResponseBody = Bytes;
+    type ErrorResponseBody = async_openai::error::WrappedError;
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints::{AiRequest, Endpoint},
+    error::mapper::MapperError,
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ImageGenerations;
+
+impl Endpoint for ImageGenerations {
+    const PATH: &'static str = "/v1/images/generations";
+    type RequestBody = CreateImageRequest;
+    type ResponseBody = ImagesResponse;
+    // image generations are never streamed
+    type StreamResponseBody = ImagesResponse;
+    type ErrorResponseBody = async_openai::error::WrappedError;
+}
+
+/// Unlike `async_openai::types::CreateImageRequest`, the model is required
+/// and free-form since the router needs it to map the request to other
+/// providers.
+///
+/// Parameters we don't map (e.g. `background` for `gpt-image-1`) are kept in
+/// `extra` so they're still forwarded to `OpenAI`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CreateImageRequest {
+    pub model: String,
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n: Option<u8>,
+    /// `{width}x{height}`, e.g. `1024x1024`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ImageResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl CreateImageRequest {
+    /// Parses the requested `size` into `(width, height)`.
+    #[must_use]
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        let (width, height) = self.size.as_deref()?.split_once('x')?;
+        Some((width.parse().ok()?, height.parse().ok()?))
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageResponseFormat {
+    #[default]
+    Url,
+    B64Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImagesResponse {
+    pub created: u64,
+    pub data: Vec<Image>,
+}
+
+/// Either `url` or `b64_json` is set, depending on the requested
+/// `response_format`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Image {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub b64_json: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revised_prompt: Option<String>,
+}
+
+impl AiRequest for CreateImageRequest {
+    fn is_stream(&self) -> bool {
+        false
+    }
+
+    fn model(&self) -> Result<ModelId, MapperError> {
+        ModelId::from_str_and_provider(&InferenceProvider::OpenAI, &self.model)
+    }
+}
@@ -1,7 +1,16 @@
+pub mod audio_speech;
+pub mod audio_transcriptions;
 pub mod chat_completions;
+pub mod embeddings;
+pub mod image_generations;
+pub mod responses;
 
 use super::EndpointType;
-pub use crate::endpoints::openai::chat_completions::ChatCompletions;
+pub use crate::endpoints::openai::{
+    audio_speech::AudioSpeech, audio_transcriptions::AudioTranscriptions,
+    chat_completions::ChatCompletions, embeddings::Embeddings,
+    image_generations::ImageGenerations, responses::Responses,
+};
 use crate::{
     endpoints::{Endpoint, EndpointRoute},
     error::invalid_req::InvalidRequestError,
@@ -10,6 +19,11 @@ use crate::{
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
 pub enum OpenAI {
     ChatCompletions(ChatCompletions),
+    Embeddings(Embeddings),
+    ImageGenerations(ImageGenerations),
+    AudioTranscriptions(AudioTranscriptions),
+    AudioSpeech(AudioSpeech),
+    Responses(Responses),
 }
 
 impl OpenAI {
@@ -17,6 +31,11 @@ impl OpenAI {
     pub fn path(&self) -> &str {
         match self {
             Self::ChatCompletions(_) => ChatCompletions::PATH,
+            Self::Embeddings(_) => Embeddings::PATH,
+            Self::ImageGenerations(_) => ImageGenerations::PATH,
+            Self::AudioTranscriptions(_) => AudioTranscriptions::PATH,
+            Self::AudioSpeech(_) => AudioSpeech::PATH,
+            Self::Responses(_) => Responses::PATH,
         }
     }
 
@@ -25,10 +44,40 @@ impl OpenAI {
         Self::ChatCompletions(ChatCompletions)
     }
 
+    #[must_use]
+    pub fn embeddings() -> Self {
+        Self::Embeddings(Embeddings)
+    }
+
+    #[must_use]
+    pub fn image_generations() -> Self {
+        Self::ImageGenerations(ImageGenerations)
+    }
+
+    #[must_use]
+    pub fn audio_transcriptions() -> Self {
+        Self::AudioTranscriptions(AudioTranscriptions)
+    }
+
+    #[must_use]
+    pub fn audio_speech() -> Self {
+        Self::AudioSpeech(AudioSpeech)
+    }
+
+    #[must_use]
+    pub fn responses() -> Self {
+        Self::Responses(Responses)
+    }
+
     #[must_use]
     pub fn endpoint_type(&self) -> EndpointType {
         match self {
-            Self::ChatCompletions(_) => EndpointType::Chat,
+            Self::ChatCompletions(_) | Self::Responses(_) => EndpointType::Chat,
+            Self::Embeddings(_) => EndpointType::Embeddings,
+            Self::ImageGenerations(_) => EndpointType::Image,
+            Self::AudioTranscriptions(_) | Self::AudioSpeech(_) => {
+                EndpointType::Audio
+            }
         }
     }
 }
@@ -41,6 +90,20 @@ impl TryFrom<&EndpointRoute> for OpenAI {
             EndpointRoute::ChatCompletions => {
                 Ok(Self::ChatCompletions(ChatCompletions))
             }
+            EndpointRoute::Embeddings => Ok(Self::Embeddings(Embeddings)),
+            EndpointRoute::ImageGenerations => {
+                Ok(Self::ImageGenerations(ImageGenerations))
+            }
+            EndpointRoute::AudioTranscriptions => {
+                Ok(Self::AudioTranscriptions(AudioTranscriptions))
+            }
+            EndpointRoute::AudioSpeech => Ok(Self::AudioSpeech(AudioSpeech)),
+            EndpointRoute::Responses => Ok(Self::Responses(Responses)),
+            EndpointRoute::Messages => {
+                Err(InvalidRequestError::UnsupportedEndpoint(
+                    endpoint.path().to_string(),
+                ))
+            }
         }
     }
 }
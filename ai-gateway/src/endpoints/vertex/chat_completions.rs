@@ -0,0 +1,26 @@
+use async_openai::types::{
+    CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
+};
+
+use crate::endpoints::{
+    Endpoint, google::generate_contents::CreateChatCompletionRequestGemini,
+};
+
+/// Models are addressed as `{publisher}/{model}`.
+pub(crate) const VERTEX_MODEL_PREFIX: &str = "google/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ChatCompletions;
+
+impl Endpoint for ChatCompletions {
+    // https://cloud.google.com/vertex-ai/generative-ai/docs/migrate/openai/overview
+    //
+    // Relative to the base url, which includes the project and location, e.g.
+    // `https://us-central1-aiplatform.googleapis.com/v1/projects/{project}/locations/us-central1`
+    const PATH: &'static str = "endpoints/openapi/chat/completions";
+    // Vertex AI serves the same `OpenAI` compatible API as the Gemini API
+    type RequestBody = CreateChatCompletionRequestGemini;
+    type ResponseBody = CreateChatCompletionResponse;
+    type StreamResponseBody = CreateChatCompletionStreamResponse;
+    type ErrorResponseBody = async_openai::error::WrappedError;
+}
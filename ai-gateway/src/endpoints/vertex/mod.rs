@@ -0,0 +1,33 @@
+pub(crate) mod chat_completions;
+
+use super::EndpointType;
+use crate::endpoints::Endpoint;
+pub(crate) use crate::endpoints::vertex::chat_completions::{
+    ChatCompletions, VERTEX_MODEL_PREFIX,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::EnumIter)]
+pub enum Vertex {
+    ChatCompletions(ChatCompletions),
+}
+
+impl Vertex {
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::ChatCompletions(_) => ChatCompletions::PATH,
+        }
+    }
+
+    #[must_use]
+    pub fn chat_completions() -> Self {
+        Self::ChatCompletions(ChatCompletions)
+    }
+
+    #[must_use]
+    pub fn endpoint_type(&self) -> EndpointType {
+        match self {
+            Self::ChatCompletions(_) => EndpointType::Chat,
+        }
+    }
+}
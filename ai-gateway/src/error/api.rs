@@ -32,6 +32,18 @@ pub enum ApiError {
     Panic(String),
 }
 
+impl ApiError {
+    /// Buffers box the errors of the services they wrap, so they're unboxed
+    /// to keep e.g. a rate limit error from becoming an internal error.
+    #[must_use]
+    pub fn from_buffer_error(error: tower::BoxError) -> Self {
+        match error.downcast::<ApiError>() {
+            Ok(error) => *error,
+            Err(error) => InternalError::BufferError(error).into(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: ErrorDetails,
@@ -144,3 +156,27 @@ impl ErrorMetric for ApiError {
         ApiErrorMetric::from(self).error_metric()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::invalid_req::TooManyRequestsError;
+
+    #[test]
+    fn buffer_errors_keep_their_status() {
+        let error = ApiError::InvalidRequest(
+            InvalidRequestError::TooManyRequests(TooManyRequestsError {
+                ratelimit_limit: 10,
+                ratelimit_remaining: 0,
+                retry_after: 1,
+            }),
+        );
+        let response =
+            ApiError::from_buffer_error(Box::new(error)).into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let response =
+            ApiError::from_buffer_error("buffer closed".into()).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
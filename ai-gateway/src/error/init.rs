@@ -4,6 +4,7 @@ use thiserror::Error;
 
 use crate::{
     config::validation::ModelMappingValidationError,
+    endpoints::EndpointType,
     types::{provider::InferenceProvider, router::RouterId},
 };
 
@@ -32,6 +33,14 @@ pub enum InitError {
     InvalidWeight(InferenceProvider),
     /// Invalid weighted balancer: {0}
     InvalidWeightedBalancer(String),
+    /// Provider is listed in more than one priority tier: {0}
+    InvalidPriorityBalancer(InferenceProvider),
+    /// Model is listed in more than one model balance group: {0}
+    InvalidModelBalancer(String),
+    /// Invalid affinity header: {0}
+    InvalidAffinityHeader(String),
+    /// Balance config not found for endpoint type: {0:?}
+    BalanceConfigNotFound(EndpointType),
     /// Converter registry endpoints not configured for provider: {0}
     EndpointsNotConfigured(InferenceProvider),
     /// Failed to create redis pool: {0}
@@ -64,4 +73,8 @@ pub enum InitError {
     DatabaseConnection(sqlx::Error),
     /// Provider not yet supported: {0}
     ProviderNotSupported(InferenceProvider),
+    /// Invalid service account key: {0}
+    InvalidServiceAccountKey(String),
+    /// Invalid probe for {0}: {1}
+    InvalidProbe(InferenceProvider, String),
 }
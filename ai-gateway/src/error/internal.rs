@@ -59,6 +59,8 @@ pub enum InternalError {
     InvalidHeader(#[from] http::header::InvalidHeaderValue),
     /// Failed to complete mapping task: {0}
     MappingTaskError(tokio::task::JoinError),
+    /// Failed to complete spend task: {0}
+    SpendTaskError(tokio::task::JoinError),
     /// Converter not present for {0:?} -> {1:?}
     InvalidConverter(ApiEndpoint, ApiEndpoint),
     /// Upstream 5xx error: {0}
@@ -67,19 +69,29 @@ pub enum InternalError {
     MetricsNotConfigured(ApiEndpoint),
     /// Failed to sign AWS request: {0}
     AwsRequestSigningError(String),
+    /// Failed to get Vertex AI access token: {0}
+    VertexTokenError(String),
     /// Cache error: {0}
     CacheError(http_cache::BoxError),
     /// Redis error: {0}
     RedisError(redis::RedisError),
     /// Pool error: {0}
     PoolError(r2d2::Error),
+    /// The circuit of provider {0} is open
+    CircuitOpen(InferenceProvider),
 }
 
 impl IntoResponse for InternalError {
     fn into_response(self) -> Response {
         error!(error = %self, "internal error");
+        // so that the request is retried or falls back to another provider
+        let status = if matches!(self, Self::CircuitOpen(_)) {
+            StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
+            status,
             Json(ErrorResponse {
                 error: ErrorDetails {
                     message: self.to_string(),
@@ -142,12 +154,16 @@ pub enum InternalErrorMetric {
     MetricsNotConfigured,
     /// Failed to sign AWS request
     AwsRequestSigningError,
+    /// Failed to get Vertex AI access token
+    VertexTokenError,
     /// Cache error
     CacheError,
     /// Redis error
     RedisError,
     /// Pool error
     PoolError,
+    /// Circuit open
+    CircuitOpen,
 }
 
 impl From<&InternalError> for InternalErrorMetric {
@@ -173,7 +189,8 @@ impl From<&InternalError> for InternalErrorMetric {
             InternalError::BufferError(_) => Self::BufferError,
             InternalError::InvalidUri(_) => Self::InvalidUri,
             InternalError::InvalidHeader(_) => Self::InvalidHeader,
-            InternalError::MappingTaskError(_) => Self::TokioTaskError,
+            InternalError::MappingTaskError(_)
+            | InternalError::SpendTaskError(_) => Self::TokioTaskError,
             InternalError::InvalidConverter(_, _) => Self::InvalidConverter,
             InternalError::Provider5xxError(_) => Self::Provider5xxError,
             InternalError::MetricsNotConfigured(_) => {
@@ -182,9 +199,11 @@ impl From<&InternalError> for InternalErrorMetric {
             InternalError::AwsRequestSigningError(_) => {
                 Self::AwsRequestSigningError
             }
+            InternalError::VertexTokenError(_) => Self::VertexTokenError,
             InternalError::CacheError(_) => Self::CacheError,
             InternalError::RedisError(_) => Self::RedisError,
             InternalError::PoolError(_) => Self::PoolError,
+            InternalError::CircuitOpen(_) => Self::CircuitOpen,
         }
     }
 }
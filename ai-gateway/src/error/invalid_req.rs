@@ -22,6 +22,17 @@ pub struct TooManyRequestsError {
     pub retry_after: u64,
 }
 
+#[derive(Debug, Display)]
+#[displaydoc("the {period} {scope} budget of ${limit} has been spent.")]
+pub struct BudgetExceededError {
+    /// The scope of the budget, e.g. `per-org`
+    pub scope: &'static str,
+    /// The period of the budget, e.g. `daily`
+    pub period: String,
+    /// The budget in USD
+    pub limit: rust_decimal::Decimal,
+}
+
 /// User errors
 #[derive(Debug, Error, Display, strum::AsRefStr)]
 pub enum InvalidRequestError {
@@ -41,12 +52,16 @@ pub enum InvalidRequestError {
     InvalidUrl(String),
     /// Invalid request body: {0}
     InvalidRequestBody(#[from] serde_json::Error),
+    /// Invalid multipart form: {0}
+    InvalidMultipartForm(String),
     /// Upstream 4xx error: {0}
     Provider4xxError(StatusCode),
     /// Invalid cache config
     InvalidCacheConfig,
     /// Too many requests: {0}
     TooManyRequests(TooManyRequestsError),
+    /// Budget exceeded: {0}
+    BudgetExceeded(BudgetExceededError),
 }
 
 impl IntoResponse for InvalidRequestError {
@@ -112,6 +127,19 @@ impl IntoResponse for InvalidRequestError {
                 )
                     .into_response()
             }
+            // mirrors the `OpenAI` error for an exhausted quota
+            Self::BudgetExceeded(_) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(ErrorResponse {
+                    error: ErrorDetails {
+                        message,
+                        r#type: Some("insufficient_quota".to_string()),
+                        param: None,
+                        code: Some("insufficient_quota".to_string()),
+                    },
+                }),
+            )
+                .into_response(),
             _ => (
                 StatusCode::BAD_REQUEST,
                 Json(ErrorResponse {
@@ -147,6 +175,8 @@ pub enum InvalidRequestErrorMetric {
     Provider4xxError,
     /// Too many requests
     TooManyRequests,
+    /// Budget exceeded
+    BudgetExceeded,
 }
 
 impl From<&InvalidRequestError> for InvalidRequestErrorMetric {
@@ -162,11 +192,13 @@ impl From<&InvalidRequestError> for InvalidRequestErrorMetric {
             | InvalidRequestError::UnsupportedEndpoint(_)
             | InvalidRequestError::InvalidCacheConfig => Self::InvalidRequest,
             InvalidRequestError::InvalidUrl(_) => Self::InvalidUrl,
-            InvalidRequestError::InvalidRequestBody(_) => {
+            InvalidRequestError::InvalidRequestBody(_)
+            | InvalidRequestError::InvalidMultipartForm(_) => {
                 Self::InvalidRequestBody
             }
             InvalidRequestError::Provider4xxError(_) => Self::Provider4xxError,
             InvalidRequestError::TooManyRequests(_) => Self::TooManyRequests,
+            InvalidRequestError::BudgetExceeded(_) => Self::BudgetExceeded,
         }
     }
 }
@@ -32,6 +32,12 @@ pub enum MapperError {
     ImageMappingInvalid(String),
     /// Failed to map Bedrock message: {0}
     FailedToMapBedrockMessage(BoxError),
+    /// Unsupported embedding input: {0}
+    UnsupportedEmbeddingInput(String),
+    /// Unsupported image request: {0}
+    UnsupportedImageRequest(String),
+    /// Unsupported responses input: {0}
+    UnsupportedResponsesInput(String),
 }
 
 /// Error types that can occur when mapping requests between providers.
@@ -61,6 +67,12 @@ pub enum MapperErrorMetric {
     ImageMappingInvalid,
     /// Failed to map Bedrock message
     FailedToMapBedrockMessage,
+    /// Unsupported embedding input
+    UnsupportedEmbeddingInput,
+    /// Unsupported image request
+    UnsupportedImageRequest,
+    /// Unsupported responses input
+    UnsupportedResponsesInput,
 }
 
 impl From<&MapperError> for MapperErrorMetric {
@@ -80,6 +92,15 @@ impl From<&MapperError> for MapperErrorMetric {
             MapperError::FailedToMapBedrockMessage(_) => {
                 Self::FailedToMapBedrockMessage
             }
+            MapperError::UnsupportedEmbeddingInput(_) => {
+                Self::UnsupportedEmbeddingInput
+            }
+            MapperError::UnsupportedImageRequest(_) => {
+                Self::UnsupportedImageRequest
+            }
+            MapperError::UnsupportedResponsesInput(_) => {
+                Self::UnsupportedResponsesInput
+            }
         }
     }
 }
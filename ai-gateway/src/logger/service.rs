@@ -55,6 +55,10 @@ pub struct LoggerService {
     start_instant: Instant,
     response_body: BodyReader,
     request_body: Bytes,
+    /// Binary response bodies (e.g. generated speech) are not uploaded, only
+    /// their size is logged.
+    #[builder(default)]
+    binary_response: bool,
     target_url: Url,
     request_headers: HeaderMap,
     response_status: StatusCode,
@@ -83,6 +87,11 @@ impl LoggerService {
         tracing::trace!(tfft_duration = ?tfft_duration, "tfft_duration");
         let req_body_len = self.request_body.len();
         let resp_body_len = response_body.len();
+        let response_body = if self.binary_response {
+            Bytes::new()
+        } else {
+            response_body
+        };
         let request_id = Uuid::new_v4();
         let s3_client = match self.app_state.config().deployment_target {
             DeploymentTarget::Cloud => S3Client::cloud(&self.app_state.0.minio),
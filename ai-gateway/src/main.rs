@@ -6,7 +6,8 @@ use ai_gateway::{
     control_plane::websocket::ControlPlaneClient,
     db_listener::DatabaseListener,
     discover::monitor::{
-        health::provider::HealthMonitor, rate_limit::RateLimitMonitor,
+        health::provider::HealthMonitor, probe::Prober,
+        rate_limit::RateLimitMonitor,
     },
     error::{init::InitError, runtime::RuntimeError},
     metrics::system::SystemMetrics,
@@ -106,6 +107,7 @@ async fn run_app(config: Config) -> Result<(), RuntimeError> {
     let config = app.state.config();
     let health_monitor = HealthMonitor::new(app.state.clone());
     let rate_limit_monitor = RateLimitMonitor::new(app.state.clone());
+    let prober = Prober::new(app.state.clone())?;
     let control_plane_state = app.state.0.control_plane_state.clone();
 
     let rate_limiting_cleanup_service =
@@ -157,6 +159,12 @@ async fn run_app(config: Config) -> Result<(), RuntimeError> {
         ))
         .register(TaggedService::new("system-metrics", SystemMetrics));
 
+    if let Some(prober) = prober {
+        meltdown =
+            meltdown.register(TaggedService::new("provider-prober", prober));
+        tasks.push("provider-prober");
+    }
+
     if let Some(rate_limiting_cleanup_service) = rate_limiting_cleanup_service {
         meltdown = meltdown.register(TaggedService::new(
             "rate-limiting-cleanup",
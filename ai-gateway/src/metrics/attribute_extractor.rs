@@ -3,7 +3,9 @@ use opentelemetry::KeyValue;
 use tower_otel_http_metrics::ResponseAttributeExtractor;
 
 use crate::types::{
-    extensions::MapperContext, provider::InferenceProvider, router::RouterId,
+    extensions::MapperContext,
+    provider::{InferenceProvider, Region},
+    router::RouterId,
 };
 
 #[derive(Debug, Clone)]
@@ -29,6 +31,9 @@ impl<B> ResponseAttributeExtractor<B> for AttributeExtractor {
         if let Some(provider) = resp_extensions.get::<InferenceProvider>() {
             attributes.push(KeyValue::new("provider", provider.to_string()));
         }
+        if let Some(region) = resp_extensions.get::<Region>() {
+            attributes.push(KeyValue::new("region", region.to_string()));
+        }
         if let Some(router_id) = resp_extensions.get::<RouterId>() {
             attributes.push(KeyValue::new("router_id", router_id.to_string()));
         }
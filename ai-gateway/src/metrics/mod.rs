@@ -19,6 +19,13 @@ pub struct Metrics {
     pub request_count: Counter<u64>,
     pub response_count: Counter<u64>,
     pub tfft_duration: Histogram<f64>,
+    pub retry_attempts: Histogram<u64>,
+    pub fallback_count: Counter<u64>,
+    pub hedge_count: Counter<u64>,
+    pub hedge_won_count: Counter<u64>,
+    pub stream_failover_count: Counter<u64>,
+    pub probe_count: Counter<u64>,
+    pub probe_duration: Histogram<f64>,
     pub cache: CacheMetrics,
 }
 
@@ -61,6 +68,46 @@ impl Metrics {
             .with_unit("ms")
             .with_description("Time to first token duration")
             .build();
+        let retry_attempts = meter
+            .u64_histogram("retry_attempts")
+            .with_description("Number of retries made per request")
+            .build();
+        let fallback_count = meter
+            .u64_counter("fallback_count")
+            .with_description(
+                "Number of requests re-dispatched to a fallback provider",
+            )
+            .build();
+        let hedge_count = meter
+            .u64_counter("hedge_count")
+            .with_description(
+                "Number of hedged requests sent to a second provider",
+            )
+            .build();
+        let hedge_won_count = meter
+            .u64_counter("hedge_won_count")
+            .with_description(
+                "Number of hedged requests that responded before the original",
+            )
+            .build();
+        let stream_failover_count = meter
+            .u64_counter("stream_failover_count")
+            .with_description(
+                "Number of streams that failed partway through, by action \
+                 taken",
+            )
+            .build();
+        let probe_count = meter
+            .u64_counter("probe_count")
+            .with_description(
+                "Number of active health probes sent to providers",
+            )
+            .build();
+        let probe_duration = meter
+            .f64_histogram("probe_duration")
+            .with_unit("ms")
+            .with_description("Duration of active health probes")
+            .build();
         let cache_hits = meter
             .u64_counter("cache_hits")
             .with_description("Number of cache hits")
@@ -86,6 +133,13 @@ impl Metrics {
             request_count,
             response_count,
             tfft_duration,
+            retry_attempts,
+            fallback_count,
+            hedge_count,
+            hedge_won_count,
+            stream_failover_count,
+            probe_count,
+            probe_duration,
             cache,
         }
     }
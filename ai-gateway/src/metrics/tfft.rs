@@ -1,5 +1,7 @@
 use std::{
+    collections::VecDeque,
     pin::Pin,
+    sync::{Mutex, PoisonError},
     task::{Context, Poll},
     time::Duration,
 };
@@ -40,3 +42,117 @@ impl Future for TFFTFuture {
         }
     }
 }
+
+/// The most recent time to first byte samples, for estimating percentiles
+/// that can't be read back from the OpenTelemetry histogram.
+#[derive(Debug)]
+pub struct TfftWindow {
+    /// Each sample and when it was recorded.
+    samples: Mutex<VecDeque<(Instant, Duration)>>,
+    capacity: usize,
+}
+
+impl TfftWindow {
+    /// Percentiles aren't estimated from fewer samples than this.
+    const MIN_SAMPLES: usize = 20;
+
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    pub fn record(&self, sample: Duration) {
+        let mut samples =
+            self.samples.lock().unwrap_or_else(PoisonError::into_inner);
+        if samples.len() == self.capacity {
+            samples.pop_front();
+        }
+        samples.push_back((Instant::now(), sample));
+    }
+
+    /// The given percentile, in `[0, 1]`, of the recorded samples.
+    #[must_use]
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        self.percentile_within(percentile, None)
+    }
+
+    /// The given percentile, in `[0, 1]`, of the samples recorded within
+    /// `max_age`, or of all samples if `None`.
+    #[must_use]
+    pub fn percentile_within(
+        &self,
+        percentile: f64,
+        max_age: Option<Duration>,
+    ) -> Option<Duration> {
+        let mut samples = self
+            .samples
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|(recorded_at, _)| {
+                max_age.is_none_or(|max_age| recorded_at.elapsed() <= max_age)
+            })
+            .map(|(_, sample)| *sample)
+            .collect::<Vec<_>>();
+        if samples.len() < Self::MIN_SAMPLES {
+            return None;
+        }
+        samples.sort_unstable();
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let index = ((samples.len() - 1) as f64 * percentile.clamp(0.0, 1.0))
+            .round() as usize;
+        samples.get(index).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_of_recent_samples() {
+        let window = TfftWindow::new(100);
+        for millis in 1..=10 {
+            window.record(Duration::from_millis(millis));
+        }
+        assert_eq!(window.percentile(0.95), None);
+
+        // older samples are evicted once the window is full
+        for millis in 1..=200 {
+            window.record(Duration::from_millis(millis));
+        }
+        assert_eq!(window.percentile(0.0), Some(Duration::from_millis(101)));
+        assert_eq!(window.percentile(0.95), Some(Duration::from_millis(195)));
+
+        // and once they are older than the max age
+        std::thread::sleep(Duration::from_millis(2));
+        let max_age = Some(Duration::from_millis(1));
+        assert_eq!(window.percentile_within(0.95, max_age), None);
+    }
+
+    #[test]
+    fn percentile_within_ignores_older_samples() {
+        let window = TfftWindow::new(100);
+        for _ in 0..20 {
+            window.record(Duration::from_millis(500));
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        for _ in 0..20 {
+            window.record(Duration::from_millis(10));
+        }
+        let max_age = Some(Duration::from_millis(10));
+        assert_eq!(
+            window.percentile_within(1.0, max_age),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(window.percentile(1.0), Some(Duration::from_millis(500)));
+        assert_eq!(window.percentile(0.0), Some(Duration::from_millis(10)));
+    }
+}
@@ -1,10 +1,18 @@
-use std::task::{Context, Poll};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
+use futures::ready;
 use http::{Request, Response};
+use pin_project_lite::pin_project;
 use tower::{Layer, Service};
 use typed_builder::TypedBuilder;
 
-use crate::types::{provider::InferenceProvider, router::RouterId};
+use crate::types::{
+    extensions::SelectedProvider, provider::InferenceProvider, router::RouterId,
+};
 
 /// [`Layer`] to add all required request extensions.
 #[derive(Clone, Debug, TypedBuilder)]
@@ -38,7 +46,7 @@ where
 {
     type Response = S::Response;
     type Error = S::Error;
-    type Future = S::Future;
+    type Future = ResponseFuture<S::Future>;
 
     #[inline]
     fn poll_ready(
@@ -50,10 +58,45 @@ where
 
     fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
         let extensions = req.extensions_mut();
+        if let Some(selected) = extensions.get::<SelectedProvider>() {
+            selected.set(self.inference_provider.clone());
+        }
         extensions.insert(self.inference_provider.clone());
         if let Some(router_id) = self.router_id.clone() {
             extensions.insert(router_id);
         }
-        self.inner.call(req)
+        ResponseFuture {
+            inner: self.inner.call(req),
+            inference_provider: Some(self.inference_provider.clone()),
+        }
+    }
+}
+
+pin_project! {
+    /// Ensures the provider that handled the request is known to outer
+    /// services, including for error responses that don't go through the
+    /// dispatcher's extension copying.
+    pub struct ResponseFuture<F> {
+        #[pin]
+        inner: F,
+        inference_provider: Option<InferenceProvider>,
+    }
+}
+
+impl<F, ResBody, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response<ResBody>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut response = ready!(this.inner.poll(cx))?;
+        if let Some(inference_provider) = this.inference_provider.take() {
+            if response.extensions().get::<InferenceProvider>().is_none() {
+                response.extensions_mut().insert(inference_provider);
+            }
+        }
+        Poll::Ready(Ok(response))
     }
 }
@@ -2,3 +2,4 @@ pub mod optional;
 mod service;
 
 pub use optional::{Layer as CacheLayer, Service as CacheService};
+pub(crate) use service::is_cache_hit;
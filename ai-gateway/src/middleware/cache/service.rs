@@ -370,6 +370,12 @@ enum CacheCheckResult {
     Miss,
 }
 
+/// Whether a response was served from the cache.
+pub(crate) fn is_cache_hit(headers: &HeaderMap) -> bool {
+    let hit = CACHE_HIT_HEADER_VALUE;
+    headers.get(CACHE_HIT_HEADER) == Some(&hit)
+}
+
 fn bucket_header_value(bucket: u8) -> HeaderValue {
     HeaderValue::from_str(&bucket.to_string())
         .unwrap_or_else(|_| HeaderValue::from_static("0"))
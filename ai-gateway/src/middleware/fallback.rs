@@ -0,0 +1,500 @@
+use std::{
+    convert::Infallible,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum_core::body::{Body, BodyDataStream};
+use bytes::Bytes;
+use compact_str::CompactString;
+use futures::{StreamExt, future::BoxFuture};
+use http::{StatusCode, header::CONTENT_TYPE, request::Parts};
+use http_body_util::BodyExt;
+use indexmap::{IndexMap, IndexSet};
+use opentelemetry::KeyValue;
+use tower::{Layer as _, ServiceExt};
+
+use crate::{
+    app_state::AppState,
+    balancer::model::{model_group, source_mapper_context},
+    config::{
+        balance::BalanceConfigInner, fallback::FallbackConfig,
+        router::RouterConfig,
+    },
+    discover::provider::{BalancerProviders, DiscoveredProviders},
+    dispatcher::DispatcherService,
+    error::{
+        api::{ApiError, ErrorDetails, ErrorResponse},
+        internal::InternalError,
+    },
+    middleware::{mapper::openai::SERVER_ERROR_TYPE, request_context},
+    types::{
+        provider::InferenceProvider, request::Request, response::Response,
+    },
+};
+
+type FallbackDispatcher = request_context::Service<DispatcherService>;
+
+/// The providers that requests sent to one balancer can fall back to.
+///
+/// They're resolved from the providers the balancer has currently
+/// discovered, so providers it removed for being unhealthy or rate limited
+/// are skipped.
+#[derive(Debug, Clone)]
+struct Candidates {
+    /// The order in which providers are tried.
+    order: Arc<IndexSet<InferenceProvider>>,
+    /// Mirrors the balancer's discovered providers.
+    discovered: DiscoveredProviders,
+    request_context_layer: request_context::Layer,
+}
+
+impl Candidates {
+    fn new(
+        config: &FallbackConfig,
+        balance_config: &BalanceConfigInner,
+        discovered: DiscoveredProviders,
+        request_context_layer: &request_context::Layer,
+    ) -> Self {
+        Self {
+            order: Arc::new(config.providers(balance_config)),
+            discovered,
+            request_context_layer: request_context_layer.clone(),
+        }
+    }
+
+    /// Returns `None` if the provider isn't currently discovered.
+    fn dispatcher(
+        &self,
+        provider: &InferenceProvider,
+    ) -> Option<FallbackDispatcher> {
+        let service = self
+            .discovered
+            .with(|services| services.get(provider).cloned())?;
+        Some(self.request_context_layer.layer(service))
+    }
+}
+
+/// The fallback candidates of the endpoint level balancer and of each model
+/// group's balancer.
+#[derive(Debug)]
+struct Fallbacks {
+    router_config: Arc<RouterConfig>,
+    endpoint: Candidates,
+    models: IndexMap<CompactString, Candidates>,
+}
+
+impl Fallbacks {
+    /// The candidates of the balancer that the request body is routed to by
+    /// the [`ModelBalancer`](crate::balancer::model::ModelBalancer).
+    fn for_body(&self, body: &[u8]) -> &Candidates {
+        model_group(&self.router_config, &source_mapper_context(body))
+            .and_then(|group| self.models.get(group))
+            .unwrap_or(&self.endpoint)
+    }
+}
+
+/// Re-dispatches a request to the next provider in the fallback order when
+/// the provider selected by the load balancer fails.
+///
+/// Since each fallback goes through its own mapper, the request body and
+/// model are re-mapped for the fallback provider.
+///
+/// With mid-stream failover enabled, streamed responses that break partway
+/// through are also failed over.
+#[derive(Debug, Clone)]
+pub struct Service<S> {
+    inner: S,
+    app_state: AppState,
+    /// If `None`, fallbacks are disabled and requests are passed through.
+    fallbacks: Option<Arc<Fallbacks>>,
+    mid_stream: bool,
+}
+
+impl<S> tower::Service<Request> for Service<S>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = ApiError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[tracing::instrument(level = "debug", name = "fallback", skip_all)]
+    fn call(&mut self, req: Request) -> Self::Future {
+        // see: https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut this = self.clone();
+        std::mem::swap(self, &mut this);
+        match this.fallbacks.clone() {
+            Some(fallbacks) => {
+                Box::pin(this.call_with_fallbacks(fallbacks, req))
+            }
+            None => Box::pin(this.inner.call(req)),
+        }
+    }
+}
+
+impl<S> Service<S>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    async fn call_with_fallbacks(
+        mut self,
+        fallbacks: Arc<Fallbacks>,
+        req: Request,
+    ) -> Result<Response, ApiError> {
+        // the body must be buffered so that it can be replayed on each attempt
+        let (parts, body) = req.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(InternalError::CollectBodyError)?
+            .to_bytes();
+        let candidates = fallbacks.for_body(&body).clone();
+
+        // the inner service was already driven to readiness by our caller
+        let mut response = self.inner.call(replay(&parts, &body)).await?;
+        let mut attempted = Vec::with_capacity(candidates.order.len());
+        for provider in candidates.order.iter() {
+            if !should_fall_back(response.status()) {
+                break;
+            }
+            let failed =
+                response.extensions().get::<InferenceProvider>().cloned();
+            if let Some(failed) = &failed {
+                attempted.push(failed.clone());
+            }
+            if attempted.contains(provider) {
+                continue;
+            }
+            let Some(dispatcher) = candidates.dispatcher(provider) else {
+                tracing::debug!(
+                    fallback = %provider,
+                    "fallback provider is not discovered, skipping"
+                );
+                continue;
+            };
+
+            tracing::warn!(
+                failed = ?failed,
+                fallback = %provider,
+                status = %response.status(),
+                "provider failed, falling back to next provider"
+            );
+            self.app_state.0.metrics.fallback_count.add(
+                1,
+                &[
+                    KeyValue::new(
+                        "failed",
+                        failed
+                            .as_ref()
+                            .map(ToString::to_string)
+                            .unwrap_or_default(),
+                    ),
+                    KeyValue::new("fallback", provider.to_string()),
+                ],
+            );
+            response = dispatch(dispatcher, replay(&parts, &body)).await;
+        }
+
+        if self.mid_stream && is_event_stream(&response) {
+            let provider =
+                response.extensions().get::<InferenceProvider>().cloned();
+            let (response_parts, response_body) = response.into_parts();
+            let failover = StreamFailover {
+                app_state: self.app_state,
+                candidates,
+                attempted,
+                provider,
+                parts,
+                body,
+                stream: response_body.into_data_stream(),
+                sent: false,
+            };
+            response =
+                Response::from_parts(response_parts, failover.into_body());
+        }
+
+        Ok(response)
+    }
+}
+
+/// Fails over a streamed response when the stream breaks before it
+/// completes.
+///
+/// If nothing has been sent to the client yet, the request is re-issued to
+/// the next fallback provider and its stream is sent instead. Otherwise the
+/// client has already seen part of the response, so the stream is ended with
+/// an `error` event rather than being cut off.
+struct StreamFailover {
+    app_state: AppState,
+    candidates: Candidates,
+    /// Providers that have already failed for this request.
+    attempted: Vec<InferenceProvider>,
+    /// The provider currently being streamed from.
+    provider: Option<InferenceProvider>,
+    parts: Parts,
+    body: Bytes,
+    stream: BodyDataStream,
+    /// Whether any of the stream has been sent to the client.
+    sent: bool,
+}
+
+enum Chunk {
+    Data(Bytes),
+    /// The error event, which is always the last chunk.
+    Error(Bytes),
+}
+
+impl StreamFailover {
+    fn into_body(self) -> Body {
+        let stream = futures::stream::unfold(Some(self), |this| async move {
+            let mut this = this?;
+            match this.next().await? {
+                Chunk::Data(bytes) => {
+                    Some((Ok::<_, Infallible>(bytes), Some(this)))
+                }
+                Chunk::Error(bytes) => Some((Ok(bytes), None)),
+            }
+        });
+        Body::from_stream(stream)
+    }
+
+    async fn next(&mut self) -> Option<Chunk> {
+        loop {
+            match self.stream.next().await? {
+                Ok(bytes) => {
+                    self.sent |= !bytes.is_empty();
+                    return Some(Chunk::Data(bytes));
+                }
+                Err(error) => {
+                    if !self.sent && self.restart(&error).await {
+                        continue;
+                    }
+                    tracing::warn!(
+                        failed = ?self.provider,
+                        error = %error,
+                        sent = self.sent,
+                        "stream failed, sending error event"
+                    );
+                    self.record("error_event");
+                    return Some(Chunk::Error(error_event()));
+                }
+            }
+        }
+    }
+
+    /// Replaces the stream with one from the next fallback provider that
+    /// responds successfully, returning `false` if there are none left.
+    async fn restart(&mut self, error: &axum_core::Error) -> bool {
+        if let Some(failed) = &self.provider {
+            self.attempted.push(failed.clone());
+        }
+        for provider in self.candidates.order.iter() {
+            if self.attempted.contains(provider) {
+                continue;
+            }
+            let Some(dispatcher) = self.candidates.dispatcher(provider) else {
+                continue;
+            };
+            tracing::warn!(
+                failed = ?self.provider,
+                fallback = %provider,
+                error = %error,
+                "stream failed before anything was sent, restarting on next \
+                 provider"
+            );
+            self.record("restarted");
+            self.attempted.push(provider.clone());
+            self.provider = Some(provider.clone());
+
+            let response =
+                dispatch(dispatcher, replay(&self.parts, &self.body)).await;
+            if response.status().is_success() {
+                self.stream = response.into_body().into_data_stream();
+                return true;
+            }
+            tracing::debug!(
+                fallback = %provider,
+                status = %response.status(),
+                "fallback provider failed"
+            );
+        }
+        false
+    }
+
+    fn record(&self, action: &'static str) {
+        self.app_state.0.metrics.stream_failover_count.add(
+            1,
+            &[
+                KeyValue::new(
+                    "failed",
+                    self.provider
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                ),
+                KeyValue::new("action", action),
+            ],
+        );
+    }
+}
+
+fn error_event() -> Bytes {
+    let error = ErrorResponse {
+        error: ErrorDetails {
+            message: "The provider's stream failed before it completed"
+                .to_string(),
+            r#type: Some(SERVER_ERROR_TYPE.to_string()),
+            param: None,
+            code: None,
+        },
+    };
+    let data = serde_json::to_string(&error)
+        .expect("error response is always serializable");
+    Bytes::from(format!("event: error\ndata: {data}\n\n"))
+}
+
+fn is_event_stream(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/event-stream"))
+}
+
+fn replay(parts: &Parts, body: &Bytes) -> Request {
+    Request::from_parts(parts.clone(), Body::from(body.clone()))
+}
+
+/// The dispatcher's error handler converts every error into a response, so
+/// there's no error to propagate.
+async fn dispatch(dispatcher: FallbackDispatcher, req: Request) -> Response {
+    match dispatcher.oneshot(req).await {
+        Ok(response) => response,
+        Err(infallible) => match infallible {},
+    }
+}
+
+/// Upstream timeouts, connection errors, and mapper errors (such as a
+/// missing model mapping for the selected provider) are all converted into
+/// 5xx responses by the dispatcher's error handler.
+pub(crate) fn should_fall_back(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+#[derive(Debug, Clone)]
+pub struct Layer {
+    app_state: AppState,
+    fallbacks: Option<Arc<Fallbacks>>,
+    mid_stream: bool,
+}
+
+impl Layer {
+    /// The router's balancers must mirror the providers they discover into
+    /// `discovered`, since only those are fallen back to.
+    #[must_use]
+    pub fn for_router(
+        app_state: &AppState,
+        router_config: &Arc<RouterConfig>,
+        balance_config: &BalanceConfigInner,
+        discovered: &BalancerProviders,
+        request_context_layer: &request_context::Layer,
+    ) -> Self {
+        let Some(config) = router_config
+            .fallback
+            .as_ref()
+            .filter(|config| config.enabled)
+        else {
+            return Self {
+                app_state: app_state.clone(),
+                fallbacks: None,
+                mid_stream: false,
+            };
+        };
+
+        let models = router_config
+            .model_load_balance
+            .as_ref()
+            .iter()
+            .map(|(group, group_config)| {
+                let candidates = Candidates::new(
+                    config,
+                    &group_config.balance,
+                    discovered.get(Some(group)).cloned().unwrap_or_default(),
+                    request_context_layer,
+                );
+                (group.clone(), candidates)
+            })
+            .collect();
+        let fallbacks = Fallbacks {
+            router_config: router_config.clone(),
+            endpoint: Candidates::new(
+                config,
+                balance_config,
+                discovered.get(None).cloned().unwrap_or_default(),
+                request_context_layer,
+            ),
+            models,
+        };
+
+        Self {
+            app_state: app_state.clone(),
+            fallbacks: Some(Arc::new(fallbacks)),
+            mid_stream: config.mid_stream,
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for Layer {
+    type Service = Service<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Service {
+            inner,
+            app_state: self.app_state.clone(),
+            fallbacks: self.fallbacks.clone(),
+            mid_stream: self.mid_stream,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_on_retryable_statuses() {
+        assert!(should_fall_back(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(should_fall_back(StatusCode::GATEWAY_TIMEOUT));
+        assert!(should_fall_back(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!should_fall_back(StatusCode::OK));
+        assert!(!should_fall_back(StatusCode::BAD_REQUEST));
+        assert!(!should_fall_back(StatusCode::UNAUTHORIZED));
+    }
+
+    #[test]
+    fn error_event_is_a_well_formed_sse_event() {
+        let event = String::from_utf8(error_event().to_vec()).unwrap();
+        let data = event
+            .strip_prefix("event: error\ndata: ")
+            .and_then(|event| event.strip_suffix("\n\n"))
+            .unwrap();
+        let error: ErrorResponse = serde_json::from_str(data).unwrap();
+        assert_eq!(error.error.r#type.as_deref(), Some(SERVER_ERROR_TYPE));
+    }
+}
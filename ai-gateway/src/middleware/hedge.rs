@@ -0,0 +1,345 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use axum_core::body::Body;
+use bytes::Bytes;
+use compact_str::CompactString;
+use futures::future::BoxFuture;
+use http::request::Parts;
+use http_body_util::BodyExt;
+use indexmap::{IndexMap, IndexSet};
+use opentelemetry::KeyValue;
+use rust_decimal::prelude::ToPrimitive;
+use tokio::time::Instant;
+use tower::{Layer as _, ServiceExt};
+
+use crate::{
+    app_state::AppState,
+    balancer::model::{model_group, source_mapper_context},
+    config::{
+        balance::BalanceConfigInner, hedge::HedgeConfig, router::RouterConfig,
+    },
+    discover::provider::{BalancerProviders, DiscoveredProviders},
+    dispatcher::DispatcherService,
+    error::{api::ApiError, internal::InternalError},
+    metrics::tfft::TfftWindow,
+    middleware::{fallback::should_fall_back, request_context},
+    types::{
+        extensions::SelectedProvider, provider::InferenceProvider,
+        request::Request, response::Response,
+    },
+};
+
+type HedgeDispatcher = request_context::Service<DispatcherService>;
+
+/// How many recent requests the hedging delay is estimated from.
+const TFFT_WINDOW_SIZE: usize = 1000;
+
+/// Sends a second, hedged request to a different provider when the provider
+/// selected by the load balancer hasn't responded within the router's recent
+/// time to first byte percentile.
+///
+/// The first response to arrive is used and the other request is cancelled,
+/// unless it failed, in which case the other request is waited for.
+#[derive(Debug, Clone)]
+pub struct Service<S> {
+    inner: S,
+    app_state: AppState,
+    /// If `None`, hedging is disabled and requests are passed through.
+    hedge: Option<Arc<Hedge>>,
+}
+
+/// The providers that requests sent to one balancer can be hedged to.
+///
+/// Like fallback candidates, they're resolved from the providers the
+/// balancer has currently discovered, so providers it removed for being
+/// unhealthy or rate limited aren't hedged to.
+#[derive(Debug)]
+struct Targets {
+    /// Hedged requests go to the first of these that wasn't selected by the
+    /// load balancer.
+    order: IndexSet<InferenceProvider>,
+    /// Mirrors the balancer's discovered providers.
+    discovered: DiscoveredProviders,
+    request_context_layer: request_context::Layer,
+}
+
+impl Targets {
+    fn new(
+        balance_config: &BalanceConfigInner,
+        discovered: DiscoveredProviders,
+        request_context_layer: &request_context::Layer,
+    ) -> Self {
+        Self {
+            order: balance_config.providers_by_preference(),
+            discovered,
+            request_context_layer: request_context_layer.clone(),
+        }
+    }
+
+    /// Returns `None` if no other provider is currently discovered.
+    fn select(
+        &self,
+        selected: &InferenceProvider,
+    ) -> Option<(InferenceProvider, HedgeDispatcher)> {
+        let (provider, service) = self.discovered.with(|services| {
+            self.order
+                .iter()
+                .filter(|provider| *provider != selected)
+                .find_map(|provider| {
+                    let service = services.get(provider)?;
+                    Some((provider.clone(), service.clone()))
+                })
+        })?;
+        Some((provider, self.request_context_layer.layer(service)))
+    }
+}
+
+#[derive(Debug)]
+struct Hedge {
+    config: HedgeConfig,
+    router_config: Arc<RouterConfig>,
+    /// The targets of the endpoint level balancer.
+    endpoint: Targets,
+    /// The targets of each model group's balancer.
+    models: IndexMap<CompactString, Targets>,
+    tfft: TfftWindow,
+}
+
+impl Hedge {
+    /// The targets of the balancer that the request body is routed to by
+    /// the [`ModelBalancer`](crate::balancer::model::ModelBalancer).
+    fn for_body(&self, body: &[u8]) -> &Targets {
+        model_group(&self.router_config, &source_mapper_context(body))
+            .and_then(|group| self.models.get(group))
+            .unwrap_or(&self.endpoint)
+    }
+
+    fn delay(&self) -> Duration {
+        self.config
+            .percentile
+            .to_f64()
+            .and_then(|percentile| self.tfft.percentile(percentile))
+            .unwrap_or(self.config.default_delay)
+    }
+}
+
+impl<S> tower::Service<Request> for Service<S>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = ApiError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[tracing::instrument(level = "debug", name = "hedge", skip_all)]
+    fn call(&mut self, req: Request) -> Self::Future {
+        // see: https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut this = self.clone();
+        std::mem::swap(self, &mut this);
+        match this.hedge.clone() {
+            Some(hedge) => Box::pin(this.call_with_hedge(hedge, req)),
+            None => Box::pin(this.inner.call(req)),
+        }
+    }
+}
+
+impl<S> Service<S>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    async fn call_with_hedge(
+        mut self,
+        hedge: Arc<Hedge>,
+        req: Request,
+    ) -> Result<Response, ApiError> {
+        // the body must be buffered so that it can be sent twice
+        let (parts, body) = req.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(InternalError::CollectBodyError)?
+            .to_bytes();
+
+        let start = Instant::now();
+        let selected = SelectedProvider::default();
+        let mut req = replay(&parts, &body);
+        req.extensions_mut().insert(selected.clone());
+        // the inner service was already driven to readiness by our caller
+        let primary = self.inner.call(req);
+        tokio::pin!(primary);
+
+        let delay = hedge.delay();
+        if let Ok(response) = tokio::time::timeout(delay, &mut primary).await {
+            hedge.tfft.record(start.elapsed());
+            return response;
+        }
+        // the primary may still be queued for a provider, and the hedged
+        // request must go to a different one
+        let selected_provider = tokio::select! {
+            response = &mut primary => {
+                hedge.tfft.record(start.elapsed());
+                return response;
+            }
+            provider = selected.wait() => provider.clone(),
+        };
+        let Some((provider, dispatcher)) =
+            hedge.for_body(&body).select(&selected_provider)
+        else {
+            let response = primary.await;
+            hedge.tfft.record(start.elapsed());
+            return response;
+        };
+
+        tracing::debug!(
+            selected = %selected_provider,
+            hedge = %provider,
+            delay = ?delay,
+            "provider is slow to respond, sending hedged request"
+        );
+        let attributes = [
+            KeyValue::new("selected", selected_provider.to_string()),
+            KeyValue::new("hedge", provider.to_string()),
+        ];
+        self.app_state.0.metrics.hedge_count.add(1, &attributes);
+        let hedged = dispatcher.oneshot(replay(&parts, &body));
+        tokio::pin!(hedged);
+
+        // whichever request loses is dropped, which cancels it
+        let response = tokio::select! {
+            response = &mut primary => match response {
+                Ok(response) if should_fall_back(response.status()) => {
+                    // the hedged request may still succeed
+                    tracing::debug!(
+                        selected = %selected_provider,
+                        status = %response.status(),
+                        "primary request failed, waiting for the hedged request"
+                    );
+                    match hedged.await {
+                        Ok(hedged) if hedged.status().is_success() => {
+                            self.app_state
+                                .0
+                                .metrics
+                                .hedge_won_count
+                                .add(1, &attributes);
+                            Ok(hedged)
+                        }
+                        Ok(_) => Ok(response),
+                        Err(infallible) => match infallible {},
+                    }
+                }
+                response => response,
+            },
+            response = &mut hedged => match response {
+                Ok(response) if response.status().is_success() => {
+                    self.app_state.0.metrics.hedge_won_count.add(1, &attributes);
+                    Ok(response)
+                }
+                Ok(response) => {
+                    // the primary may still succeed
+                    tracing::debug!(
+                        hedge = %provider,
+                        status = %response.status(),
+                        "hedged request failed, waiting for the primary"
+                    );
+                    primary.await
+                }
+                Err(infallible) => match infallible {},
+            },
+        };
+        hedge.tfft.record(start.elapsed());
+        response
+    }
+}
+
+fn replay(parts: &Parts, body: &Bytes) -> Request {
+    Request::from_parts(parts.clone(), Body::from(body.clone()))
+}
+
+#[derive(Debug, Clone)]
+pub struct Layer {
+    app_state: AppState,
+    hedge: Option<Arc<Hedge>>,
+}
+
+impl Layer {
+    /// The router's balancers must mirror the providers they discover into
+    /// `discovered`, since only those are hedged to.
+    #[must_use]
+    pub fn for_router(
+        app_state: &AppState,
+        router_config: &Arc<RouterConfig>,
+        balance_config: &BalanceConfigInner,
+        discovered: &BalancerProviders,
+        request_context_layer: &request_context::Layer,
+    ) -> Self {
+        let Some(config) =
+            router_config.hedge.as_ref().filter(|config| config.enabled)
+        else {
+            return Self {
+                app_state: app_state.clone(),
+                hedge: None,
+            };
+        };
+
+        let models = router_config
+            .model_load_balance
+            .as_ref()
+            .iter()
+            .map(|(group, group_config)| {
+                let targets = Targets::new(
+                    &group_config.balance,
+                    discovered.get(Some(group)).cloned().unwrap_or_default(),
+                    request_context_layer,
+                );
+                (group.clone(), targets)
+            })
+            .collect();
+        let hedge = Hedge {
+            config: config.clone(),
+            router_config: router_config.clone(),
+            endpoint: Targets::new(
+                balance_config,
+                discovered.get(None).cloned().unwrap_or_default(),
+                request_context_layer,
+            ),
+            models,
+            tfft: TfftWindow::new(TFFT_WINDOW_SIZE),
+        };
+
+        Self {
+            app_state: app_state.clone(),
+            hedge: Some(Arc::new(hedge)),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for Layer {
+    type Service = Service<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Service {
+            inner,
+            app_state: self.app_state.clone(),
+            hedge: self.hedge.clone(),
+        }
+    }
+}
@@ -4,11 +4,22 @@ use http::response::Parts;
 
 use super::{TryConvert, TryConvertStreamData};
 use crate::{
-    endpoints::openai::chat_completions::system_prompt,
+    endpoints::openai::{
+        chat_completions::system_prompt,
+        responses::{
+            CreateResponseRequest, ResponseObject, ResponseStreamEvent,
+        },
+    },
     error::mapper::MapperError,
     middleware::mapper::{
-        DEFAULT_MAX_TOKENS, TryConvertError, mime_from_data_uri,
+        DEFAULT_MAX_TOKENS, TryConvertError,
+        messages::qualified_model,
+        mime_from_data_uri,
         model::ModelMapper,
+        responses::{
+            chat_request_from_responses, response_from_chat,
+            stream_event_from_chat_chunk,
+        },
     },
     types::{
         model_id::{ModelId, Version},
@@ -700,7 +711,7 @@ impl
         anthropic_ai_sdk::types::message::CreateMessageParams,
         Self::Error,
     > {
-        let source_model = ModelId::from_str(&value.model)?;
+        let source_model = ModelId::from_str(&qualified_model(&value.model))?;
         let target_model = self
             .model_mapper
             .map_model(&source_model, &InferenceProvider::Anthropic)?;
@@ -749,6 +760,25 @@ impl
     }
 }
 
+impl
+    TryConvertError<
+        crate::endpoints::anthropic::messages::AnthropicApiError,
+        crate::endpoints::anthropic::messages::AnthropicApiError,
+    > for AnthropicConverter
+{
+    type Error = MapperError;
+    fn try_convert_error(
+        &self,
+        _resp_parts: &Parts,
+        value: crate::endpoints::anthropic::messages::AnthropicApiError,
+    ) -> Result<
+        crate::endpoints::anthropic::messages::AnthropicApiError,
+        Self::Error,
+    > {
+        Ok(value)
+    }
+}
+
 impl
     TryConvertError<
         crate::endpoints::anthropic::messages::AnthropicApiError,
@@ -767,3 +797,66 @@ impl
         Ok(error)
     }
 }
+
+impl
+    TryConvert<
+        CreateResponseRequest,
+        anthropic_ai_sdk::types::message::CreateMessageParams,
+    > for AnthropicConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateResponseRequest,
+    ) -> Result<
+        anthropic_ai_sdk::types::message::CreateMessageParams,
+        Self::Error,
+    > {
+        let chat_request = chat_request_from_responses(value)?;
+        <Self as TryConvert<
+            async_openai::types::CreateChatCompletionRequest,
+            anthropic_ai_sdk::types::message::CreateMessageParams,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl
+    TryConvert<
+        anthropic_ai_sdk::types::message::CreateMessageResponse,
+        ResponseObject,
+    > for AnthropicConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: anthropic_ai_sdk::types::message::CreateMessageResponse,
+    ) -> Result<ResponseObject, Self::Error> {
+        let chat_response = <Self as TryConvert<
+            anthropic_ai_sdk::types::message::CreateMessageResponse,
+            async_openai::types::CreateChatCompletionResponse,
+        >>::try_convert(self, value)?;
+        Ok(response_from_chat(chat_response))
+    }
+}
+
+impl
+    TryConvertStreamData<
+        anthropic_ai_sdk::types::message::StreamEvent,
+        ResponseStreamEvent,
+    > for AnthropicConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: anthropic_ai_sdk::types::message::StreamEvent,
+    ) -> Result<Option<ResponseStreamEvent>, Self::Error> {
+        let chunk = <Self as TryConvertStreamData<
+            anthropic_ai_sdk::types::message::StreamEvent,
+            async_openai::types::CreateChatCompletionStreamResponse,
+        >>::try_convert_chunk(self, value)?;
+        Ok(chunk.and_then(stream_event_from_chat_chunk))
+    }
+}
@@ -0,0 +1,275 @@
+use std::str::FromStr;
+
+use anthropic_ai_sdk::types::message::{
+    CreateMessageParams, CreateMessageResponse, StreamEvent,
+};
+use async_openai::{
+    error::WrappedError,
+    types::{
+        CreateChatCompletionRequest, CreateChatCompletionResponse,
+        CreateChatCompletionStreamResponse, CreateEmbeddingRequest,
+        CreateEmbeddingResponse,
+    },
+};
+use http::response::Parts;
+
+use super::{TryConvert, TryConvertStreamData};
+use crate::{
+    endpoints::{
+        EndpointType,
+        anthropic::messages::AnthropicApiError,
+        openai::responses::{
+            CreateResponseRequest, ResponseObject, ResponseStreamEvent,
+        },
+    },
+    error::mapper::MapperError,
+    middleware::mapper::{
+        TryConvertError,
+        messages::{
+            anthropic_error_from_status, chat_request_from_messages,
+            message_from_chat, message_stream_event_from_chat_chunk,
+        },
+        model::ModelMapper,
+        responses::{
+            chat_request_from_responses, response_from_chat,
+            stream_event_from_chat_chunk,
+        },
+    },
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+/// Azure `OpenAI` accepts `OpenAI` payloads, so the only conversion needed is
+/// from the requested model to the deployment serving it.
+pub struct AzureConverter {
+    model_mapper: ModelMapper,
+}
+
+impl AzureConverter {
+    #[must_use]
+    pub fn new(model_mapper: ModelMapper) -> Self {
+        Self { model_mapper }
+    }
+
+    fn deployment(
+        &self,
+        source_model: &str,
+        endpoint_type: EndpointType,
+    ) -> Result<String, MapperError> {
+        let source_model = ModelId::from_str(source_model)?;
+        let target_model = self.model_mapper.map_model_for_endpoint(
+            &source_model,
+            &InferenceProvider::Azure,
+            endpoint_type,
+        )?;
+        let deployment = self
+            .model_mapper
+            .deployment(&target_model, &InferenceProvider::Azure);
+        tracing::trace!(
+            source_model = ?source_model,
+            target_model = ?target_model,
+            deployment = %deployment,
+            "mapped model"
+        );
+        Ok(deployment)
+    }
+}
+
+impl TryConvert<CreateChatCompletionRequest, CreateChatCompletionRequest>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        mut value: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionRequest, Self::Error> {
+        value.model = self.deployment(&value.model, EndpointType::Chat)?;
+        Ok(value)
+    }
+}
+
+impl TryConvert<CreateChatCompletionResponse, CreateChatCompletionResponse>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateChatCompletionResponse,
+    ) -> Result<CreateChatCompletionResponse, Self::Error> {
+        Ok(value)
+    }
+}
+
+impl
+    TryConvertStreamData<
+        CreateChatCompletionStreamResponse,
+        CreateChatCompletionStreamResponse,
+    > for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateChatCompletionStreamResponse,
+    ) -> Result<Option<CreateChatCompletionStreamResponse>, Self::Error> {
+        Ok(Some(value))
+    }
+}
+
+impl TryConvertError<WrappedError, WrappedError> for AzureConverter {
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        _resp_parts: &Parts,
+        value: WrappedError,
+    ) -> Result<WrappedError, Self::Error> {
+        Ok(value)
+    }
+}
+
+impl TryConvert<CreateEmbeddingRequest, CreateEmbeddingRequest>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        mut value: CreateEmbeddingRequest,
+    ) -> Result<CreateEmbeddingRequest, Self::Error> {
+        value.model =
+            self.deployment(&value.model, EndpointType::Embeddings)?;
+        Ok(value)
+    }
+}
+
+impl TryConvert<CreateEmbeddingResponse, CreateEmbeddingResponse>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateEmbeddingResponse,
+    ) -> Result<CreateEmbeddingResponse, Self::Error> {
+        Ok(value)
+    }
+}
+
+impl TryConvertStreamData<CreateEmbeddingResponse, CreateEmbeddingResponse>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateEmbeddingResponse,
+    ) -> Result<Option<CreateEmbeddingResponse>, Self::Error> {
+        Ok(Some(value))
+    }
+}
+
+impl TryConvert<CreateResponseRequest, CreateChatCompletionRequest>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateResponseRequest,
+    ) -> Result<CreateChatCompletionRequest, Self::Error> {
+        let chat_request = chat_request_from_responses(value)?;
+        <Self as TryConvert<
+            CreateChatCompletionRequest,
+            CreateChatCompletionRequest,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl TryConvert<CreateChatCompletionResponse, ResponseObject>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateChatCompletionResponse,
+    ) -> Result<ResponseObject, Self::Error> {
+        Ok(response_from_chat(value))
+    }
+}
+
+impl
+    TryConvertStreamData<
+        CreateChatCompletionStreamResponse,
+        ResponseStreamEvent,
+    > for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateChatCompletionStreamResponse,
+    ) -> Result<Option<ResponseStreamEvent>, Self::Error> {
+        Ok(stream_event_from_chat_chunk(value))
+    }
+}
+
+impl TryConvert<CreateMessageParams, CreateChatCompletionRequest>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateMessageParams,
+    ) -> Result<CreateChatCompletionRequest, Self::Error> {
+        let chat_request = chat_request_from_messages(value)?;
+        <Self as TryConvert<
+            CreateChatCompletionRequest,
+            CreateChatCompletionRequest,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl TryConvert<CreateChatCompletionResponse, CreateMessageResponse>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateChatCompletionResponse,
+    ) -> Result<CreateMessageResponse, Self::Error> {
+        Ok(message_from_chat(value))
+    }
+}
+
+impl TryConvertStreamData<CreateChatCompletionStreamResponse, StreamEvent>
+    for AzureConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateChatCompletionStreamResponse,
+    ) -> Result<Option<StreamEvent>, Self::Error> {
+        message_stream_event_from_chat_chunk(value)
+    }
+}
+
+impl TryConvertError<WrappedError, AnthropicApiError> for AzureConverter {
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        value: WrappedError,
+    ) -> Result<AnthropicApiError, Self::Error> {
+        Ok(anthropic_error_from_status(
+            resp_parts.status,
+            Some(value.error.message),
+        ))
+    }
+}
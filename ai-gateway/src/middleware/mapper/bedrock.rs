@@ -1,7 +1,11 @@
 use std::{collections::HashMap, str::FromStr};
 
+use anthropic_ai_sdk::types::message::{
+    CreateMessageParams, CreateMessageResponse, StreamEvent,
+};
 use async_openai::types::{
     CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
+    CreateEmbeddingRequest, CreateEmbeddingResponse,
 };
 use http::response::Parts;
 use uuid::Uuid;
@@ -10,7 +14,40 @@ use super::{
     MapperError, TryConvert, TryConvertStreamData, model::ModelMapper,
 };
 use crate::{
-    middleware::mapper::{DEFAULT_MAX_TOKENS, TryConvertError},
+    endpoints::{
+        EndpointType,
+        anthropic::messages::AnthropicApiError,
+        bedrock::{
+            invoke_model::{
+                CohereEmbeddingRequest, InvokeModelEmbeddingBody,
+                InvokeModelEmbeddingRequest, InvokeModelEmbeddingResponse,
+                InvokeModelError, TitanEmbeddingRequest,
+            },
+            invoke_model_image::{
+                InvokeModelImageBody, InvokeModelImageRequest,
+                InvokeModelImageResponse, StabilityImageRequest,
+                TitanImageGenerationConfig, TitanImageRequest,
+                TitanTextToImageParams,
+            },
+        },
+        openai::{
+            image_generations::{CreateImageRequest, Image, ImagesResponse},
+            responses::{
+                CreateResponseRequest, ResponseObject, ResponseStreamEvent,
+            },
+        },
+    },
+    middleware::mapper::{
+        DEFAULT_MAX_TOKENS, TryConvertError,
+        messages::{
+            anthropic_error_from_status, chat_request_from_messages,
+            message_from_chat, message_stream_event_from_chat_chunk,
+        },
+        responses::{
+            chat_request_from_responses, response_from_chat,
+            stream_event_from_chat_chunk,
+        },
+    },
     types::{model_id::ModelId, provider::InferenceProvider},
 };
 
@@ -656,3 +693,390 @@ impl
         Ok(super::openai_error_from_status(resp_parts.status, None))
     }
 }
+
+/// Cohere distinguishes between embedding documents to store and queries to
+/// search them with, `OpenAI` has no such distinction so we assume the
+/// former.
+const COHERE_EMBED_INPUT_TYPE: &str = "search_document";
+
+impl TryConvert<CreateEmbeddingRequest, InvokeModelEmbeddingRequest>
+    for BedrockConverter
+{
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        value: CreateEmbeddingRequest,
+    ) -> Result<InvokeModelEmbeddingRequest, Self::Error> {
+        let source_model = ModelId::from_str(&value.model)?;
+        let target_model = self.model_mapper.map_model_for_endpoint(
+            &source_model,
+            &InferenceProvider::Bedrock,
+            EndpointType::Embeddings,
+        )?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+
+        let model_id = target_model.to_string();
+        let mut texts = super::embedding_input_texts(value.input)?;
+        let body = if model_id.starts_with("cohere.") {
+            InvokeModelEmbeddingBody::Cohere(CohereEmbeddingRequest {
+                texts,
+                input_type: COHERE_EMBED_INPUT_TYPE.to_string(),
+            })
+        } else {
+            // Titan only embeds a single input per request
+            if texts.len() != 1 {
+                return Err(MapperError::UnsupportedEmbeddingInput(format!(
+                    "{model_id} only supports a single input"
+                )));
+            }
+            InvokeModelEmbeddingBody::Titan(TitanEmbeddingRequest {
+                input_text: texts.remove(0),
+                dimensions: value.dimensions,
+            })
+        };
+
+        Ok(InvokeModelEmbeddingRequest { model_id, body })
+    }
+}
+
+impl TryConvert<InvokeModelEmbeddingResponse, CreateEmbeddingResponse>
+    for BedrockConverter
+{
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        value: InvokeModelEmbeddingResponse,
+    ) -> Result<CreateEmbeddingResponse, Self::Error> {
+        // the model is not included in `InvokeModel` responses
+        let response = match value {
+            InvokeModelEmbeddingResponse::Titan(titan) => {
+                super::embedding_response(
+                    String::new(),
+                    vec![titan.embedding],
+                    titan.input_text_token_count,
+                )
+            }
+            InvokeModelEmbeddingResponse::Cohere(cohere) => {
+                super::embedding_response(String::new(), cohere.embeddings, 0)
+            }
+        };
+        Ok(response)
+    }
+}
+
+impl TryConvertStreamData<InvokeModelEmbeddingResponse, CreateEmbeddingResponse>
+    for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: InvokeModelEmbeddingResponse,
+    ) -> Result<Option<CreateEmbeddingResponse>, Self::Error> {
+        // embeddings are never streamed, but this is required by the
+        // `TypedEndpointConverter`
+        self.try_convert(value).map(Some)
+    }
+}
+
+impl TryConvertError<InvokeModelError, async_openai::error::WrappedError>
+    for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        value: InvokeModelError,
+    ) -> Result<async_openai::error::WrappedError, Self::Error> {
+        Ok(super::openai_error_from_status(
+            resp_parts.status,
+            value.message,
+        ))
+    }
+}
+
+/// Aspect ratios supported by the Stability image models.
+const STABILITY_ASPECT_RATIOS: [(u32, u32); 9] = [
+    (21, 9),
+    (16, 9),
+    (3, 2),
+    (5, 4),
+    (1, 1),
+    (4, 5),
+    (2, 3),
+    (9, 16),
+    (9, 21),
+];
+
+/// Stability only accepts a fixed set of aspect ratios rather than explicit
+/// sizes, so we pick the closest one to the requested size.
+fn closest_stability_aspect_ratio(width: u32, height: u32) -> String {
+    let requested = f64::from(width) / f64::from(height);
+    let (w, h) = STABILITY_ASPECT_RATIOS
+        .into_iter()
+        .min_by(|(w1, h1), (w2, h2)| {
+            let diff1 = (f64::from(*w1) / f64::from(*h1) - requested).abs();
+            let diff2 = (f64::from(*w2) / f64::from(*h2) - requested).abs();
+            diff1.total_cmp(&diff2)
+        })
+        .unwrap_or((1, 1));
+    format!("{w}:{h}")
+}
+
+impl TryConvert<CreateImageRequest, InvokeModelImageRequest>
+    for BedrockConverter
+{
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        value: CreateImageRequest,
+    ) -> Result<InvokeModelImageRequest, Self::Error> {
+        let source_model = ModelId::from_str(&value.model)?;
+        let target_model = self.model_mapper.map_model_for_endpoint(
+            &source_model,
+            &InferenceProvider::Bedrock,
+            EndpointType::Image,
+        )?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+
+        let model_id = target_model.to_string();
+        let dimensions = value.dimensions();
+        let body = if model_id.starts_with("stability.") {
+            // Stability models generate a single image per request
+            if value.n.is_some_and(|n| n > 1) {
+                return Err(MapperError::UnsupportedImageRequest(format!(
+                    "{model_id} only supports generating a single image"
+                )));
+            }
+            InvokeModelImageBody::Stability(StabilityImageRequest {
+                prompt: value.prompt,
+                aspect_ratio: dimensions.map(|(width, height)| {
+                    closest_stability_aspect_ratio(width, height)
+                }),
+                output_format: "png".to_string(),
+            })
+        } else if model_id.starts_with("amazon.titan-image") {
+            let quality = match value.quality.as_deref() {
+                Some("hd" | "high") => "premium",
+                _ => "standard",
+            };
+            InvokeModelImageBody::Titan(TitanImageRequest {
+                task_type: "TEXT_IMAGE".to_string(),
+                text_to_image_params: TitanTextToImageParams {
+                    text: value.prompt,
+                },
+                image_generation_config: TitanImageGenerationConfig {
+                    number_of_images: value.n.unwrap_or(1),
+                    width: dimensions.map(|(width, _)| width),
+                    height: dimensions.map(|(_, height)| height),
+                    quality: quality.to_string(),
+                },
+            })
+        } else {
+            return Err(MapperError::UnsupportedImageRequest(format!(
+                "{model_id} is not a supported image model"
+            )));
+        };
+
+        Ok(InvokeModelImageRequest { model_id, body })
+    }
+}
+
+impl TryConvert<InvokeModelImageResponse, ImagesResponse> for BedrockConverter {
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        value: InvokeModelImageResponse,
+    ) -> Result<ImagesResponse, Self::Error> {
+        // Bedrock only returns base64 encoded images, regardless of the
+        // requested `response_format`
+        let data = value
+            .images
+            .into_iter()
+            .map(|b64_json| Image {
+                b64_json: Some(b64_json),
+                ..Default::default()
+            })
+            .collect();
+        Ok(ImagesResponse {
+            created: u64::try_from(chrono::Utc::now().timestamp())
+                .unwrap_or_default(),
+            data,
+        })
+    }
+}
+
+impl TryConvertStreamData<InvokeModelImageResponse, ImagesResponse>
+    for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: InvokeModelImageResponse,
+    ) -> Result<Option<ImagesResponse>, Self::Error> {
+        // image generations are never streamed, but this is required by the
+        // `TypedEndpointConverter`
+        self.try_convert(value).map(Some)
+    }
+}
+
+impl
+    TryConvert<
+        CreateResponseRequest,
+        aws_sdk_bedrockruntime::operation::converse::ConverseInput,
+    > for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateResponseRequest,
+    ) -> Result<
+        aws_sdk_bedrockruntime::operation::converse::ConverseInput,
+        Self::Error,
+    > {
+        let chat_request = chat_request_from_responses(value)?;
+        <Self as TryConvert<
+            async_openai::types::CreateChatCompletionRequest,
+            aws_sdk_bedrockruntime::operation::converse::ConverseInput,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl
+    TryConvert<
+        aws_sdk_bedrockruntime::operation::converse::ConverseOutput,
+        ResponseObject,
+    > for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: aws_sdk_bedrockruntime::operation::converse::ConverseOutput,
+    ) -> Result<ResponseObject, Self::Error> {
+        let chat_response = <Self as TryConvert<
+            aws_sdk_bedrockruntime::operation::converse::ConverseOutput,
+            CreateChatCompletionResponse,
+        >>::try_convert(self, value)?;
+        Ok(response_from_chat(chat_response))
+    }
+}
+
+impl
+    TryConvertStreamData<
+        aws_sdk_bedrockruntime::types::ConverseStreamOutput,
+        ResponseStreamEvent,
+    > for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: aws_sdk_bedrockruntime::types::ConverseStreamOutput,
+    ) -> Result<Option<ResponseStreamEvent>, Self::Error> {
+        let chunk = <Self as TryConvertStreamData<
+            aws_sdk_bedrockruntime::types::ConverseStreamOutput,
+            CreateChatCompletionStreamResponse,
+        >>::try_convert_chunk(self, value)?;
+        Ok(chunk.and_then(stream_event_from_chat_chunk))
+    }
+}
+
+impl
+    TryConvert<
+        CreateMessageParams,
+        aws_sdk_bedrockruntime::operation::converse::ConverseInput,
+    > for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateMessageParams,
+    ) -> Result<
+        aws_sdk_bedrockruntime::operation::converse::ConverseInput,
+        Self::Error,
+    > {
+        let chat_request = chat_request_from_messages(value)?;
+        <Self as TryConvert<
+            async_openai::types::CreateChatCompletionRequest,
+            aws_sdk_bedrockruntime::operation::converse::ConverseInput,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl
+    TryConvert<
+        aws_sdk_bedrockruntime::operation::converse::ConverseOutput,
+        CreateMessageResponse,
+    > for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: aws_sdk_bedrockruntime::operation::converse::ConverseOutput,
+    ) -> Result<CreateMessageResponse, Self::Error> {
+        let chat_response = <Self as TryConvert<
+            aws_sdk_bedrockruntime::operation::converse::ConverseOutput,
+            CreateChatCompletionResponse,
+        >>::try_convert(self, value)?;
+        Ok(message_from_chat(chat_response))
+    }
+}
+
+impl
+    TryConvertStreamData<
+        aws_sdk_bedrockruntime::types::ConverseStreamOutput,
+        StreamEvent,
+    > for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: aws_sdk_bedrockruntime::types::ConverseStreamOutput,
+    ) -> Result<Option<StreamEvent>, Self::Error> {
+        let chunk = <Self as TryConvertStreamData<
+            aws_sdk_bedrockruntime::types::ConverseStreamOutput,
+            CreateChatCompletionStreamResponse,
+        >>::try_convert_chunk(self, value)?;
+        match chunk {
+            Some(chunk) => message_stream_event_from_chat_chunk(chunk),
+            None => Ok(None),
+        }
+    }
+}
+
+impl
+    TryConvertError<
+        crate::endpoints::bedrock::converse::ConverseError,
+        AnthropicApiError,
+    > for BedrockConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        _value: crate::endpoints::bedrock::converse::ConverseError,
+    ) -> Result<AnthropicApiError, Self::Error> {
+        Ok(anthropic_error_from_status(resp_parts.status, None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_closest_stability_aspect_ratio() {
+        assert_eq!(closest_stability_aspect_ratio(1024, 1024), "1:1");
+        assert_eq!(closest_stability_aspect_ratio(1792, 1024), "16:9");
+        assert_eq!(closest_stability_aspect_ratio(1024, 1792), "9:16");
+        assert_eq!(closest_stability_aspect_ratio(1536, 1024), "3:2");
+    }
+}
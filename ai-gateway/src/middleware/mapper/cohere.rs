@@ -0,0 +1,672 @@
+use std::str::FromStr;
+
+use anthropic_ai_sdk::types::message::{
+    CreateMessageParams, CreateMessageResponse, StreamEvent,
+};
+use async_openai::{
+    error::WrappedError,
+    types::{
+        self as openai, CreateChatCompletionRequest,
+        CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
+        CreateEmbeddingRequest, CreateEmbeddingResponse,
+    },
+};
+use http::response::Parts;
+
+use super::{TryConvert, TryConvertStreamData};
+use crate::{
+    endpoints::{
+        EndpointType,
+        anthropic::messages::AnthropicApiError,
+        cohere::{
+            chat::{
+                CohereChatRequest, CohereChatResponse, CohereContent,
+                CohereContentBlock, CohereDocument, CohereError,
+                CohereFinishReason, CohereFunction, CohereImageUrl,
+                CohereMessage, CohereResponseContent, CohereResponseFormat,
+                CohereStreamDelta, CohereStreamEvent, CohereTool,
+                CohereToolChoice, CohereUsage,
+            },
+            embed::{
+                CohereEmbedRequest, CohereEmbedResponse, CohereEmbeddingType,
+                CohereInputType,
+            },
+        },
+        openai::responses::{
+            CreateResponseRequest, ResponseObject, ResponseStreamEvent,
+        },
+    },
+    error::mapper::MapperError,
+    middleware::mapper::{
+        TryConvertError,
+        anthropic::OPENAI_CHAT_COMPLETION_OBJECT,
+        messages::{
+            anthropic_error_from_status, chat_request_from_messages,
+            message_from_chat, message_stream_event_from_chat_chunk,
+        },
+        model::ModelMapper,
+        responses::{
+            chat_request_from_responses, response_from_chat,
+            stream_event_from_chat_chunk,
+        },
+    },
+    types::{model_id::ModelId, provider::InferenceProvider},
+};
+
+const OPENAI_CHAT_COMPLETION_CHUNK_OBJECT: &str = "chat.completion.chunk";
+// Cohere only sends the id in the `message-start` event and never sends the
+// model, and we map each stream event on its own.
+const PLACEHOLDER_STREAM_ID: &str = "cohere-stream-id";
+const PLACEHOLDER_MODEL_NAME: &str = "cohere-model";
+const DEFAULT_CREATED_TIMESTAMP: u32 = 0;
+
+/// Maps `OpenAI` chat completions to Cohere's v2 chat API.
+///
+/// Tool results are sent to Cohere as documents so that they can be cited,
+/// however `OpenAI` responses have no equivalent for citations, so they are
+/// only returned when using Cohere directly.
+pub struct CohereConverter {
+    model_mapper: ModelMapper,
+}
+
+impl CohereConverter {
+    #[must_use]
+    pub fn new(model_mapper: ModelMapper) -> Self {
+        Self { model_mapper }
+    }
+}
+
+fn text_content(parts: impl IntoIterator<Item = String>) -> String {
+    parts.into_iter().collect::<Vec<_>>().join("\n")
+}
+
+fn map_message(
+    message: openai::ChatCompletionRequestMessage,
+) -> Option<CohereMessage> {
+    match message {
+        openai::ChatCompletionRequestMessage::Developer(message) => {
+            let content = match message.content {
+                openai::ChatCompletionRequestDeveloperMessageContent::Text(
+                    text,
+                ) => text,
+                openai::ChatCompletionRequestDeveloperMessageContent::Array(
+                    parts,
+                ) => text_content(parts.into_iter().map(|part| part.text)),
+            };
+            Some(CohereMessage::System {
+                content: CohereContent::Text(content),
+            })
+        }
+        openai::ChatCompletionRequestMessage::System(message) => {
+            let content = match message.content {
+                openai::ChatCompletionRequestSystemMessageContent::Text(
+                    text,
+                ) => text,
+                openai::ChatCompletionRequestSystemMessageContent::Array(
+                    parts,
+                ) => text_content(parts.into_iter().map(|part| {
+                    match part {
+                    openai::ChatCompletionRequestSystemMessageContentPart::Text(
+                        text,
+                    ) => text.text,
+                }
+                })),
+            };
+            Some(CohereMessage::System {
+                content: CohereContent::Text(content),
+            })
+        }
+        openai::ChatCompletionRequestMessage::User(message) => {
+            let content = match message.content {
+                openai::ChatCompletionRequestUserMessageContent::Text(text) => {
+                    CohereContent::Text(text)
+                }
+                openai::ChatCompletionRequestUserMessageContent::Array(
+                    parts,
+                ) => CohereContent::Blocks(
+                    parts
+                        .into_iter()
+                        .filter_map(|part| match part {
+                            openai::ChatCompletionRequestUserMessageContentPart::Text(text) => {
+                                Some(CohereContentBlock::Text { text: text.text })
+                            }
+                            openai::ChatCompletionRequestUserMessageContentPart::ImageUrl(image) => {
+                                Some(CohereContentBlock::ImageUrl {
+                                    image_url: CohereImageUrl {
+                                        url: image.image_url.url,
+                                    },
+                                })
+                            }
+                            // Cohere does not support audio
+                            openai::ChatCompletionRequestUserMessageContentPart::InputAudio(_) => None,
+                        })
+                        .collect(),
+                ),
+            };
+            Some(CohereMessage::User { content })
+        }
+        openai::ChatCompletionRequestMessage::Assistant(message) => {
+            let content = message.content.map(|content| match content {
+                openai::ChatCompletionRequestAssistantMessageContent::Text(
+                    text,
+                ) => CohereContent::Text(text),
+                openai::ChatCompletionRequestAssistantMessageContent::Array(
+                    parts,
+                ) => CohereContent::Text(text_content(parts.into_iter().map(
+                    |part| match part {
+                        openai::ChatCompletionRequestAssistantMessageContentPart::Text(text) => text.text,
+                        openai::ChatCompletionRequestAssistantMessageContentPart::Refusal(refusal) => refusal.refusal,
+                    },
+                ))),
+            });
+            Some(CohereMessage::Assistant {
+                content,
+                tool_plan: None,
+                tool_calls: message.tool_calls,
+            })
+        }
+        openai::ChatCompletionRequestMessage::Tool(message) => {
+            let text = match message.content {
+                openai::ChatCompletionRequestToolMessageContent::Text(text) => {
+                    text
+                }
+                openai::ChatCompletionRequestToolMessageContent::Array(
+                    parts,
+                ) => text_content(parts.into_iter().map(|part| {
+                    match part {
+                    openai::ChatCompletionRequestToolMessageContentPart::Text(
+                        text,
+                    ) => text.text,
+                }
+                })),
+            };
+            let document = CohereDocument {
+                id: Some(message.tool_call_id.clone()),
+                data: serde_json::json!({ "content": text }),
+            };
+            Some(CohereMessage::Tool {
+                tool_call_id: message.tool_call_id,
+                content: CohereContent::Blocks(vec![
+                    CohereContentBlock::Document { document },
+                ]),
+            })
+        }
+        // deprecated by `OpenAI` and not supported by Cohere
+        openai::ChatCompletionRequestMessage::Function(_) => None,
+    }
+}
+
+fn finish_reason(value: CohereFinishReason) -> openai::FinishReason {
+    match value {
+        // `OpenAI` has no equivalent for errors or timeouts during generation
+        CohereFinishReason::Complete
+        | CohereFinishReason::StopSequence
+        | CohereFinishReason::Error
+        | CohereFinishReason::Timeout => openai::FinishReason::Stop,
+        CohereFinishReason::MaxTokens => openai::FinishReason::Length,
+        CohereFinishReason::ToolCall => openai::FinishReason::ToolCalls,
+    }
+}
+
+fn usage(value: &CohereUsage) -> openai::CompletionUsage {
+    let tokens = value.tokens();
+    openai::CompletionUsage {
+        prompt_tokens: tokens.input_tokens,
+        completion_tokens: tokens.output_tokens,
+        total_tokens: tokens.input_tokens + tokens.output_tokens,
+        prompt_tokens_details: None,
+        completion_tokens_details: None,
+    }
+}
+
+/// `thinking` content is dropped since chat completions have no equivalent.
+fn response_text(content: Vec<CohereResponseContent>) -> Option<String> {
+    let texts = content
+        .into_iter()
+        .filter(|content| content.kind != "thinking")
+        .filter_map(|content| content.text)
+        .collect::<Vec<_>>();
+    if texts.is_empty() {
+        None
+    } else {
+        Some(texts.concat())
+    }
+}
+
+fn stream_chunk(
+    id: Option<String>,
+    delta: openai::ChatCompletionStreamResponseDelta,
+    finish_reason: Option<openai::FinishReason>,
+    usage: Option<openai::CompletionUsage>,
+) -> CreateChatCompletionStreamResponse {
+    CreateChatCompletionStreamResponse {
+        id: id.unwrap_or_else(|| PLACEHOLDER_STREAM_ID.to_string()),
+        choices: vec![openai::ChatChoiceStream {
+            index: 0,
+            delta,
+            finish_reason,
+            logprobs: None,
+        }],
+        created: DEFAULT_CREATED_TIMESTAMP,
+        model: PLACEHOLDER_MODEL_NAME.to_string(),
+        object: OPENAI_CHAT_COMPLETION_CHUNK_OBJECT.to_string(),
+        system_fingerprint: None,
+        service_tier: None,
+        usage,
+    }
+}
+
+#[allow(deprecated)]
+fn empty_delta() -> openai::ChatCompletionStreamResponseDelta {
+    openai::ChatCompletionStreamResponseDelta {
+        role: None,
+        content: None,
+        tool_calls: None,
+        refusal: None,
+        function_call: None,
+    }
+}
+
+fn tool_call_chunk(
+    index: u32,
+    delta: CohereStreamDelta,
+) -> Option<openai::ChatCompletionMessageToolCallChunk> {
+    let tool_call = delta.message.tool_calls?;
+    Some(openai::ChatCompletionMessageToolCallChunk {
+        index,
+        r#type: tool_call
+            .id
+            .as_ref()
+            .map(|_| openai::ChatCompletionToolType::Function),
+        id: tool_call.id,
+        function: tool_call.function.map(|function| {
+            openai::FunctionCallStream {
+                name: function.name,
+                arguments: function.arguments,
+            }
+        }),
+    })
+}
+
+impl TryConvert<CreateChatCompletionRequest, CohereChatRequest>
+    for CohereConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateChatCompletionRequest,
+    ) -> Result<CohereChatRequest, Self::Error> {
+        let source_model = ModelId::from_str(&value.model)?;
+        let target_model = self
+            .model_mapper
+            .map_model(&source_model, &InferenceProvider::Cohere)?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+
+        let strict_tools = value
+            .tools
+            .as_ref()
+            .is_some_and(|tools| {
+                tools.iter().any(|tool| tool.function.strict == Some(true))
+            })
+            .then_some(true);
+        let mut tools = value.tools.map(|tools| {
+            tools
+                .into_iter()
+                .map(|tool| CohereTool {
+                    kind: "function".to_string(),
+                    function: CohereFunction {
+                        name: tool.function.name,
+                        description: tool.function.description,
+                        parameters: tool.function.parameters,
+                    },
+                })
+                .collect::<Vec<_>>()
+        });
+        // Cohere can't force a specific tool, so we only send that tool and
+        // require a tool call instead
+        let tool_choice = match value.tool_choice {
+            Some(openai::ChatCompletionToolChoiceOption::Named(named)) => {
+                if let Some(tools) = &mut tools {
+                    tools.retain(|tool| {
+                        tool.function.name == named.function.name
+                    });
+                }
+                Some(CohereToolChoice::Required)
+            }
+            Some(openai::ChatCompletionToolChoiceOption::Required) => {
+                Some(CohereToolChoice::Required)
+            }
+            Some(openai::ChatCompletionToolChoiceOption::None) => {
+                Some(CohereToolChoice::None)
+            }
+            Some(openai::ChatCompletionToolChoiceOption::Auto) | None => None,
+        };
+        let response_format = match value.response_format {
+            Some(openai::ResponseFormat::Text) => {
+                Some(CohereResponseFormat::Text)
+            }
+            Some(openai::ResponseFormat::JsonObject) => {
+                Some(CohereResponseFormat::JsonObject { json_schema: None })
+            }
+            Some(openai::ResponseFormat::JsonSchema { json_schema }) => {
+                Some(CohereResponseFormat::JsonObject {
+                    json_schema: json_schema.schema,
+                })
+            }
+            None => None,
+        };
+        let stop_sequences = match value.stop {
+            Some(openai::Stop::String(stop)) => Some(vec![stop]),
+            Some(openai::Stop::StringArray(stops)) => Some(stops),
+            None => None,
+        };
+        #[allow(deprecated)]
+        let max_tokens = value.max_completion_tokens.or(value.max_tokens);
+
+        Ok(CohereChatRequest {
+            model: target_model.to_string(),
+            messages: value
+                .messages
+                .into_iter()
+                .filter_map(map_message)
+                .collect(),
+            stream: value.stream,
+            tools,
+            tool_choice,
+            strict_tools,
+            response_format,
+            max_tokens,
+            stop_sequences,
+            temperature: value.temperature,
+            p: value.top_p,
+            seed: value.seed,
+            frequency_penalty: value.frequency_penalty,
+            presence_penalty: value.presence_penalty,
+        })
+    }
+}
+
+impl TryConvert<CohereChatResponse, CreateChatCompletionResponse>
+    for CohereConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CohereChatResponse,
+    ) -> Result<CreateChatCompletionResponse, Self::Error> {
+        #[allow(deprecated)]
+        let message = openai::ChatCompletionResponseMessage {
+            content: response_text(value.message.content),
+            refusal: None,
+            tool_calls: value.message.tool_calls,
+            role: openai::Role::Assistant,
+            function_call: None,
+            audio: None,
+        };
+        let choice = openai::ChatChoice {
+            index: 0,
+            message,
+            finish_reason: Some(finish_reason(value.finish_reason)),
+            logprobs: None,
+        };
+
+        Ok(CreateChatCompletionResponse {
+            id: value.id,
+            choices: vec![choice],
+            created: DEFAULT_CREATED_TIMESTAMP,
+            // Cohere does not include the model in responses
+            model: String::new(),
+            object: OPENAI_CHAT_COMPLETION_OBJECT.to_string(),
+            usage: value.usage.as_ref().map(usage),
+            service_tier: None,
+            system_fingerprint: None,
+        })
+    }
+}
+
+impl TryConvertStreamData<CohereStreamEvent, CreateChatCompletionStreamResponse>
+    for CohereConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CohereStreamEvent,
+    ) -> Result<Option<CreateChatCompletionStreamResponse>, Self::Error> {
+        let chunk = match value {
+            CohereStreamEvent::MessageStart { id } => {
+                let delta = openai::ChatCompletionStreamResponseDelta {
+                    role: Some(openai::Role::Assistant),
+                    ..empty_delta()
+                };
+                Some(stream_chunk(id, delta, None, None))
+            }
+            CohereStreamEvent::ContentStart { delta, .. }
+            | CohereStreamEvent::ContentDelta { delta, .. } => delta
+                .message
+                .content
+                .and_then(|content| response_text(vec![content]))
+                .map(|text| {
+                    let delta = openai::ChatCompletionStreamResponseDelta {
+                        content: Some(text),
+                        ..empty_delta()
+                    };
+                    stream_chunk(None, delta, None, None)
+                }),
+            CohereStreamEvent::ToolCallStart { index, delta }
+            | CohereStreamEvent::ToolCallDelta { index, delta } => {
+                tool_call_chunk(index, delta).map(|tool_call| {
+                    let delta = openai::ChatCompletionStreamResponseDelta {
+                        tool_calls: Some(vec![tool_call]),
+                        ..empty_delta()
+                    };
+                    stream_chunk(None, delta, None, None)
+                })
+            }
+            CohereStreamEvent::MessageEnd { delta } => Some(stream_chunk(
+                None,
+                empty_delta(),
+                delta.finish_reason.map(finish_reason),
+                delta.usage.as_ref().map(usage),
+            )),
+            CohereStreamEvent::ToolPlanDelta { .. }
+            | CohereStreamEvent::CitationStart { .. }
+            | CohereStreamEvent::Other => None,
+        };
+        Ok(chunk)
+    }
+}
+
+impl TryConvertError<CohereError, WrappedError> for CohereConverter {
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        value: CohereError,
+    ) -> Result<WrappedError, Self::Error> {
+        Ok(super::openai_error_from_status(
+            resp_parts.status,
+            value.message,
+        ))
+    }
+}
+
+impl TryConvert<CreateEmbeddingRequest, CohereEmbedRequest>
+    for CohereConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateEmbeddingRequest,
+    ) -> Result<CohereEmbedRequest, Self::Error> {
+        let source_model = ModelId::from_str(&value.model)?;
+        let target_model = self.model_mapper.map_model_for_endpoint(
+            &source_model,
+            &InferenceProvider::Cohere,
+            EndpointType::Embeddings,
+        )?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+
+        Ok(CohereEmbedRequest {
+            model: target_model.to_string(),
+            texts: super::embedding_input_texts(value.input)?,
+            // `OpenAI` embeddings don't distinguish between documents and
+            // queries
+            input_type: CohereInputType::SearchDocument,
+            embedding_types: vec![CohereEmbeddingType::Float],
+            output_dimension: value.dimensions,
+        })
+    }
+}
+
+impl TryConvert<CohereEmbedResponse, CreateEmbeddingResponse>
+    for CohereConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CohereEmbedResponse,
+    ) -> Result<CreateEmbeddingResponse, Self::Error> {
+        let prompt_tokens = value
+            .meta
+            .and_then(|meta| meta.billed_units)
+            .map_or(0, |tokens| tokens.input_tokens);
+        // the model is not included in Cohere's embed responses
+        Ok(super::embedding_response(
+            String::new(),
+            value.embeddings.float,
+            prompt_tokens,
+        ))
+    }
+}
+
+impl TryConvertStreamData<CohereEmbedResponse, CreateEmbeddingResponse>
+    for CohereConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CohereEmbedResponse,
+    ) -> Result<Option<CreateEmbeddingResponse>, Self::Error> {
+        // embeddings are never streamed, but this is required by the
+        // `TypedEndpointConverter`
+        <Self as TryConvert<CohereEmbedResponse, CreateEmbeddingResponse>>::try_convert(self, value)
+            .map(Some)
+    }
+}
+
+impl TryConvert<CreateResponseRequest, CohereChatRequest> for CohereConverter {
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateResponseRequest,
+    ) -> Result<CohereChatRequest, Self::Error> {
+        let chat_request = chat_request_from_responses(value)?;
+        <Self as TryConvert<CreateChatCompletionRequest, CohereChatRequest>>::try_convert(
+            self,
+            chat_request,
+        )
+    }
+}
+
+impl TryConvert<CohereChatResponse, ResponseObject> for CohereConverter {
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CohereChatResponse,
+    ) -> Result<ResponseObject, Self::Error> {
+        let chat_response = <Self as TryConvert<
+            CohereChatResponse,
+            CreateChatCompletionResponse,
+        >>::try_convert(self, value)?;
+        Ok(response_from_chat(chat_response))
+    }
+}
+
+impl TryConvertStreamData<CohereStreamEvent, ResponseStreamEvent>
+    for CohereConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CohereStreamEvent,
+    ) -> Result<Option<ResponseStreamEvent>, Self::Error> {
+        let chunk = <Self as TryConvertStreamData<
+            CohereStreamEvent,
+            CreateChatCompletionStreamResponse,
+        >>::try_convert_chunk(self, value)?;
+        Ok(chunk.and_then(stream_event_from_chat_chunk))
+    }
+}
+
+impl TryConvert<CreateMessageParams, CohereChatRequest> for CohereConverter {
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateMessageParams,
+    ) -> Result<CohereChatRequest, Self::Error> {
+        let chat_request = chat_request_from_messages(value)?;
+        <Self as TryConvert<CreateChatCompletionRequest, CohereChatRequest>>::try_convert(
+            self,
+            chat_request,
+        )
+    }
+}
+
+impl TryConvert<CohereChatResponse, CreateMessageResponse> for CohereConverter {
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CohereChatResponse,
+    ) -> Result<CreateMessageResponse, Self::Error> {
+        let chat_response = <Self as TryConvert<
+            CohereChatResponse,
+            CreateChatCompletionResponse,
+        >>::try_convert(self, value)?;
+        Ok(message_from_chat(chat_response))
+    }
+}
+
+impl TryConvertStreamData<CohereStreamEvent, StreamEvent> for CohereConverter {
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CohereStreamEvent,
+    ) -> Result<Option<StreamEvent>, Self::Error> {
+        let chunk = <Self as TryConvertStreamData<
+            CohereStreamEvent,
+            CreateChatCompletionStreamResponse,
+        >>::try_convert_chunk(self, value)?;
+        match chunk {
+            Some(chunk) => message_stream_event_from_chat_chunk(chunk),
+            None => Ok(None),
+        }
+    }
+}
+
+impl TryConvertError<CohereError, AnthropicApiError> for CohereConverter {
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        value: CohereError,
+    ) -> Result<AnthropicApiError, Self::Error> {
+        Ok(anthropic_error_from_status(
+            resp_parts.status,
+            value.message,
+        ))
+    }
+}
@@ -1,26 +1,71 @@
 use std::str::FromStr;
 
+use anthropic_ai_sdk::types::message::{
+    CreateMessageParams, CreateMessageResponse, StreamEvent,
+};
 use async_openai::types::{
     CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
+    CreateEmbeddingRequest, CreateEmbeddingResponse,
 };
 use http::response::Parts;
 
 use super::{TryConvert, TryConvertStreamData};
 use crate::{
-    endpoints::google::generate_contents::CreateChatCompletionRequestGemini,
+    endpoints::{
+        EndpointType,
+        anthropic::messages::AnthropicApiError,
+        google::{
+            embed_content::CreateEmbeddingRequestGemini,
+            generate_contents::CreateChatCompletionRequestGemini,
+            image_generations::CreateImageRequestGemini,
+        },
+        openai::{
+            image_generations::{
+                CreateImageRequest, ImageResponseFormat, ImagesResponse,
+            },
+            responses::{
+                CreateResponseRequest, ResponseObject, ResponseStreamEvent,
+            },
+        },
+        vertex::VERTEX_MODEL_PREFIX,
+    },
     error::mapper::MapperError,
-    middleware::mapper::{TryConvertError, model::ModelMapper},
+    middleware::mapper::{
+        TryConvertError,
+        messages::{
+            anthropic_error_from_status, chat_request_from_messages,
+            message_from_chat, message_stream_event_from_chat_chunk,
+        },
+        model::ModelMapper,
+        responses::{
+            chat_request_from_responses, response_from_chat,
+            stream_event_from_chat_chunk,
+        },
+    },
     types::{model_id::ModelId, provider::InferenceProvider},
 };
 
 pub struct GoogleGeminiConverter {
     model_mapper: ModelMapper,
+    target_provider: InferenceProvider,
 }
 
 impl GoogleGeminiConverter {
     #[must_use]
     pub fn new(model_mapper: ModelMapper) -> Self {
-        Self { model_mapper }
+        Self::for_provider(model_mapper, InferenceProvider::GoogleGemini)
+    }
+
+    /// For Vertex AI, which serves the same API for Gemini models.
+    #[must_use]
+    pub fn for_provider(
+        model_mapper: ModelMapper,
+        target_provider: InferenceProvider,
+    ) -> Self {
+        Self {
+            model_mapper,
+            target_provider,
+        }
     }
 }
 
@@ -38,10 +83,15 @@ impl
         let source_model = ModelId::from_str(&value.model)?;
         let target_model = self
             .model_mapper
-            .map_model(&source_model, &InferenceProvider::GoogleGemini)?;
+            .map_model(&source_model, &self.target_provider)?;
         tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
 
-        value.model = target_model.to_string();
+        value.model = if self.target_provider == InferenceProvider::GoogleVertex
+        {
+            format!("{VERTEX_MODEL_PREFIX}{target_model}")
+        } else {
+            target_model.to_string()
+        };
 
         Ok(CreateChatCompletionRequestGemini(value))
     }
@@ -94,3 +144,208 @@ impl
         Ok(value)
     }
 }
+
+impl TryConvert<CreateEmbeddingRequest, CreateEmbeddingRequestGemini>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        mut value: CreateEmbeddingRequest,
+    ) -> Result<CreateEmbeddingRequestGemini, Self::Error> {
+        let source_model = ModelId::from_str(&value.model)?;
+        let target_model = self.model_mapper.map_model_for_endpoint(
+            &source_model,
+            &InferenceProvider::GoogleGemini,
+            EndpointType::Embeddings,
+        )?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+
+        value.model = target_model.to_string();
+
+        Ok(CreateEmbeddingRequestGemini(value))
+    }
+}
+
+impl TryConvert<CreateEmbeddingResponse, CreateEmbeddingResponse>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        value: CreateEmbeddingResponse,
+    ) -> Result<CreateEmbeddingResponse, Self::Error> {
+        Ok(value)
+    }
+}
+
+impl TryConvertStreamData<CreateEmbeddingResponse, CreateEmbeddingResponse>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateEmbeddingResponse,
+    ) -> Result<Option<CreateEmbeddingResponse>, Self::Error> {
+        Ok(Some(value))
+    }
+}
+
+impl TryConvert<CreateImageRequest, CreateImageRequestGemini>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        value: CreateImageRequest,
+    ) -> Result<CreateImageRequestGemini, Self::Error> {
+        let source_model = ModelId::from_str(&value.model)?;
+        let target_model = self.model_mapper.map_model_for_endpoint(
+            &source_model,
+            &InferenceProvider::GoogleGemini,
+            EndpointType::Image,
+        )?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+
+        // Imagen only returns base64 encoded images, and the remaining
+        // `OpenAI` parameters (size, quality, style) aren't supported by
+        // Gemini's compatibility layer.
+        Ok(CreateImageRequestGemini(CreateImageRequest {
+            model: target_model.to_string(),
+            prompt: value.prompt,
+            n: value.n,
+            response_format: Some(ImageResponseFormat::B64Json),
+            ..Default::default()
+        }))
+    }
+}
+
+impl TryConvert<ImagesResponse, ImagesResponse> for GoogleGeminiConverter {
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        value: ImagesResponse,
+    ) -> Result<ImagesResponse, Self::Error> {
+        Ok(value)
+    }
+}
+
+impl TryConvertStreamData<ImagesResponse, ImagesResponse>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: ImagesResponse,
+    ) -> Result<Option<ImagesResponse>, Self::Error> {
+        Ok(Some(value))
+    }
+}
+
+impl TryConvert<CreateResponseRequest, CreateChatCompletionRequestGemini>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateResponseRequest,
+    ) -> Result<CreateChatCompletionRequestGemini, Self::Error> {
+        let chat_request = chat_request_from_responses(value)?;
+        <Self as TryConvert<
+            async_openai::types::CreateChatCompletionRequest,
+            CreateChatCompletionRequestGemini,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl TryConvert<CreateChatCompletionResponse, ResponseObject>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateChatCompletionResponse,
+    ) -> Result<ResponseObject, Self::Error> {
+        Ok(response_from_chat(value))
+    }
+}
+
+impl
+    TryConvertStreamData<
+        CreateChatCompletionStreamResponse,
+        ResponseStreamEvent,
+    > for GoogleGeminiConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateChatCompletionStreamResponse,
+    ) -> Result<Option<ResponseStreamEvent>, Self::Error> {
+        Ok(stream_event_from_chat_chunk(value))
+    }
+}
+
+impl TryConvert<CreateMessageParams, CreateChatCompletionRequestGemini>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateMessageParams,
+    ) -> Result<CreateChatCompletionRequestGemini, Self::Error> {
+        let chat_request = chat_request_from_messages(value)?;
+        <Self as TryConvert<
+            async_openai::types::CreateChatCompletionRequest,
+            CreateChatCompletionRequestGemini,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl TryConvert<CreateChatCompletionResponse, CreateMessageResponse>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateChatCompletionResponse,
+    ) -> Result<CreateMessageResponse, Self::Error> {
+        Ok(message_from_chat(value))
+    }
+}
+
+impl TryConvertStreamData<CreateChatCompletionStreamResponse, StreamEvent>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateChatCompletionStreamResponse,
+    ) -> Result<Option<StreamEvent>, Self::Error> {
+        message_stream_event_from_chat_chunk(value)
+    }
+}
+
+impl TryConvertError<async_openai::error::WrappedError, AnthropicApiError>
+    for GoogleGeminiConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        value: async_openai::error::WrappedError,
+    ) -> Result<AnthropicApiError, Self::Error> {
+        Ok(anthropic_error_from_status(
+            resp_parts.status,
+            Some(value.error.message),
+        ))
+    }
+}
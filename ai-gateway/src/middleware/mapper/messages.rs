@@ -0,0 +1,548 @@
+//! Conversions between the Anthropic Messages API and chat completions.
+//!
+//! Anthropic Messages requests sent to other providers are converted to chat
+//! completion requests and then mapped by the provider's chat converter.
+//! Responses, stream chunks and errors take the reverse path.
+use anthropic_ai_sdk::types::message as anthropic;
+use async_openai::types as openai;
+use http::StatusCode;
+
+use crate::{
+    endpoints::anthropic::messages::{AnthropicApiError, ErrorDetails},
+    error::mapper::MapperError,
+    types::provider::InferenceProvider,
+};
+
+const ANTHROPIC_MESSAGE_TYPE: &str = "message";
+const ANTHROPIC_ERROR_TYPE: &str = "error";
+
+/// Anthropic SDK users will typically send model names without a provider
+/// prefix, which we take to be Anthropic models.
+pub(super) fn qualified_model(model: &str) -> String {
+    if model.contains('/') {
+        model.to_string()
+    } else {
+        format!("{}/{model}", InferenceProvider::Anthropic)
+    }
+}
+
+/// Converts the request without mapping the model, which is left to the
+/// target provider's chat converter.
+#[allow(clippy::too_many_lines)]
+pub(super) fn chat_request_from_messages(
+    value: anthropic::CreateMessageParams,
+) -> Result<openai::CreateChatCompletionRequest, MapperError> {
+    let model = qualified_model(&value.model);
+    let reasoning_effort = if let Some(thinking) = value.thinking {
+        match thinking.type_ {
+            anthropic::ThinkingType::Enabled => {
+                #[allow(clippy::cast_precision_loss)]
+                let reasoning_budget =
+                    thinking.budget_tokens as f64 / f64::from(value.max_tokens);
+                match reasoning_budget {
+                    reasoning_budget if reasoning_budget < 0.33 => {
+                        Some(openai::ReasoningEffort::Low)
+                    }
+                    reasoning_budget if reasoning_budget < 0.66 => {
+                        Some(openai::ReasoningEffort::Medium)
+                    }
+                    reasoning_budget if reasoning_budget <= 1.0 => {
+                        Some(openai::ReasoningEffort::High)
+                    }
+                    _ => Some(openai::ReasoningEffort::Medium),
+                }
+            }
+        }
+    } else {
+        None
+    };
+
+    let max_completion_tokens = Some(value.max_tokens);
+    let stop = value.stop_sequences.map(openai::Stop::StringArray);
+    let stream = value.stream;
+    let stream_options = if stream.is_some_and(|s| s) {
+        Some(openai::ChatCompletionStreamOptions {
+            include_usage: true,
+        })
+    } else {
+        None
+    };
+    let temperature = value.temperature;
+    let top_p = value.top_p;
+    let tool_choice = match value.tool_choice {
+        Some(tool_choice) => match tool_choice {
+            anthropic::ToolChoice::Auto => {
+                Some(openai::ChatCompletionToolChoiceOption::Auto)
+            }
+            anthropic::ToolChoice::None => {
+                Some(openai::ChatCompletionToolChoiceOption::None)
+            }
+            anthropic::ToolChoice::Any => {
+                Some(openai::ChatCompletionToolChoiceOption::Required)
+            }
+            anthropic::ToolChoice::Tool { name } => {
+                let named_tool_choice = openai::ChatCompletionNamedToolChoice {
+                    r#type: openai::ChatCompletionToolType::Function,
+                    function: openai::FunctionName { name: name.clone() },
+                };
+                Some(openai::ChatCompletionToolChoiceOption::Named(
+                    named_tool_choice,
+                ))
+            }
+        },
+        None => None,
+    };
+    let tools: Option<Vec<openai::ChatCompletionTool>> =
+        if let Some(tools) = value.tools {
+            let mapped_tools: Vec<_> = tools
+                .into_iter()
+                .map(|tool| openai::ChatCompletionTool {
+                    r#type: openai::ChatCompletionToolType::Function,
+                    function: openai::FunctionObject {
+                        name: tool.name,
+                        description: tool.description,
+                        parameters: Some(tool.input_schema),
+                        strict: None,
+                    },
+                })
+                .collect();
+
+            Some(mapped_tools)
+        } else {
+            None
+        };
+    let mut metadata = value.metadata;
+    let user = metadata
+        .as_mut()
+        .and_then(|metadata| metadata.fields.remove("user_id"));
+    let metadata = match metadata {
+        Some(metadata) => Some(
+            serde_json::to_value(metadata)
+                .map_err(|_| MapperError::InvalidRequest)?,
+        ),
+        None => None,
+    };
+
+    let mut messages: Vec<openai::ChatCompletionRequestMessage> =
+        Vec::with_capacity(value.messages.len());
+    if let Some(system_prompt) = value.system {
+        messages.push(openai::ChatCompletionRequestMessage::Developer(
+            openai::ChatCompletionRequestDeveloperMessage {
+                content:
+                    openai::ChatCompletionRequestDeveloperMessageContent::Text(
+                        system_prompt,
+                    ),
+                name: None,
+            },
+        ));
+    }
+    for message in value.messages {
+        let mapped_message: openai::ChatCompletionRequestMessage = match message
+            .role
+        {
+            anthropic::Role::Assistant => {
+                let mapped_content: openai::ChatCompletionRequestAssistantMessageContent = match message.content {
+                    anthropic::MessageContent::Text { content } => {
+                        openai::ChatCompletionRequestAssistantMessageContent::Text(content)
+                    }
+                    anthropic::MessageContent::Blocks { content } => {
+                        let blocks = content.into_iter().filter_map(|block| {
+                            match block {
+                                anthropic::ContentBlock::Text { text, .. } => {
+                                    Some(openai::ChatCompletionRequestAssistantMessageContentPart::Text(openai::ChatCompletionRequestMessageContentPartText {
+                                        text
+                                    }))
+                                },
+                                anthropic::ContentBlock::Image { .. } |
+                                anthropic::ContentBlock::ToolUse { .. } |
+                                anthropic::ContentBlock::ToolResult { .. } |
+                                anthropic::ContentBlock::Thinking { .. } |
+                                anthropic::ContentBlock::RedactedThinking { .. } => {
+                                    None
+                                }
+                            }
+                        }).collect();
+                        openai::ChatCompletionRequestAssistantMessageContent::Array(blocks)
+                    }
+                };
+                #[allow(deprecated)]
+                openai::ChatCompletionRequestMessage::Assistant(
+                    openai::ChatCompletionRequestAssistantMessage {
+                        content: Some(mapped_content),
+                        tool_calls: None,
+                        refusal: None,
+                        name: None,
+                        audio: None,
+                        function_call: None,
+                    },
+                )
+            }
+            anthropic::Role::User => {
+                let content: openai::ChatCompletionRequestUserMessageContent  = match message.content {
+                    anthropic::MessageContent::Text { content } => {
+                        openai::ChatCompletionRequestUserMessageContent::Text(content)
+                    }
+                    anthropic::MessageContent::Blocks { content } => {
+                        let blocks = content.into_iter().filter_map(|block| {
+                            match block {
+                                anthropic::ContentBlock::Text { text, .. } => {
+                                    Some(openai::ChatCompletionRequestUserMessageContentPart::Text(openai::ChatCompletionRequestMessageContentPartText {
+                                        text,
+                                    }))
+                                },
+                                anthropic::ContentBlock::Image { source } => {
+                                    let image_url = openai::ImageUrl {
+                                        url: source.data,
+                                        detail: None,
+                                    };
+                                    Some(openai::ChatCompletionRequestUserMessageContentPart::ImageUrl(openai::ChatCompletionRequestMessageContentPartImage {
+                                        image_url,
+                                    }))
+                                },
+                                anthropic::ContentBlock::ToolUse { .. } |
+                                anthropic::ContentBlock::ToolResult { .. } |
+                                anthropic::ContentBlock::Thinking { .. } |
+                                anthropic::ContentBlock::RedactedThinking { .. } => {
+                                    None
+                                }
+                            }
+                        }).collect();
+                        openai::ChatCompletionRequestUserMessageContent::Array(blocks)
+                    }
+                };
+                openai::ChatCompletionRequestMessage::User(
+                    openai::ChatCompletionRequestUserMessage {
+                        content,
+                        name: None,
+                    },
+                )
+            }
+        };
+        messages.push(mapped_message);
+    }
+
+    #[allow(deprecated)]
+    let request = async_openai::types::CreateChatCompletionRequest {
+        messages,
+        model,
+        store: None,
+        reasoning_effort,
+        metadata,
+        parallel_tool_calls: None,
+        stop,
+        stream,
+        stream_options,
+        temperature,
+        top_p,
+        tools,
+        tool_choice,
+        user,
+        max_completion_tokens,
+        max_tokens: None,
+        frequency_penalty: None,
+        logit_bias: None,
+        logprobs: None,
+        n: None,
+        modalities: None,
+        presence_penalty: None,
+        prediction: None,
+        response_format: None,
+        seed: None,
+        service_tier: None,
+        top_logprobs: None,
+        audio: None,
+        function_call: None,
+        functions: None,
+        web_search_options: None,
+    };
+
+    Ok(request)
+}
+
+pub(super) fn message_from_chat(
+    mut value: openai::CreateChatCompletionResponse,
+) -> anthropic::CreateMessageResponse {
+    let id = value.id;
+    let model = value.model;
+    let role = anthropic::Role::Assistant;
+    // not exposed by OpenAI
+    let stop_sequence: Option<String> = None;
+    // For Messages, this is always "message"
+    let type_ = ANTHROPIC_MESSAGE_TYPE.to_string();
+    let usage = value.usage.map_or(
+        anthropic::Usage {
+            input_tokens: 0,
+            output_tokens: 0,
+        },
+        |usage| anthropic::Usage {
+            input_tokens: usage.prompt_tokens,
+            output_tokens: usage.completion_tokens,
+        },
+    );
+
+    let openai_message = value.choices.remove(0);
+    let stop_reason = if openai_message.message.refusal.is_some() {
+        Some(anthropic::StopReason::Refusal)
+    } else {
+        None
+    };
+    let mut content: Vec<anthropic::ContentBlock> = Vec::new();
+
+    if let Some(tool_uses) = openai_message.message.tool_calls {
+        for tool_use in tool_uses {
+            if let Ok(input) =
+                serde_json::from_str(&tool_use.function.arguments)
+            {
+                let tool_use = anthropic::ContentBlock::ToolUse {
+                    id: tool_use.id,
+                    name: tool_use.function.name,
+                    input,
+                };
+                content.push(tool_use);
+            }
+        }
+    }
+    if let Some(text) = openai_message.message.content {
+        let text = anthropic::ContentBlock::Text { text };
+        content.push(text);
+    }
+
+    anthropic::CreateMessageResponse {
+        content,
+        id,
+        model,
+        role,
+        stop_reason,
+        stop_sequence,
+        type_,
+        usage,
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+pub(super) fn message_stream_event_from_chat_chunk(
+    value: openai::CreateChatCompletionStreamResponse,
+) -> Result<Option<anthropic::StreamEvent>, MapperError> {
+    let choices = &value.choices;
+    if choices.is_empty() {
+        return Ok(None);
+    }
+    let first_choice = &choices[0];
+    let delta = &first_choice.delta;
+
+    // Priority 1: MessageStart Event (indicated by `role` field)
+    if let Some(openai_role) = delta.role {
+        let anthropic_role = match openai_role {
+            openai::Role::Assistant => anthropic::Role::Assistant,
+            openai::Role::User => anthropic::Role::User, /* Should not */
+            // happen in assistant
+            // stream
+            _ => return Err(MapperError::InvalidRequest), /* Or a more specific error */
+        };
+
+        let mut content_blocks: Vec<anthropic::ContentBlock> = Vec::new();
+
+        // Add text content if present in the MessageStart delta
+        if let Some(text) = &delta.content {
+            content_blocks
+                .push(anthropic::ContentBlock::Text { text: text.clone() });
+        }
+
+        // Add tool_calls if present in the MessageStart delta
+        if let Some(tool_call_chunks) = &delta.tool_calls {
+            for tc_chunk in tool_call_chunks {
+                if let (Some(id), Some(func), Some(name)) = (
+                    tc_chunk.id.as_ref(),
+                    tc_chunk.function.as_ref(),
+                    tc_chunk.function.as_ref().and_then(|f| f.name.as_ref()),
+                ) {
+                    let input_str = func.arguments.as_deref().unwrap_or("{}");
+                    if let Ok(input_json) = serde_json::from_str(input_str) {
+                        let tool_use_block = anthropic::ContentBlock::ToolUse {
+                            id: id.clone(),
+                            name: name.clone(),
+                            input: input_json,
+                        };
+                        content_blocks.push(tool_use_block);
+                    }
+                    // Handle error or default for bad JSON
+                    let tool_use_block = anthropic::ContentBlock::ToolUse {
+                        id: id.clone(),
+                        name: name.clone(),
+                        input: serde_json::json!({}),
+                    };
+                    content_blocks.push(tool_use_block);
+                }
+            }
+        }
+
+        // Note: Anthropic's MessageStartContent.usage is full usage,
+        // OpenAI stream start doesn't have it. It's sent with MessageDelta
+        // typically. So, we initialize usage with default/zero
+        // values.
+        let initial_usage = anthropic::Usage {
+            input_tokens: 0,
+            output_tokens: 0,
+        };
+
+        let message_start_content = anthropic::MessageStartContent {
+            id: value.id.clone(),
+            type_: ANTHROPIC_MESSAGE_TYPE.to_string(),
+            role: anthropic_role,
+            content: content_blocks,
+            model: value.model.clone(),
+            stop_reason: None, // Stop reason comes later
+            stop_sequence: None,
+            usage: initial_usage,
+        };
+        return Ok(Some(anthropic::StreamEvent::MessageStart {
+            message: message_start_content,
+        }));
+    }
+
+    // Priority 2: MessageDelta Event (for finish_reason)
+    if let Some(finish_reason) = first_choice.finish_reason {
+        let anthropic_stop_reason = match finish_reason {
+            openai::FinishReason::Stop => anthropic::StopReason::EndTurn,
+            openai::FinishReason::Length => anthropic::StopReason::MaxTokens,
+            openai::FinishReason::ToolCalls
+            | openai::FinishReason::FunctionCall => {
+                anthropic::StopReason::ToolUse
+            }
+            openai::FinishReason::ContentFilter => {
+                anthropic::StopReason::Refusal
+            }
+        };
+
+        let stream_usage = value.usage.map_or_else(
+            || anthropic::StreamUsage {
+                // Default if OpenAI chunk has no usage
+                input_tokens: 0,
+                output_tokens: 0,
+            },
+            |u| anthropic::StreamUsage {
+                input_tokens: u.prompt_tokens,
+                output_tokens: u.completion_tokens,
+            },
+        );
+
+        let message_delta_content = anthropic::MessageDeltaContent {
+            stop_reason: Some(anthropic_stop_reason),
+            stop_sequence: None, /* OpenAI stream doesn't provide the
+                                  * matched stop sequence in delta */
+        };
+        return Ok(Some(anthropic::StreamEvent::MessageDelta {
+            delta: message_delta_content,
+            usage: Some(stream_usage),
+        }));
+    }
+
+    // Priority 3: Tool Call Events
+    if let Some(tool_call_chunks) = &delta.tool_calls {
+        if let Some(tc_chunk) = tool_call_chunks.first() {
+            // Process only the first tool_call_chunk due to
+            // Option<StreamEvent> return
+            let anthropic_block_idx = tc_chunk.index as usize; // Use OpenAI's tool index as Anthropic's content block index
+
+            // Check if it's a new tool call (ContentBlockStart)
+            if let (Some(id), Some(func), Some(name)) = (
+                tc_chunk.id.as_ref(),
+                tc_chunk.function.as_ref(),
+                tc_chunk.function.as_ref().and_then(|f| f.name.as_ref()),
+            ) {
+                let input_str = func.arguments.as_deref().unwrap_or("{}");
+                if let Ok(input_json) = serde_json::from_str(input_str) {
+                    let tool_use_block = anthropic::ContentBlock::ToolUse {
+                        id: id.clone(),
+                        name: name.clone(),
+                        input: input_json,
+                    };
+                    return Ok(Some(
+                        anthropic::StreamEvent::ContentBlockStart {
+                            index: anthropic_block_idx,
+                            content_block: tool_use_block,
+                        },
+                    ));
+                }
+                // Handle error or default for bad JSON
+                let tool_use_block = anthropic::ContentBlock::ToolUse {
+                    id: id.clone(),
+                    name: name.clone(),
+                    input: serde_json::json!({}),
+                };
+                return Ok(Some(anthropic::StreamEvent::ContentBlockStart {
+                    index: anthropic_block_idx,
+                    content_block: tool_use_block,
+                }));
+            }
+            // Check if it's a delta for an existing tool call's arguments
+            // (ContentBlockDelta)
+            else if let Some(func_args) = tc_chunk
+                .function
+                .as_ref()
+                .and_then(|f| f.arguments.as_ref())
+            {
+                let input_json_delta =
+                    anthropic::ContentBlockDelta::InputJsonDelta {
+                        partial_json: func_args.clone(),
+                    };
+                return Ok(Some(anthropic::StreamEvent::ContentBlockDelta {
+                    index: anthropic_block_idx,
+                    delta: input_json_delta,
+                }));
+            }
+        }
+    }
+
+    // Priority 4: Text Content Delta
+    if let Some(text_content) = &delta.content {
+        let text_delta = anthropic::ContentBlockDelta::TextDelta {
+            text: text_content.clone(),
+        };
+        // Convention: general text deltas map to Anthropic content block
+        // index 0
+        return Ok(Some(anthropic::StreamEvent::ContentBlockDelta {
+            index: 0,
+            delta: text_delta,
+        }));
+    }
+
+    // Priority 5: Refusal Content Delta (if OpenAI includes it separately)
+    if let Some(refusal_text) = &delta.refusal {
+        let refusal_delta = anthropic::ContentBlockDelta::TextDelta {
+            text: refusal_text.clone(),
+        };
+        // Convention: map refusal text to Anthropic content block index 0
+        // (or a specific index for refusals)
+        return Ok(Some(anthropic::StreamEvent::ContentBlockDelta {
+            index: 0,
+            delta: refusal_delta,
+        }));
+    }
+
+    Ok(None) // No convertible event found in this chunk
+}
+
+/// Builds an error body in the shape the Anthropic SDKs expect, using the
+/// error type Anthropic returns for the given status.
+pub(super) fn anthropic_error_from_status(
+    status_code: StatusCode,
+    message: Option<String>,
+) -> AnthropicApiError {
+    let kind = match status_code.as_u16() {
+        400 => "invalid_request_error",
+        401 => "authentication_error",
+        403 => "permission_error",
+        404 => "not_found_error",
+        413 => "request_too_large",
+        429 => "rate_limit_error",
+        529 => "overloaded_error",
+        _ => "api_error",
+    }
+    .to_string();
+    let message = message.unwrap_or_else(|| kind.clone());
+    AnthropicApiError {
+        error: ErrorDetails { message, kind },
+        kind: ANTHROPIC_ERROR_TYPE.to_string(),
+    }
+}
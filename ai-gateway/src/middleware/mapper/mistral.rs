@@ -0,0 +1,599 @@
+use std::{
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use anthropic_ai_sdk::types::message::{
+    CreateMessageParams, CreateMessageResponse, StreamEvent,
+};
+use async_openai::{
+    error::WrappedError,
+    types::{
+        self as openai, CreateChatCompletionRequest,
+        CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
+        CreateEmbeddingRequest, CreateEmbeddingResponse,
+    },
+};
+use http::response::Parts;
+
+use super::{TryConvert, TryConvertStreamData};
+use crate::{
+    endpoints::{
+        EndpointType,
+        anthropic::messages::AnthropicApiError,
+        mistral::{
+            chat_completions::{
+                MistralChatCompletionChunk, MistralChatCompletionRequest,
+                MistralChatCompletionResponse, MistralError,
+                MistralFinishReason, MistralToolCall, MistralUsage,
+            },
+            embeddings::MistralEmbeddingRequest,
+        },
+        openai::responses::{
+            CreateResponseRequest, ResponseObject, ResponseStreamEvent,
+        },
+    },
+    error::mapper::MapperError,
+    middleware::mapper::{
+        TryConvertError,
+        anthropic::OPENAI_CHAT_COMPLETION_OBJECT,
+        messages::{
+            anthropic_error_from_status, chat_request_from_messages,
+            message_from_chat, message_stream_event_from_chat_chunk,
+        },
+        model::ModelMapper,
+        responses::{
+            chat_request_from_responses, response_from_chat,
+            stream_event_from_chat_chunk,
+        },
+    },
+    types::{
+        model_id::{ModelId, Version},
+        provider::InferenceProvider,
+    },
+};
+
+const OPENAI_CHAT_COMPLETION_CHUNK_OBJECT: &str = "chat.completion.chunk";
+const MISTRAL_TOOL_CALL_ID_LEN: usize = 9;
+const ALPHANUMERIC: &[u8] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Mistral mostly accepts `OpenAI` chat completion payloads, but differs in
+/// a few ways:
+/// - tool call ids must be 9 alphanumeric characters, so ids generated by other
+///   providers are rewritten when a conversation is replayed
+/// - the `developer` role is not supported
+/// - tool call arguments may be returned as an object, and tool calls may be
+///   streamed without an `index`
+pub struct MistralConverter {
+    model_mapper: ModelMapper,
+}
+
+impl MistralConverter {
+    #[must_use]
+    pub fn new(model_mapper: ModelMapper) -> Self {
+        Self { model_mapper }
+    }
+}
+
+/// Deterministically maps a tool call id to one Mistral accepts, so that an
+/// assistant's tool call and the matching tool message keep the same id.
+fn mistral_tool_call_id(id: &str) -> String {
+    if id.len() == MISTRAL_TOOL_CALL_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return id.to_string();
+    }
+    let mut hasher = rustc_hash::FxHasher::default();
+    id.hash(&mut hasher);
+    let mut hash = hasher.finish();
+    let base = ALPHANUMERIC.len() as u64;
+    (0..MISTRAL_TOOL_CALL_ID_LEN)
+        .map(|_| {
+            #[allow(clippy::cast_possible_truncation)]
+            let c = ALPHANUMERIC[(hash % base) as usize] as char;
+            hash /= base;
+            c
+        })
+        .collect()
+}
+
+fn map_message(
+    message: openai::ChatCompletionRequestMessage,
+) -> Option<openai::ChatCompletionRequestMessage> {
+    match message {
+        openai::ChatCompletionRequestMessage::Developer(message) => {
+            let content = match message.content {
+                openai::ChatCompletionRequestDeveloperMessageContent::Text(
+                    text,
+                ) => openai::ChatCompletionRequestSystemMessageContent::Text(
+                    text,
+                ),
+                openai::ChatCompletionRequestDeveloperMessageContent::Array(
+                    parts,
+                ) => openai::ChatCompletionRequestSystemMessageContent::Array(
+                    parts
+                        .into_iter()
+                        .map(
+                            openai::ChatCompletionRequestSystemMessageContentPart::Text,
+                        )
+                        .collect(),
+                ),
+            };
+            Some(openai::ChatCompletionRequestMessage::System(
+                openai::ChatCompletionRequestSystemMessage {
+                    content,
+                    name: None,
+                },
+            ))
+        }
+        openai::ChatCompletionRequestMessage::System(mut message) => {
+            message.name = None;
+            Some(openai::ChatCompletionRequestMessage::System(message))
+        }
+        openai::ChatCompletionRequestMessage::User(mut message) => {
+            message.name = None;
+            if let openai::ChatCompletionRequestUserMessageContent::Array(
+                parts,
+            ) = &mut message.content
+            {
+                // audio inputs use a different format
+                parts.retain(|part| {
+                    !matches!(
+                        part,
+                        openai::ChatCompletionRequestUserMessageContentPart::InputAudio(_)
+                    )
+                });
+            }
+            Some(openai::ChatCompletionRequestMessage::User(message))
+        }
+        openai::ChatCompletionRequestMessage::Assistant(mut message) => {
+            message.name = None;
+            message.refusal = None;
+            message.audio = None;
+            if let Some(
+                openai::ChatCompletionRequestAssistantMessageContent::Array(
+                    parts,
+                ),
+            ) = message.content
+            {
+                let text = parts
+                    .into_iter()
+                    .map(|part| match part {
+                        openai::ChatCompletionRequestAssistantMessageContentPart::Text(text) => text.text,
+                        openai::ChatCompletionRequestAssistantMessageContentPart::Refusal(refusal) => refusal.refusal,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                message.content = Some(
+                    openai::ChatCompletionRequestAssistantMessageContent::Text(
+                        text,
+                    ),
+                );
+            }
+            if let Some(tool_calls) = &mut message.tool_calls {
+                for tool_call in tool_calls {
+                    tool_call.id = mistral_tool_call_id(&tool_call.id);
+                }
+            }
+            Some(openai::ChatCompletionRequestMessage::Assistant(message))
+        }
+        openai::ChatCompletionRequestMessage::Tool(mut message) => {
+            message.tool_call_id = mistral_tool_call_id(&message.tool_call_id);
+            Some(openai::ChatCompletionRequestMessage::Tool(message))
+        }
+        // deprecated by `OpenAI` and not supported by Mistral
+        openai::ChatCompletionRequestMessage::Function(_) => None,
+    }
+}
+
+fn finish_reason(value: MistralFinishReason) -> openai::FinishReason {
+    match value {
+        // `OpenAI` has no equivalent for errors during generation
+        MistralFinishReason::Stop | MistralFinishReason::Error => {
+            openai::FinishReason::Stop
+        }
+        MistralFinishReason::Length | MistralFinishReason::ModelLength => {
+            openai::FinishReason::Length
+        }
+        MistralFinishReason::ToolCalls => openai::FinishReason::ToolCalls,
+    }
+}
+
+fn tool_call_from_mistral(
+    value: MistralToolCall,
+) -> openai::ChatCompletionMessageToolCall {
+    let arguments = value.function.arguments_string().unwrap_or_default();
+    openai::ChatCompletionMessageToolCall {
+        id: value.id.unwrap_or_default(),
+        r#type: openai::ChatCompletionToolType::Function,
+        function: openai::FunctionCall {
+            name: value.function.name.unwrap_or_default(),
+            arguments,
+        },
+    }
+}
+
+fn usage(value: MistralUsage) -> openai::CompletionUsage {
+    openai::CompletionUsage {
+        prompt_tokens: value.prompt_tokens,
+        completion_tokens: value.completion_tokens,
+        total_tokens: value.total_tokens,
+        prompt_tokens_details: None,
+        completion_tokens_details: None,
+    }
+}
+
+impl TryConvert<CreateChatCompletionRequest, MistralChatCompletionRequest>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateChatCompletionRequest,
+    ) -> Result<MistralChatCompletionRequest, Self::Error> {
+        let source_model = ModelId::from_str(&value.model)?;
+        let mut target_model = self
+            .model_mapper
+            .map_model(&source_model, &InferenceProvider::Mistral)?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+
+        // Mistral's aliases for the latest version of a model require an
+        // explicit `-latest` suffix
+        if let ModelId::ModelIdWithVersion { id, .. } = &mut target_model
+            && matches!(id.version, Version::ImplicitLatest)
+        {
+            id.version = Version::Latest;
+        }
+
+        #[allow(deprecated)]
+        let max_tokens = value.max_completion_tokens.or(value.max_tokens);
+
+        Ok(MistralChatCompletionRequest {
+            model: target_model.to_string(),
+            messages: value
+                .messages
+                .into_iter()
+                .filter_map(map_message)
+                .collect(),
+            temperature: value.temperature,
+            top_p: value.top_p,
+            max_tokens,
+            stream: value.stream,
+            stop: value.stop,
+            random_seed: value.seed,
+            response_format: value.response_format,
+            tools: value.tools,
+            tool_choice: value.tool_choice,
+            parallel_tool_calls: value.parallel_tool_calls,
+            presence_penalty: value.presence_penalty,
+            frequency_penalty: value.frequency_penalty,
+            n: value.n,
+        })
+    }
+}
+
+impl TryConvert<MistralChatCompletionResponse, CreateChatCompletionResponse>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: MistralChatCompletionResponse,
+    ) -> Result<CreateChatCompletionResponse, Self::Error> {
+        let choices = value
+            .choices
+            .into_iter()
+            .map(|choice| {
+                let tool_calls = choice.message.tool_calls.map(|tool_calls| {
+                    tool_calls.into_iter().map(tool_call_from_mistral).collect()
+                });
+                #[allow(deprecated)]
+                let message = openai::ChatCompletionResponseMessage {
+                    content: choice
+                        .message
+                        .content
+                        .map(|content| content.into_text()),
+                    refusal: None,
+                    tool_calls,
+                    role: openai::Role::Assistant,
+                    function_call: None,
+                    audio: None,
+                };
+                openai::ChatChoice {
+                    index: choice.index,
+                    message,
+                    finish_reason: choice.finish_reason.map(finish_reason),
+                    logprobs: None,
+                }
+            })
+            .collect();
+
+        Ok(CreateChatCompletionResponse {
+            id: value.id,
+            choices,
+            created: value.created,
+            model: value.model,
+            object: OPENAI_CHAT_COMPLETION_OBJECT.to_string(),
+            usage: value.usage.map(usage),
+            service_tier: None,
+            system_fingerprint: None,
+        })
+    }
+}
+
+impl
+    TryConvertStreamData<
+        MistralChatCompletionChunk,
+        CreateChatCompletionStreamResponse,
+    > for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: MistralChatCompletionChunk,
+    ) -> Result<Option<CreateChatCompletionStreamResponse>, Self::Error> {
+        let choices = value
+            .choices
+            .into_iter()
+            .map(|choice| {
+                let tool_calls = choice.delta.tool_calls.map(|tool_calls| {
+                    tool_calls
+                        .into_iter()
+                        .zip(0..)
+                        .map(|(tool_call, position)| {
+                            let arguments =
+                                tool_call.function.arguments_string();
+                            openai::ChatCompletionMessageToolCallChunk {
+                                index: tool_call.index.unwrap_or(position),
+                                r#type: tool_call.id.as_ref().map(|_| {
+                                    openai::ChatCompletionToolType::Function
+                                }),
+                                id: tool_call.id,
+                                function: Some(openai::FunctionCallStream {
+                                    name: tool_call.function.name,
+                                    arguments,
+                                }),
+                            }
+                        })
+                        .collect()
+                });
+                #[allow(deprecated)]
+                let delta = openai::ChatCompletionStreamResponseDelta {
+                    role: choice.delta.role.map(|_| openai::Role::Assistant),
+                    content: choice
+                        .delta
+                        .content
+                        .map(|content| content.into_text()),
+                    tool_calls,
+                    refusal: None,
+                    function_call: None,
+                };
+                openai::ChatChoiceStream {
+                    index: choice.index,
+                    delta,
+                    finish_reason: choice.finish_reason.map(finish_reason),
+                    logprobs: None,
+                }
+            })
+            .collect();
+
+        Ok(Some(CreateChatCompletionStreamResponse {
+            id: value.id,
+            choices,
+            created: value.created,
+            model: value.model,
+            object: OPENAI_CHAT_COMPLETION_CHUNK_OBJECT.to_string(),
+            system_fingerprint: None,
+            service_tier: None,
+            usage: value.usage.map(usage),
+        }))
+    }
+}
+
+impl TryConvertError<MistralError, WrappedError> for MistralConverter {
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        value: MistralError,
+    ) -> Result<WrappedError, Self::Error> {
+        Ok(super::openai_error_from_status(
+            resp_parts.status,
+            value.into_message(),
+        ))
+    }
+}
+
+impl TryConvert<CreateEmbeddingRequest, MistralEmbeddingRequest>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateEmbeddingRequest,
+    ) -> Result<MistralEmbeddingRequest, Self::Error> {
+        let source_model = ModelId::from_str(&value.model)?;
+        let target_model = self.model_mapper.map_model_for_endpoint(
+            &source_model,
+            &InferenceProvider::Mistral,
+            EndpointType::Embeddings,
+        )?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+
+        Ok(MistralEmbeddingRequest {
+            model: target_model.to_string(),
+            input: super::embedding_input_texts(value.input)?,
+            output_dimension: value.dimensions,
+        })
+    }
+}
+
+impl TryConvert<CreateEmbeddingResponse, CreateEmbeddingResponse>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateEmbeddingResponse,
+    ) -> Result<CreateEmbeddingResponse, Self::Error> {
+        Ok(value)
+    }
+}
+
+impl TryConvertStreamData<CreateEmbeddingResponse, CreateEmbeddingResponse>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateEmbeddingResponse,
+    ) -> Result<Option<CreateEmbeddingResponse>, Self::Error> {
+        // embeddings are never streamed, but this is required by the
+        // `TypedEndpointConverter`
+        Ok(Some(value))
+    }
+}
+
+impl TryConvert<CreateResponseRequest, MistralChatCompletionRequest>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateResponseRequest,
+    ) -> Result<MistralChatCompletionRequest, Self::Error> {
+        let chat_request = chat_request_from_responses(value)?;
+        <Self as TryConvert<
+            CreateChatCompletionRequest,
+            MistralChatCompletionRequest,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl TryConvert<MistralChatCompletionResponse, ResponseObject>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: MistralChatCompletionResponse,
+    ) -> Result<ResponseObject, Self::Error> {
+        let chat_response = <Self as TryConvert<
+            MistralChatCompletionResponse,
+            CreateChatCompletionResponse,
+        >>::try_convert(self, value)?;
+        Ok(response_from_chat(chat_response))
+    }
+}
+
+impl TryConvertStreamData<MistralChatCompletionChunk, ResponseStreamEvent>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: MistralChatCompletionChunk,
+    ) -> Result<Option<ResponseStreamEvent>, Self::Error> {
+        let chunk = <Self as TryConvertStreamData<
+            MistralChatCompletionChunk,
+            CreateChatCompletionStreamResponse,
+        >>::try_convert_chunk(self, value)?;
+        Ok(chunk.and_then(stream_event_from_chat_chunk))
+    }
+}
+
+impl TryConvert<CreateMessageParams, MistralChatCompletionRequest>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateMessageParams,
+    ) -> Result<MistralChatCompletionRequest, Self::Error> {
+        let chat_request = chat_request_from_messages(value)?;
+        <Self as TryConvert<
+            CreateChatCompletionRequest,
+            MistralChatCompletionRequest,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl TryConvert<MistralChatCompletionResponse, CreateMessageResponse>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: MistralChatCompletionResponse,
+    ) -> Result<CreateMessageResponse, Self::Error> {
+        let chat_response = <Self as TryConvert<
+            MistralChatCompletionResponse,
+            CreateChatCompletionResponse,
+        >>::try_convert(self, value)?;
+        Ok(message_from_chat(chat_response))
+    }
+}
+
+impl TryConvertStreamData<MistralChatCompletionChunk, StreamEvent>
+    for MistralConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: MistralChatCompletionChunk,
+    ) -> Result<Option<StreamEvent>, Self::Error> {
+        let chunk = <Self as TryConvertStreamData<
+            MistralChatCompletionChunk,
+            CreateChatCompletionStreamResponse,
+        >>::try_convert_chunk(self, value)?;
+        match chunk {
+            Some(chunk) => message_stream_event_from_chat_chunk(chunk),
+            None => Ok(None),
+        }
+    }
+}
+
+impl TryConvertError<MistralError, AnthropicApiError> for MistralConverter {
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        value: MistralError,
+    ) -> Result<AnthropicApiError, Self::Error> {
+        Ok(anthropic_error_from_status(
+            resp_parts.status,
+            value.into_message(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_call_ids_are_rewritten_for_mistral() {
+        let id = mistral_tool_call_id("call_Xt3kbLwN3yS2yYdXqDBcN1pG");
+        assert_eq!(id.len(), MISTRAL_TOOL_CALL_ID_LEN);
+        assert!(id.chars().all(|c| c.is_ascii_alphanumeric()));
+        // the assistant's tool call and the tool message must agree
+        assert_eq!(id, mistral_tool_call_id("call_Xt3kbLwN3yS2yYdXqDBcN1pG"));
+        // ids generated by mistral are kept as is
+        assert_eq!(mistral_tool_call_id("D681PevKs"), "D681PevKs");
+    }
+}
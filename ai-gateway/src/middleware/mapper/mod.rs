@@ -23,18 +23,29 @@
 //! this struct then helps us deserialize to the correct type and then
 //! call the `TryConvert` fn.
 pub mod anthropic;
+mod azure;
 mod bedrock;
+mod cohere;
 mod gemini;
+mod messages;
+mod mistral;
 pub mod model;
 pub mod ollama;
 pub mod openai;
+pub mod passthrough;
 pub mod registry;
+mod responses;
 pub mod service;
 
-use async_openai::error::WrappedError;
+use async_openai::{
+    error::WrappedError,
+    types::{
+        CreateEmbeddingResponse, Embedding, EmbeddingInput, EmbeddingUsage,
+    },
+};
 use base64::Engine;
 use bytes::Bytes;
-use http::{StatusCode, response::Parts};
+use http::{HeaderMap, StatusCode, response::Parts};
 use serde::{Serialize, de::DeserializeOwned};
 
 pub use self::service::*;
@@ -88,6 +99,7 @@ pub trait EndpointConverter {
     /// bytes to a concrete type.
     fn convert_req_body(
         &self,
+        req_headers: &HeaderMap,
         req_body_bytes: Bytes,
     ) -> Result<(Bytes, MapperContext), ApiError>;
     /// Convert a response body to a target response body with raw bytes.
@@ -100,6 +112,11 @@ pub trait EndpointConverter {
         resp_body_bytes: Bytes,
         is_stream: bool,
     ) -> Result<Option<Bytes>, ApiError>;
+    /// If `true`, response bodies are forwarded to the client as is rather
+    /// than being buffered and passed to `convert_resp_body`.
+    fn is_passthrough(&self) -> bool {
+        false
+    }
 }
 
 pub struct TypedEndpointConverter<S, T, C>
@@ -152,6 +169,7 @@ where
 {
     fn convert_req_body(
         &self,
+        _req_headers: &HeaderMap,
         bytes: Bytes,
     ) -> Result<(Bytes, MapperContext), ApiError> {
         let source_request: S::RequestBody = serde_json::from_slice(&bytes)
@@ -271,6 +289,48 @@ pub(crate) fn openai_error_from_status(
     }
 }
 
+/// Only text inputs can be mapped to other providers, since token arrays are
+/// specific to `OpenAI`'s tokenizers.
+pub(super) fn embedding_input_texts(
+    input: EmbeddingInput,
+) -> Result<Vec<String>, MapperError> {
+    match input {
+        EmbeddingInput::String(text) => Ok(vec![text]),
+        EmbeddingInput::StringArray(texts) => Ok(texts),
+        EmbeddingInput::IntegerArray(_)
+        | EmbeddingInput::ArrayOfIntegerArray(_) => {
+            Err(MapperError::UnsupportedEmbeddingInput(
+                "token array inputs are only supported by openai".to_string(),
+            ))
+        }
+    }
+}
+
+pub(super) fn embedding_response(
+    model: String,
+    embeddings: Vec<Vec<f32>>,
+    prompt_tokens: u32,
+) -> CreateEmbeddingResponse {
+    let data = embeddings
+        .into_iter()
+        .zip(0..)
+        .map(|(embedding, index)| Embedding {
+            index,
+            object: "embedding".to_string(),
+            embedding,
+        })
+        .collect();
+    CreateEmbeddingResponse {
+        object: "list".to_string(),
+        model,
+        data,
+        usage: EmbeddingUsage {
+            prompt_tokens,
+            total_tokens: prompt_tokens,
+        },
+    }
+}
+
 pub(super) fn mime_from_data_uri(uri: &str) -> Option<infer::Type> {
     // Split on the first comma.  If no comma => not a data-URI.
     let (_first, b64) = uri.split_once(',')?;
@@ -6,6 +6,7 @@ use crate::{
         model_mapping::ModelMappingConfig, providers::ProvidersConfig,
         router::RouterConfig,
     },
+    endpoints::EndpointType,
     error::mapper::MapperError,
     types::{
         model_id::{ModelId, ModelName},
@@ -43,7 +44,7 @@ impl ModelMapper {
         &self.app_state.0.config.default_model_mapping
     }
 
-    fn providers_config(&self) -> &ProvidersConfig {
+    pub(super) fn providers_config(&self) -> &ProvidersConfig {
         &self.app_state.0.config.providers
     }
 
@@ -60,13 +61,28 @@ impl ModelMapper {
         source_model: &ModelId,
         target_provider: &InferenceProvider,
     ) -> Result<ModelId, MapperError> {
-        let models_offered_by_target_provider = &self
+        self.map_model_for_endpoint(
+            source_model,
+            target_provider,
+            EndpointType::Chat,
+        )
+    }
+
+    /// Same as [`Self::map_model`], but only considers the target provider's
+    /// models for the given endpoint type.
+    pub fn map_model_for_endpoint(
+        &self,
+        source_model: &ModelId,
+        target_provider: &InferenceProvider,
+        endpoint_type: EndpointType,
+    ) -> Result<ModelId, MapperError> {
+        let models_offered_by_target_provider = self
             .providers_config()
             .get(target_provider)
             .ok_or_else(|| {
                 MapperError::NoProviderConfig(target_provider.clone())
             })?
-            .models;
+            .models_for(endpoint_type);
 
         let source_model_name = ModelName::from_model(source_model);
         if models_offered_by_target_provider.contains(&source_model_name) {
@@ -112,4 +128,21 @@ impl ModelMapper {
             default_mapping.as_ref(),
         )
     }
+
+    /// The name of the deployment serving `model` for providers that address
+    /// models by deployment, such as Azure `OpenAI`.
+    #[must_use]
+    pub fn deployment(
+        &self,
+        model: &ModelId,
+        target_provider: &InferenceProvider,
+    ) -> String {
+        self.providers_config()
+            .get(target_provider)
+            .and_then(|config| {
+                config.deployments.get(&ModelName::from_model(model))
+            })
+            .cloned()
+            .unwrap_or_else(|| model.to_string())
+    }
 }
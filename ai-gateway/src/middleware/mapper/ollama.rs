@@ -1,15 +1,40 @@
 use std::str::FromStr;
 
+use anthropic_ai_sdk::types::message::{
+    CreateMessageParams, CreateMessageResponse, StreamEvent,
+};
 use async_openai::types::{
     CreateChatCompletionResponse, CreateChatCompletionStreamResponse,
+    CreateEmbeddingRequest, CreateEmbeddingResponse,
 };
 use http::response::Parts;
 
 use super::{TryConvert, TryConvertStreamData};
 use crate::{
-    endpoints::ollama::chat_completions::CreateChatCompletionRequestOllama,
+    endpoints::{
+        EndpointType,
+        anthropic::messages::AnthropicApiError,
+        ollama::{
+            chat_completions::CreateChatCompletionRequestOllama,
+            embed::{EmbedRequest, EmbedResponse, OllamaError},
+        },
+        openai::responses::{
+            CreateResponseRequest, ResponseObject, ResponseStreamEvent,
+        },
+    },
     error::mapper::MapperError,
-    middleware::mapper::{TryConvertError, model::ModelMapper},
+    middleware::mapper::{
+        TryConvertError,
+        messages::{
+            anthropic_error_from_status, chat_request_from_messages,
+            message_from_chat, message_stream_event_from_chat_chunk,
+        },
+        model::ModelMapper,
+        responses::{
+            chat_request_from_responses, response_from_chat,
+            stream_event_from_chat_chunk,
+        },
+    },
     types::{model_id::ModelId, provider::InferenceProvider},
 };
 
@@ -94,3 +119,177 @@ impl
         Ok(value)
     }
 }
+
+impl TryConvert<CreateEmbeddingRequest, EmbedRequest> for OllamaConverter {
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        value: CreateEmbeddingRequest,
+    ) -> Result<EmbedRequest, Self::Error> {
+        let source_model = ModelId::from_str(&value.model)?;
+        let target_model = self.model_mapper.map_model_for_endpoint(
+            &source_model,
+            &InferenceProvider::Ollama,
+            EndpointType::Embeddings,
+        )?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+
+        Ok(EmbedRequest {
+            model: target_model.to_string(),
+            input: super::embedding_input_texts(value.input)?,
+            dimensions: value.dimensions,
+        })
+    }
+}
+
+impl TryConvert<EmbedResponse, CreateEmbeddingResponse> for OllamaConverter {
+    type Error = MapperError;
+    fn try_convert(
+        &self,
+        value: EmbedResponse,
+    ) -> Result<CreateEmbeddingResponse, Self::Error> {
+        Ok(super::embedding_response(
+            value.model,
+            value.embeddings,
+            value.prompt_eval_count,
+        ))
+    }
+}
+
+impl TryConvertStreamData<EmbedResponse, CreateEmbeddingResponse>
+    for OllamaConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: EmbedResponse,
+    ) -> Result<Option<CreateEmbeddingResponse>, Self::Error> {
+        // embeddings are never streamed, but this is required by the
+        // `TypedEndpointConverter`
+        self.try_convert(value).map(Some)
+    }
+}
+
+impl TryConvertError<OllamaError, async_openai::error::WrappedError>
+    for OllamaConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        value: OllamaError,
+    ) -> Result<async_openai::error::WrappedError, Self::Error> {
+        Ok(super::openai_error_from_status(
+            resp_parts.status,
+            Some(value.error),
+        ))
+    }
+}
+
+impl TryConvert<CreateResponseRequest, CreateChatCompletionRequestOllama>
+    for OllamaConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateResponseRequest,
+    ) -> Result<CreateChatCompletionRequestOllama, Self::Error> {
+        let chat_request = chat_request_from_responses(value)?;
+        <Self as TryConvert<
+            async_openai::types::CreateChatCompletionRequest,
+            CreateChatCompletionRequestOllama,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl TryConvert<CreateChatCompletionResponse, ResponseObject>
+    for OllamaConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateChatCompletionResponse,
+    ) -> Result<ResponseObject, Self::Error> {
+        Ok(response_from_chat(value))
+    }
+}
+
+impl
+    TryConvertStreamData<
+        CreateChatCompletionStreamResponse,
+        ResponseStreamEvent,
+    > for OllamaConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateChatCompletionStreamResponse,
+    ) -> Result<Option<ResponseStreamEvent>, Self::Error> {
+        Ok(stream_event_from_chat_chunk(value))
+    }
+}
+
+impl TryConvert<CreateMessageParams, CreateChatCompletionRequestOllama>
+    for OllamaConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateMessageParams,
+    ) -> Result<CreateChatCompletionRequestOllama, Self::Error> {
+        let chat_request = chat_request_from_messages(value)?;
+        <Self as TryConvert<
+            async_openai::types::CreateChatCompletionRequest,
+            CreateChatCompletionRequestOllama,
+        >>::try_convert(self, chat_request)
+    }
+}
+
+impl TryConvert<CreateChatCompletionResponse, CreateMessageResponse>
+    for OllamaConverter
+{
+    type Error = MapperError;
+
+    fn try_convert(
+        &self,
+        value: CreateChatCompletionResponse,
+    ) -> Result<CreateMessageResponse, Self::Error> {
+        Ok(message_from_chat(value))
+    }
+}
+
+impl TryConvertStreamData<CreateChatCompletionStreamResponse, StreamEvent>
+    for OllamaConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_chunk(
+        &self,
+        value: CreateChatCompletionStreamResponse,
+    ) -> Result<Option<StreamEvent>, Self::Error> {
+        message_stream_event_from_chat_chunk(value)
+    }
+}
+
+impl TryConvertError<async_openai::error::WrappedError, AnthropicApiError>
+    for OllamaConverter
+{
+    type Error = MapperError;
+
+    fn try_convert_error(
+        &self,
+        resp_parts: &Parts,
+        value: async_openai::error::WrappedError,
+    ) -> Result<AnthropicApiError, Self::Error> {
+        Ok(anthropic_error_from_status(
+            resp_parts.status,
+            Some(value.error.message),
+        ))
+    }
+}
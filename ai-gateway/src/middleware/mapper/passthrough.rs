@@ -0,0 +1,149 @@
+use std::str::FromStr;
+
+use bytes::Bytes;
+use http::{HeaderMap, response::Parts};
+use serde::Deserialize;
+
+use super::{EndpointConverter, model::ModelMapper};
+use crate::{
+    endpoints::EndpointType,
+    error::{
+        api::ApiError, internal::InternalError,
+        invalid_req::InvalidRequestError,
+    },
+    types::{
+        extensions::MapperContext, model_id::ModelId, multipart::MultipartForm,
+        provider::InferenceProvider,
+    },
+};
+
+/// The fields of a JSON request body the passthrough converter reads.
+#[derive(Debug, Deserialize)]
+struct JsonRequest {
+    model: String,
+    #[serde(default)]
+    stream: bool,
+    #[serde(default)]
+    stream_format: Option<String>,
+}
+
+/// Converter for requests that only need their model mapped, such as audio
+/// or Responses API requests to `OpenAI`.
+///
+/// Bodies are otherwise forwarded as is, since they may not be JSON
+/// (multipart audio uploads and binary speech responses) or may contain
+/// parameters we don't model.
+pub struct PassthroughConverter {
+    model_mapper: ModelMapper,
+    target_provider: InferenceProvider,
+    endpoint_type: EndpointType,
+}
+
+impl PassthroughConverter {
+    #[must_use]
+    pub fn new(
+        model_mapper: ModelMapper,
+        target_provider: InferenceProvider,
+        endpoint_type: EndpointType,
+    ) -> Self {
+        Self {
+            model_mapper,
+            target_provider,
+            endpoint_type,
+        }
+    }
+
+    fn map_model(&self, source_model: &str) -> Result<ModelId, ApiError> {
+        let source_model = ModelId::from_str(source_model)
+            .map_err(InternalError::MapperError)?;
+        let target_model = self
+            .model_mapper
+            .map_model_for_endpoint(
+                &source_model,
+                &self.target_provider,
+                self.endpoint_type,
+            )
+            .map_err(InternalError::MapperError)?;
+        tracing::trace!(source_model = ?source_model, target_model = ?target_model, "mapped model");
+        Ok(target_model)
+    }
+
+    fn convert_multipart_req_body(
+        &self,
+        mut form: MultipartForm,
+    ) -> Result<(Bytes, MapperContext), ApiError> {
+        let source_model = form.text_field("model").ok_or_else(|| {
+            InvalidRequestError::InvalidMultipartForm(
+                "missing `model` field".to_string(),
+            )
+        })?;
+        let target_model = self.map_model(source_model)?;
+        let is_stream = form.text_field("stream") == Some("true");
+        form.set_text_field("model", &target_model.to_string());
+
+        let mapper_ctx = MapperContext {
+            is_stream,
+            model: Some(target_model),
+        };
+        Ok((form.into_body(), mapper_ctx))
+    }
+
+    fn convert_json_req_body(
+        &self,
+        bytes: &Bytes,
+    ) -> Result<(Bytes, MapperContext), ApiError> {
+        let request: JsonRequest = serde_json::from_slice(bytes)
+            .map_err(InvalidRequestError::InvalidRequestBody)?;
+        let target_model = self.map_model(&request.model)?;
+        let is_stream =
+            request.stream || request.stream_format.as_deref() == Some("sse");
+
+        // only the model is replaced so that fields we don't know about are
+        // still forwarded
+        let mut body: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_slice(bytes)
+                .map_err(InvalidRequestError::InvalidRequestBody)?;
+        body.insert(
+            "model".to_string(),
+            serde_json::Value::String(target_model.to_string()),
+        );
+        let body = serde_json::to_vec(&body).map_err(|e| {
+            InternalError::Serialize {
+                ty: "serde_json::Map",
+                error: e,
+            }
+        })?;
+
+        let mapper_ctx = MapperContext {
+            is_stream,
+            model: Some(target_model),
+        };
+        Ok((Bytes::from(body), mapper_ctx))
+    }
+}
+
+impl EndpointConverter for PassthroughConverter {
+    fn convert_req_body(
+        &self,
+        req_headers: &HeaderMap,
+        req_body_bytes: Bytes,
+    ) -> Result<(Bytes, MapperContext), ApiError> {
+        match MultipartForm::new(req_headers, req_body_bytes.clone()) {
+            Some(form) => self.convert_multipart_req_body(form),
+            None => self.convert_json_req_body(&req_body_bytes),
+        }
+    }
+
+    fn convert_resp_body(
+        &self,
+        _resp_parts: Parts,
+        resp_body_bytes: Bytes,
+        _is_stream: bool,
+    ) -> Result<Option<Bytes>, ApiError> {
+        Ok(Some(resp_body_bytes))
+    }
+
+    fn is_passthrough(&self) -> bool {
+        true
+    }
+}
@@ -4,14 +4,19 @@ use rustc_hash::FxHashMap as HashMap;
 
 use super::{
     EndpointConverter, TypedEndpointConverter, anthropic::AnthropicConverter,
-    gemini::GoogleGeminiConverter, model::ModelMapper, openai::OpenAIConverter,
+    azure::AzureConverter, cohere::CohereConverter,
+    gemini::GoogleGeminiConverter, mistral::MistralConverter,
+    model::ModelMapper, openai::OpenAIConverter,
+    passthrough::PassthroughConverter,
 };
 use crate::{
     endpoints::{
-        self, ApiEndpoint, anthropic::Anthropic, bedrock::Bedrock,
-        google::Google, ollama::Ollama, openai::OpenAI,
+        self, ApiEndpoint, EndpointType, anthropic::Anthropic, azure::Azure,
+        bedrock::Bedrock, cohere::Cohere, google::Google, mistral::Mistral,
+        ollama::Ollama, openai::OpenAI, vertex::Vertex,
     },
     middleware::mapper::{bedrock::BedrockConverter, ollama::OllamaConverter},
+    types::provider::InferenceProvider,
 };
 
 #[derive(Debug, Default, Clone)]
@@ -32,12 +37,15 @@ impl EndpointConverterRegistry {
     ) -> Option<&(dyn EndpointConverter + Send + Sync + 'static)> {
         self.0
             .converters
-            .get(&RegistryKey::new(*source_endpoint, *target_endpoint))
+            .get(&RegistryKey::new(
+                source_endpoint.clone(),
+                target_endpoint.clone(),
+            ))
             .map(|v| &**v)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct RegistryKey {
     source_endpoint: ApiEndpoint,
     target_endpoint: ApiEndpoint,
@@ -140,9 +148,571 @@ impl EndpointConverterRegistryInner {
 
         registry.register_converter(key, converter);
 
+        registry.register_embeddings_converters(model_mapper);
+        registry.register_image_converters(model_mapper);
+        registry.register_audio_converters(model_mapper);
+        registry.register_responses_converters(model_mapper);
+        registry.register_messages_converters(model_mapper);
+        registry.register_azure_converters(model_mapper);
+        registry.register_mistral_converters(model_mapper);
+        registry.register_cohere_converters(model_mapper);
+        registry.register_vertex_converters(model_mapper);
+
+        for (provider, config) in model_mapper.providers_config().iter() {
+            if matches!(provider, InferenceProvider::Named(_))
+                && config.is_openai_compatible()
+            {
+                registry.register_openai_compatible_converters(
+                    model_mapper,
+                    provider,
+                );
+            }
+        }
+
         registry
     }
 
+    fn register_embeddings_converters(&mut self, model_mapper: &ModelMapper) {
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::embeddings()),
+            ApiEndpoint::OpenAI(OpenAI::embeddings()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Embeddings,
+                endpoints::openai::Embeddings,
+                OpenAIConverter,
+            >::new(OpenAIConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::embeddings()),
+            ApiEndpoint::Google(Google::embed_content()),
+        );
+        let converter = TypedEndpointConverter::<
+            endpoints::openai::Embeddings,
+            endpoints::google::EmbedContent,
+            GoogleGeminiConverter,
+        >::new(GoogleGeminiConverter::new(
+            model_mapper.clone(),
+        ));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::embeddings()),
+            ApiEndpoint::Bedrock(Bedrock::invoke_model()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Embeddings,
+                endpoints::bedrock::InvokeModel,
+                BedrockConverter,
+            >::new(BedrockConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::embeddings()),
+            ApiEndpoint::Ollama(Ollama::embed()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Embeddings,
+                endpoints::ollama::embed::Embed,
+                OllamaConverter,
+            >::new(OllamaConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+    }
+
+    fn register_image_converters(&mut self, model_mapper: &ModelMapper) {
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::image_generations()),
+            ApiEndpoint::OpenAI(OpenAI::image_generations()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::ImageGenerations,
+                endpoints::openai::ImageGenerations,
+                OpenAIConverter,
+            >::new(OpenAIConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::image_generations()),
+            ApiEndpoint::Google(Google::image_generations()),
+        );
+        let converter = TypedEndpointConverter::<
+            endpoints::openai::ImageGenerations,
+            endpoints::google::ImageGenerations,
+            GoogleGeminiConverter,
+        >::new(GoogleGeminiConverter::new(
+            model_mapper.clone(),
+        ));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::image_generations()),
+            ApiEndpoint::Bedrock(Bedrock::invoke_model_image()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::ImageGenerations,
+                endpoints::bedrock::InvokeModelImage,
+                BedrockConverter,
+            >::new(BedrockConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+    }
+
+    /// Audio is only served by `OpenAI`, so the converters only map the
+    /// model.
+    fn register_audio_converters(&mut self, model_mapper: &ModelMapper) {
+        for endpoint in [OpenAI::audio_transcriptions(), OpenAI::audio_speech()]
+        {
+            let key = RegistryKey::new(
+                ApiEndpoint::OpenAI(endpoint),
+                ApiEndpoint::OpenAI(endpoint),
+            );
+            let converter = PassthroughConverter::new(
+                model_mapper.clone(),
+                InferenceProvider::OpenAI,
+                EndpointType::Audio,
+            );
+            self.register_converter(key, converter);
+        }
+    }
+
+    /// Requests to other providers are mapped via their chat endpoints,
+    /// while requests to `OpenAI` are forwarded as is.
+    fn register_responses_converters(&mut self, model_mapper: &ModelMapper) {
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+        );
+        let converter = PassthroughConverter::new(
+            model_mapper.clone(),
+            InferenceProvider::OpenAI,
+            EndpointType::Chat,
+        );
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Responses,
+                endpoints::anthropic::Messages,
+                AnthropicConverter,
+            >::new(AnthropicConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            ApiEndpoint::Google(Google::generate_contents()),
+        );
+        let converter = TypedEndpointConverter::<
+            endpoints::openai::Responses,
+            endpoints::google::GenerateContents,
+            GoogleGeminiConverter,
+        >::new(GoogleGeminiConverter::new(
+            model_mapper.clone(),
+        ));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            ApiEndpoint::Ollama(Ollama::chat_completions()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Responses,
+                endpoints::ollama::chat_completions::ChatCompletions,
+                OllamaConverter,
+            >::new(OllamaConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            ApiEndpoint::Bedrock(Bedrock::converse()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Responses,
+                endpoints::bedrock::Converse,
+                BedrockConverter,
+            >::new(BedrockConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+    }
+
+    /// Anthropic Messages requests to other providers are mapped via their
+    /// chat endpoints.
+    fn register_messages_converters(&mut self, model_mapper: &ModelMapper) {
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::anthropic::Messages,
+                endpoints::anthropic::Messages,
+                AnthropicConverter,
+            >::new(AnthropicConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            ApiEndpoint::OpenAI(OpenAI::chat_completions()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::anthropic::Messages,
+                endpoints::openai::ChatCompletions,
+                OpenAIConverter,
+            >::new(OpenAIConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            ApiEndpoint::Google(Google::generate_contents()),
+        );
+        let converter = TypedEndpointConverter::<
+            endpoints::anthropic::Messages,
+            endpoints::google::GenerateContents,
+            GoogleGeminiConverter,
+        >::new(GoogleGeminiConverter::new(
+            model_mapper.clone(),
+        ));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            ApiEndpoint::Ollama(Ollama::chat_completions()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::anthropic::Messages,
+                endpoints::ollama::chat_completions::ChatCompletions,
+                OllamaConverter,
+            >::new(OllamaConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            ApiEndpoint::Bedrock(Bedrock::converse()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::anthropic::Messages,
+                endpoints::bedrock::Converse,
+                BedrockConverter,
+            >::new(BedrockConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+    }
+
+    /// Azure `OpenAI` serves the same APIs as `OpenAI`, so requests only need
+    /// their model mapped to a deployment. Responses API and Anthropic
+    /// Messages requests are mapped via chat completions.
+    fn register_azure_converters(&mut self, model_mapper: &ModelMapper) {
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::chat_completions()),
+            ApiEndpoint::Azure(Azure::chat_completions()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::ChatCompletions,
+                endpoints::azure::ChatCompletions,
+                AzureConverter,
+            >::new(AzureConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::embeddings()),
+            ApiEndpoint::Azure(Azure::embeddings()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Embeddings,
+                endpoints::azure::Embeddings,
+                AzureConverter,
+            >::new(AzureConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            ApiEndpoint::Azure(Azure::chat_completions()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Responses,
+                endpoints::azure::ChatCompletions,
+                AzureConverter,
+            >::new(AzureConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            ApiEndpoint::Azure(Azure::chat_completions()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::anthropic::Messages,
+                endpoints::azure::ChatCompletions,
+                AzureConverter,
+            >::new(AzureConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+    }
+
+    fn register_mistral_converters(&mut self, model_mapper: &ModelMapper) {
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::chat_completions()),
+            ApiEndpoint::Mistral(Mistral::chat_completions()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::ChatCompletions,
+                endpoints::mistral::ChatCompletions,
+                MistralConverter,
+            >::new(MistralConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::embeddings()),
+            ApiEndpoint::Mistral(Mistral::embeddings()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Embeddings,
+                endpoints::mistral::Embeddings,
+                MistralConverter,
+            >::new(MistralConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            ApiEndpoint::Mistral(Mistral::chat_completions()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Responses,
+                endpoints::mistral::ChatCompletions,
+                MistralConverter,
+            >::new(MistralConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            ApiEndpoint::Mistral(Mistral::chat_completions()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::anthropic::Messages,
+                endpoints::mistral::ChatCompletions,
+                MistralConverter,
+            >::new(MistralConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+    }
+
+    fn register_cohere_converters(&mut self, model_mapper: &ModelMapper) {
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::chat_completions()),
+            ApiEndpoint::Cohere(Cohere::chat()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::ChatCompletions,
+                endpoints::cohere::Chat,
+                CohereConverter,
+            >::new(CohereConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::embeddings()),
+            ApiEndpoint::Cohere(Cohere::embed()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Embeddings,
+                endpoints::cohere::Embed,
+                CohereConverter,
+            >::new(CohereConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            ApiEndpoint::Cohere(Cohere::chat()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::openai::Responses,
+                endpoints::cohere::Chat,
+                CohereConverter,
+            >::new(CohereConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            ApiEndpoint::Cohere(Cohere::chat()),
+        );
+        let converter =
+            TypedEndpointConverter::<
+                endpoints::anthropic::Messages,
+                endpoints::cohere::Chat,
+                CohereConverter,
+            >::new(CohereConverter::new(model_mapper.clone()));
+        self.register_converter(key, converter);
+    }
+
+    /// Vertex AI serves the same `OpenAI` compatible API as the Gemini API,
+    /// so we reuse its converter.
+    fn register_vertex_converters(&mut self, model_mapper: &ModelMapper) {
+        let converter = || {
+            GoogleGeminiConverter::for_provider(
+                model_mapper.clone(),
+                InferenceProvider::GoogleVertex,
+            )
+        };
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::chat_completions()),
+            ApiEndpoint::Vertex(Vertex::chat_completions()),
+        );
+        self.register_converter(
+            key,
+            TypedEndpointConverter::<
+                endpoints::openai::ChatCompletions,
+                endpoints::vertex::ChatCompletions,
+                GoogleGeminiConverter,
+            >::new(converter()),
+        );
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            ApiEndpoint::Vertex(Vertex::chat_completions()),
+        );
+        self.register_converter(
+            key,
+            TypedEndpointConverter::<
+                endpoints::openai::Responses,
+                endpoints::vertex::ChatCompletions,
+                GoogleGeminiConverter,
+            >::new(converter()),
+        );
+
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            ApiEndpoint::Vertex(Vertex::chat_completions()),
+        );
+        self.register_converter(
+            key,
+            TypedEndpointConverter::<
+                endpoints::anthropic::Messages,
+                endpoints::vertex::ChatCompletions,
+                GoogleGeminiConverter,
+            >::new(converter()),
+        );
+    }
+
+    /// Providers configured by name serve the `OpenAI` API, so their
+    /// converters only map the model. Responses API and Anthropic Messages
+    /// requests are mapped via chat completions.
+    fn register_openai_compatible_converters(
+        &mut self,
+        model_mapper: &ModelMapper,
+        provider: &InferenceProvider,
+    ) {
+        let target = |openai_endpoint| ApiEndpoint::OpenAICompatible {
+            provider: provider.clone(),
+            openai_endpoint,
+        };
+        let converter = || {
+            OpenAIConverter::for_provider(
+                model_mapper.clone(),
+                provider.clone(),
+            )
+        };
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::chat_completions()),
+            target(OpenAI::chat_completions()),
+        );
+        self.register_converter(
+            key,
+            TypedEndpointConverter::<
+                endpoints::openai::ChatCompletions,
+                endpoints::openai::ChatCompletions,
+                OpenAIConverter,
+            >::new(converter()),
+        );
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::embeddings()),
+            target(OpenAI::embeddings()),
+        );
+        self.register_converter(
+            key,
+            TypedEndpointConverter::<
+                endpoints::openai::Embeddings,
+                endpoints::openai::Embeddings,
+                OpenAIConverter,
+            >::new(converter()),
+        );
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::image_generations()),
+            target(OpenAI::image_generations()),
+        );
+        self.register_converter(
+            key,
+            TypedEndpointConverter::<
+                endpoints::openai::ImageGenerations,
+                endpoints::openai::ImageGenerations,
+                OpenAIConverter,
+            >::new(converter()),
+        );
+
+        for endpoint in [OpenAI::audio_transcriptions(), OpenAI::audio_speech()]
+        {
+            let key = RegistryKey::new(
+                ApiEndpoint::OpenAI(endpoint),
+                target(endpoint),
+            );
+            self.register_converter(
+                key,
+                PassthroughConverter::new(
+                    model_mapper.clone(),
+                    provider.clone(),
+                    EndpointType::Audio,
+                ),
+            );
+        }
+
+        let key = RegistryKey::new(
+            ApiEndpoint::OpenAI(OpenAI::responses()),
+            target(OpenAI::chat_completions()),
+        );
+        self.register_converter(
+            key,
+            TypedEndpointConverter::<
+                endpoints::openai::Responses,
+                endpoints::openai::ChatCompletions,
+                OpenAIConverter,
+            >::new(converter()),
+        );
+
+        let key = RegistryKey::new(
+            ApiEndpoint::Anthropic(Anthropic::messages()),
+            target(OpenAI::chat_completions()),
+        );
+        self.register_converter(
+            key,
+            TypedEndpointConverter::<
+                endpoints::anthropic::Messages,
+                endpoints::openai::ChatCompletions,
+                OpenAIConverter,
+            >::new(converter()),
+        );
+    }
+
     fn register_converter<C>(&mut self, key: RegistryKey, converter: C)
     where
         C: EndpointConverter + Send + Sync + 'static,
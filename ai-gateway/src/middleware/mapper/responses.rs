@@ -0,0 +1,559 @@
+//! Conversions between the Responses API and chat completions.
+//!
+//! Providers other than `OpenAI` only offer a chat API, so Responses API
+//! requests are converted to chat completion requests and then mapped by the
+//! provider's chat converter. Responses and stream chunks take the reverse
+//! path.
+use async_openai::types as openai;
+
+use crate::{
+    endpoints::openai::responses::{
+        CreateResponseRequest, FunctionCallItem, FunctionCallOutputItem,
+        IncompleteDetails, InputContentPart, InputMessage, InputMessageContent,
+        OutputContent, OutputItem, ResponseInput, ResponseInputItem,
+        ResponseObject, ResponseStatus, ResponseStreamEvent, ResponseTool,
+        ResponseToolChoice, ResponseUsage, ToolChoiceMode,
+    },
+    error::mapper::MapperError,
+};
+
+const RESPONSE_OBJECT: &str = "response";
+
+#[allow(clippy::too_many_lines)]
+pub(super) fn chat_request_from_responses(
+    value: CreateResponseRequest,
+) -> Result<openai::CreateChatCompletionRequest, MapperError> {
+    // chat converters only read the system prompt from the first message, so
+    // instructions and system/developer messages are combined into one
+    let mut system_prompt: Vec<String> =
+        value.instructions.into_iter().collect();
+    let mut messages: Vec<openai::ChatCompletionRequestMessage> = Vec::new();
+    let items = match value.input {
+        ResponseInput::Text(text) => {
+            vec![ResponseInputItem::Message(InputMessage {
+                role: "user".to_string(),
+                content: InputMessageContent::Text(text),
+            })]
+        }
+        ResponseInput::Items(items) => items,
+    };
+    for item in items {
+        match item {
+            ResponseInputItem::Message(message) => {
+                match message.role.as_str() {
+                    "system" | "developer" => {
+                        system_prompt.push(message_text(message.content)?);
+                    }
+                    "assistant" => {
+                        #[allow(deprecated)]
+                        messages.push(
+                            openai::ChatCompletionRequestMessage::Assistant(
+                                openai::ChatCompletionRequestAssistantMessage {
+                                    content: Some(openai::ChatCompletionRequestAssistantMessageContent::Text(
+                                        message_text(message.content)?,
+                                    )),
+                                    tool_calls: None,
+                                    refusal: None,
+                                    name: None,
+                                    audio: None,
+                                    function_call: None,
+                                },
+                            ),
+                        );
+                    }
+                    "user" => {
+                        messages.push(
+                            openai::ChatCompletionRequestMessage::User(
+                                openai::ChatCompletionRequestUserMessage {
+                                    content: user_message_content(
+                                        message.content,
+                                    )?,
+                                    name: None,
+                                },
+                            ),
+                        );
+                    }
+                    role => {
+                        return Err(MapperError::UnsupportedResponsesInput(
+                            format!("unknown message role: {role}"),
+                        ));
+                    }
+                }
+            }
+            ResponseInputItem::FunctionCall(FunctionCallItem {
+                call_id,
+                name,
+                arguments,
+            }) => {
+                let tool_call = openai::ChatCompletionMessageToolCall {
+                    id: call_id,
+                    r#type: openai::ChatCompletionToolType::Function,
+                    function: openai::FunctionCall { name, arguments },
+                };
+                // parallel function calls are separate items but belong to
+                // the same assistant message
+                if let Some(openai::ChatCompletionRequestMessage::Assistant(
+                    openai::ChatCompletionRequestAssistantMessage {
+                        tool_calls: Some(tool_calls),
+                        ..
+                    },
+                )) = messages.last_mut()
+                {
+                    tool_calls.push(tool_call);
+                } else {
+                    #[allow(deprecated)]
+                    messages.push(
+                        openai::ChatCompletionRequestMessage::Assistant(
+                            openai::ChatCompletionRequestAssistantMessage {
+                                content: None,
+                                tool_calls: Some(vec![tool_call]),
+                                refusal: None,
+                                name: None,
+                                audio: None,
+                                function_call: None,
+                            },
+                        ),
+                    );
+                }
+            }
+            ResponseInputItem::FunctionCallOutput(FunctionCallOutputItem {
+                call_id,
+                output,
+            }) => {
+                messages.push(openai::ChatCompletionRequestMessage::Tool(
+                    openai::ChatCompletionRequestToolMessage {
+                        content:
+                            openai::ChatCompletionRequestToolMessageContent::Text(
+                                output,
+                            ),
+                        tool_call_id: call_id,
+                    },
+                ));
+            }
+            ResponseInputItem::Other(item) => {
+                let ty = item
+                    .get("type")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or("unknown");
+                return Err(MapperError::UnsupportedResponsesInput(format!(
+                    "{ty} input items are only supported by openai"
+                )));
+            }
+        }
+    }
+    if !system_prompt.is_empty() {
+        messages.insert(
+            0,
+            openai::ChatCompletionRequestMessage::Developer(
+                openai::ChatCompletionRequestDeveloperMessage {
+                    content: openai::ChatCompletionRequestDeveloperMessageContent::Text(
+                        system_prompt.join("\n\n"),
+                    ),
+                    name: None,
+                },
+            ),
+        );
+    }
+
+    let tools = value
+        .tools
+        .map(|tools| {
+            tools
+                .into_iter()
+                .map(|tool| match tool {
+                    ResponseTool::Function {
+                        name,
+                        description,
+                        parameters,
+                        strict,
+                    } => Ok(openai::ChatCompletionTool {
+                        r#type: openai::ChatCompletionToolType::Function,
+                        function: openai::FunctionObject {
+                            name,
+                            description,
+                            parameters,
+                            strict,
+                        },
+                    }),
+                    ResponseTool::BuiltIn => {
+                        Err(MapperError::UnsupportedResponsesInput(
+                            "built-in tools are only supported by openai"
+                                .to_string(),
+                        ))
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+    let tool_choice = value.tool_choice.map(|tool_choice| match tool_choice {
+        ResponseToolChoice::Mode(ToolChoiceMode::None) => {
+            openai::ChatCompletionToolChoiceOption::None
+        }
+        ResponseToolChoice::Mode(ToolChoiceMode::Auto) => {
+            openai::ChatCompletionToolChoiceOption::Auto
+        }
+        ResponseToolChoice::Mode(ToolChoiceMode::Required) => {
+            openai::ChatCompletionToolChoiceOption::Required
+        }
+        ResponseToolChoice::Function { name } => {
+            openai::ChatCompletionToolChoiceOption::Named(
+                openai::ChatCompletionNamedToolChoice {
+                    r#type: openai::ChatCompletionToolType::Function,
+                    function: openai::FunctionName { name },
+                },
+            )
+        }
+    });
+
+    #[allow(deprecated)]
+    let request = openai::CreateChatCompletionRequest {
+        messages,
+        model: value.model,
+        store: None,
+        reasoning_effort: None,
+        metadata: None,
+        parallel_tool_calls: value.parallel_tool_calls,
+        stop: None,
+        stream: value.stream,
+        stream_options: None,
+        temperature: value.temperature,
+        top_p: value.top_p,
+        tools,
+        tool_choice,
+        user: value.user,
+        max_completion_tokens: value.max_output_tokens,
+        max_tokens: None,
+        frequency_penalty: None,
+        logit_bias: None,
+        logprobs: None,
+        n: None,
+        modalities: None,
+        presence_penalty: None,
+        prediction: None,
+        response_format: None,
+        seed: None,
+        service_tier: None,
+        top_logprobs: None,
+        audio: None,
+        function_call: None,
+        functions: None,
+        web_search_options: None,
+    };
+    Ok(request)
+}
+
+fn message_text(content: InputMessageContent) -> Result<String, MapperError> {
+    match content {
+        InputMessageContent::Text(text) => Ok(text),
+        InputMessageContent::Parts(parts) => {
+            let mut text = String::new();
+            for part in parts {
+                match part {
+                    InputContentPart::InputText { text: part }
+                    | InputContentPart::OutputText { text: part }
+                    | InputContentPart::Refusal { refusal: part } => {
+                        text.push_str(&part);
+                    }
+                    InputContentPart::InputImage { .. }
+                    | InputContentPart::InputFile { .. } => {
+                        return Err(MapperError::UnsupportedResponsesInput(
+                            "only user messages may contain images or files"
+                                .to_string(),
+                        ));
+                    }
+                }
+            }
+            Ok(text)
+        }
+    }
+}
+
+fn user_message_content(
+    content: InputMessageContent,
+) -> Result<openai::ChatCompletionRequestUserMessageContent, MapperError> {
+    let parts = match content {
+        InputMessageContent::Text(text) => {
+            return Ok(openai::ChatCompletionRequestUserMessageContent::Text(
+                text,
+            ));
+        }
+        InputMessageContent::Parts(parts) => parts,
+    };
+    let mut mapped_parts = Vec::with_capacity(parts.len());
+    for part in parts {
+        let mapped_part = match part {
+            InputContentPart::InputText { text }
+            | InputContentPart::OutputText { text }
+            | InputContentPart::Refusal { refusal: text } => {
+                openai::ChatCompletionRequestUserMessageContentPart::Text(
+                    openai::ChatCompletionRequestMessageContentPartText {
+                        text,
+                    },
+                )
+            }
+            InputContentPart::InputImage {
+                image_url: Some(url),
+            } => openai::ChatCompletionRequestUserMessageContentPart::ImageUrl(
+                openai::ChatCompletionRequestMessageContentPartImage {
+                    image_url: openai::ImageUrl { url, detail: None },
+                },
+            ),
+            InputContentPart::InputImage { image_url: None } => {
+                return Err(MapperError::UnsupportedResponsesInput(
+                    "images must be given by url".to_string(),
+                ));
+            }
+            InputContentPart::InputFile { .. } => {
+                return Err(MapperError::UnsupportedResponsesInput(
+                    "file inputs are only supported by openai".to_string(),
+                ));
+            }
+        };
+        mapped_parts.push(mapped_part);
+    }
+    Ok(openai::ChatCompletionRequestUserMessageContent::Array(
+        mapped_parts,
+    ))
+}
+
+pub(super) fn response_from_chat(
+    mut value: openai::CreateChatCompletionResponse,
+) -> ResponseObject {
+    let mut output = Vec::new();
+    let mut finish_reason = None;
+    if !value.choices.is_empty() {
+        let choice = value.choices.remove(0);
+        finish_reason = choice.finish_reason;
+        let mut content = Vec::new();
+        if let Some(text) = choice.message.content {
+            content.push(OutputContent::OutputText {
+                text,
+                annotations: Vec::new(),
+            });
+        }
+        if let Some(refusal) = choice.message.refusal {
+            content.push(OutputContent::Refusal { refusal });
+        }
+        if !content.is_empty() {
+            output.push(OutputItem::Message {
+                id: message_item_id(&value.id),
+                role: "assistant".to_string(),
+                status: ResponseStatus::Completed,
+                content,
+            });
+        }
+        for tool_call in choice.message.tool_calls.unwrap_or_default() {
+            output.push(OutputItem::FunctionCall {
+                id: function_call_item_id(&tool_call.id),
+                call_id: tool_call.id,
+                name: tool_call.function.name,
+                arguments: tool_call.function.arguments,
+                status: ResponseStatus::Completed,
+            });
+        }
+    }
+    let (status, incomplete_details) = status_from_finish_reason(finish_reason);
+
+    ResponseObject {
+        id: value.id,
+        object: RESPONSE_OBJECT.to_string(),
+        created_at: u64::from(value.created),
+        model: value.model,
+        status,
+        output,
+        incomplete_details,
+        usage: value.usage.map(usage_from_chat),
+    }
+}
+
+/// Returns `None` for chunks without an equivalent event.
+///
+/// Chunks are converted independently, so the final `response.completed`
+/// event does not repeat the streamed output.
+pub(super) fn stream_event_from_chat_chunk(
+    mut value: openai::CreateChatCompletionStreamResponse,
+) -> Option<ResponseStreamEvent> {
+    if value.choices.is_empty() {
+        return None;
+    }
+    let choice = value.choices.remove(0);
+
+    if let Some(finish_reason) = choice.finish_reason {
+        let (status, incomplete_details) =
+            status_from_finish_reason(Some(finish_reason));
+        let response = ResponseObject {
+            id: value.id,
+            object: RESPONSE_OBJECT.to_string(),
+            created_at: u64::from(value.created),
+            model: value.model,
+            status,
+            output: Vec::new(),
+            incomplete_details,
+            usage: value.usage.map(usage_from_chat),
+        };
+        return Some(match status {
+            ResponseStatus::Incomplete => {
+                ResponseStreamEvent::Incomplete { response }
+            }
+            _ => ResponseStreamEvent::Completed { response },
+        });
+    }
+
+    if let Some(delta) = choice.delta.content.filter(|c| !c.is_empty()) {
+        return Some(ResponseStreamEvent::OutputTextDelta {
+            item_id: message_item_id(&value.id),
+            output_index: 0,
+            content_index: 0,
+            delta,
+        });
+    }
+
+    if let Some(tool_call) = choice
+        .delta
+        .tool_calls
+        .and_then(|tool_calls| tool_calls.into_iter().next())
+    {
+        // the output index is offset by the assistant message, and since
+        // argument deltas don't include the call id, the item id is derived
+        // from the tool call index instead
+        let output_index = tool_call.index + 1;
+        let item_id = function_call_item_id(&tool_call.index.to_string());
+        let function =
+            tool_call.function.unwrap_or(openai::FunctionCallStream {
+                name: None,
+                arguments: None,
+            });
+        return match (tool_call.id, function.name) {
+            (Some(call_id), Some(name)) => {
+                Some(ResponseStreamEvent::OutputItemAdded {
+                    output_index,
+                    item: OutputItem::FunctionCall {
+                        id: item_id,
+                        call_id,
+                        name,
+                        arguments: function.arguments.unwrap_or_default(),
+                        status: ResponseStatus::InProgress,
+                    },
+                })
+            }
+            _ => function.arguments.filter(|a| !a.is_empty()).map(|delta| {
+                ResponseStreamEvent::FunctionCallArgumentsDelta {
+                    item_id,
+                    output_index,
+                    delta,
+                }
+            }),
+        };
+    }
+
+    if choice.delta.role.is_some() {
+        return Some(ResponseStreamEvent::Created {
+            response: ResponseObject {
+                id: value.id,
+                object: RESPONSE_OBJECT.to_string(),
+                created_at: u64::from(value.created),
+                model: value.model,
+                status: ResponseStatus::InProgress,
+                output: Vec::new(),
+                incomplete_details: None,
+                usage: None,
+            },
+        });
+    }
+
+    None
+}
+
+fn status_from_finish_reason(
+    finish_reason: Option<openai::FinishReason>,
+) -> (ResponseStatus, Option<IncompleteDetails>) {
+    let reason = match finish_reason {
+        Some(openai::FinishReason::Length) => "max_output_tokens",
+        Some(openai::FinishReason::ContentFilter) => "content_filter",
+        _ => return (ResponseStatus::Completed, None),
+    };
+    (
+        ResponseStatus::Incomplete,
+        Some(IncompleteDetails {
+            reason: reason.to_string(),
+        }),
+    )
+}
+
+fn usage_from_chat(usage: openai::CompletionUsage) -> ResponseUsage {
+    ResponseUsage {
+        input_tokens: usage.prompt_tokens,
+        output_tokens: usage.completion_tokens,
+        total_tokens: usage.total_tokens,
+    }
+}
+
+fn message_item_id(id: &str) -> String {
+    format!("msg_{id}")
+}
+
+fn function_call_item_id(call_id: &str) -> String {
+    format!("fc_{call_id}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_input_items_to_chat_messages() {
+        let request: CreateResponseRequest =
+            serde_json::from_value(serde_json::json!({
+                "model": "anthropic/claude-sonnet-4-0",
+                "instructions": "Be concise.",
+                "input": [
+                    { "role": "user", "content": "What's the weather in Paris?" },
+                    {
+                        "type": "function_call",
+                        "call_id": "call_1",
+                        "name": "get_weather",
+                        "arguments": "{\"city\":\"Paris\"}"
+                    },
+                    {
+                        "type": "function_call_output",
+                        "call_id": "call_1",
+                        "output": "sunny"
+                    }
+                ],
+                "max_output_tokens": 100
+            }))
+            .unwrap();
+        let chat = chat_request_from_responses(request).unwrap();
+        assert_eq!(chat.max_completion_tokens, Some(100));
+        assert_eq!(chat.messages.len(), 4);
+        assert!(matches!(
+            chat.messages[0],
+            openai::ChatCompletionRequestMessage::Developer(_)
+        ));
+        assert!(matches!(
+            &chat.messages[2],
+            openai::ChatCompletionRequestMessage::Assistant(message)
+                if message.tool_calls.as_ref().is_some_and(|calls| calls.len() == 1)
+        ));
+        assert!(matches!(
+            chat.messages[3],
+            openai::ChatCompletionRequestMessage::Tool(_)
+        ));
+    }
+
+    #[test]
+    fn rejects_unmappable_input_items() {
+        let request: CreateResponseRequest =
+            serde_json::from_value(serde_json::json!({
+                "model": "anthropic/claude-sonnet-4-0",
+                "input": [
+                    { "type": "reasoning", "id": "rs_1", "summary": [] }
+                ]
+            }))
+            .unwrap();
+        assert!(matches!(
+            chat_request_from_responses(request),
+            Err(MapperError::UnsupportedResponsesInput(_))
+        ));
+    }
+}
@@ -85,20 +85,22 @@ where
                     "PathAndQuery",
                 )))?;
             let source_endpoint =
-                req.extensions().get::<ApiEndpoint>().copied();
+                req.extensions().get::<ApiEndpoint>().cloned();
             let source_endpoint = source_endpoint.ok_or(ApiError::Internal(
                 InternalError::ExtensionNotFound("ApiEndpoint"),
             ))?;
             let target_endpoint =
-                ApiEndpoint::mapped(source_endpoint, &target_provider)?;
+                ApiEndpoint::mapped(source_endpoint.clone(), &target_provider)?;
             // serialization/deserialization should be done on a dedicated
             // thread
             let converter_registry_cloned = converter_registry.clone();
+            let (req_source_endpoint, req_target_endpoint) =
+                (source_endpoint.clone(), target_endpoint.clone());
             let req = tokio::task::spawn_blocking(move || async move {
                 map_request(
                     converter_registry_cloned,
-                    &source_endpoint,
-                    &target_endpoint,
+                    &req_source_endpoint,
+                    &req_target_endpoint,
                     &extracted_path_and_query,
                     req,
                 )
@@ -144,10 +146,14 @@ async fn map_request(
     let converter = converter_registry
         .get_converter(source_endpoint, target_endpoint)
         .ok_or_else(|| {
-            InternalError::InvalidConverter(*source_endpoint, *target_endpoint)
+            InternalError::InvalidConverter(
+                source_endpoint.clone(),
+                target_endpoint.clone(),
+            )
         })?;
 
-    let (body, mapper_ctx) = converter.convert_req_body(body)?;
+    let (body, mapper_ctx) =
+        converter.convert_req_body(&parts.headers, body)?;
     let base_path = target_endpoint
         .path(mapper_ctx.model.as_ref(), mapper_ctx.is_stream)?;
 
@@ -176,7 +182,7 @@ async fn map_request(
     );
     req.extensions_mut().insert(target_path_and_query);
     req.extensions_mut().insert(mapper_ctx);
-    req.extensions_mut().insert(*target_endpoint);
+    req.extensions_mut().insert(target_endpoint.clone());
     Ok(req)
 }
 
@@ -196,9 +202,22 @@ async fn map_response(
     let converter = converter_registry
         .get_converter(&target_endpoint, &source_endpoint)
         .ok_or_else(|| {
-            InternalError::InvalidConverter(target_endpoint, source_endpoint)
+            InternalError::InvalidConverter(
+                target_endpoint.clone(),
+                source_endpoint.clone(),
+            )
         })?;
 
+    if converter.is_passthrough() && !is_stream {
+        // e.g. binary audio, which must not be buffered
+        tracing::trace!(
+            source_endpoint = ?target_endpoint,
+            target_endpoint = ?source_endpoint,
+            "passing through response"
+        );
+        return Ok(Response::from_parts(parts, body));
+    }
+
     if is_stream {
         // because we are using our custom body type, and we know it was
         // constructed in the dispatcher from either an SSE stream or a
@@ -210,16 +229,20 @@ async fn map_response(
             .try_filter_map({
                 let captured_registry = converter_registry.clone();
                 let resp_parts = parts.clone();
+                let captured_target_endpoint = target_endpoint.clone();
+                let captured_source_endpoint = source_endpoint.clone();
                 move |bytes| {
                     let registry_for_future = captured_registry.clone();
                     let resp_parts = resp_parts.clone();
+                    let target_endpoint = captured_target_endpoint.clone();
+                    let source_endpoint = captured_source_endpoint.clone();
                     async move {
                         let converter = registry_for_future
                             .get_converter(&target_endpoint, &source_endpoint)
                             .ok_or_else(|| {
                                 InternalError::InvalidConverter(
-                                    target_endpoint,
-                                    source_endpoint,
+                                    target_endpoint.clone(),
+                                    source_endpoint.clone(),
                                 )
                             })?;
 
@@ -229,6 +252,14 @@ async fn map_response(
                         // add the `data: ` prefix expected by the OpenAI SDK
                         if let Some(converted_data) = converted_data {
                             let mut new_bytes = BytesMut::new();
+                            if let Some(event) = sse_event_name(
+                                &target_endpoint,
+                                &converted_data,
+                            ) {
+                                new_bytes.put("event: ".as_bytes());
+                                new_bytes.put(event.as_bytes());
+                                new_bytes.put("\n".as_bytes());
+                            }
                             new_bytes.put("data: ".as_bytes());
                             new_bytes.put(converted_data);
                             new_bytes.put("\n\n".as_bytes());
@@ -273,6 +304,23 @@ async fn map_response(
     }
 }
 
+/// The Anthropic SDKs dispatch stream events on the SSE `event` field
+/// rather than the `type` of the data, so it is added for Anthropic clients.
+fn sse_event_name(endpoint: &ApiEndpoint, data: &[u8]) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct EventType {
+        #[serde(rename = "type")]
+        kind: String,
+    }
+
+    if !matches!(endpoint, ApiEndpoint::Anthropic(_)) {
+        return None;
+    }
+    serde_json::from_slice::<EventType>(data)
+        .ok()
+        .map(|event| event.kind)
+}
+
 #[derive(Debug, Clone)]
 pub struct Layer {
     endpoint_converter_registry: EndpointConverterRegistry,
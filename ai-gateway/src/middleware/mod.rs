@@ -1,7 +1,11 @@
 pub mod add_extension;
 pub mod auth;
 pub mod cache;
+pub mod fallback;
+pub mod hedge;
 pub mod mapper;
 pub mod rate_limit;
 pub mod request_context;
 pub mod response_headers;
+pub mod retry;
+pub mod spend;
@@ -35,3 +35,48 @@ pub fn get_redis_rl_key<T>(
         Ok(format!("rl:per-api-key:GLOBAL:{user_id}"))
     }
 }
+
+pub fn get_tokens_rl_key<T>(
+    req: &Request<T>,
+    router_id: Option<&RouterId>,
+) -> Result<String, InternalError> {
+    let user_id = get_user_id(req)?;
+    if let Some(router_id) = router_id {
+        Ok(format!("rl:tokens-per-api-key:{router_id}:{user_id}"))
+    } else {
+        Ok(format!("rl:tokens-per-api-key:GLOBAL:{user_id}"))
+    }
+}
+
+pub fn get_org_rl_key<T>(
+    req: &Request<T>,
+    router_id: Option<&RouterId>,
+) -> Result<String, InternalError> {
+    let Some(ctx) = req.extensions().get::<AuthContext>() else {
+        return Err(InternalError::ExtensionNotFound("AuthContext"));
+    };
+    let org_id = ctx.org_id;
+    if let Some(router_id) = router_id {
+        Ok(format!("rl:per-org:{router_id}:{org_id}"))
+    } else {
+        Ok(format!("rl:per-org:GLOBAL:{org_id}"))
+    }
+}
+
+#[must_use]
+pub fn get_model_rl_key(model: &str, router_id: Option<&RouterId>) -> String {
+    if let Some(router_id) = router_id {
+        format!("rl:per-model:{router_id}:{model}")
+    } else {
+        format!("rl:per-model:GLOBAL:{model}")
+    }
+}
+
+#[must_use]
+pub fn get_router_total_rl_key(router_id: Option<&RouterId>) -> String {
+    if let Some(router_id) = router_id {
+        format!("rl:per-router-total:{router_id}")
+    } else {
+        "rl:per-router-total:GLOBAL".to_string()
+    }
+}
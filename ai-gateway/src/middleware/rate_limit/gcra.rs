@@ -0,0 +1,321 @@
+//! A GCRA where each request may cost more than one cell, kept either in
+//! memory or in Redis.
+//!
+//! The per API key request limits use `governor` when kept in memory, which
+//! only supports a single key per limiter, so this is used for the other
+//! limits instead.
+use std::{
+    sync::{Arc, LazyLock, Mutex, PoisonError},
+    time::Duration,
+};
+
+use chrono::Utc;
+use r2d2::Pool;
+use redis::{Client, Commands, Script};
+use rustc_hash::FxHashMap as HashMap;
+
+use crate::{
+    config::rate_limit::GcraConfig,
+    error::{
+        api::ApiError,
+        internal::InternalError,
+        invalid_req::{InvalidRequestError, TooManyRequestsError},
+    },
+};
+
+/// Moves the TAT of `KEYS[1]` by `ARGV[2]` milliseconds from no earlier than
+/// `ARGV[1]`, the current time, and expires it a second after the new TAT.
+///
+/// This is done in a script since the key may have expired, or its TAT may
+/// have passed, while the request was in flight.
+static RECONCILE_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+local now = tonumber(ARGV[1])
+local tat = math.max(tonumber(redis.call('GET', KEYS[1])) or now, now)
+tat = tat + tonumber(ARGV[2])
+local ttl = math.max(tat - now, 0) + 1000
+redis.call('SET', KEYS[1], string.format('%d', tat), 'PX', string.format('%d', ttl))
+return string.format('%d', tat)
+",
+    )
+});
+
+#[derive(Debug, Clone)]
+enum Store {
+    /// Expired keys are pruned when the map is written to, at most once per
+    /// refill frequency.
+    InMemory(Arc<Mutex<InMemoryState>>),
+    Redis(Pool<Client>),
+}
+
+#[derive(Debug, Default)]
+struct InMemoryState {
+    tats: HashMap<String, i64>,
+    last_pruned_ms: i64,
+}
+
+/// Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct GcraLimiter {
+    config: GcraConfig,
+    store: Store,
+}
+
+/// The outcome of [`GcraLimiter::reserve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reservation {
+    Allowed { remaining: u64 },
+    Denied { retry_after_ms: i64 },
+}
+
+impl GcraLimiter {
+    #[must_use]
+    pub fn in_memory(config: GcraConfig) -> Self {
+        Self {
+            config,
+            store: Store::InMemory(Arc::default()),
+        }
+    }
+
+    #[must_use]
+    pub fn redis(config: GcraConfig, pool: Pool<Client>) -> Self {
+        Self {
+            config,
+            store: Store::Redis(pool),
+        }
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> u64 {
+        u64::from(self.config.capacity.get())
+    }
+
+    fn refill_ms(&self) -> i64 {
+        i64::try_from(self.config.refill_frequency.as_millis())
+            .unwrap_or(i64::MAX)
+            .max(1)
+    }
+
+    /// The time it takes to refill `cost` cells, which is negative for a
+    /// refund.
+    fn cost_to_ms(&self, cost: i64) -> i64 {
+        let ms = i128::from(cost) * i128::from(self.refill_ms())
+            / i128::from(self.capacity());
+        i64::try_from(ms).unwrap_or(if ms < 0 { i64::MIN } else { i64::MAX })
+    }
+
+    fn remaining(&self, tat: i64, now_ms: i64) -> u64 {
+        let used_ms = i128::from(tat.saturating_sub(now_ms).max(0));
+        let used = used_ms * i128::from(self.capacity())
+            / i128::from(self.refill_ms());
+        self.capacity()
+            .saturating_sub(u64::try_from(used).unwrap_or(u64::MAX))
+    }
+
+    /// Returns the new theoretical arrival time (TAT) if `cost` fits within
+    /// the limit, or otherwise the milliseconds until it will.
+    fn gcra(
+        &self,
+        tat: Option<i64>,
+        now_ms: i64,
+        cost: u64,
+    ) -> Result<i64, i64> {
+        let tat = tat.unwrap_or(now_ms).max(now_ms);
+        let new_tat = tat.saturating_add(
+            self.cost_to_ms(i64::try_from(cost).unwrap_or(i64::MAX)),
+        );
+        let earliest_allowed_time = new_tat.saturating_sub(self.refill_ms());
+        if earliest_allowed_time <= now_ms {
+            Ok(new_tat)
+        } else {
+            Err(earliest_allowed_time - now_ms)
+        }
+    }
+
+    /// Seconds until a TAT has passed, after which its key can be dropped.
+    fn ttl_secs(tat: i64, now_ms: i64) -> i64 {
+        tat.saturating_sub(now_ms).max(0) / 1000 + 1
+    }
+
+    pub fn reserve(
+        &self,
+        key: &str,
+        now_ms: i64,
+        cost: u64,
+    ) -> Result<Reservation, InternalError> {
+        let result = match &self.store {
+            Store::InMemory(state) => {
+                let mut state =
+                    state.lock().unwrap_or_else(PoisonError::into_inner);
+                if now_ms - state.last_pruned_ms >= self.refill_ms() {
+                    state.tats.retain(|_, tat| *tat > now_ms);
+                    state.last_pruned_ms = now_ms;
+                }
+                let result =
+                    self.gcra(state.tats.get(key).copied(), now_ms, cost);
+                if let Ok(new_tat) = result {
+                    state.tats.insert(key.to_string(), new_tat);
+                }
+                result
+            }
+            Store::Redis(pool) => {
+                let mut conn = pool.get().map_err(InternalError::PoolError)?;
+                let existing_tat: Option<i64> =
+                    conn.get(key).map_err(InternalError::RedisError)?;
+                let result = self.gcra(existing_tat, now_ms, cost);
+                if let Ok(new_tat) = result {
+                    let ttl = Self::ttl_secs(new_tat, now_ms).unsigned_abs();
+                    let _: () = conn
+                        .set_ex(key, new_tat, ttl)
+                        .map_err(InternalError::RedisError)?;
+                }
+                result
+            }
+        };
+        Ok(match result {
+            Ok(new_tat) => Reservation::Allowed {
+                remaining: self.remaining(new_tat, now_ms),
+            },
+            Err(retry_after_ms) => Reservation::Denied { retry_after_ms },
+        })
+    }
+
+    /// Charges the key for `cost` more cells than it reserved, or refunds it
+    /// if `cost` is negative.
+    pub fn reconcile(&self, key: &str, cost: i64) -> Result<(), InternalError> {
+        let now_ms = Utc::now().timestamp_millis();
+        let delta_ms = self.cost_to_ms(cost);
+        match &self.store {
+            Store::InMemory(state) => {
+                let mut state =
+                    state.lock().unwrap_or_else(PoisonError::into_inner);
+                let tat = state.tats.entry(key.to_string()).or_insert(now_ms);
+                *tat = (*tat).max(now_ms).saturating_add(delta_ms);
+            }
+            Store::Redis(pool) => {
+                let mut conn = pool.get().map_err(InternalError::PoolError)?;
+                let _: i64 = RECONCILE_SCRIPT
+                    .key(key)
+                    .arg(now_ms)
+                    .arg(delta_ms)
+                    .invoke(&mut *conn)
+                    .map_err(InternalError::RedisError)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Like [`Self::reconcile`], for callers on the runtime's worker threads
+    /// that can't block on Redis, e.g. when a response body is dropped.
+    ///
+    /// The in memory state is reconciled immediately, so that the key's
+    /// next reservation sees it, while Redis is reconciled on a blocking
+    /// thread.
+    pub fn reconcile_in_background(&self, key: String, cost: i64) {
+        match &self.store {
+            Store::InMemory(_) => {
+                if let Err(e) = self.reconcile(&key, cost) {
+                    tracing::error!(error = %e, "failed to reconcile usage");
+                }
+            }
+            Store::Redis(_) => {
+                let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+                    tracing::error!("no runtime to reconcile usage on");
+                    return;
+                };
+                let limiter = self.clone();
+                runtime.spawn_blocking(move || {
+                    if let Err(e) = limiter.reconcile(&key, cost) {
+                        tracing::error!(error = %e, "failed to reconcile usage");
+                    }
+                });
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn too_many_requests(&self, retry_after_ms: i64) -> ApiError {
+        let retry_after = Duration::from_millis(
+            retry_after_ms.try_into().expect("value too large"),
+        )
+        .as_secs()
+            + 1; // adding a second to retry-after header to prevent rounding errors
+        ApiError::InvalidRequest(InvalidRequestError::TooManyRequests(
+            TooManyRequestsError {
+                ratelimit_limit: self.capacity(),
+                ratelimit_remaining: 0,
+                retry_after,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use super::*;
+
+    #[test]
+    fn reservations_are_limited_and_reconciled() {
+        let limiter = GcraLimiter::in_memory(GcraConfig {
+            refill_frequency: Duration::from_secs(60),
+            capacity: NonZeroU32::new(1000).unwrap(),
+        });
+        let now_ms = Utc::now().timestamp_millis();
+        assert_eq!(
+            limiter.reserve("key", now_ms, 600).unwrap(),
+            Reservation::Allowed { remaining: 400 }
+        );
+        assert!(matches!(
+            limiter.reserve("key", now_ms, 600).unwrap(),
+            Reservation::Denied { .. }
+        ));
+
+        // the request used fewer cells than were reserved
+        limiter.reconcile("key", -500).unwrap();
+        assert!(matches!(
+            limiter.reserve("key", now_ms, 600).unwrap(),
+            Reservation::Allowed { .. }
+        ));
+    }
+
+    #[cfg(feature = "redis-testing")]
+    #[test]
+    fn redis_reconcile_charges_from_now_after_expiry() {
+        let client = Client::open("redis://localhost:6340").unwrap();
+        let pool = Pool::builder().build(client).unwrap();
+        let limiter = GcraLimiter::redis(
+            GcraConfig {
+                refill_frequency: Duration::from_secs(60),
+                capacity: NonZeroU32::new(1000).unwrap(),
+            },
+            pool.clone(),
+        );
+        let key = format!("gcra-test:{}", uuid::Uuid::new_v4());
+        let now_ms = Utc::now().timestamp_millis();
+        assert_eq!(
+            limiter.reserve(&key, now_ms, 100).unwrap(),
+            Reservation::Allowed { remaining: 900 }
+        );
+
+        // the key expired while a long response was streamed, so the usage
+        // is charged from now rather than from zero
+        let mut conn = pool.get().unwrap();
+        let _: () = conn.del(&key).unwrap();
+        limiter.reconcile(&key, 600).unwrap();
+        let tat: i64 = conn.get(&key).unwrap();
+        let charged_ms = tat - Utc::now().timestamp_millis();
+        assert!((35_000..=36_000).contains(&charged_ms), "{charged_ms}");
+        let ttl_ms: i64 = conn.pttl(&key).unwrap();
+        assert!(ttl_ms > 35_000, "{ttl_ms}");
+
+        assert!(matches!(
+            limiter
+                .reserve(&key, Utc::now().timestamp_millis(), 500)
+                .unwrap(),
+            Reservation::Denied { .. }
+        ));
+    }
+}
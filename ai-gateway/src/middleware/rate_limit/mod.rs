@@ -1,6 +1,9 @@
 pub mod cleanup;
 pub mod extractor;
+pub mod gcra;
 pub mod redis_service;
+pub mod scopes;
 pub mod service;
+pub mod tokens;
 
 pub use self::service::{Layer, Service};
@@ -133,6 +133,10 @@ where
         + 'static,
     S::Future: Send + 'static,
 {
+    // only the scoped and token limits are configured
+    let Some(gcra) = &config.per_api_key else {
+        return inner.call(req).await;
+    };
     let mut conn = pool.get().map_err(InternalError::PoolError)?;
 
     let key = get_redis_rl_key(&req, router_id)?;
@@ -149,7 +153,6 @@ where
         })
         .timestamp_millis();
 
-    let gcra = &config.per_api_key;
     let interval_per_token_ms = gcra
         .refill_frequency
         .checked_div(gcra.capacity.into())
@@ -194,19 +197,16 @@ where
 
         let ratelimit_limit = u64::from(gcra.capacity.get());
 
-        if let Ok(mut res) = inner.call(req).await {
-            res.headers_mut().insert(
-                "x-ratelimit-limit",
-                ratelimit_limit.to_string().parse().unwrap(),
-            );
-            res.headers_mut().insert(
-                "x-ratelimit-remaining",
-                ratelimit_remaining.to_string().parse().unwrap(),
-            );
-            Ok(res)
-        } else {
-            Err(ApiError::Internal(InternalError::Internal))
-        }
+        let mut res = inner.call(req).await?;
+        res.headers_mut().insert(
+            "x-ratelimit-limit",
+            ratelimit_limit.to_string().parse().unwrap(),
+        );
+        res.headers_mut().insert(
+            "x-ratelimit-remaining",
+            ratelimit_remaining.to_string().parse().unwrap(),
+        );
+        Ok(res)
     } else {
         let ratelimit_limit = u64::from(gcra.capacity.get());
         let ratelimit_remaining = 0;
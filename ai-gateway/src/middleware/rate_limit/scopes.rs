@@ -0,0 +1,365 @@
+//! Request limits for scopes other than the API key, e.g. per org or per
+//! model, which are all enforced together with the per API key limit.
+use std::{
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum_core::{body::Body, response::Response};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use http_body_util::BodyExt;
+use serde::Deserialize;
+
+use crate::{
+    config::rate_limit::{GcraConfig, LimitsConfig},
+    error::{
+        api::ApiError, internal::InternalError,
+        invalid_req::InvalidRequestError,
+    },
+    middleware::rate_limit::{
+        extractor::{
+            get_model_rl_key, get_org_rl_key, get_router_total_rl_key,
+        },
+        gcra::{GcraLimiter, Reservation},
+    },
+    types::{
+        model_id::{ModelId, ModelName},
+        request::Request,
+        router::RouterId,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitScope {
+    ApiKey,
+    Org,
+    Model,
+    RouterTotal,
+}
+
+impl RateLimitScope {
+    /// The name of the scope in the config, which is also sent in the
+    /// `x-ratelimit-scope` header.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ApiKey => "per-api-key",
+            Self::Org => "per-org",
+            Self::Model => "per-model",
+            Self::RouterTotal => "per-router-total",
+        }
+    }
+}
+
+/// The scoped limits of a router, or of all routers when applied globally.
+///
+/// Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct ScopedRateLimiter {
+    limiters: Arc<[(RateLimitScope, GcraLimiter)]>,
+    router_id: Option<RouterId>,
+}
+
+impl ScopedRateLimiter {
+    /// Returns `None` if no scopes other than the API key are configured.
+    #[must_use]
+    pub fn new(
+        limits: &LimitsConfig,
+        router_id: Option<RouterId>,
+        limiter: impl Fn(&GcraConfig) -> GcraLimiter,
+    ) -> Option<Self> {
+        let limiters: Arc<[_]> = [
+            (RateLimitScope::Org, &limits.per_org),
+            (RateLimitScope::Model, &limits.per_model),
+            (RateLimitScope::RouterTotal, &limits.per_router_total),
+        ]
+        .into_iter()
+        .filter_map(|(scope, gcra)| {
+            gcra.as_ref().map(|gcra| (scope, limiter(gcra)))
+        })
+        .collect();
+        if limiters.is_empty() {
+            None
+        } else {
+            Some(Self {
+                limiters,
+                router_id,
+            })
+        }
+    }
+
+    fn has_scope(&self, scope: RateLimitScope) -> bool {
+        self.limiters.iter().any(|(s, _)| *s == scope)
+    }
+
+    fn key(
+        &self,
+        scope: RateLimitScope,
+        req: &Request,
+        model: Option<&ModelId>,
+    ) -> Result<Option<String>, InternalError> {
+        let router_id = self.router_id.as_ref();
+        Ok(match scope {
+            // enforced by the request limiter that this service wraps
+            RateLimitScope::ApiKey => None,
+            RateLimitScope::Org => Some(get_org_rl_key(req, router_id)?),
+            // requests without a model, e.g. to list models, aren't limited
+            RateLimitScope::Model => model.map(|model| {
+                get_model_rl_key(
+                    ModelName::from_model(model).as_ref(),
+                    router_id,
+                )
+            }),
+            RateLimitScope::RouterTotal => {
+                Some(get_router_total_rl_key(router_id))
+            }
+        })
+    }
+}
+
+/// A reservation of a single request in one of the scopes.
+#[derive(Debug)]
+struct Reserved {
+    scope: RateLimitScope,
+    limiter: GcraLimiter,
+    key: String,
+    remaining: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScopedRateLimitService<S> {
+    inner: S,
+    limiter: Option<ScopedRateLimiter>,
+}
+
+impl<S> ScopedRateLimitService<S> {
+    pub fn new(inner: S, limiter: Option<ScopedRateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+}
+
+impl<S> tower::Service<Request> for ScopedRateLimitService<S>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Send
+        + Clone
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = ApiError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[tracing::instrument(name = "scoped_rate_limit", skip_all)]
+    fn call(&mut self, req: Request) -> Self::Future {
+        // see: https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut this = self.clone();
+        std::mem::swap(self, &mut this);
+        Box::pin(async move {
+            match this.limiter {
+                Some(limiter) => {
+                    make_request(&mut this.inner, &limiter, req).await
+                }
+                None => this.inner.call(req).await,
+            }
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RequestModel {
+    model: Option<String>,
+}
+
+async fn make_request<S>(
+    inner: &mut S,
+    limiter: &ScopedRateLimiter,
+    req: Request,
+) -> Result<Response, ApiError>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Send
+        + Clone
+        + 'static,
+    S::Future: Send + 'static,
+{
+    let now_ms = req
+        .extensions()
+        .get::<DateTime<Utc>>()
+        .copied()
+        .unwrap_or_else(|| {
+            tracing::warn!(
+                "did not find expected DateTime<Utc> in req extensions"
+            );
+            Utc::now()
+        })
+        .timestamp_millis();
+
+    let (req, model) = if limiter.has_scope(RateLimitScope::Model) {
+        let (parts, body) = req.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(InternalError::CollectBodyError)?
+            .to_bytes();
+        // the model is parsed so that it shares one limit whichever provider
+        // or version it's requested with
+        let model = serde_json::from_slice::<RequestModel>(&body)
+            .ok()
+            .and_then(|request| request.model)
+            .and_then(|model| ModelId::from_str(&model).ok());
+        (Request::from_parts(parts, Body::from(body)), model)
+    } else {
+        (req, None)
+    };
+
+    let mut reserved = Vec::with_capacity(limiter.limiters.len());
+    let mut denied: Option<(&GcraLimiter, i64)> = None;
+    for (scope, gcra) in limiter.limiters.iter() {
+        let Some(key) = limiter.key(*scope, &req, model.as_ref())? else {
+            continue;
+        };
+        match gcra.reserve(&key, now_ms, 1)? {
+            Reservation::Allowed { remaining } => reserved.push(Reserved {
+                scope: *scope,
+                limiter: gcra.clone(),
+                key,
+                remaining,
+            }),
+            Reservation::Denied { retry_after_ms } => {
+                if denied.is_none_or(|(_, longest)| retry_after_ms > longest) {
+                    denied = Some((gcra, retry_after_ms));
+                }
+            }
+        }
+    }
+    if let Some((gcra, retry_after_ms)) = denied {
+        refund(&reserved);
+        return Err(gcra.too_many_requests(retry_after_ms));
+    }
+
+    match inner.call(req).await {
+        // rate limited requests, e.g. by the per API key limit, aren't
+        // counted
+        Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+            refund(&reserved);
+            Ok(response)
+        }
+        Ok(mut response) => {
+            report_most_restrictive(response.headers_mut(), &reserved);
+            Ok(response)
+        }
+        Err(e) => {
+            if is_too_many_requests(&e) {
+                refund(&reserved);
+            }
+            Err(e)
+        }
+    }
+}
+
+fn is_too_many_requests(error: &ApiError) -> bool {
+    matches!(
+        error,
+        ApiError::InvalidRequest(InvalidRequestError::TooManyRequests(_))
+    )
+}
+
+fn refund(reserved: &[Reserved]) {
+    for reserved in reserved {
+        if let Err(e) = reserved.limiter.reconcile(&reserved.key, -1) {
+            tracing::error!(error = %e, scope = reserved.scope.as_str(), "failed to refund rate limit reservation");
+        }
+    }
+}
+
+/// Replaces the per API key rate limit headers with those of a scope that
+/// has fewer requests remaining.
+fn report_most_restrictive(headers: &mut HeaderMap, reserved: &[Reserved]) {
+    let api_key_remaining = headers
+        .get("x-ratelimit-remaining")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    let most_restrictive = reserved
+        .iter()
+        .min_by_key(|reserved| reserved.remaining)
+        .filter(|reserved| {
+            api_key_remaining
+                .is_none_or(|remaining| reserved.remaining < remaining)
+        });
+    let Some(reserved) = most_restrictive else {
+        headers.insert(
+            "x-ratelimit-scope",
+            HeaderValue::from_static(RateLimitScope::ApiKey.as_str()),
+        );
+        return;
+    };
+    headers.insert(
+        "x-ratelimit-limit",
+        HeaderValue::from(reserved.limiter.capacity()),
+    );
+    headers.insert(
+        "x-ratelimit-remaining",
+        HeaderValue::from(reserved.remaining),
+    );
+    headers.insert(
+        "x-ratelimit-scope",
+        HeaderValue::from_static(reserved.scope.as_str()),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU32, time::Duration};
+
+    use super::*;
+
+    fn reserved(scope: RateLimitScope, remaining: u64) -> Reserved {
+        Reserved {
+            scope,
+            limiter: GcraLimiter::in_memory(GcraConfig {
+                refill_frequency: Duration::from_secs(60),
+                capacity: NonZeroU32::new(100).unwrap(),
+            }),
+            key: String::new(),
+            remaining,
+        }
+    }
+
+    #[test]
+    fn most_restrictive_scope_is_reported() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from(10));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from(5));
+        report_most_restrictive(
+            &mut headers,
+            &[
+                reserved(RateLimitScope::Org, 50),
+                reserved(RateLimitScope::Model, 2),
+            ],
+        );
+        assert_eq!(headers["x-ratelimit-limit"], "100");
+        assert_eq!(headers["x-ratelimit-remaining"], "2");
+        assert_eq!(headers["x-ratelimit-scope"], "per-model");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from(10));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from(1));
+        report_most_restrictive(
+            &mut headers,
+            &[reserved(RateLimitScope::RouterTotal, 50)],
+        );
+        assert_eq!(headers["x-ratelimit-limit"], "10");
+        assert_eq!(headers["x-ratelimit-scope"], "per-api-key");
+    }
+}
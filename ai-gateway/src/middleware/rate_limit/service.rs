@@ -14,14 +14,17 @@ use crate::{
     app_state::AppState,
     config::{
         rate_limit::{
-            LimitsConfig, RateLimitStore, RateLimiterConfig,
+            GcraConfig, LimitsConfig, RateLimitStore, RateLimiterConfig,
             default_refill_frequency,
         },
         router::{RouterConfig, RouterRateLimitConfig},
     },
     error::init::InitError,
-    middleware::rate_limit::redis_service::{
-        RedisRateLimitLayer, RedisRateLimitService,
+    middleware::rate_limit::{
+        gcra::GcraLimiter,
+        redis_service::{RedisRateLimitLayer, RedisRateLimitService},
+        scopes::{ScopedRateLimitService, ScopedRateLimiter},
+        tokens::{TokenRateLimitService, TokenRateLimiter},
     },
     types::router::RouterId,
 };
@@ -39,11 +42,25 @@ pub enum InnerLayer {
     None,
     InMemory(GovernorLayer<RateLimitKeyExtractor, StateInformationMiddleware>),
     Redis(RedisRateLimitLayer),
+    /// Only the scoped and token limits, for in memory limits without a per
+    /// API key request limit.
+    Unkeyed,
 }
 
+pub type InMemoryService<S> =
+    ScopedRateLimitService<GovernorService<TokenRateLimitService<S>>>;
+pub type RedisService<S> =
+    ScopedRateLimitService<RedisRateLimitService<TokenRateLimitService<S>>>;
+pub type UnkeyedService<S> = ScopedRateLimitService<TokenRateLimitService<S>>;
+
 #[derive(Clone)]
 pub struct Layer {
     inner: InnerLayer,
+    /// The scoped and token limits are kept in the same store as the per API
+    /// key request limits. Scoped limits are checked before, and token limits
+    /// after, the per API key limit.
+    scopes: Option<ScopedRateLimiter>,
+    tokens: Option<TokenRateLimiter>,
 }
 
 impl Layer {
@@ -59,40 +76,61 @@ impl Layer {
                     redis_config.host_url.expose().clone(),
                 )
             } else {
-                Self::new_in_memory_inner(app_state.0.global_rate_limit.clone())
+                Self::new_in_memory_inner(
+                    app_state.0.global_rate_limit.clone(),
+                    rate_limit_config.limits.as_ref(),
+                )
             }
         } else {
-            Self {
-                inner: InnerLayer::None,
-            }
+            Self::disabled()
         }
     }
 
     #[must_use]
     fn new_redis_inner(rl: Option<LimitsConfig>, url: url::Url) -> Self {
         if let Some(rl) = rl
-            && let Ok(layer) = RedisRateLimitLayer::new(Arc::new(rl), url, None)
+            && let Ok(layer) =
+                RedisRateLimitLayer::new(Arc::new(rl.clone()), url, None)
         {
-            Self {
-                inner: InnerLayer::Redis(layer),
-            }
+            let pool = layer.pool.clone();
+            Self::with_limits(InnerLayer::Redis(layer), &rl, None, |gcra| {
+                GcraLimiter::redis(gcra.clone(), pool.clone())
+            })
         } else {
-            Self {
-                inner: InnerLayer::None,
-            }
+            Self::disabled()
         }
     }
 
+    /// `rl` is `None` if there's no per API key limit.
     #[must_use]
-    fn new_in_memory_inner(rl: Option<Arc<RateLimiterConfig>>) -> Self {
-        if let Some(rl) = rl {
-            Self {
-                inner: InnerLayer::InMemory(GovernorLayer { config: rl }),
-            }
-        } else {
-            Self {
-                inner: InnerLayer::None,
-            }
+    fn new_in_memory_inner(
+        rl: Option<Arc<RateLimiterConfig>>,
+        limits: Option<&LimitsConfig>,
+    ) -> Self {
+        let Some(limits) = limits else {
+            return Self::disabled();
+        };
+        let inner = match rl {
+            Some(rl) => InnerLayer::InMemory(GovernorLayer { config: rl }),
+            None => InnerLayer::Unkeyed,
+        };
+        Self::with_limits(inner, limits, None, |gcra| {
+            GcraLimiter::in_memory(gcra.clone())
+        })
+    }
+
+    fn with_limits(
+        inner: InnerLayer,
+        limits: &LimitsConfig,
+        router_id: Option<RouterId>,
+        limiter: impl Fn(&GcraConfig) -> GcraLimiter,
+    ) -> Self {
+        Self {
+            inner,
+            tokens: limits.per_api_key_tokens.as_ref().map(|gcra| {
+                TokenRateLimiter::new(limiter(gcra), router_id.clone())
+            }),
+            scopes: ScopedRateLimiter::new(limits, router_id, limiter),
         }
     }
 
@@ -101,6 +139,8 @@ impl Layer {
     pub fn disabled() -> Self {
         Self {
             inner: InnerLayer::None,
+            scopes: None,
+            tokens: None,
         }
     }
 
@@ -110,9 +150,7 @@ impl Layer {
         router_config: &RouterConfig,
     ) -> Result<Self, InitError> {
         match &router_config.rate_limit {
-            RouterRateLimitConfig::None => Ok(Self {
-                inner: InnerLayer::None,
-            }),
+            RouterRateLimitConfig::None => Ok(Self::disabled()),
             RouterRateLimitConfig::Custom { store, limits } => {
                 let ratelimit_store = store.clone().or_else(|| {
                     Some(app_state.0.config.rate_limit_store.clone())
@@ -126,11 +164,22 @@ impl Layer {
                         Some(router_id.clone()),
                     )
                 {
-                    return Ok(Self {
-                        inner: InnerLayer::Redis(layer),
-                    });
+                    let pool = layer.pool.clone();
+                    return Ok(Self::with_limits(
+                        InnerLayer::Redis(layer),
+                        limits,
+                        Some(router_id),
+                        |gcra| GcraLimiter::redis(gcra.clone(), pool.clone()),
+                    ));
                 }
-                let gcra = &limits.per_api_key;
+                let Some(gcra) = &limits.per_api_key else {
+                    return Ok(Self::with_limits(
+                        InnerLayer::Unkeyed,
+                        limits,
+                        Some(router_id),
+                        |gcra| GcraLimiter::in_memory(gcra.clone()),
+                    ));
+                };
                 let per_cell_duration = gcra
                     .refill_frequency
                     .checked_div(gcra.capacity.into())
@@ -152,14 +201,19 @@ impl Layer {
                         "Invalid rate limit config",
                     ))?;
                 let rl = Arc::new(rl);
-                add_rate_limit_to_app_state(app_state, router_id, rl.clone())
-                    .await;
-
-                Ok(Self {
-                    inner: InnerLayer::InMemory(GovernorLayer {
-                        config: rl.clone(),
-                    }),
-                })
+                add_rate_limit_to_app_state(
+                    app_state,
+                    router_id.clone(),
+                    rl.clone(),
+                )
+                .await;
+
+                Ok(Self::with_limits(
+                    InnerLayer::InMemory(GovernorLayer { config: rl.clone() }),
+                    limits,
+                    Some(router_id),
+                    |gcra| GcraLimiter::in_memory(gcra.clone()),
+                ))
             }
         }
     }
@@ -180,10 +234,28 @@ impl<S> tower::layer::Layer<S> for Layer {
     fn layer(&self, service: S) -> Self::Service {
         match &self.inner {
             InnerLayer::InMemory(inner) => Service::InMemory {
-                service: inner.layer(service),
+                service: ScopedRateLimitService::new(
+                    inner.layer(TokenRateLimitService::new(
+                        service,
+                        self.tokens.clone(),
+                    )),
+                    self.scopes.clone(),
+                ),
             },
             InnerLayer::Redis(inner) => Service::Redis {
-                service: inner.layer(service),
+                service: ScopedRateLimitService::new(
+                    inner.layer(TokenRateLimitService::new(
+                        service,
+                        self.tokens.clone(),
+                    )),
+                    self.scopes.clone(),
+                ),
+            },
+            InnerLayer::Unkeyed => Service::Unkeyed {
+                service: ScopedRateLimitService::new(
+                    TokenRateLimitService::new(service, self.tokens.clone()),
+                    self.scopes.clone(),
+                ),
             },
             InnerLayer::None => Service::Disabled { service },
         }
@@ -193,16 +265,18 @@ impl<S> tower::layer::Layer<S> for Layer {
 #[derive(Debug, Clone)]
 pub enum Service<S> {
     Disabled { service: S },
-    InMemory { service: GovernorService<S> },
-    Redis { service: RedisRateLimitService<S> },
+    InMemory { service: InMemoryService<S> },
+    Redis { service: RedisService<S> },
+    Unkeyed { service: UnkeyedService<S> },
 }
 
 pin_project_lite::pin_project! {
     #[derive(Debug)]
     #[project = EnumProj]
-    pub enum ResponseFuture<InMemoryFuture, RedisFuture, DisabledFuture> {
+    pub enum ResponseFuture<InMemoryFuture, RedisFuture, UnkeyedFuture, DisabledFuture> {
         InMemory { #[pin] future: InMemoryFuture },
         Redis { #[pin] future: RedisFuture },
+        Unkeyed { #[pin] future: UnkeyedFuture },
         Disabled { #[pin] future: DisabledFuture },
     }
 }
@@ -230,11 +304,24 @@ fn increment_retry_after_header<ResponseBody>(
     }
 }
 
-impl<InMemoryFuture, RedisFuture, DisabledFuture, ResponseBody, Error> Future
-    for ResponseFuture<InMemoryFuture, RedisFuture, DisabledFuture>
+impl<
+    InMemoryFuture,
+    RedisFuture,
+    UnkeyedFuture,
+    DisabledFuture,
+    ResponseBody,
+    Error,
+> Future
+    for ResponseFuture<
+        InMemoryFuture,
+        RedisFuture,
+        UnkeyedFuture,
+        DisabledFuture,
+    >
 where
     InMemoryFuture: Future<Output = Result<Response<ResponseBody>, Error>>,
     RedisFuture: Future<Output = Result<Response<ResponseBody>, Error>>,
+    UnkeyedFuture: Future<Output = Result<Response<ResponseBody>, Error>>,
     DisabledFuture: Future<Output = Result<Response<ResponseBody>, Error>>,
 {
     type Output = Result<Response<ResponseBody>, Error>;
@@ -251,6 +338,7 @@ where
                 }
             }
             EnumProj::Redis { future } => future.poll(cx),
+            EnumProj::Unkeyed { future } => future.poll(cx),
             EnumProj::Disabled { future } => future.poll(cx),
         }
     }
@@ -259,12 +347,17 @@ where
 impl<S, Request, ResponseBody> tower::Service<Request> for Service<S>
 where
     S: tower::Service<Request, Response = Response<ResponseBody>>,
-    GovernorService<S>: tower::Service<
+    InMemoryService<S>: tower::Service<
             Request,
             Response = Response<ResponseBody>,
             Error = S::Error,
         >,
-    RedisRateLimitService<S>: tower::Service<
+    RedisService<S>: tower::Service<
+            Request,
+            Response = Response<ResponseBody>,
+            Error = S::Error,
+        >,
+    UnkeyedService<S>: tower::Service<
             Request,
             Response = Response<ResponseBody>,
             Error = S::Error,
@@ -273,8 +366,9 @@ where
     type Response = Response<ResponseBody>;
     type Error = S::Error;
     type Future = ResponseFuture<
-        <GovernorService<S> as tower::Service<Request>>::Future,
-        <RedisRateLimitService<S> as tower::Service<Request>>::Future,
+        <InMemoryService<S> as tower::Service<Request>>::Future,
+        <RedisService<S> as tower::Service<Request>>::Future,
+        <UnkeyedService<S> as tower::Service<Request>>::Future,
         S::Future,
     >;
 
@@ -285,6 +379,7 @@ where
         match self {
             Service::InMemory { service } => service.poll_ready(cx),
             Service::Redis { service } => service.poll_ready(cx),
+            Service::Unkeyed { service } => service.poll_ready(cx),
             Service::Disabled { service } => service.poll_ready(cx),
         }
     }
@@ -297,6 +392,9 @@ where
             Service::Redis { service } => ResponseFuture::Redis {
                 future: service.call(request),
             },
+            Service::Unkeyed { service } => ResponseFuture::Unkeyed {
+                future: service.call(request),
+            },
             Service::Disabled { service } => ResponseFuture::Disabled {
                 future: service.call(request),
             },
@@ -335,10 +433,14 @@ mod tests {
 
     fn create_test_limits() -> LimitsConfig {
         LimitsConfig {
-            per_api_key: GcraConfig {
+            per_api_key: Some(GcraConfig {
                 capacity: NonZeroU32::new(10).unwrap(),
                 refill_frequency: Duration::from_secs(1),
-            },
+            }),
+            per_api_key_tokens: None,
+            per_org: None,
+            per_model: None,
+            per_router_total: None,
         }
     }
 
@@ -0,0 +1,264 @@
+//! Token rate limiting, which limits the tokens used by each API key rather
+//! than the number of requests it makes.
+//!
+//! Like the request limits this is a GCRA, except each request costs the
+//! number of tokens it uses. Since that isn't known until the response
+//! completes, the estimated prompt tokens are reserved up front and the
+//! difference is charged or refunded once the `usage` has been read from the
+//! response body.
+use std::task::{Context, Poll};
+
+use axum_core::{body::Body, response::Response};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, future::BoxFuture};
+use http::{HeaderMap, HeaderValue};
+use http_body_util::BodyExt;
+
+use crate::{
+    error::{api::ApiError, internal::InternalError},
+    middleware::rate_limit::{
+        extractor::get_tokens_rl_key,
+        gcra::{GcraLimiter, Reservation},
+    },
+    types::{
+        multipart::MultipartForm,
+        request::Request,
+        router::RouterId,
+        usage::{UsageReader, estimate_tokens},
+    },
+};
+
+/// The token limit of a router, or of all routers when applied globally.
+///
+/// Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct TokenRateLimiter {
+    limiter: GcraLimiter,
+    router_id: Option<RouterId>,
+}
+
+impl TokenRateLimiter {
+    #[must_use]
+    pub fn new(limiter: GcraLimiter, router_id: Option<RouterId>) -> Self {
+        Self { limiter, router_id }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenRateLimitService<S> {
+    inner: S,
+    limiter: Option<TokenRateLimiter>,
+}
+
+impl<S> TokenRateLimitService<S> {
+    pub fn new(inner: S, limiter: Option<TokenRateLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+}
+
+impl<S> tower::Service<Request> for TokenRateLimitService<S>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Send
+        + Clone
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = ApiError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[tracing::instrument(name = "token_rate_limit", skip_all)]
+    fn call(&mut self, req: Request) -> Self::Future {
+        // see: https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut this = self.clone();
+        std::mem::swap(self, &mut this);
+        Box::pin(async move {
+            match this.limiter {
+                Some(limiter) => {
+                    make_request(&mut this.inner, limiter, req).await
+                }
+                None => this.inner.call(req).await,
+            }
+        })
+    }
+}
+
+async fn make_request<S>(
+    inner: &mut S,
+    tokens: TokenRateLimiter,
+    req: Request,
+) -> Result<Response, ApiError>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Send
+        + Clone
+        + 'static,
+    S::Future: Send + 'static,
+{
+    let key = get_tokens_rl_key(&req, tokens.router_id.as_ref())?;
+    let now_ms = req
+        .extensions()
+        .get::<DateTime<Utc>>()
+        .copied()
+        .unwrap_or_else(|| {
+            tracing::warn!(
+                "did not find expected DateTime<Utc> in req extensions"
+            );
+            Utc::now()
+        })
+        .timestamp_millis();
+
+    let (parts, body) = req.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map_err(InternalError::CollectBodyError)?
+        .to_bytes();
+    // a prompt larger than the whole capacity would never be allowed, so it
+    // is let through once the limit has fully refilled instead
+    let limiter = tokens.limiter;
+    let reserved =
+        estimated_prompt_tokens(&parts.headers, &body).min(limiter.capacity());
+    let remaining = match limiter.reserve(&key, now_ms, reserved)? {
+        Reservation::Allowed { remaining } => remaining,
+        Reservation::Denied { retry_after_ms } => {
+            return Err(limiter.too_many_requests(retry_after_ms));
+        }
+    };
+
+    let mut response = match inner
+        .call(Request::from_parts(parts, Body::from(body)))
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            let refund = -i64::try_from(reserved).unwrap_or(i64::MAX);
+            if let Err(refund_error) = limiter.reconcile(&key, refund) {
+                tracing::error!(error = %refund_error, "failed to refund token reservation");
+            }
+            return Err(e);
+        }
+    };
+    response.headers_mut().insert(
+        "x-ratelimit-limit-tokens",
+        HeaderValue::from(limiter.capacity()),
+    );
+    response
+        .headers_mut()
+        .insert("x-ratelimit-remaining-tokens", HeaderValue::from(remaining));
+
+    let recorder = UsageRecorder {
+        limiter,
+        key,
+        reserved,
+        failed: !response.status().is_success(),
+        reader: UsageReader::for_response(&response),
+    };
+    Ok(response.map(|body| recorder.wrap(body)))
+}
+
+/// Files uploaded in multipart forms, e.g. audio to transcribe, aren't
+/// counted since their size says little about the tokens they use, so only
+/// the text fields of a form are.
+fn estimated_prompt_tokens(headers: &HeaderMap, body: &Bytes) -> u64 {
+    let len = MultipartForm::new(headers, body.clone())
+        .map_or(body.len(), |form| form.to_json().to_string().len());
+    estimate_tokens(len)
+}
+
+/// Reads the `usage` from a response body as it is sent to the client, and
+/// reconciles the reservation when the body is dropped.
+struct UsageRecorder {
+    limiter: GcraLimiter,
+    key: String,
+    reserved: u64,
+    /// Failed requests are refunded, since providers don't charge for them.
+    failed: bool,
+    reader: UsageReader,
+}
+
+impl UsageRecorder {
+    fn wrap(mut self, body: Body) -> Body {
+        let stream = body.into_data_stream().map(move |chunk| {
+            if let Ok(bytes) = &chunk
+                && !self.failed
+            {
+                self.reader.record(bytes);
+            }
+            chunk
+        });
+        Body::from_stream(stream)
+    }
+
+    fn used_tokens(&mut self) -> Option<u64> {
+        if self.failed {
+            return Some(0);
+        }
+        self.reader.finish().total()
+    }
+}
+
+impl Drop for UsageRecorder {
+    fn drop(&mut self) {
+        // without a usage, e.g. if the client disconnected, the reservation
+        // is kept as is
+        let Some(used) = self.used_tokens() else {
+            tracing::debug!("no usage in response, keeping token reservation");
+            return;
+        };
+        let difference = i64::try_from(used)
+            .unwrap_or(i64::MAX)
+            .saturating_sub(i64::try_from(self.reserved).unwrap_or(i64::MAX));
+        if difference == 0 {
+            return;
+        }
+        self.limiter
+            .reconcile_in_background(std::mem::take(&mut self.key), difference);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uploaded_files_are_not_estimated() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            HeaderValue::from_static("multipart/form-data; boundary=abc123"),
+        );
+        let audio = vec![0u8; 4 * 1024 * 1024];
+        let mut body = concat!(
+            "--abc123\r\n",
+            "Content-Disposition: form-data; name=\"model\"\r\n\r\n",
+            "openai/whisper-1\r\n",
+            "--abc123\r\n",
+            "Content-Disposition: form-data; name=\"file\"; ",
+            "filename=\"speech.mp3\"\r\n",
+            "Content-Type: audio/mpeg\r\n\r\n",
+        )
+        .as_bytes()
+        .to_vec();
+        body.extend_from_slice(&audio);
+        body.extend_from_slice(b"\r\n--abc123--\r\n");
+
+        let tokens = estimated_prompt_tokens(&headers, &Bytes::from(body));
+        assert!(tokens < 20, "{tokens}");
+
+        let json = Bytes::from_static(b"{\"model\":\"openai/gpt-4o-mini\"}");
+        assert_eq!(
+            estimated_prompt_tokens(&HeaderMap::new(), &json),
+            estimate_tokens(json.len())
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum_core::body::Body;
+use backon::{BackoffBuilder, ExponentialBackoff, ExponentialBuilder};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use http::{HeaderName, HeaderValue, StatusCode, request::Parts};
+use http_body_util::BodyExt;
+use opentelemetry::KeyValue;
+use tower::ServiceExt;
+
+use crate::{
+    app_state::AppState,
+    config::{
+        retry::{RetryConfig, Strategy},
+        router::RouterConfig,
+    },
+    error::{api::ApiError, internal::InternalError},
+    types::{request::Request, response::Response, router::RouterId},
+};
+
+const RETRY_ATTEMPTS_HEADER: HeaderName =
+    HeaderName::from_static("helicone-retry-attempts");
+
+#[derive(Debug, Clone)]
+pub struct Service<S> {
+    inner: S,
+    app_state: AppState,
+    router_id: RouterId,
+    /// If `None`, retries are disabled and requests are passed through.
+    config: Option<Arc<RetryConfig>>,
+}
+
+impl<S> tower::Service<Request> for Service<S>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = ApiError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[tracing::instrument(level = "debug", name = "retry", skip_all)]
+    fn call(&mut self, req: Request) -> Self::Future {
+        // see: https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut this = self.clone();
+        std::mem::swap(self, &mut this);
+        match this.config.as_deref().map(backoff) {
+            Some(backoff) => Box::pin(this.call_with_retries(backoff, req)),
+            None => Box::pin(this.inner.call(req)),
+        }
+    }
+}
+
+impl<S> Service<S>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    async fn call_with_retries(
+        mut self,
+        mut backoff: ExponentialBackoff,
+        req: Request,
+    ) -> Result<Response, ApiError> {
+        // the body must be buffered so that it can be replayed on each attempt
+        let (parts, body) = req.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map_err(InternalError::CollectBodyError)?
+            .to_bytes();
+        let mut retries: u64 = 0;
+
+        // the inner service was already driven to readiness by our caller
+        let mut result = self.inner.call(replay(&parts, &body)).await;
+        while is_retryable(&result) {
+            let Some(delay) = backoff.next() else {
+                break;
+            };
+            tracing::debug!(
+                retries,
+                delay_ms = delay.as_millis(),
+                "retrying request"
+            );
+            tokio::time::sleep(delay).await;
+            retries += 1;
+            result =
+                self.inner.ready().await?.call(replay(&parts, &body)).await;
+        }
+
+        let status = match &result {
+            Ok(response) => response.status().as_u16().to_string(),
+            Err(_) => "error".to_string(),
+        };
+        self.app_state.0.metrics.retry_attempts.record(
+            retries,
+            &[
+                KeyValue::new("router_id", self.router_id.to_string()),
+                KeyValue::new("status", status),
+            ],
+        );
+
+        result.map(|mut response| {
+            response
+                .headers_mut()
+                .insert(RETRY_ATTEMPTS_HEADER, HeaderValue::from(retries));
+            response
+        })
+    }
+}
+
+fn replay(parts: &Parts, body: &Bytes) -> Request {
+    Request::from_parts(parts.clone(), Body::from(body.clone()))
+}
+
+/// Upstream connection errors are converted into 5xx responses by the
+/// dispatcher's error handler, so we only need to inspect the status code.
+/// Errors returned from the balancer itself are not worth replaying.
+fn is_retryable(result: &Result<Response, ApiError>) -> bool {
+    match result {
+        Ok(response) => {
+            response.status().is_server_error()
+                || response.status() == StatusCode::TOO_MANY_REQUESTS
+        }
+        Err(_) => false,
+    }
+}
+
+fn backoff(config: &RetryConfig) -> ExponentialBackoff {
+    match config.strategy {
+        Strategy::Exponential { base, max } => ExponentialBuilder::default()
+            .with_min_delay(base)
+            .with_max_delay(max)
+            .with_max_times(usize::from(config.max_retries))
+            .with_jitter()
+            .build(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Layer {
+    app_state: AppState,
+    router_id: RouterId,
+    config: Option<Arc<RetryConfig>>,
+}
+
+impl Layer {
+    #[must_use]
+    pub fn for_router(
+        app_state: AppState,
+        router_id: RouterId,
+        router_config: &RouterConfig,
+    ) -> Self {
+        let config = router_config
+            .retries
+            .as_ref()
+            .filter(|config| config.enabled)
+            .cloned()
+            .map(Arc::new);
+        Self {
+            app_state,
+            router_id,
+            config,
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for Layer {
+    type Service = Service<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Service {
+            inner,
+            app_state: self.app_state.clone(),
+            router_id: self.router_id.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn response(status: StatusCode) -> Result<Response, ApiError> {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = status;
+        Ok(response)
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        assert!(is_retryable(&response(StatusCode::INTERNAL_SERVER_ERROR)));
+        assert!(is_retryable(&response(StatusCode::BAD_GATEWAY)));
+        assert!(is_retryable(&response(StatusCode::TOO_MANY_REQUESTS)));
+        assert!(!is_retryable(&response(StatusCode::OK)));
+        assert!(!is_retryable(&response(StatusCode::BAD_REQUEST)));
+        assert!(!is_retryable(&Err(InternalError::Internal.into())));
+    }
+
+    #[test]
+    fn backoff_respects_max_retries_and_max_delay() {
+        let config = RetryConfig {
+            enabled: true,
+            max_retries: 3,
+            strategy: Strategy::Exponential {
+                base: Duration::from_millis(10),
+                max: Duration::from_millis(25),
+            },
+        };
+        let delays = backoff(&config).collect::<Vec<_>>();
+        assert_eq!(delays.len(), 3);
+        // jitter may add up to one extra `base` on top of the delay
+        assert!(delays.iter().all(|d| *d <= Duration::from_millis(50)));
+    }
+}
@@ -0,0 +1,581 @@
+//! Spend controls, which reject requests once an API key or org has spent
+//! its budget.
+//!
+//! The spend of a request is only known once its response completes, so
+//! concurrent requests may overrun a budget before it is exhausted.
+use std::{
+    sync::{Arc, LazyLock, Mutex, PoisonError},
+    task::{Context, Poll},
+};
+
+use axum_core::{body::Body, response::Response};
+use chrono::{DateTime, Datelike, Months, NaiveTime, Utc};
+use futures::{StreamExt, future::BoxFuture};
+use http::HeaderValue;
+use r2d2::Pool;
+use redis::{Client, Script};
+use rust_decimal::{Decimal, prelude::ToPrimitive};
+use rustc_hash::FxHashMap as HashMap;
+
+use crate::{
+    app_state::AppState,
+    config::{
+        providers::ModelPrice,
+        rate_limit::RateLimitStore,
+        spend::{BudgetConfig, BudgetPeriod},
+    },
+    error::{
+        api::ApiError,
+        init::InitError,
+        internal::InternalError,
+        invalid_req::{BudgetExceededError, InvalidRequestError},
+    },
+    middleware::cache::is_cache_hit,
+    types::{
+        extensions::{AuthContext, MapperContext},
+        model_id::ModelName,
+        provider::InferenceProvider,
+        request::Request,
+        usage::UsageReader,
+    },
+};
+
+/// Spend is accrued as an integer number of micro-dollars, so that it can be
+/// incremented atomically in Redis.
+const MICROS_PER_USD: i64 = 1_000_000;
+/// Rolling budgets are accrued into this many buckets across their window.
+const ROLLING_BUCKETS: i64 = 60;
+
+/// Sums the buckets of `KEYS[1]` from `ARGV[1]`, the oldest bucket in the
+/// period, deleting the older ones that have left it.
+///
+/// This is done in a script so that the spend of a rolling budget is read
+/// in a single round trip.
+static SPENT_SCRIPT: LazyLock<Script> = LazyLock::new(|| {
+    Script::new(
+        r"
+local oldest = tonumber(ARGV[1])
+local buckets = redis.call('HGETALL', KEYS[1])
+local spent = 0
+for i = 1, #buckets, 2 do
+  if tonumber(buckets[i]) < oldest then
+    redis.call('HDEL', KEYS[1], buckets[i])
+  else
+    spent = spent + tonumber(buckets[i + 1])
+  end
+end
+return spent
+",
+    )
+});
+
+#[derive(Debug, Clone)]
+enum Store {
+    /// Expired windows are pruned when the map is written to, at most once
+    /// a minute.
+    InMemory(Arc<Mutex<InMemoryState>>),
+    /// Each window is a hash of its buckets. Since the pool is blocking, it
+    /// is only used from blocking tasks.
+    Redis(Pool<Client>),
+}
+
+#[derive(Debug, Default)]
+struct InMemoryState {
+    windows: HashMap<String, Buckets>,
+    last_pruned_ms: i64,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    micros: HashMap<i64, i64>,
+    expires_at_ms: i64,
+}
+
+/// The key that the spend of a budget over its current period is accrued
+/// into.
+///
+/// Rolling periods are split into buckets, so that the spend of the oldest
+/// ones can be dropped as they leave the period, whereas calendar periods
+/// have a single bucket.
+#[derive(Debug, Clone)]
+struct Window {
+    key: String,
+    /// The bucket that spend is currently accrued into.
+    bucket: i64,
+    /// The oldest bucket that is still in the period.
+    oldest_bucket: i64,
+    expires_at_ms: i64,
+}
+
+impl Store {
+    async fn spent(
+        &self,
+        window: &Window,
+        now_ms: i64,
+    ) -> Result<i64, InternalError> {
+        match self {
+            Self::InMemory(state) => {
+                let state =
+                    state.lock().unwrap_or_else(PoisonError::into_inner);
+                Ok(state
+                    .windows
+                    .get(&window.key)
+                    .filter(|buckets| buckets.expires_at_ms > now_ms)
+                    .map_or(0, |buckets| {
+                        buckets
+                            .micros
+                            .iter()
+                            .filter(|(bucket, _)| {
+                                **bucket >= window.oldest_bucket
+                            })
+                            .map(|(_, micros)| micros)
+                            .sum()
+                    }))
+            }
+            Self::Redis(pool) => {
+                let pool = pool.clone();
+                let window = window.clone();
+                tokio::task::spawn_blocking(move || {
+                    let mut conn =
+                        pool.get().map_err(InternalError::PoolError)?;
+                    SPENT_SCRIPT
+                        .key(&window.key)
+                        .arg(window.oldest_bucket)
+                        .invoke::<i64>(&mut *conn)
+                        .map_err(InternalError::RedisError)
+                })
+                .await
+                .map_err(InternalError::SpendTaskError)?
+            }
+        }
+    }
+
+    /// Accrues `micros` into the current bucket of each window, which is
+    /// done on a blocking task for Redis so that it can be called when a
+    /// response body is dropped.
+    fn accrue(&self, windows: Vec<Window>, micros: i64, now_ms: i64) {
+        match self {
+            Self::InMemory(state) => {
+                let mut state =
+                    state.lock().unwrap_or_else(PoisonError::into_inner);
+                if now_ms - state.last_pruned_ms >= 60_000 {
+                    state
+                        .windows
+                        .retain(|_, buckets| buckets.expires_at_ms > now_ms);
+                    state.last_pruned_ms = now_ms;
+                }
+                for window in windows {
+                    let buckets = state.windows.entry(window.key).or_default();
+                    if buckets.expires_at_ms <= now_ms {
+                        buckets.micros.clear();
+                    }
+                    buckets
+                        .micros
+                        .retain(|bucket, _| *bucket >= window.oldest_bucket);
+                    *buckets.micros.entry(window.bucket).or_default() += micros;
+                    buckets.expires_at_ms =
+                        buckets.expires_at_ms.max(window.expires_at_ms);
+                }
+            }
+            Self::Redis(pool) => {
+                let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+                    tracing::error!("no runtime to accrue spend on");
+                    return;
+                };
+                let pool = pool.clone();
+                runtime.spawn_blocking(move || {
+                    if let Err(e) = accrue_in_redis(&pool, &windows, micros) {
+                        tracing::error!(error = %e, "failed to accrue spend");
+                    }
+                });
+            }
+        }
+    }
+}
+
+fn accrue_in_redis(
+    pool: &Pool<Client>,
+    windows: &[Window],
+    micros: i64,
+) -> Result<(), InternalError> {
+    let mut conn = pool.get().map_err(InternalError::PoolError)?;
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    for window in windows {
+        pipe.hincr(&window.key, window.bucket, micros)
+            .ignore()
+            .pexpire_at(&window.key, window.expires_at_ms)
+            .ignore();
+    }
+    pipe.query::<()>(&mut *conn)
+        .map_err(InternalError::RedisError)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BudgetScope {
+    ApiKey,
+    Org,
+}
+
+impl BudgetScope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::ApiKey => "per-api-key",
+            Self::Org => "per-org",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Budget {
+    scope: BudgetScope,
+    config: BudgetConfig,
+}
+
+impl Budget {
+    fn subject(&self, auth: &AuthContext) -> String {
+        match self.scope {
+            BudgetScope::ApiKey => auth.user_id.to_string(),
+            BudgetScope::Org => auth.org_id.to_string(),
+        }
+    }
+
+    /// The window that the spend over the current period is accrued into.
+    fn window(&self, subject: &str, now: DateTime<Utc>) -> Window {
+        let prefix = format!("spend:{}:{subject}", self.scope.as_str());
+        let today = now.date_naive();
+        let (key, expires_at) = match self.config.period {
+            BudgetPeriod::Daily => (
+                format!("{prefix}:{}", today.format("%Y-%m-%d")),
+                today.succ_opt().unwrap_or(today),
+            ),
+            BudgetPeriod::Monthly => {
+                let first = today.with_day(1).unwrap_or(today);
+                (
+                    format!("{prefix}:{}", first.format("%Y-%m")),
+                    first.checked_add_months(Months::new(1)).unwrap_or(first),
+                )
+            }
+            BudgetPeriod::Rolling(window) => {
+                let window_ms =
+                    i64::try_from(window.as_millis()).unwrap_or(i64::MAX);
+                let bucket_ms = (window_ms / ROLLING_BUCKETS).max(1000);
+                let bucket = now.timestamp_millis() / bucket_ms;
+                let count = (window_ms + bucket_ms - 1) / bucket_ms;
+                return Window {
+                    key: format!("{prefix}:rolling-{}", window.as_secs()),
+                    bucket,
+                    oldest_bucket: bucket - count + 1,
+                    expires_at_ms: (bucket + 1) * bucket_ms + window_ms,
+                };
+            }
+        };
+        Window {
+            key,
+            bucket: 0,
+            oldest_bucket: 0,
+            expires_at_ms: expires_at
+                .and_time(NaiveTime::MIN)
+                .and_utc()
+                .timestamp_millis(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpendControls {
+    app_state: AppState,
+    budgets: Arc<[Budget]>,
+    store: Store,
+}
+
+impl SpendControls {
+    /// Returns `None` if no budgets are configured.
+    pub fn new(app_state: &AppState) -> Result<Option<Self>, InitError> {
+        let Some(config) = &app_state.0.config.global.spend else {
+            return Ok(None);
+        };
+        let budgets: Arc<[Budget]> = [
+            (BudgetScope::ApiKey, &config.per_api_key),
+            (BudgetScope::Org, &config.per_org),
+        ]
+        .into_iter()
+        .filter_map(|(scope, budget)| {
+            budget.clone().map(|config| Budget { scope, config })
+        })
+        .collect();
+        if budgets.is_empty() {
+            return Ok(None);
+        }
+        let store = match &app_state.0.config.rate_limit_store {
+            RateLimitStore::InMemory => Store::InMemory(Arc::default()),
+            RateLimitStore::Redis(redis_config) => {
+                let client =
+                    Client::open(redis_config.host_url.expose().clone())?;
+                Store::Redis(Pool::builder().build(client)?)
+            }
+        };
+        Ok(Some(Self {
+            app_state: app_state.clone(),
+            budgets,
+            store,
+        }))
+    }
+
+    fn price(&self, response: &Response) -> Option<ModelPrice> {
+        let provider = response.extensions().get::<InferenceProvider>()?;
+        let model = response
+            .extensions()
+            .get::<MapperContext>()?
+            .model
+            .as_ref()?;
+        self.app_state
+            .0
+            .config
+            .providers
+            .get(provider)
+            .and_then(|config| {
+                config.pricing.get(&ModelName::from_model(model)).copied()
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Layer {
+    controls: Option<SpendControls>,
+}
+
+impl Layer {
+    pub fn global(app_state: &AppState) -> Result<Self, InitError> {
+        Ok(Self {
+            controls: SpendControls::new(app_state)?,
+        })
+    }
+}
+
+impl<S> tower::Layer<S> for Layer {
+    type Service = Service<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Service {
+            inner,
+            controls: self.controls.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Service<S> {
+    inner: S,
+    controls: Option<SpendControls>,
+}
+
+impl<S> tower::Service<Request> for Service<S>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Send
+        + Clone
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = ApiError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[tracing::instrument(name = "spend", skip_all)]
+    fn call(&mut self, req: Request) -> Self::Future {
+        // see: https://docs.rs/tower/latest/tower/trait.Service.html#be-careful-when-cloning-inner-services
+        let mut this = self.clone();
+        std::mem::swap(self, &mut this);
+        Box::pin(async move {
+            // budgets are per API key or org, so unauthenticated requests
+            // aren't budgeted
+            let auth = req.extensions().get::<AuthContext>().cloned();
+            match (this.controls, auth) {
+                (Some(controls), Some(auth)) => {
+                    make_request(&mut this.inner, controls, &auth, req).await
+                }
+                _ => this.inner.call(req).await,
+            }
+        })
+    }
+}
+
+async fn make_request<S>(
+    inner: &mut S,
+    controls: SpendControls,
+    auth: &AuthContext,
+    req: Request,
+) -> Result<Response, ApiError>
+where
+    S: tower::Service<Request, Response = Response, Error = ApiError>
+        + Send
+        + Clone
+        + 'static,
+    S::Future: Send + 'static,
+{
+    let now = Utc::now();
+    let now_ms = now.timestamp_millis();
+    let mut windows = Vec::with_capacity(controls.budgets.len());
+    let mut warnings = Vec::new();
+    for budget in controls.budgets.iter() {
+        let window = budget.window(&budget.subject(auth), now);
+        let spent =
+            Decimal::new(controls.store.spent(&window, now_ms).await?, 6);
+        let limit = budget.config.limit;
+        if spent >= limit {
+            return Err(ApiError::InvalidRequest(
+                InvalidRequestError::BudgetExceeded(BudgetExceededError {
+                    scope: budget.scope.as_str(),
+                    period: budget.config.period.to_string(),
+                    limit,
+                }),
+            ));
+        }
+        if let Some(soft_limit) = budget.config.soft_limit
+            && spent >= limit * soft_limit
+        {
+            warnings.push(format!(
+                "{} {} budget is {}% spent, ${} of ${limit} remaining",
+                budget.config.period,
+                budget.scope.as_str(),
+                (spent / limit * Decimal::ONE_HUNDRED).round(),
+                (limit - spent).round_dp(2),
+            ));
+        }
+        windows.push(window);
+    }
+
+    let mut response = inner.call(req).await?;
+    for warning in warnings {
+        if let Ok(value) = HeaderValue::from_str(&warning) {
+            response.headers_mut().append("x-budget-warning", value);
+        }
+    }
+    // providers don't charge for failed requests or responses served from
+    // the cache, and requests to models without a price are free
+    if !response.status().is_success() || is_cache_hit(response.headers()) {
+        return Ok(response);
+    }
+    let Some(price) = controls.price(&response) else {
+        return Ok(response);
+    };
+    let recorder = SpendRecorder {
+        store: controls.store,
+        windows,
+        price,
+        reader: UsageReader::for_response(&response),
+    };
+    Ok(response.map(|body| recorder.wrap(body)))
+}
+
+/// Reads the `usage` from a response body as it is sent to the client, and
+/// accrues its cost when the body is dropped.
+struct SpendRecorder {
+    store: Store,
+    windows: Vec<Window>,
+    price: ModelPrice,
+    reader: UsageReader,
+}
+
+impl SpendRecorder {
+    fn wrap(mut self, body: Body) -> Body {
+        let stream = body.into_data_stream().map(move |chunk| {
+            if let Ok(bytes) = &chunk {
+                self.reader.record(bytes);
+            }
+            chunk
+        });
+        Body::from_stream(stream)
+    }
+}
+
+impl Drop for SpendRecorder {
+    fn drop(&mut self) {
+        let usage = self.reader.finish();
+        if usage.total().is_none() {
+            tracing::debug!("no usage in response, not accruing spend");
+            return;
+        }
+        let cost = self
+            .price
+            .cost(usage.input.unwrap_or(0), usage.output.unwrap_or(0));
+        let Some(micros) = (cost * Decimal::from(MICROS_PER_USD))
+            .round()
+            .to_i64()
+            .filter(|micros| *micros > 0)
+        else {
+            return;
+        };
+        self.store.accrue(
+            std::mem::take(&mut self.windows),
+            micros,
+            Utc::now().timestamp_millis(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn budget(period: BudgetPeriod) -> Budget {
+        Budget {
+            scope: BudgetScope::Org,
+            config: BudgetConfig {
+                limit: Decimal::ONE_HUNDRED,
+                period,
+                soft_limit: None,
+            },
+        }
+    }
+
+    #[test]
+    fn windows_cover_the_current_period() {
+        let now = Utc.with_ymd_and_hms(2025, 12, 31, 18, 30, 0).unwrap();
+        let next_day = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+        let window = budget(BudgetPeriod::Daily).window("org", now);
+        assert_eq!(window.key, "spend:per-org:org:2025-12-31");
+        assert_eq!(window.expires_at_ms, next_day.timestamp_millis());
+
+        let window = budget(BudgetPeriod::Monthly).window("org", now);
+        assert_eq!(window.key, "spend:per-org:org:2025-12");
+        assert_eq!(window.expires_at_ms, next_day.timestamp_millis());
+
+        let period = Duration::from_secs(60 * 60);
+        let window = budget(BudgetPeriod::Rolling(period)).window("org", now);
+        let current = now.timestamp_millis() / 60_000;
+        assert_eq!(window.key, "spend:per-org:org:rolling-3600");
+        assert_eq!(window.bucket, current);
+        assert_eq!(window.oldest_bucket, current - 59);
+        assert_eq!(window.expires_at_ms, (current + 1) * 60_000 + 3_600_000);
+    }
+
+    #[tokio::test]
+    async fn in_memory_spend_leaves_with_its_buckets() {
+        let store = Store::InMemory(Arc::default());
+        let window = |bucket, oldest_bucket| Window {
+            key: "key".to_string(),
+            bucket,
+            oldest_bucket,
+            expires_at_ms: 1_000,
+        };
+        store.accrue(vec![window(1, 0)], 5, 0);
+        store.accrue(vec![window(2, 0)], 7, 0);
+        assert_eq!(store.spent(&window(2, 0), 500).await.unwrap(), 12);
+        assert_eq!(store.spent(&window(2, 2), 500).await.unwrap(), 7);
+        assert_eq!(store.spent(&window(2, 0), 1_500).await.unwrap(), 0);
+    }
+}
@@ -5,7 +5,9 @@ use std::{
     task::{Context, Poll},
 };
 
+use compact_str::CompactString;
 use http::uri::PathAndQuery;
+use indexmap::IndexMap;
 use pin_project_lite::pin_project;
 use rustc_hash::FxHashMap as HashMap;
 use tower::{ServiceBuilder, buffer, util::BoxCloneService};
@@ -13,17 +15,23 @@ use tower::{ServiceBuilder, buffer, util::BoxCloneService};
 use crate::{
     app::BUFFER_SIZE,
     app_state::AppState,
-    balancer::provider::ProviderBalancer,
-    config::{DeploymentTarget, SDK},
+    balancer::{model::ModelBalancer, provider::ProviderBalancer},
+    config::{
+        DeploymentTarget, SDK, balance::BalanceConfig, monitor::HealthScope,
+        router::RouterConfig,
+    },
+    discover::provider::BalancerProviders,
     dispatcher::Dispatcher,
     endpoints::{ApiEndpoint, EndpointType},
     error::{
         api::ApiError, init::InitError, internal::InternalError,
         invalid_req::InvalidRequestError,
     },
-    middleware::{cache::CacheLayer, rate_limit, request_context},
+    middleware::{
+        cache::CacheLayer, fallback, hedge, rate_limit, request_context, retry,
+    },
     router::direct::DirectProxyService,
-    types::router::RouterId,
+    types::router::{BalancerId, RouterId},
     utils::handle_error::ErrorHandlerLayer,
 };
 
@@ -74,6 +82,11 @@ impl Router {
         )
         .await?;
         let cache_layer = CacheLayer::for_router(&app_state, &id)?;
+        let retry_layer = retry::Layer::for_router(
+            app_state.clone(),
+            id.clone(),
+            &router_config,
+        );
         let request_context_layer = request_context::Layer::for_router(
             router_config.clone(),
             provider_keys.clone(),
@@ -81,23 +94,70 @@ impl Router {
         for (endpoint_type, balance_config) in
             router_config.load_balance.as_ref()
         {
+            let balancer_id = BalancerId::new(id.clone(), *endpoint_type);
+            let discovered = BalancerProviders::new(&router_config);
+            let fallback_layer = fallback::Layer::for_router(
+                &app_state,
+                &router_config,
+                balance_config,
+                &discovered,
+                &request_context_layer,
+            );
+            let hedge_layer = hedge::Layer::for_router(
+                &app_state,
+                &router_config,
+                balance_config,
+                &discovered,
+                &request_context_layer,
+            );
+            let observed =
+                observes_providers(&router_config).then_some(&discovered);
             let balancer = ProviderBalancer::new(
                 app_state.clone(),
-                id.clone(),
+                balancer_id,
                 router_config.clone(),
                 balance_config,
+                observed
+                    .and_then(|discovered| discovered.get(None))
+                    .cloned(),
             )
             .await?;
             let service_stack = ServiceBuilder::new()
                 .layer(cache_layer.clone())
                 .layer(ErrorHandlerLayer::new(app_state.clone()))
                 .layer(rl_layer.clone())
-                .map_err(|e| ApiError::from(InternalError::BufferError(e)))
+                .layer(retry_layer.clone())
+                .layer(fallback_layer.clone())
+                .layer(hedge_layer)
+                .map_err(ApiError::from_buffer_error)
                 .layer(buffer::BufferLayer::new(BUFFER_SIZE))
-                .layer(request_context_layer.clone())
-                .service(balancer);
+                .layer(request_context_layer.clone());
 
-            inner.insert(*endpoint_type, BoxCloneService::new(service_stack));
+            // the model balancer also reads each request's model, which is
+            // needed to skip models that are unhealthy
+            let model_scoped =
+                app_state.config().discover.monitor.scope == HealthScope::Model;
+            let service = if router_config.model_load_balance.is_empty()
+                && !model_scoped
+            {
+                BoxCloneService::new(service_stack.service(balancer))
+            } else {
+                let model_balancers = Self::model_balancers(
+                    &app_state,
+                    &id,
+                    &router_config,
+                    *endpoint_type,
+                    observed,
+                )
+                .await?;
+                let balancer = ModelBalancer::new(
+                    balancer,
+                    model_balancers,
+                    router_config.clone(),
+                );
+                BoxCloneService::new(service_stack.service(balancer))
+            };
+            inner.insert(*endpoint_type, service);
         }
         let direct_proxy_dispatcher =
             Dispatcher::new_direct_proxy(app_state.clone(), SDK)?;
@@ -115,6 +175,60 @@ impl Router {
             direct_proxy,
         })
     }
+
+    /// Creates a balancer with its own provider discovery for each model
+    /// group.
+    async fn model_balancers(
+        app_state: &AppState,
+        id: &RouterId,
+        router_config: &Arc<RouterConfig>,
+        endpoint_type: EndpointType,
+        observed: Option<&BalancerProviders>,
+    ) -> Result<IndexMap<CompactString, ProviderBalancer>, InitError> {
+        let mut balancers = IndexMap::new();
+        for (group, config) in router_config.model_load_balance.as_ref() {
+            // discovery and the monitors read the balance config for the
+            // endpoint type from the router config
+            let group_router_config = Arc::new(RouterConfig {
+                load_balance: BalanceConfig::from(
+                    std::collections::HashMap::from([(
+                        endpoint_type,
+                        config.balance.clone(),
+                    )]),
+                ),
+                ..RouterConfig::clone(router_config)
+            });
+            let balancer = ProviderBalancer::new(
+                app_state.clone(),
+                BalancerId::for_model_group(
+                    id.clone(),
+                    endpoint_type,
+                    group.clone(),
+                ),
+                group_router_config,
+                &config.balance,
+                observed
+                    .and_then(|discovered| discovered.get(Some(group)))
+                    .cloned(),
+            )
+            .await?;
+            balancers.insert(group.clone(), balancer);
+        }
+        Ok(balancers)
+    }
+}
+
+/// Whether the router's fallback or hedge layers send requests to the
+/// providers its balancers discover.
+fn observes_providers(router_config: &RouterConfig) -> bool {
+    router_config
+        .fallback
+        .as_ref()
+        .is_some_and(|config| config.enabled)
+        || router_config
+            .hedge
+            .as_ref()
+            .is_some_and(|config| config.enabled)
 }
 
 impl tower::Service<crate::types::request::Request> for Router {
@@ -10,6 +10,7 @@ use futures::ready;
 use http::uri::PathAndQuery;
 use http_body_util::{BodyExt, combinators::Collect};
 use pin_project_lite::pin_project;
+use serde::Deserialize;
 use tower::Service as _;
 
 use crate::{
@@ -112,6 +113,14 @@ impl ResponseFuture {
 
 pub enum UnifiedApi {
     ChatCompletions(),
+    Responses(),
+}
+
+/// The rest of the request body is deserialized by the mapper, we only need
+/// the model here to determine the provider.
+#[derive(Debug, Deserialize)]
+struct UnifiedApiRequest {
+    model: String,
 }
 
 impl TryFrom<&str> for UnifiedApi {
@@ -119,6 +128,7 @@ impl TryFrom<&str> for UnifiedApi {
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
             "/chat/completions" => Ok(Self::ChatCompletions()),
+            "/responses" => Ok(Self::Responses()),
             _ => {
                 Err(InvalidRequestError::UnsupportedEndpoint(value.to_string()))
             }
@@ -168,14 +178,18 @@ impl Future for ResponseFuture {
                             // in order to
                             // deserialize it and extract the model
                             // id (in order to know the appropriate
-                            // provider), we can only support OpenAI chat
-                            // completions
-                            // as the endpoint for now.
+                            // provider), we can only support OpenAI
+                            // endpoints for now.
 
                             parts.extensions.insert(ApiEndpoint::OpenAI(
                                 OpenAI::chat_completions(),
                             ));
                         }
+                        UnifiedApi::Responses() => {
+                            parts.extensions.insert(ApiEndpoint::OpenAI(
+                                OpenAI::responses(),
+                            ));
+                        }
                     }
 
                     this.state.set(State::DetermineProvider {
@@ -190,10 +204,9 @@ impl Future for ResponseFuture {
                     let body = collected_body
                         .take()
                         .expect("future polled after completion");
-                    let deserialized_body = serde_json::from_slice::<
-                        async_openai::types::CreateChatCompletionRequest,
-                    >(&body)
-                    .map_err(InvalidRequestError::InvalidRequestBody)?;
+                    let deserialized_body =
+                        serde_json::from_slice::<UnifiedApiRequest>(&body)
+                            .map_err(InvalidRequestError::InvalidRequestBody)?;
                     let source_model =
                         ModelId::from_str(&deserialized_body.model)
                             .map_err(InternalError::MapperError)?;
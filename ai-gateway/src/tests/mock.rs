@@ -28,6 +28,14 @@ pub struct MockArgs {
     pub global_ollama_latency: Option<u64>,
     #[builder(setter(strip_option), default = None)]
     pub global_bedrock_latency: Option<u64>,
+    #[builder(setter(strip_option), default = None)]
+    pub global_azure_latency: Option<u64>,
+    #[builder(setter(strip_option), default = None)]
+    pub global_mistral_latency: Option<u64>,
+    #[builder(setter(strip_option), default = None)]
+    pub global_cohere_latency: Option<u64>,
+    #[builder(setter(strip_option), default = None)]
+    pub global_vertex_latency: Option<u64>,
 
     #[builder(setter(strip_option), default = None)]
     pub openai_port: Option<u16>,
@@ -40,6 +48,14 @@ pub struct MockArgs {
     #[builder(setter(strip_option), default = None)]
     pub bedrock_port: Option<u16>,
     #[builder(setter(strip_option), default = None)]
+    pub azure_port: Option<u16>,
+    #[builder(setter(strip_option), default = None)]
+    pub mistral_port: Option<u16>,
+    #[builder(setter(strip_option), default = None)]
+    pub cohere_port: Option<u16>,
+    #[builder(setter(strip_option), default = None)]
+    pub vertex_port: Option<u16>,
+    #[builder(setter(strip_option), default = None)]
     pub minio_port: Option<u16>,
     #[builder(setter(strip_option), default = None)]
     pub jawn_port: Option<u16>,
@@ -58,6 +74,10 @@ pub struct Mock {
     pub google_mock: Stubr,
     pub ollama_mock: Stubr,
     pub bedrock_mock: Stubr,
+    pub azure_mock: Stubr,
+    pub mistral_mock: Stubr,
+    pub cohere_mock: Stubr,
+    pub vertex_mock: Stubr,
     pub minio_mock: Stubr,
     pub jawn_mock: Stubr,
     args: MockArgs,
@@ -139,6 +159,65 @@ impl Mock {
             .unwrap()
             .base_url = Url::parse(&bedrock_mock.uri()).unwrap();
 
+        let azure_mock = start_mock_for_test(
+            &get_stubs_path("azure"),
+            args.global_azure_latency,
+            args.stubs.as_ref(),
+            args.verify,
+            args.azure_port,
+        )
+        .await;
+        config
+            .providers
+            .get_mut(&InferenceProvider::Azure)
+            .unwrap()
+            .base_url = Url::parse(&azure_mock.uri()).unwrap();
+
+        let mistral_mock = start_mock_for_test(
+            &get_stubs_path("mistral"),
+            args.global_mistral_latency,
+            args.stubs.as_ref(),
+            args.verify,
+            args.mistral_port,
+        )
+        .await;
+        config
+            .providers
+            .get_mut(&InferenceProvider::Mistral)
+            .unwrap()
+            .base_url = Url::parse(&mistral_mock.uri()).unwrap();
+
+        let cohere_mock = start_mock_for_test(
+            &get_stubs_path("cohere"),
+            args.global_cohere_latency,
+            args.stubs.as_ref(),
+            args.verify,
+            args.cohere_port,
+        )
+        .await;
+        config
+            .providers
+            .get_mut(&InferenceProvider::Cohere)
+            .unwrap()
+            .base_url = Url::parse(&cohere_mock.uri()).unwrap();
+
+        let vertex_mock = start_mock_for_test(
+            &get_stubs_path("vertex"),
+            args.global_vertex_latency,
+            args.stubs.as_ref(),
+            args.verify,
+            args.vertex_port,
+        )
+        .await;
+        let vertex_config = config
+            .providers
+            .get_mut(&InferenceProvider::GoogleVertex)
+            .unwrap();
+        vertex_config.base_url = Url::parse(&vertex_mock.uri()).unwrap();
+        // access tokens are minted by the mock too
+        vertex_config.token_url =
+            Some(Url::parse(&format!("{}/token", vertex_mock.uri())).unwrap());
+
         let minio_mock = start_mock_for_test(
             &get_stubs_path("minio"),
             None,
@@ -168,6 +247,10 @@ impl Mock {
             google_mock,
             ollama_mock,
             bedrock_mock,
+            azure_mock,
+            mistral_mock,
+            cohere_mock,
+            vertex_mock,
             minio_mock,
             jawn_mock,
             args,
@@ -224,6 +307,46 @@ impl Mock {
         )
         .await;
 
+        let azure_mock = start_mock(
+            &get_stubs_path("azure"),
+            args.global_azure_latency,
+            args.stubs.as_ref(),
+            false,
+            false,
+            args.azure_port,
+        )
+        .await;
+
+        let mistral_mock = start_mock(
+            &get_stubs_path("mistral"),
+            args.global_mistral_latency,
+            args.stubs.as_ref(),
+            false,
+            false,
+            args.mistral_port,
+        )
+        .await;
+
+        let cohere_mock = start_mock(
+            &get_stubs_path("cohere"),
+            args.global_cohere_latency,
+            args.stubs.as_ref(),
+            false,
+            false,
+            args.cohere_port,
+        )
+        .await;
+
+        let vertex_mock = start_mock(
+            &get_stubs_path("vertex"),
+            args.global_vertex_latency,
+            args.stubs.as_ref(),
+            false,
+            false,
+            args.vertex_port,
+        )
+        .await;
+
         let minio_mock = start_mock(
             &get_stubs_path("minio"),
             None,
@@ -250,6 +373,10 @@ impl Mock {
             google_mock,
             ollama_mock,
             bedrock_mock,
+            azure_mock,
+            mistral_mock,
+            cohere_mock,
+            vertex_mock,
             minio_mock,
             jawn_mock,
             args,
@@ -262,6 +389,10 @@ impl Mock {
         self.google_mock.http_server.verify().await;
         self.ollama_mock.http_server.verify().await;
         self.bedrock_mock.http_server.verify().await;
+        self.azure_mock.http_server.verify().await;
+        self.mistral_mock.http_server.verify().await;
+        self.cohere_mock.http_server.verify().await;
+        self.vertex_mock.http_server.verify().await;
         self.minio_mock.http_server.verify().await;
         self.jawn_mock.http_server.verify().await;
     }
@@ -272,6 +403,10 @@ impl Mock {
         self.google_mock.http_server.reset().await;
         self.ollama_mock.http_server.reset().await;
         self.bedrock_mock.http_server.reset().await;
+        self.azure_mock.http_server.reset().await;
+        self.mistral_mock.http_server.reset().await;
+        self.cohere_mock.http_server.reset().await;
+        self.vertex_mock.http_server.reset().await;
         self.minio_mock.http_server.reset().await;
         self.jawn_mock.http_server.reset().await;
     }
@@ -322,6 +457,42 @@ impl Mock {
         )
         .await;
 
+        register_stubs_for_mock(
+            &self.azure_mock,
+            &get_stubs_path("azure"),
+            self.args.global_azure_latency,
+            &stubs,
+            self.args.verify,
+        )
+        .await;
+
+        register_stubs_for_mock(
+            &self.mistral_mock,
+            &get_stubs_path("mistral"),
+            self.args.global_mistral_latency,
+            &stubs,
+            self.args.verify,
+        )
+        .await;
+
+        register_stubs_for_mock(
+            &self.cohere_mock,
+            &get_stubs_path("cohere"),
+            self.args.global_cohere_latency,
+            &stubs,
+            self.args.verify,
+        )
+        .await;
+
+        register_stubs_for_mock(
+            &self.vertex_mock,
+            &get_stubs_path("vertex"),
+            self.args.global_vertex_latency,
+            &stubs,
+            self.args.verify,
+        )
+        .await;
+
         register_stubs_for_mock(
             &self.minio_mock,
             &get_stubs_path("minio"),
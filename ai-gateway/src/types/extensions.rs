@@ -1,9 +1,13 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use derive_more::{AsRef, From, Into};
+use tokio::sync::Notify;
 
 use super::{
-    model_id::ModelId, org::OrgId, provider::ProviderKeys, user::UserId,
+    model_id::ModelId,
+    org::OrgId,
+    provider::{InferenceProvider, ProviderKeys},
+    user::UserId,
 };
 use crate::{config::router::RouterConfig, types::secret::Secret};
 
@@ -36,3 +40,54 @@ pub struct MapperContext {
     /// models.
     pub model: Option<ModelId>,
 }
+
+/// Set to the provider a request is dispatched to, for middleware that needs
+/// to know it before the response arrives.
+#[derive(Debug, Clone, Default)]
+pub struct SelectedProvider(Arc<SelectedProviderInner>);
+
+#[derive(Debug, Default)]
+struct SelectedProviderInner {
+    provider: OnceLock<InferenceProvider>,
+    notify: Notify,
+}
+
+impl SelectedProvider {
+    pub fn set(&self, provider: InferenceProvider) {
+        // a request is only dispatched once, so this is only set once
+        if self.0.provider.set(provider).is_ok() {
+            self.0.notify.notify_waiters();
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self) -> Option<&InferenceProvider> {
+        self.0.provider.get()
+    }
+
+    /// Waits until the request is dispatched, e.g. if it is queued for a
+    /// provider.
+    pub async fn wait(&self) -> &InferenceProvider {
+        loop {
+            // created before checking so that a provider set in between
+            // isn't missed
+            let notified = self.0.notify.notified();
+            if let Some(provider) = self.get() {
+                return provider;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Providers that balancers should skip for a request, e.g. because they're
+/// unhealthy for its model, unless every provider they could use is skipped.
+#[derive(Debug, Clone, Default)]
+pub struct ExcludedProviders(pub Vec<InferenceProvider>);
+
+impl ExcludedProviders {
+    #[must_use]
+    pub fn contains(&self, provider: &InferenceProvider) -> bool {
+        self.0.contains(provider)
+    }
+}
@@ -4,6 +4,7 @@ pub mod extensions;
 pub mod json;
 pub mod logger;
 pub mod model_id;
+pub mod multipart;
 pub mod org;
 pub mod provider;
 pub mod rate_limit;
@@ -11,4 +12,5 @@ pub mod request;
 pub mod response;
 pub mod router;
 pub mod secret;
+pub mod usage;
 pub mod user;
@@ -9,7 +9,9 @@ use derive_more::AsRef;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use super::provider::InferenceProvider;
-use crate::error::mapper::MapperError;
+use crate::{
+    endpoints::vertex::VERTEX_MODEL_PREFIX, error::mapper::MapperError,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Version {
@@ -186,6 +188,37 @@ impl ModelId {
                     id: model_with_version,
                 })
             }
+            InferenceProvider::GoogleVertex => {
+                // Vertex AI's `OpenAI` compatible endpoint expects models
+                // to be prefixed with their publisher
+                let model = s.strip_prefix(VERTEX_MODEL_PREFIX).unwrap_or(s);
+                let model_with_version = ModelIdWithVersion::from_str(model)?;
+                Ok(ModelId::ModelIdWithVersion {
+                    provider: InferenceProvider::GoogleVertex,
+                    id: model_with_version,
+                })
+            }
+            InferenceProvider::Azure => {
+                let model_with_version = ModelIdWithVersion::from_str(s)?;
+                Ok(ModelId::ModelIdWithVersion {
+                    provider: InferenceProvider::Azure,
+                    id: model_with_version,
+                })
+            }
+            InferenceProvider::Mistral => {
+                let model_with_version = ModelIdWithVersion::from_str(s)?;
+                Ok(ModelId::ModelIdWithVersion {
+                    provider: InferenceProvider::Mistral,
+                    id: model_with_version,
+                })
+            }
+            InferenceProvider::Cohere => {
+                let model_with_version = ModelIdWithVersion::from_str(s)?;
+                Ok(ModelId::ModelIdWithVersion {
+                    provider: InferenceProvider::Cohere,
+                    id: model_with_version,
+                })
+            }
             InferenceProvider::Named(name) => {
                 let model_with_version = ModelIdWithVersion::from_str(s)?;
                 Ok(ModelId::ModelIdWithVersion {
@@ -0,0 +1,235 @@
+use std::ops::Range;
+
+use bytes::{Bytes, BytesMut};
+use http::HeaderMap;
+
+/// A `multipart/form-data` request body, e.g. an audio upload.
+///
+/// We only ever need to read or rewrite the text fields (such as `model`),
+/// so rather than fully decoding the form, we index the parts and forward
+/// file contents untouched.
+#[derive(Debug, Clone)]
+pub struct MultipartForm {
+    boundary: String,
+    body: Bytes,
+}
+
+#[derive(Debug)]
+struct Part {
+    name: String,
+    filename: Option<String>,
+    value: Range<usize>,
+}
+
+impl MultipartForm {
+    /// Returns `None` if the request is not `multipart/form-data`.
+    #[must_use]
+    pub fn new(headers: &HeaderMap, body: Bytes) -> Option<Self> {
+        let content_type: mime::Mime = headers
+            .get(http::header::CONTENT_TYPE)?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()?;
+        if content_type.type_() != mime::MULTIPART
+            || content_type.subtype() != mime::FORM_DATA
+        {
+            return None;
+        }
+        let boundary = content_type
+            .get_param(mime::BOUNDARY)?
+            .as_str()
+            .trim_matches('"')
+            .to_string();
+        Some(Self { boundary, body })
+    }
+
+    #[must_use]
+    pub fn text_field(&self, name: &str) -> Option<&str> {
+        let part = self
+            .parts()
+            .into_iter()
+            .find(|part| part.name == name && part.filename.is_none())?;
+        std::str::from_utf8(&self.body[part.value]).ok()
+    }
+
+    /// Replaces the value of an existing text field, returning `false` if the
+    /// form has no such field.
+    pub fn set_text_field(&mut self, name: &str, value: &str) -> bool {
+        let Some(part) = self
+            .parts()
+            .into_iter()
+            .find(|part| part.name == name && part.filename.is_none())
+        else {
+            return false;
+        };
+        let mut body = BytesMut::with_capacity(
+            self.body.len() - part.value.len() + value.len(),
+        );
+        body.extend_from_slice(&self.body[..part.value.start]);
+        body.extend_from_slice(value.as_bytes());
+        body.extend_from_slice(&self.body[part.value.end..]);
+        self.body = body.freeze();
+        true
+    }
+
+    /// A JSON representation of the form for logging, with file parts
+    /// replaced by their filename.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        let fields = self
+            .parts()
+            .into_iter()
+            .map(|part| {
+                let value = match part.filename {
+                    Some(filename) => filename,
+                    None => String::from_utf8_lossy(&self.body[part.value])
+                        .into_owned(),
+                };
+                (part.name, serde_json::Value::String(value))
+            })
+            .collect();
+        serde_json::Value::Object(fields)
+    }
+
+    #[must_use]
+    pub fn into_body(self) -> Bytes {
+        self.body
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        let delimiter = format!("--{}", self.boundary);
+        let delimiter = delimiter.as_bytes();
+        let body = self.body.as_ref();
+        let mut parts = Vec::new();
+        let Some(mut cursor) = find(body, delimiter, 0) else {
+            return parts;
+        };
+        loop {
+            cursor += delimiter.len();
+            // the final delimiter is suffixed with `--`
+            if body[cursor..].starts_with(b"--") {
+                break;
+            }
+            let Some(headers_start) = find(body, b"\r\n", cursor) else {
+                break;
+            };
+            let headers_start = headers_start + 2;
+            let Some(headers_end) = find(body, b"\r\n\r\n", headers_start)
+            else {
+                break;
+            };
+            let value_start = headers_end + 4;
+            // the CRLF preceding a delimiter belongs to the delimiter
+            let Some(next) = find(body, delimiter, value_start) else {
+                break;
+            };
+            let value_end = next.saturating_sub(2).max(value_start);
+
+            let headers =
+                String::from_utf8_lossy(&body[headers_start..headers_end]);
+            if let Some((name, filename)) = content_disposition(&headers) {
+                parts.push(Part {
+                    name,
+                    filename,
+                    value: value_start..value_end,
+                });
+            }
+            cursor = next;
+        }
+        parts
+    }
+}
+
+/// Parses the `name` and `filename` parameters of a part's
+/// `Content-Disposition` header.
+fn content_disposition(headers: &str) -> Option<(String, Option<String>)> {
+    let disposition = headers.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("content-disposition")
+            .then_some(value)
+    })?;
+    let mut name = None;
+    let mut filename = None;
+    for param in disposition.split(';').skip(1) {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"').to_string();
+        match key.trim() {
+            "name" => name = Some(value),
+            "filename" => filename = Some(value),
+            _ => {}
+        }
+    }
+    Some((name?, filename))
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|pos| pos + from)
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn form() -> MultipartForm {
+        let body = concat!(
+            "--abc123\r\n",
+            "Content-Disposition: form-data; name=\"model\"\r\n\r\n",
+            "openai/whisper-1\r\n",
+            "--abc123\r\n",
+            "Content-Disposition: form-data; name=\"file\"; ",
+            "filename=\"speech.mp3\"\r\n",
+            "Content-Type: audio/mpeg\r\n\r\n",
+            "\x00\x01binary\r\n",
+            "--abc123--\r\n",
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            HeaderValue::from_static("multipart/form-data; boundary=abc123"),
+        );
+        MultipartForm::new(&headers, Bytes::from(body)).unwrap()
+    }
+
+    #[test]
+    fn reads_text_fields() {
+        let form = form();
+        assert_eq!(form.text_field("model"), Some("openai/whisper-1"));
+        // file parts are not text fields
+        assert_eq!(form.text_field("file"), None);
+        assert_eq!(form.text_field("language"), None);
+    }
+
+    #[test]
+    fn rewrites_text_field_without_touching_files() {
+        let mut form = form();
+        assert!(form.set_text_field("model", "whisper-1"));
+        assert!(!form.set_text_field("language", "en"));
+        assert_eq!(form.text_field("model"), Some("whisper-1"));
+        assert_eq!(
+            form.to_json(),
+            serde_json::json!({ "model": "whisper-1", "file": "speech.mp3" })
+        );
+        let body = form.into_body();
+        assert!(body.ends_with(b"\x00\x01binary\r\n--abc123--\r\n"));
+    }
+
+    #[test]
+    fn ignores_non_multipart_requests() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        assert!(MultipartForm::new(&headers, Bytes::new()).is_none());
+    }
+}
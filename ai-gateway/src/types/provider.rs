@@ -7,10 +7,7 @@ use strum::{EnumIter, IntoEnumIterator};
 
 use super::secret::Secret;
 use crate::{
-    config::{
-        SDK, balance::BalanceConfig, providers::ProvidersConfig,
-        router::RouterConfig,
-    },
+    config::{SDK, providers::ProvidersConfig, router::RouterConfig},
     endpoints::ApiEndpoint,
     error::provider::ProviderError,
 };
@@ -77,6 +74,11 @@ pub enum InferenceProvider {
     Ollama,
     #[serde(rename = "gemini")]
     GoogleGemini,
+    #[serde(rename = "vertex")]
+    GoogleVertex,
+    Azure,
+    Mistral,
+    Cohere,
     #[serde(untagged)]
     Named(CompactString),
 }
@@ -100,6 +102,11 @@ impl InferenceProvider {
                     .map(ApiEndpoint::Google)
                     .collect()
             }
+            InferenceProvider::GoogleVertex => {
+                crate::endpoints::vertex::Vertex::iter()
+                    .map(ApiEndpoint::Vertex)
+                    .collect()
+            }
             InferenceProvider::Ollama => {
                 crate::endpoints::ollama::Ollama::iter()
                     .map(ApiEndpoint::Ollama)
@@ -110,7 +117,34 @@ impl InferenceProvider {
                     .map(ApiEndpoint::Bedrock)
                     .collect()
             }
-            InferenceProvider::Named(_name) => Vec::new(),
+            InferenceProvider::Azure => crate::endpoints::azure::Azure::iter()
+                .map(ApiEndpoint::Azure)
+                .collect(),
+            InferenceProvider::Mistral => {
+                crate::endpoints::mistral::Mistral::iter()
+                    .map(ApiEndpoint::Mistral)
+                    .collect()
+            }
+            InferenceProvider::Cohere => {
+                crate::endpoints::cohere::Cohere::iter()
+                    .map(ApiEndpoint::Cohere)
+                    .collect()
+            }
+            // the Responses API is served via chat completions
+            InferenceProvider::Named(_) => {
+                crate::endpoints::openai::OpenAI::iter()
+                    .filter(|endpoint| {
+                        !matches!(
+                            endpoint,
+                            crate::endpoints::openai::OpenAI::Responses(_)
+                        )
+                    })
+                    .map(|openai_endpoint| ApiEndpoint::OpenAICompatible {
+                        provider: self.clone(),
+                        openai_endpoint,
+                    })
+                    .collect()
+            }
         }
     }
 }
@@ -127,6 +161,10 @@ impl FromStr for InferenceProvider {
             "bedrock" => Ok(InferenceProvider::Bedrock),
             "ollama" => Ok(InferenceProvider::Ollama),
             "gemini" => Ok(InferenceProvider::GoogleGemini),
+            "vertex" => Ok(InferenceProvider::GoogleVertex),
+            "azure" => Ok(InferenceProvider::Azure),
+            "mistral" => Ok(InferenceProvider::Mistral),
+            "cohere" => Ok(InferenceProvider::Cohere),
             s => Ok(InferenceProvider::Named(s.into())),
         }
     }
@@ -141,6 +179,10 @@ impl AsRef<str> for InferenceProvider {
             InferenceProvider::Bedrock => "bedrock",
             InferenceProvider::Ollama => "ollama",
             InferenceProvider::GoogleGemini => "gemini",
+            InferenceProvider::GoogleVertex => "vertex",
+            InferenceProvider::Azure => "azure",
+            InferenceProvider::Mistral => "mistral",
+            InferenceProvider::Cohere => "cohere",
         }
     }
 }
@@ -154,6 +196,32 @@ impl std::fmt::Display for InferenceProvider {
     }
 }
 
+/// One of a provider's regional deployments, e.g. `us-east-1` for Bedrock
+/// or the name of an Azure `OpenAI` resource.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Region(CompactString);
+
+impl Region {
+    /// The suffix appended to the provider's key environment variables to
+    /// look up keys for this region, e.g. `US_EAST_1`.
+    fn env_suffix(&self) -> String {
+        self.0.to_uppercase().replace('-', "_")
+    }
+}
+
+impl AsRef<str> for Region {
+    fn as_ref(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ProviderKey {
     Secret(Secret<String>),
@@ -188,10 +256,31 @@ impl ProviderKey {
 
     #[must_use]
     pub fn from_env(provider: &InferenceProvider) -> Option<Self> {
+        Self::from_env_with_suffix(provider, "")
+    }
+
+    /// Looks up a key for one of the provider's regions, e.g.
+    /// `AWS_ACCESS_KEY_US_WEST_2` and `AWS_SECRET_KEY_US_WEST_2` for Bedrock's
+    /// `us-west-2` region.
+    #[must_use]
+    pub fn from_env_for_region(
+        provider: &InferenceProvider,
+        region: &Region,
+    ) -> Option<Self> {
+        Self::from_env_with_suffix(
+            provider,
+            &format!("_{}", region.env_suffix()),
+        )
+    }
+
+    fn from_env_with_suffix(
+        provider: &InferenceProvider,
+        suffix: &str,
+    ) -> Option<Self> {
         if *provider == InferenceProvider::Bedrock {
             if let (Ok(access_key), Ok(secret_key)) = (
-                std::env::var("AWS_ACCESS_KEY"),
-                std::env::var("AWS_SECRET_KEY"),
+                std::env::var(format!("AWS_ACCESS_KEY{suffix}")),
+                std::env::var(format!("AWS_SECRET_KEY{suffix}")),
             ) {
                 Some(ProviderKey::AwsCredentials {
                     access_key: Secret::from(access_key),
@@ -200,9 +289,24 @@ impl ProviderKey {
             } else {
                 None
             }
+        } else if *provider == InferenceProvider::GoogleVertex {
+            // the path to a service account key, as used by Google's SDKs
+            let path = std::env::var(format!(
+                "GOOGLE_APPLICATION_CREDENTIALS{suffix}"
+            ))
+            .ok()?;
+            match std::fs::read_to_string(&path) {
+                Ok(key) => Some(ProviderKey::Secret(Secret::from(key))),
+                Err(error) => {
+                    tracing::warn!(path = %path, error = %error, "failed to read service account key");
+                    None
+                }
+            }
         } else {
-            let provider_str = provider.to_string().to_uppercase();
-            let env_var = format!("{provider_str}_API_KEY");
+            // e.g. `TOGETHER_AI_API_KEY` for a provider named `together-ai`
+            let provider_str =
+                provider.to_string().to_uppercase().replace('-', "_");
+            let env_var = format!("{provider_str}_API_KEY{suffix}");
             if let Ok(key) = std::env::var(&env_var) {
                 Some(ProviderKey::Secret(Secret::from(key)))
             } else {
@@ -225,11 +329,11 @@ impl std::ops::Deref for ProviderKeys {
 
 impl ProviderKeys {
     fn from_env_inner(
-        balance_config: &BalanceConfig,
+        router_config: &RouterConfig,
     ) -> HashMap<InferenceProvider, ProviderKey> {
         tracing::debug!("Discovering provider keys");
         let mut keys = HashMap::default();
-        let providers = balance_config.providers();
+        let providers = router_config.providers();
 
         for provider in providers {
             if provider == InferenceProvider::Ollama {
@@ -247,7 +351,7 @@ impl ProviderKeys {
     pub fn from_env(
         router_config: &Arc<RouterConfig>,
     ) -> Result<Self, ProviderError> {
-        let mut keys = Self::from_env_inner(&router_config.load_balance);
+        let mut keys = Self::from_env_inner(router_config);
         let default_provider = SDK;
         if let Some(key) = ProviderKey::from_env(&default_provider) {
             tracing::debug!(provider = %default_provider, "got llm provider key");
@@ -11,14 +11,14 @@ use tokio::sync::{
     mpsc::{Receiver, Sender},
 };
 
-use crate::{endpoints::ApiEndpoint, types::router::RouterId};
+use crate::{endpoints::ApiEndpoint, types::router::BalancerId};
 
 pub type RateLimitEventSenders =
-    RwLock<HashMap<RouterId, Sender<RateLimitEvent>>>;
+    RwLock<HashMap<BalancerId, Sender<RateLimitEvent>>>;
 pub type RateLimitEventReceivers =
-    RwLock<HashMap<RouterId, Receiver<RateLimitEvent>>>;
+    RwLock<HashMap<BalancerId, Receiver<RateLimitEvent>>>;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct RateLimitEvent {
     pub api_endpoint: ApiEndpoint,
     pub retry_after_seconds: Option<u64>,
@@ -57,7 +57,7 @@ impl<K> Future for ProviderRestore<K> {
                 this.key.take().expect(
                     "should never poll future after restore completion",
                 ),
-                *this.api_endpoint,
+                this.api_endpoint.clone(),
             )),
             Poll::Pending => Poll::Pending,
         }
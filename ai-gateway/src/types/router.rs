@@ -3,7 +3,7 @@ use compact_str::CompactString;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::config::router::RouterConfig;
+use crate::{config::router::RouterConfig, endpoints::EndpointType};
 
 #[derive(
     Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default,
@@ -34,6 +34,42 @@ impl std::fmt::Display for RouterId {
     }
 }
 
+/// Identifies one of a router's load balancers.
+///
+/// Each endpoint type gets its own balancer, as does each model group with a
+/// model level balance config.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BalancerId {
+    pub router_id: RouterId,
+    pub endpoint_type: EndpointType,
+    /// `None` for the endpoint level balancer.
+    pub model_group: Option<CompactString>,
+}
+
+impl BalancerId {
+    #[must_use]
+    pub fn new(router_id: RouterId, endpoint_type: EndpointType) -> Self {
+        Self {
+            router_id,
+            endpoint_type,
+            model_group: None,
+        }
+    }
+
+    #[must_use]
+    pub fn for_model_group(
+        router_id: RouterId,
+        endpoint_type: EndpointType,
+        model_group: CompactString,
+    ) -> Self {
+        Self {
+            router_id,
+            endpoint_type,
+            model_group: Some(model_group),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct VersionedRouter {
     pub version_id: Uuid,
@@ -0,0 +1,164 @@
+//! Token usage read from response bodies, for the limits and budgets that
+//! are charged by tokens.
+use serde_json::Value;
+
+use crate::types::response::Response;
+
+/// A rough average for English text, good enough to rank providers and to
+/// reserve tokens before the usage is known.
+const BYTES_PER_TOKEN: u64 = 4;
+
+/// Estimates the tokens in `len` bytes of a request, before the provider has
+/// reported its usage.
+#[must_use]
+pub fn estimate_tokens(len: usize) -> u64 {
+    len as u64 / BYTES_PER_TOKEN
+}
+
+/// The token usage of a response.
+///
+/// Streams may report usage across several events, e.g. Anthropic sends the
+/// input tokens in `message_start` and the output tokens in `message_delta`,
+/// so the largest value seen for each field is kept.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Usage {
+    pub input: Option<u64>,
+    pub output: Option<u64>,
+    total: Option<u64>,
+}
+
+impl Usage {
+    fn observe_events(&mut self, lines: &[u8]) {
+        for line in lines.split(|b| *b == b'\n') {
+            let Some(data) = line.strip_prefix(b"data:") else {
+                continue;
+            };
+            if let Ok(event) =
+                serde_json::from_slice::<Value>(data.trim_ascii())
+            {
+                self.observe(&event);
+            }
+        }
+    }
+
+    fn observe(&mut self, value: &Value) {
+        // the Anthropic `message_start` event and the Responses API nest the
+        // usage in the message or response
+        let usages = [
+            value.get("usage"),
+            value.pointer("/message/usage"),
+            value.pointer("/response/usage"),
+        ];
+        for usage in usages.into_iter().flatten() {
+            observe_max(
+                &mut self.input,
+                usage,
+                &["prompt_tokens", "input_tokens"],
+            );
+            observe_max(
+                &mut self.output,
+                usage,
+                &["completion_tokens", "output_tokens"],
+            );
+            observe_max(&mut self.total, usage, &["total_tokens"]);
+        }
+    }
+
+    /// Returns `None` if the response didn't report any usage.
+    #[must_use]
+    pub fn total(&self) -> Option<u64> {
+        if self.input.is_none() && self.output.is_none() && self.total.is_none()
+        {
+            return None;
+        }
+        let sum = self.input.unwrap_or(0) + self.output.unwrap_or(0);
+        Some(self.total.unwrap_or(0).max(sum))
+    }
+}
+
+fn observe_max(field: &mut Option<u64>, usage: &Value, names: &[&str]) {
+    for name in names {
+        if let Some(tokens) = usage.get(name).and_then(Value::as_u64) {
+            *field = Some(field.map_or(tokens, |field| field.max(tokens)));
+        }
+    }
+}
+
+/// Reads the [`Usage`] from a response body as its chunks are sent to the
+/// client.
+#[derive(Debug)]
+pub struct UsageReader {
+    is_stream: bool,
+    /// The whole body for JSON responses, or the incomplete last line of a
+    /// stream.
+    buffer: Vec<u8>,
+    usage: Usage,
+}
+
+impl UsageReader {
+    #[must_use]
+    pub fn for_response(response: &Response) -> Self {
+        let is_stream = response
+            .headers()
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("text/event-stream"));
+        Self {
+            is_stream,
+            buffer: Vec::new(),
+            usage: Usage::default(),
+        }
+    }
+
+    pub fn record(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+        if !self.is_stream {
+            return;
+        }
+        let Some(end) = self.buffer.iter().rposition(|b| *b == b'\n') else {
+            return;
+        };
+        let lines: Vec<u8> = self.buffer.drain(..=end).collect();
+        self.usage.observe_events(&lines);
+    }
+
+    /// The usage of the body recorded so far, which is only complete once
+    /// the whole body has been recorded.
+    pub fn finish(&mut self) -> Usage {
+        let rest = std::mem::take(&mut self.buffer);
+        if self.is_stream {
+            self.usage.observe_events(&rest);
+        } else if let Ok(value) = serde_json::from_slice::<Value>(&rest) {
+            self.usage.observe(&value);
+        }
+        self.usage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_from_json_and_event_streams() {
+        let mut usage = Usage::default();
+        usage.observe(&serde_json::json!({
+            "usage": { "prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15 }
+        }));
+        assert_eq!(usage.total(), Some(15));
+
+        let events = b"event: message_start\n\
+            data: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":20,\"output_tokens\":1}}}\n\n\
+            event: message_delta\n\
+            data: {\"type\":\"message_delta\",\"usage\":{\"output_tokens\":30}}\n\n";
+        let mut usage = Usage::default();
+        usage.observe_events(events);
+        assert_eq!(usage.input, Some(20));
+        assert_eq!(usage.output, Some(30));
+        assert_eq!(usage.total(), Some(50));
+
+        let mut usage = Usage::default();
+        usage.observe_events(b"data: {\"choices\":[]}\n\ndata: [DONE]\n\n");
+        assert_eq!(usage.total(), None);
+    }
+}
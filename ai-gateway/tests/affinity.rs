@@ -0,0 +1,160 @@
+use std::{collections::HashMap, time::Duration};
+
+use ai_gateway::{
+    config::{
+        Config,
+        affinity::AffinityConfig,
+        balance::{BalanceConfig, BalanceConfigInner},
+        helicone::HeliconeFeatures,
+        monitor::{GracePeriod, HealthMonitorConfig},
+        router::{RouterConfig, RouterConfigs},
+    },
+    discover::monitor::health::HealthMonitor,
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+
+const PROVIDERS: [InferenceProvider; 2] =
+    [InferenceProvider::OpenAI, InferenceProvider::Anthropic];
+
+fn affinity_config() -> Config {
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.discover.monitor.health = HealthMonitorConfig::ErrorRatio {
+        ratio: Decimal::try_from(0.5).unwrap(),
+        window: Duration::from_secs(60),
+        buckets: 10,
+        interval: Duration::from_millis(50),
+        grace_period: GracePeriod::Requests { min_requests: 1 },
+    };
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Latency {
+                    providers: nes![
+                        InferenceProvider::OpenAI,
+                        InferenceProvider::Anthropic
+                    ],
+                },
+            )])),
+            affinity: Some(AffinityConfig::default()),
+            ..Default::default()
+        },
+    )]));
+    config
+}
+
+/// Returns the provider that served the request.
+async fn chat_request(
+    harness: &mut Harness,
+    session: &str,
+) -> InferenceProvider {
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ]
+    }))
+    .unwrap();
+    let request = Request::builder()
+        .method(Method::POST)
+        .header("helicone-session-id", session)
+        .uri("http://router.helicone.com/router/default/chat/completions")
+        .body(axum_core::body::Body::from(body_bytes))
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let provider = response.headers()["helicone-provider"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let _body = response.into_body().collect().await.unwrap();
+    PROVIDERS
+        .into_iter()
+        .find(|candidate| candidate.as_ref() == provider)
+        .expect("request should be served by a balanced provider")
+}
+
+/// Sends a few requests for the session, which must all be served by the
+/// same provider.
+async fn pinned_provider(
+    harness: &mut Harness,
+    session: &str,
+) -> InferenceProvider {
+    let provider = chat_request(harness, session).await;
+    for _ in 0..4 {
+        assert_eq!(chat_request(harness, session).await, provider, "{session}");
+    }
+    provider
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn session_stays_on_provider_until_it_is_removed() {
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:openai:chat_completion", (0..).into()),
+            ("success:anthropic:messages", (0..).into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(affinity_config())
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let app_state = harness.app_factory.state.clone();
+    let health_monitor = HealthMonitor::new(app_state.clone());
+    tokio::spawn(async move {
+        health_monitor.run_forever().await.unwrap();
+    });
+
+    let removed = pinned_provider(&mut harness, "session-0").await;
+    // a session pinned to the other provider, which shouldn't move
+    let mut kept = None;
+    for session in 1..50 {
+        let session = format!("session-{session}");
+        let provider = pinned_provider(&mut harness, &session).await;
+        if provider != removed {
+            kept = Some((session, provider));
+            break;
+        }
+    }
+    let (kept_session, kept_provider) =
+        kept.expect("sessions should be spread across providers");
+
+    // enough failures to outweigh the successful requests above make the
+    // pinned provider unhealthy, so the health monitor removes it from the
+    // balancer
+    let endpoint = removed.endpoints()[0].clone();
+    let metrics = app_state
+        .0
+        .endpoint_metrics
+        .health_metrics(&endpoint)
+        .unwrap();
+    for _ in 0..1000 {
+        metrics.incr_req_count();
+        metrics.incr_remote_internal_error_count();
+    }
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    assert_eq!(
+        pinned_provider(&mut harness, "session-0").await,
+        kept_provider
+    );
+    assert_eq!(
+        pinned_provider(&mut harness, &kept_session).await,
+        kept_provider
+    );
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner, BalanceTarget},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+
+async fn audio_harness(stub: &'static str) -> Harness {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're not testing authentication
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Audio,
+                BalanceConfigInner::Weighted {
+                    providers: nes![BalanceTarget {
+                        provider: InferenceProvider::OpenAI,
+                        weight: Decimal::from(1),
+                    }],
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            (stub, 1.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn multipart_transcription() {
+    let mut harness =
+        audio_harness("success:openai:audio_transcriptions").await;
+    let body = concat!(
+        "--boundary\r\n",
+        "Content-Disposition: form-data; name=\"model\"\r\n\r\n",
+        "openai/whisper-1\r\n",
+        "--boundary\r\n",
+        "Content-Disposition: form-data; name=\"file\"; ",
+        "filename=\"speech.mp3\"\r\n",
+        "Content-Type: audio/mpeg\r\n\r\n",
+        "ID3fake-mp3-frames\r\n",
+        "--boundary--\r\n",
+    );
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/audio/transcriptions")
+        .header("content-type", "multipart/form-data; boundary=boundary")
+        .body(axum_core::body::Body::from(body))
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(body["text"].is_string());
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn speech_response_is_passed_through() {
+    let mut harness = audio_harness("success:openai:audio_speech").await;
+    let request_body = axum_core::body::Body::from(
+        serde_json::to_vec(&json!({
+            "model": "openai/tts-1",
+            "input": "The quick brown fox jumped over the lazy dog.",
+            "voice": "alloy"
+        }))
+        .unwrap(),
+    );
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/audio/speech")
+        .body(request_body)
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "audio/mpeg");
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(body.as_ref(), b"ID3fake-mp3-frames");
+}
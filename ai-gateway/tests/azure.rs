@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner, BalanceTarget},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{
+        model_id::ModelName, provider::InferenceProvider, router::RouterId,
+    },
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+
+fn azure_config() -> Config {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're not testing authentication
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Weighted {
+                    providers: nes![BalanceTarget {
+                        provider: InferenceProvider::Azure,
+                        weight: Decimal::from(1),
+                    }],
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    config
+}
+
+async fn chat_completion(config: Config, stub: &'static str) {
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            (stub, 1.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let request_body = axum_core::body::Body::from(
+        serde_json::to_vec(&json!({
+            "model": "openai/gpt-4o-mini",
+            "messages": [
+                {
+                    "role": "user",
+                    "content": "Hello, world!"
+                }
+            ]
+        }))
+        .unwrap(),
+    );
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/chat/completions")
+        .body(request_body)
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        body["choices"][0]["message"]["content"],
+        "Hello! How can I assist you today?"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn azure_chat_completion() {
+    // without a configured deployment, the model name is used as the
+    // deployment name
+    chat_completion(azure_config(), "success:azure:chat_completion").await;
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn azure_chat_completion_with_deployment() {
+    let mut config = azure_config();
+    config
+        .providers
+        .get_mut(&InferenceProvider::Azure)
+        .unwrap()
+        .deployments
+        .insert(ModelName::borrowed("gpt-4o-mini"), "chat-prod".to_string());
+    chat_completion(config, "success:azure:chat_completion_deployment").await;
+}
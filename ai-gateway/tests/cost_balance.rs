@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use nonempty_collections::nes;
+use serde_json::json;
+use tower::Service;
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn routes_to_cheapest_provider_for_mapped_model() {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're testing load balancing behavior
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Cost {
+                    providers: nes![
+                        InferenceProvider::OpenAI,
+                        InferenceProvider::Anthropic,
+                        InferenceProvider::GoogleGemini
+                    ],
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    let requests = 5;
+    // `gpt-4o-mini` is mapped to `claude-3-5-haiku` and `gemini-2.0-flash`,
+    // the latter being the cheapest of the three
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:openai:chat_completion", 0.into()),
+            ("success:anthropic:messages", 0.into()),
+            ("success:gemini:generate_content", requests.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ]
+    }))
+    .unwrap();
+
+    for _ in 0..requests {
+        let request_body = axum_core::body::Body::from(body_bytes.clone());
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://router.helicone.com/router/default/chat/completions")
+            .body(request_body)
+            .unwrap();
+        let response = harness.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner, BalanceTarget},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+
+fn embeddings_balance_config(provider: InferenceProvider) -> BalanceConfig {
+    BalanceConfig::from(HashMap::from([(
+        EndpointType::Embeddings,
+        BalanceConfigInner::Weighted {
+            providers: nes![BalanceTarget {
+                provider,
+                weight: Decimal::from(1),
+            }],
+        },
+    )]))
+}
+
+async fn assert_embeddings_served_by(
+    provider: InferenceProvider,
+    stub: &'static str,
+) {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're not testing authentication
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: embeddings_balance_config(provider),
+            ..Default::default()
+        },
+    )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            (stub, 1.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let request_body = axum_core::body::Body::from(
+        serde_json::to_vec(&json!({
+            "model": "openai/text-embedding-3-small",
+            "input": "Hello, world!"
+        }))
+        .unwrap(),
+    );
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/embeddings")
+        .body(request_body)
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["object"], "list");
+    assert_eq!(body["data"][0]["object"], "embedding");
+    assert!(!body["data"][0]["embedding"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_embeddings() {
+    assert_embeddings_served_by(
+        InferenceProvider::OpenAI,
+        "success:openai:embeddings",
+    )
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_embeddings_mapped_to_gemini() {
+    assert_embeddings_served_by(
+        InferenceProvider::GoogleGemini,
+        "success:gemini:embed_content",
+    )
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_embeddings_mapped_to_ollama() {
+    assert_embeddings_served_by(
+        InferenceProvider::Ollama,
+        "success:ollama:embed",
+    )
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_embeddings_mapped_to_bedrock_titan() {
+    assert_embeddings_served_by(
+        InferenceProvider::Bedrock,
+        "success:bedrock:invoke_model",
+    )
+    .await;
+}
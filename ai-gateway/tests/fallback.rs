@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{
+            BalanceConfig, BalanceConfigInner, BalanceTarget,
+            ModelBalanceConfig, ModelBalanceGroup,
+        },
+        fallback::FallbackConfig,
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use indexmap::{IndexMap, IndexSet};
+use nonempty_collections::{nes, nev};
+use opentelemetry_sdk::metrics::{
+    InMemoryMetricExporter, SdkMeterProvider, data::Sum,
+};
+use rust_decimal::Decimal;
+use serde_json::json;
+use stubr::wiremock_rs::{Mock, ResponseTemplate, matchers};
+use tower::Service;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn failed_provider_falls_back_to_next_provider() {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're testing fallbacks
+    config.helicone.features = HeliconeFeatures::None;
+    let balance_config = BalanceConfig::from(HashMap::from([(
+        EndpointType::Chat,
+        BalanceConfigInner::Weighted {
+            providers: nes![
+                BalanceTarget {
+                    provider: InferenceProvider::OpenAI,
+                    weight: Decimal::try_from(0.50).unwrap(),
+                },
+                BalanceTarget {
+                    provider: InferenceProvider::Anthropic,
+                    weight: Decimal::try_from(0.50).unwrap(),
+                },
+            ],
+        },
+    )]));
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: balance_config,
+            fallback: Some(FallbackConfig {
+                enabled: true,
+                order: vec![InferenceProvider::OpenAI],
+                mid_stream: false,
+            }),
+            ..Default::default()
+        },
+    )]));
+    let num_requests = 10;
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            // every request is eventually served by openai
+            ("success:openai:chat_completion", num_requests.into()),
+            ("error:anthropic:messages", (..=num_requests).into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ]
+    }))
+    .unwrap();
+
+    for _ in 0..num_requests {
+        let request_body = axum_core::body::Body::from(body_bytes.clone());
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://router.helicone.com/router/default/chat/completions")
+            .body(request_body)
+            .unwrap();
+        let response = harness.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get("helicone-provider").unwrap(),
+            "openai"
+        );
+        let _response_body = response.into_body().collect().await.unwrap();
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn model_group_falls_back_to_its_own_providers() {
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Latency {
+                    providers: nes![InferenceProvider::OpenAI],
+                },
+            )])),
+            model_load_balance: ModelBalanceConfig::from(IndexMap::from([(
+                "gpt-4o-mini".into(),
+                ModelBalanceGroup {
+                    models: IndexSet::new(),
+                    balance: BalanceConfigInner::Priority {
+                        tiers: nev![
+                            nes![InferenceProvider::Anthropic],
+                            nes![InferenceProvider::Ollama]
+                        ],
+                    },
+                },
+            )])),
+            fallback: Some(FallbackConfig {
+                enabled: true,
+                order: Vec::new(),
+                mid_stream: false,
+            }),
+            ..Default::default()
+        },
+    )]));
+    let num_requests = 4;
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            // the endpoint level provider is never fallen back to
+            ("success:openai:chat_completion", 0.into()),
+            ("error:anthropic:messages", num_requests.into()),
+            ("success:ollama:chat_completions", num_requests.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ]
+    }))
+    .unwrap();
+
+    for _ in 0..num_requests {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://router.helicone.com/router/default/chat/completions")
+            .body(axum_core::body::Body::from(body_bytes.clone()))
+            .unwrap();
+        let response = harness.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get("helicone-provider").unwrap(),
+            "ollama"
+        );
+        let _response_body = response.into_body().collect().await.unwrap();
+    }
+}
+
+/// OpenAI is always selected by the load balancer, so broken streams are
+/// failed over to Ollama.
+fn mid_stream_config() -> Config {
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Priority {
+                    tiers: nev![
+                        nes![InferenceProvider::OpenAI],
+                        nes![InferenceProvider::Ollama]
+                    ],
+                },
+            )])),
+            fallback: Some(FallbackConfig {
+                enabled: true,
+                order: Vec::new(),
+                mid_stream: true,
+            }),
+            ..Default::default()
+        },
+    )]));
+    config
+}
+
+/// Records metrics in memory, since the app's meter is taken from the
+/// global provider when the harness is built.
+fn in_memory_metrics() -> (SdkMeterProvider, InMemoryMetricExporter) {
+    let exporter = InMemoryMetricExporter::default();
+    let provider = SdkMeterProvider::builder()
+        .with_periodic_exporter(exporter.clone())
+        .build();
+    opentelemetry::global::set_meter_provider(provider.clone());
+    (provider, exporter)
+}
+
+fn stream_failover_count(
+    provider: &SdkMeterProvider,
+    exporter: &InMemoryMetricExporter,
+    action: &str,
+) -> u64 {
+    provider.force_flush().unwrap();
+    let metrics = exporter.get_finished_metrics().unwrap();
+    // counters are cumulative, so only the latest export is needed
+    metrics
+        .last()
+        .into_iter()
+        .flat_map(|resource| &resource.scope_metrics)
+        .flat_map(|scope| &scope.metrics)
+        .filter(|metric| metric.name == "stream_failover_count")
+        .filter_map(|metric| metric.data.as_any().downcast_ref::<Sum<u64>>())
+        .flat_map(|sum| &sum.data_points)
+        .filter(|point| {
+            point.attributes.iter().any(|attribute| {
+                attribute.key.as_str() == "action"
+                    && attribute.value.as_str() == action
+            })
+        })
+        .map(|point| point.value)
+        .sum()
+}
+
+fn chunk(content: &str) -> serde_json::Value {
+    json!({
+        "id": "chatcmpl-1",
+        "object": "chat.completion.chunk",
+        "created": 1_741_569_952,
+        "model": "gpt-4o-mini",
+        "choices": [{
+            "index": 0,
+            "delta": { "role": "assistant", "content": content },
+            "finish_reason": null
+        }]
+    })
+}
+
+/// Responds with `events` followed by `trailer`, which is invalid UTF-8 when
+/// the stream should break.
+async fn mount_stream(
+    server: &stubr::wiremock_rs::MockServer,
+    events: &[serde_json::Value],
+    trailer: &[u8],
+) {
+    let mut body: Vec<u8> = events
+        .iter()
+        .flat_map(|event| format!("data: {event}\n\n").into_bytes())
+        .collect();
+    body.extend_from_slice(trailer);
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_bytes(body),
+        )
+        .mount(server)
+        .await;
+}
+
+/// Bytes that can never be decoded, which break the stream once they are
+/// reached.
+const BROKEN: &[u8] = b"data: \xff\xfe\n\n";
+
+async fn stream_request(harness: &mut Harness) -> (StatusCode, String) {
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ],
+        "stream": true
+    }))
+    .unwrap();
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/chat/completions")
+        .body(axum_core::body::Body::from(body_bytes))
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn stream_broken_before_first_byte_restarts_on_next_provider() {
+    let (meter_provider, exporter) = in_memory_metrics();
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(mid_stream_config())
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    mount_stream(&harness.mock.openai_mock.http_server, &[], BROKEN).await;
+    mount_stream(
+        &harness.mock.ollama_mock.http_server,
+        &[chunk("Hello from Ollama")],
+        b"data: [DONE]\n\n",
+    )
+    .await;
+
+    let (status, body) = stream_request(&mut harness).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Hello from Ollama"), "{body}");
+    assert!(!body.contains("event: error"), "{body}");
+    assert!(
+        !harness
+            .mock
+            .openai_mock
+            .http_server
+            .received_requests()
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        stream_failover_count(&meter_provider, &exporter, "restarted"),
+        1
+    );
+    assert_eq!(
+        stream_failover_count(&meter_provider, &exporter, "error_event"),
+        0
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn stream_broken_after_first_byte_ends_with_error_event() {
+    let (meter_provider, exporter) = in_memory_metrics();
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(mid_stream_config())
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    mount_stream(
+        &harness.mock.openai_mock.http_server,
+        &[chunk("Hello from OpenAI")],
+        BROKEN,
+    )
+    .await;
+    // the client has already seen part of the response, so it must not be
+    // restarted on another provider
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/chat/completions"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&harness.mock.ollama_mock.http_server)
+        .await;
+
+    let (status, body) = stream_request(&mut harness).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let (sent, error) = body.split_once("event: error\n").expect(&body);
+    assert!(sent.contains("Hello from OpenAI"), "{body}");
+    assert!(error.starts_with("data: "), "{body}");
+    assert!(error.ends_with("\n\n"), "{body}");
+    assert_eq!(
+        stream_failover_count(&meter_provider, &exporter, "error_event"),
+        1
+    );
+    assert_eq!(
+        stream_failover_count(&meter_provider, &exporter, "restarted"),
+        0
+    );
+}
@@ -1,23 +1,32 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use ai_gateway::{
     config::{
         Config,
         balance::{BalanceConfig, BalanceConfigInner, BalanceTarget},
         helicone::HeliconeFeatures,
+        monitor::{HealthMonitorConfig, HealthScope, MonitorConfig},
+        probe::{ProbeConfig, ProbeRequest},
         router::{RouterConfig, RouterConfigs},
     },
-    discover::monitor::health::HealthMonitor,
+    discover::monitor::{
+        circuit_breaker::Circuit, health::HealthMonitor, probe::Prober,
+    },
     endpoints::EndpointType,
     tests::{TestDefault, harness::Harness, mock::MockArgs},
-    types::{provider::InferenceProvider, router::RouterId},
+    types::{
+        provider::InferenceProvider,
+        router::{BalancerId, RouterId},
+    },
 };
-use http::{Method, Request};
+use http::{Method, Request, StatusCode};
 use http_body_util::BodyExt;
 use nonempty_collections::nes;
 use rust_decimal::Decimal;
 use serde_json::json;
-use tower::Service;
+use stubr::wiremock_rs::{Mock, MockServer, ResponseTemplate, matchers};
+use tokio::sync::mpsc::channel;
+use tower::{Service, discover::Change};
 
 #[tokio::test]
 #[serial_test::serial]
@@ -106,3 +115,641 @@ async fn errors_remove_provider_from_lb_pool() {
     // but this is totes good for now
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 }
+
+async fn anthropic_requests(harness: &Harness) -> usize {
+    harness
+        .mock
+        .anthropic_mock
+        .http_server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .len()
+}
+
+async fn chat_request(harness: &mut Harness) -> StatusCode {
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ]
+    }))
+    .unwrap();
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/chat/completions")
+        .body(axum_core::body::Body::from(body_bytes))
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    let status = response.status();
+    let _response_body = response.into_body().collect().await.unwrap();
+    status
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn unhealthy_model_is_skipped_until_it_recovers() {
+    let open_duration = Duration::from_secs(2);
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.discover.monitor = MonitorConfig {
+        health: HealthMonitorConfig::CircuitBreaker {
+            failure_threshold: 3,
+            open_duration,
+            half_open_requests: 1,
+            interval: Duration::from_secs(1),
+        },
+        scope: HealthScope::Model,
+        probe: None,
+    };
+    let balance_config = BalanceConfig::from(HashMap::from([(
+        EndpointType::Chat,
+        BalanceConfigInner::Weighted {
+            providers: nes![
+                BalanceTarget {
+                    provider: InferenceProvider::OpenAI,
+                    weight: Decimal::try_from(0.50).unwrap(),
+                },
+                BalanceTarget {
+                    provider: InferenceProvider::Anthropic,
+                    weight: Decimal::try_from(0.50).unwrap(),
+                },
+            ],
+        },
+    )]));
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: balance_config,
+            ..Default::default()
+        },
+    )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:openai:chat_completion", (1..).into()),
+            ("success:anthropic:messages", (1..).into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    // the first requests to Anthropic fail, after which its mapping of the
+    // model is healthy again
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(3)
+        .with_priority(1)
+        .expect(3)
+        .mount(&harness.mock.anthropic_mock.http_server)
+        .await;
+
+    let mut failures = 0;
+    for _ in 0..50 {
+        if chat_request(&mut harness).await != StatusCode::OK {
+            failures += 1;
+        }
+        if failures == 3 {
+            break;
+        }
+    }
+    assert_eq!(failures, 3, "Anthropic should have been picked 3 times");
+
+    // the open circuit skips Anthropic for the model without the health
+    // monitor removing it
+    for _ in 0..20 {
+        assert_eq!(chat_request(&mut harness).await, StatusCode::OK);
+    }
+    assert_eq!(anthropic_requests(&harness).await, 3);
+
+    // once the circuit is half open a trial request succeeds, after which
+    // Anthropic gets its share of traffic again
+    tokio::time::sleep(open_duration).await;
+    for _ in 0..30 {
+        assert_eq!(chat_request(&mut harness).await, StatusCode::OK);
+    }
+    assert!(anthropic_requests(&harness).await > 4);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn circuit_breaker_cycles_through_open_and_half_open() {
+    let interval = Duration::from_millis(200);
+    let open_duration = Duration::from_secs(1);
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.discover.monitor = MonitorConfig {
+        health: HealthMonitorConfig::CircuitBreaker {
+            failure_threshold: 3,
+            open_duration,
+            half_open_requests: 1,
+            interval,
+        },
+        scope: HealthScope::Provider,
+        probe: None,
+    };
+    let balance_config = BalanceConfig::from(HashMap::from([(
+        EndpointType::Chat,
+        BalanceConfigInner::Weighted {
+            providers: nes![
+                BalanceTarget {
+                    provider: InferenceProvider::OpenAI,
+                    weight: Decimal::try_from(0.50).unwrap(),
+                },
+                BalanceTarget {
+                    provider: InferenceProvider::Anthropic,
+                    weight: Decimal::try_from(0.50).unwrap(),
+                },
+            ],
+        },
+    )]));
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: balance_config,
+            ..Default::default()
+        },
+    )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:openai:chat_completion", (1..).into()),
+            ("success:anthropic:messages", (1..).into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    // the first requests to Anthropic fail, after which it has recovered
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(3)
+        .with_priority(1)
+        .expect(3)
+        .mount(&harness.mock.anthropic_mock.http_server)
+        .await;
+    let app_state = harness.app_factory.state.clone();
+    let health_monitor = HealthMonitor::new(app_state.clone());
+    tokio::spawn(async move {
+        health_monitor.run_forever().await.unwrap();
+    });
+    let endpoint = InferenceProvider::Anthropic.endpoints()[0].clone();
+    let metrics = app_state
+        .0
+        .endpoint_metrics
+        .health_metrics(&endpoint)
+        .unwrap();
+
+    let mut failures = 0;
+    for _ in 0..50 {
+        if chat_request(&mut harness).await != StatusCode::OK {
+            failures += 1;
+        }
+        if failures == 3 {
+            break;
+        }
+    }
+    assert_eq!(failures, 3, "Anthropic should have been picked 3 times");
+    assert_eq!(metrics.circuit(), Circuit::Closed);
+
+    // the monitor opens the circuit and removes Anthropic
+    tokio::time::sleep(interval * 2).await;
+    assert!(matches!(metrics.circuit(), Circuit::Open { .. }));
+    for _ in 0..20 {
+        assert_eq!(chat_request(&mut harness).await, StatusCode::OK);
+    }
+    assert_eq!(anthropic_requests(&harness).await, 3);
+
+    // once the circuit is half open Anthropic is added back for a single
+    // trial request, and skipped after it rather than failing requests
+    // until the monitor closes the circuit
+    tokio::time::sleep(open_duration + interval * 2).await;
+    assert!(matches!(metrics.circuit(), Circuit::HalfOpen { .. }));
+    for _ in 0..50 {
+        assert_eq!(chat_request(&mut harness).await, StatusCode::OK);
+        if anthropic_requests(&harness).await == 4 {
+            break;
+        }
+    }
+    assert_eq!(anthropic_requests(&harness).await, 4);
+    for _ in 0..20 {
+        assert_eq!(chat_request(&mut harness).await, StatusCode::OK);
+    }
+
+    // the trial request succeeded, so the monitor closes the circuit and
+    // Anthropic gets its share of traffic again
+    tokio::time::sleep(interval * 2).await;
+    assert_eq!(metrics.circuit(), Circuit::Closed);
+    for _ in 0..30 {
+        assert_eq!(chat_request(&mut harness).await, StatusCode::OK);
+    }
+    assert!(anthropic_requests(&harness).await > 5);
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn sustained_latency_slo_breach_removes_provider_until_it_recovers() {
+    let sustained_for = Duration::from_millis(500);
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.discover.monitor = MonitorConfig {
+        health: HealthMonitorConfig::LatencySlo {
+            percentile: Decimal::try_from(0.5).unwrap(),
+            slo: Duration::from_millis(100),
+            window: Duration::from_secs(60),
+            sustained_for,
+            interval: Duration::from_millis(20),
+        },
+        scope: HealthScope::Provider,
+        probe: None,
+    };
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([(
+            "success:openai:chat_completion",
+            0.into(),
+        )]))
+        .build();
+    let harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let app_state = harness.app_factory.state.clone();
+
+    let router_config = std::sync::Arc::new(RouterConfig {
+        load_balance: BalanceConfig::from(HashMap::from([(
+            EndpointType::Chat,
+            BalanceConfigInner::Latency {
+                providers: nes![InferenceProvider::OpenAI],
+            },
+        )])),
+        ..Default::default()
+    });
+    let (tx, mut rx) = channel(16);
+    app_state
+        .add_p2c_router_health_monitor(
+            BalancerId::new(RouterId::Named("slo".into()), EndpointType::Chat),
+            router_config,
+            tx,
+        )
+        .await;
+    let health_monitor = HealthMonitor::new(app_state.clone());
+    tokio::spawn(async move {
+        health_monitor.run_forever().await.unwrap();
+    });
+    let endpoint = InferenceProvider::OpenAI.endpoints()[0].clone();
+    let metrics = app_state
+        .0
+        .endpoint_metrics
+        .health_metrics(&endpoint)
+        .unwrap();
+    let record = |millis, count| {
+        for _ in 0..count {
+            metrics.record_latency(Duration::from_millis(millis));
+        }
+    };
+
+    // a breach that ends before it is sustained doesn't remove the provider
+    record(500, 20);
+    tokio::time::sleep(sustained_for / 2).await;
+    record(10, 40);
+    tokio::time::sleep(sustained_for * 2).await;
+    assert!(rx.try_recv().is_err(), "provider should not be removed");
+
+    record(500, 100);
+    let change = tokio::time::timeout(sustained_for * 4, rx.recv())
+        .await
+        .expect("provider should be removed")
+        .unwrap();
+    assert!(
+        matches!(&change, Change::Remove(key) if key.provider == InferenceProvider::OpenAI)
+    );
+
+    record(10, 500);
+    let change = tokio::time::timeout(sustained_for * 4, rx.recv())
+        .await
+        .expect("provider should be added back")
+        .unwrap();
+    assert!(
+        matches!(&change, Change::Insert(key, _) if key.provider == InferenceProvider::OpenAI)
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn failed_probes_remove_idle_provider_until_they_succeed() {
+    let interval = Duration::from_millis(50);
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.discover.monitor = MonitorConfig {
+        health: HealthMonitorConfig::LatencySlo {
+            percentile: Decimal::try_from(0.5).unwrap(),
+            slo: Duration::from_millis(100),
+            window: Duration::from_secs(60),
+            sustained_for: Duration::from_secs(60),
+            interval,
+        },
+        scope: HealthScope::Provider,
+        probe: Some(ProbeConfig {
+            interval,
+            timeout: Duration::from_secs(1),
+            failure_threshold: 2,
+            providers: [(
+                InferenceProvider::OpenAI,
+                ProbeRequest {
+                    path: "/v1/models".to_string(),
+                    body: None,
+                },
+            )]
+            .into_iter()
+            .collect(),
+        }),
+    };
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([(
+            "success:openai:chat_completion",
+            0.into(),
+        )]))
+        .build();
+    let harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    // the first probes fail, after which the provider has recovered
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/v1/models"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(3)
+        .with_priority(1)
+        .mount(&harness.mock.openai_mock.http_server)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "data": []
+        })))
+        .with_priority(2)
+        .mount(&harness.mock.openai_mock.http_server)
+        .await;
+    let app_state = harness.app_factory.state.clone();
+
+    // the router has no traffic, so only the probes can change its health
+    let router_config = std::sync::Arc::new(RouterConfig {
+        load_balance: BalanceConfig::from(HashMap::from([(
+            EndpointType::Chat,
+            BalanceConfigInner::Latency {
+                providers: nes![InferenceProvider::OpenAI],
+            },
+        )])),
+        ..Default::default()
+    });
+    let (tx, mut rx) = channel(16);
+    app_state
+        .add_p2c_router_health_monitor(
+            BalancerId::new(
+                RouterId::Named("probed".into()),
+                EndpointType::Chat,
+            ),
+            router_config,
+            tx,
+        )
+        .await;
+    let prober = Prober::new(app_state.clone())
+        .unwrap()
+        .expect("probes are configured");
+    tokio::spawn(async move {
+        prober.run_forever().await.unwrap();
+    });
+    let health_monitor = HealthMonitor::new(app_state);
+    tokio::spawn(async move {
+        health_monitor.run_forever().await.unwrap();
+    });
+
+    let change = tokio::time::timeout(interval * 20, rx.recv())
+        .await
+        .expect("provider should be removed")
+        .unwrap();
+    assert!(
+        matches!(&change, Change::Remove(key) if key.provider == InferenceProvider::OpenAI)
+    );
+
+    let change = tokio::time::timeout(interval * 20, rx.recv())
+        .await
+        .expect("provider should be added back")
+        .unwrap();
+    assert!(
+        matches!(&change, Change::Insert(key, _) if key.provider == InferenceProvider::OpenAI)
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn passing_probe_brings_back_provider_once_traffic_stops() {
+    let interval = Duration::from_millis(20);
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.discover.monitor = MonitorConfig {
+        health: HealthMonitorConfig::LatencySlo {
+            percentile: Decimal::try_from(0.5).unwrap(),
+            slo: Duration::from_millis(100),
+            window: Duration::from_secs(60),
+            sustained_for: Duration::from_millis(100),
+            interval,
+        },
+        scope: HealthScope::Provider,
+        probe: Some(ProbeConfig {
+            interval: interval * 5,
+            timeout: Duration::from_secs(1),
+            failure_threshold: 2,
+            providers: [(
+                InferenceProvider::OpenAI,
+                ProbeRequest {
+                    path: "/v1/models".to_string(),
+                    body: None,
+                },
+            )]
+            .into_iter()
+            .collect(),
+        }),
+    };
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([(
+            "success:openai:chat_completion",
+            0.into(),
+        )]))
+        .build();
+    let harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/v1/models"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "data": []
+        })))
+        .mount(&harness.mock.openai_mock.http_server)
+        .await;
+    let app_state = harness.app_factory.state.clone();
+
+    let router_config = std::sync::Arc::new(RouterConfig {
+        load_balance: BalanceConfig::from(HashMap::from([(
+            EndpointType::Chat,
+            BalanceConfigInner::Latency {
+                providers: nes![InferenceProvider::OpenAI],
+            },
+        )])),
+        ..Default::default()
+    });
+    let (tx, mut rx) = channel(16);
+    app_state
+        .add_p2c_router_health_monitor(
+            BalancerId::new(
+                RouterId::Named("stale".into()),
+                EndpointType::Chat,
+            ),
+            router_config,
+            tx,
+        )
+        .await;
+    let prober = Prober::new(app_state.clone())
+        .unwrap()
+        .expect("probes are configured");
+    tokio::spawn(async move {
+        prober.run_forever().await.unwrap();
+    });
+    let health_monitor = HealthMonitor::new(app_state.clone());
+    tokio::spawn(async move {
+        health_monitor.run_forever().await.unwrap();
+    });
+
+    // slow traffic breaches the latency SLO while the probes pass
+    let traffic_state = app_state.clone();
+    let traffic = tokio::spawn(async move {
+        let endpoint = InferenceProvider::OpenAI.endpoints()[0].clone();
+        let metrics = traffic_state
+            .0
+            .endpoint_metrics
+            .health_metrics(&endpoint)
+            .unwrap();
+        loop {
+            metrics.incr_req_count();
+            metrics.record_latency(Duration::from_millis(500));
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+    });
+    let change = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("provider should be removed")
+        .unwrap();
+    assert!(
+        matches!(&change, Change::Remove(key) if key.provider == InferenceProvider::OpenAI)
+    );
+
+    // the SLO is still breached, but without traffic the next passing probe
+    // is the most recent sign of the provider's health
+    traffic.abort();
+    let change = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+        .await
+        .expect("provider should be added back")
+        .unwrap();
+    assert!(
+        matches!(&change, Change::Insert(key, _) if key.provider == InferenceProvider::OpenAI)
+    );
+}
+
+async fn models_server(authorization: &str) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/v1/models"))
+        .and(matchers::header("authorization", authorization))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "object": "list",
+            "data": []
+        })))
+        .expect(1..)
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn regions_are_probed_with_their_own_keys() {
+    // SAFETY: tests that read provider keys from the environment run
+    // serially
+    unsafe {
+        std::env::set_var("OPENAI_API_KEY_EAST_US", "sk-east-us");
+    }
+    let east = models_server("Bearer sk-east-us").await;
+    // regions without their own key use the provider's key
+    let west = models_server("Bearer sk-...").await;
+
+    let interval = Duration::from_millis(50);
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.discover.monitor.probe = Some(ProbeConfig {
+        interval,
+        timeout: Duration::from_secs(1),
+        failure_threshold: 2,
+        providers: [(
+            InferenceProvider::OpenAI,
+            ProbeRequest {
+                path: "/v1/models".to_string(),
+                body: None,
+            },
+        )]
+        .into_iter()
+        .collect(),
+    });
+    config
+        .providers
+        .get_mut(&InferenceProvider::OpenAI)
+        .unwrap()
+        .regions = serde_json::from_value(json!({
+        "east-us": { "base-url": east.uri() },
+        "west-us": { "base-url": west.uri() },
+    }))
+    .unwrap();
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([(
+            "success:openai:chat_completion",
+            0.into(),
+        )]))
+        .build();
+    let harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let prober = Prober::new(harness.app_factory.state.clone())
+        .unwrap()
+        .expect("probes are configured");
+    tokio::spawn(async move {
+        prober.run_forever().await.unwrap();
+    });
+    tokio::time::sleep(interval * 3).await;
+
+    east.verify().await;
+    west.verify().await;
+    // the provider's own base url isn't used for requests, so it isn't
+    // probed
+    let base_url_probes = harness
+        .mock
+        .openai_mock
+        .http_server
+        .received_requests()
+        .await
+        .unwrap_or_default();
+    assert!(base_url_probes.is_empty());
+}
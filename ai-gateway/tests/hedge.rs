@@ -0,0 +1,252 @@
+use std::{collections::HashMap, time::Duration};
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{
+            BalanceConfig, BalanceConfigInner, ModelBalanceConfig,
+            ModelBalanceGroup,
+        },
+        hedge::HedgeConfig,
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use indexmap::{IndexMap, IndexSet};
+use nonempty_collections::{nes, nev};
+use serde_json::json;
+use stubr::wiremock_rs::{Mock, ResponseTemplate, matchers};
+use tokio::time::Instant;
+use tower::Service;
+
+/// OpenAI is always selected by the load balancer, so hedged requests go to
+/// Anthropic.
+fn hedged_config() -> Config {
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Priority {
+                    tiers: nev![
+                        nes![InferenceProvider::OpenAI],
+                        nes![InferenceProvider::Anthropic]
+                    ],
+                },
+            )])),
+            hedge: Some(HedgeConfig {
+                enabled: true,
+                default_delay: Duration::from_millis(100),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )]));
+    config
+}
+
+async fn mount_slow_openai(harness: &Harness, delay: Duration) {
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "id": "chatcmpl-slow",
+                    "object": "chat.completion",
+                    "created": 1_741_569_952,
+                    "model": "gpt-4o-mini",
+                    "choices": [{
+                        "index": 0,
+                        "message": {
+                            "role": "assistant",
+                            "content": "Hello from the slow provider"
+                        },
+                        "finish_reason": "stop"
+                    }],
+                    "usage": {
+                        "prompt_tokens": 10,
+                        "completion_tokens": 5,
+                        "total_tokens": 15
+                    }
+                }))
+                .set_delay(delay),
+        )
+        .expect(1)
+        .mount(&harness.mock.openai_mock.http_server)
+        .await;
+}
+
+/// Returns the response status and the provider that served it.
+async fn chat_request(harness: &mut Harness) -> (StatusCode, String) {
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ]
+    }))
+    .unwrap();
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/chat/completions")
+        .body(axum_core::body::Body::from(body_bytes))
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    let status = response.status();
+    let provider = response
+        .headers()
+        .get("helicone-provider")
+        .map(|provider| provider.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let _response_body = response.into_body().collect().await.unwrap();
+    (status, provider)
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn fast_hedge_wins_over_slow_primary() {
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([("success:anthropic:messages", 1.into())]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(hedged_config())
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    mount_slow_openai(&harness, Duration::from_secs(5)).await;
+
+    let start = Instant::now();
+    let (status, provider) = chat_request(&mut harness).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(provider, "anthropic");
+    assert!(
+        start.elapsed() < Duration::from_secs(2),
+        "the hedged response should be used"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn failed_hedge_waits_for_primary() {
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([("error:anthropic:messages", 1.into())]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(hedged_config())
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let delay = Duration::from_millis(500);
+    mount_slow_openai(&harness, delay).await;
+
+    let start = Instant::now();
+    let (status, provider) = chat_request(&mut harness).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(provider, "openai");
+    assert!(
+        start.elapsed() >= delay,
+        "the primary response should be used"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn failed_primary_waits_for_hedge() {
+    let mut harness = Harness::builder()
+        .with_config(hedged_config())
+        .with_mock_args(MockArgs::builder().stubs(HashMap::new()).build())
+        .build()
+        .await;
+    // the primary fails after the hedged request was sent, but before the
+    // hedged request responds
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(500).set_delay(Duration::from_millis(300)),
+        )
+        .expect(1)
+        .mount(&harness.mock.openai_mock.http_server)
+        .await;
+    let delay = Duration::from_millis(800);
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/messages"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "id": "msg_slow",
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "claude-3-7-sonnet-20250219",
+                    "content": [{
+                        "type": "text",
+                        "text": "Hello from the hedge"
+                    }],
+                    "stop_reason": "end_turn",
+                    "stop_sequence": null,
+                    "usage": {
+                        "input_tokens": 10,
+                        "output_tokens": 5
+                    }
+                }))
+                .set_delay(delay),
+        )
+        .expect(1)
+        .mount(&harness.mock.anthropic_mock.http_server)
+        .await;
+
+    let start = Instant::now();
+    let (status, provider) = chat_request(&mut harness).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(provider, "anthropic");
+    assert!(
+        start.elapsed() >= delay,
+        "the hedged response should be waited for"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn model_group_hedges_to_its_own_providers() {
+    let mut config = hedged_config();
+    let router_config =
+        config.routers.as_mut().get_mut(&RouterId::Default).unwrap();
+    router_config.model_load_balance =
+        ModelBalanceConfig::from(IndexMap::from([(
+            "gpt-4o-mini".into(),
+            ModelBalanceGroup {
+                models: IndexSet::new(),
+                balance: BalanceConfigInner::Priority {
+                    tiers: nev![
+                        nes![InferenceProvider::OpenAI],
+                        nes![InferenceProvider::Ollama]
+                    ],
+                },
+            },
+        )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            // the endpoint level hedge target is never used
+            ("success:anthropic:messages", 0.into()),
+            ("success:ollama:chat_completions", 1.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    mount_slow_openai(&harness, Duration::from_secs(5)).await;
+
+    let (status, provider) = chat_request(&mut harness).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(provider, "ollama");
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner, BalanceTarget},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+
+fn image_balance_config(provider: InferenceProvider) -> BalanceConfig {
+    BalanceConfig::from(HashMap::from([(
+        EndpointType::Image,
+        BalanceConfigInner::Weighted {
+            providers: nes![BalanceTarget {
+                provider,
+                weight: Decimal::from(1),
+            }],
+        },
+    )]))
+}
+
+/// Returns the first generated image from the response.
+async fn generate_image(
+    provider: InferenceProvider,
+    stub: &'static str,
+) -> serde_json::Value {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're not testing authentication
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: image_balance_config(provider),
+            ..Default::default()
+        },
+    )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            (stub, 1.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+    let request_body = axum_core::body::Body::from(
+        serde_json::to_vec(&json!({
+            "model": "openai/dall-e-3",
+            "prompt": "A cute baby sea otter",
+            "n": 1,
+            "size": "1024x1024"
+        }))
+        .unwrap(),
+    );
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/images/generations")
+        .body(request_body)
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let mut body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    body["data"][0].take()
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_image_generations() {
+    let image = generate_image(
+        InferenceProvider::OpenAI,
+        "success:openai:image_generations",
+    )
+    .await;
+    assert!(image["url"].is_string());
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_image_generations_mapped_to_imagen() {
+    let image = generate_image(
+        InferenceProvider::GoogleGemini,
+        "success:gemini:image_generations",
+    )
+    .await;
+    assert!(image["b64_json"].is_string());
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_image_generations_mapped_to_bedrock_stability() {
+    let image = generate_image(
+        InferenceProvider::Bedrock,
+        "success:bedrock:invoke_model_image",
+    )
+    .await;
+    assert!(image["b64_json"].is_string());
+    assert!(image.get("url").is_none());
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use ai_gateway::{
     config::{
         Config,
-        balance::{BalanceConfig, BalanceConfigInner},
+        balance::{BalanceConfig, BalanceConfigInner, ModelBalanceConfig},
         helicone::HeliconeFeatures,
         router::{RouterConfig, RouterConfigs, RouterRateLimitConfig},
     },
@@ -30,9 +30,13 @@ fn p2c_config_openai_anthropic_google() -> RouterConfigs {
                     ],
                 },
             )])),
+            model_load_balance: ModelBalanceConfig::default(),
             model_mappings: None,
             cache: None,
             retries: None,
+            fallback: None,
+            hedge: None,
+            affinity: None,
             rate_limit: RouterRateLimitConfig::default(),
         },
     )]))
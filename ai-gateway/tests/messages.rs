@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner, BalanceTarget},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+
+async fn harness(provider: InferenceProvider, stub: &'static str) -> Harness {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're not testing authentication
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Weighted {
+                    providers: nes![BalanceTarget {
+                        provider,
+                        weight: Decimal::from(1),
+                    }],
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            (stub, 1.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await
+}
+
+async fn create_message(
+    harness: &mut Harness,
+    model: &str,
+) -> serde_json::Value {
+    let request_body = axum_core::body::Body::from(
+        serde_json::to_vec(&json!({
+            "model": model,
+            "max_tokens": 1024,
+            "system": "You are a helpful assistant.",
+            "messages": [
+                {
+                    "role": "user",
+                    "content": "Hello, world!"
+                }
+            ]
+        }))
+        .unwrap(),
+    );
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/v1/messages")
+        .header("content-type", "application/json")
+        .body(request_body)
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+fn message_text(message: &serde_json::Value) -> &str {
+    assert_eq!(message["type"], "message");
+    assert_eq!(message["role"], "assistant");
+    assert_eq!(message["content"][0]["type"], "text");
+    message["content"][0]["text"].as_str().unwrap()
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn anthropic_messages() {
+    let mut harness =
+        harness(InferenceProvider::Anthropic, "success:anthropic:messages")
+            .await;
+    // models without a provider prefix are taken to be Anthropic models
+    let message = create_message(&mut harness, "claude-3-7-sonnet").await;
+    assert_eq!(message_text(&message), "Hi! My name is Claude.");
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn messages_mapped_to_openai_chat_completions() {
+    let mut harness =
+        harness(InferenceProvider::OpenAI, "success:openai:chat_completion")
+            .await;
+    let message =
+        create_message(&mut harness, "anthropic/claude-3-7-sonnet").await;
+    assert_eq!(message_text(&message), "Hello! How can I assist you today?");
+    assert_eq!(message["usage"]["input_tokens"], 19);
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn messages_mapped_to_gemini() {
+    let mut harness = harness(
+        InferenceProvider::GoogleGemini,
+        "success:gemini:generate_content",
+    )
+    .await;
+    let message = create_message(&mut harness, "claude-3-7-sonnet").await;
+    assert!(message_text(&message).starts_with("Okay, let's break down"));
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{
+            BalanceConfig, BalanceConfigInner, ModelBalanceConfig,
+            ModelBalanceGroup,
+        },
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use indexmap::{IndexMap, IndexSet};
+use nonempty_collections::nes;
+use serde_json::json;
+use tower::Service;
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn model_balancer_takes_precedence_over_endpoint_balancer() {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're testing load balancing behavior
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Latency {
+                    providers: nes![InferenceProvider::OpenAI],
+                },
+            )])),
+            model_load_balance: ModelBalanceConfig::from(IndexMap::from([(
+                "gpt-4o-mini".into(),
+                ModelBalanceGroup {
+                    models: IndexSet::new(),
+                    balance: BalanceConfigInner::Latency {
+                        providers: nes![InferenceProvider::Anthropic],
+                    },
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    let requests = 4;
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:openai:chat_completion", requests.into()),
+            ("success:anthropic:messages", requests.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+
+    // `gpt-4o-mini` is balanced across its own providers, every other model
+    // falls back to the endpoint level balancer
+    for model in ["openai/gpt-4o-mini", "openai/gpt-4o"] {
+        let body_bytes = serde_json::to_vec(&json!({
+            "model": model,
+            "messages": [
+                {
+                    "role": "user",
+                    "content": "Hello, world!"
+                }
+            ]
+        }))
+        .unwrap();
+        for _ in 0..requests {
+            let request_body = axum_core::body::Body::from(body_bytes.clone());
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(
+                    "http://router.helicone.com/router/default/chat/completions",
+                )
+                .body(request_body)
+                .unwrap();
+            let response = harness.call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner, BalanceTarget},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+
+/// The named provider is served by the `OpenAI` mock, which we start on a
+/// known port so that it can be used as the provider's base url.
+const OPENAI_MOCK_PORT: u16 = 9191;
+
+fn groq() -> InferenceProvider {
+    InferenceProvider::Named("groq".into())
+}
+
+async fn harness() -> Harness {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're not testing authentication
+    config.helicone.features = HeliconeFeatures::None;
+    config.providers.insert(
+        groq(),
+        serde_json::from_value(json!({
+            "flavor": "openai-compatible",
+            "models": ["llama-3.1-8b-instant"],
+            "base-url": format!("http://localhost:{OPENAI_MOCK_PORT}/v1"),
+        }))
+        .unwrap(),
+    );
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Weighted {
+                    providers: nes![BalanceTarget {
+                        provider: groq(),
+                        weight: Decimal::from(1),
+                    }],
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:openai:chat_completion", 1.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .openai_port(OPENAI_MOCK_PORT)
+        .build();
+    Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await
+}
+
+fn chat_completion_request(uri: &str) -> Request<axum_core::body::Body> {
+    let request_body = axum_core::body::Body::from(
+        serde_json::to_vec(&json!({
+            "model": "groq/llama-3.1-8b-instant",
+            "messages": [
+                {
+                    "role": "user",
+                    "content": "Hello, world!"
+                }
+            ]
+        }))
+        .unwrap(),
+    );
+    Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(request_body)
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_compatible_provider_via_router() {
+    let mut harness = harness().await;
+    let request = chat_completion_request(
+        "http://router.helicone.com/router/default/chat/completions",
+    );
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        body["choices"][0]["message"]["content"],
+        "Hello! How can I assist you today?"
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_compatible_provider_direct_proxy() {
+    let mut harness = harness().await;
+    let request = chat_completion_request(
+        "http://router.helicone.com/groq/chat/completions",
+    );
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    discover::monitor::rate_limit::RateLimitMonitor,
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request};
+use http_body_util::BodyExt;
+use nonempty_collections::{nes, nev};
+use serde_json::json;
+use tower::Service;
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn rate_limited_tier_fails_over_to_next_tier() {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're testing load balancing behavior
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Priority {
+                    tiers: nev![
+                        nes![InferenceProvider::OpenAI],
+                        nes![InferenceProvider::Anthropic]
+                    ],
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    // OpenAI is preferred until it is rate limited, after which every request
+    // goes to the next tier
+    let requests = 10;
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("rate_limit:openai:chat_completion", 1.into()),
+            ("success:anthropic:messages", (requests - 1).into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+
+    let rate_limit_monitor =
+        RateLimitMonitor::new(harness.app_factory.state.clone());
+    tokio::spawn(async move {
+        rate_limit_monitor.run_forever().await.unwrap();
+    });
+    // Give time for the monitor to pick up the router (polls every 100ms in
+    // test mode)
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ]
+    }))
+    .unwrap();
+
+    for _ in 0..requests {
+        let request_body = axum_core::body::Body::from(body_bytes.clone());
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://router.helicone.com/router/default/chat/completions")
+            .body(request_body)
+            .unwrap();
+        let response = harness.call(request).await.unwrap();
+        let _response_body = response.into_body().collect().await.unwrap();
+        // let the monitor remove the rate limited provider
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+}
@@ -1,10 +1,12 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, num::NonZeroU32, time::Duration};
 
 use ai_gateway::{
     config::{
         Config,
         helicone::HeliconeFeatures,
-        rate_limit::{GlobalRateLimitConfig, RateLimitStore},
+        rate_limit::{
+            GcraConfig, GlobalRateLimitConfig, LimitsConfig, RateLimitStore,
+        },
     },
     control_plane::types::{Key, hash_key},
     tests::{TestDefault, harness::Harness, mock::MockArgs},
@@ -12,6 +14,7 @@ use ai_gateway::{
 use http::{Method, Request, StatusCode};
 use http_body_util::BodyExt;
 use serde_json::json;
+use stubr::wiremock_rs::{Mock, ResponseTemplate, matchers};
 use tower::Service;
 use uuid::Uuid;
 
@@ -57,6 +60,48 @@ async fn rate_limit_per_user_isolation_redis() {
     .await;
 }
 
+#[tokio::test]
+#[serial_test::serial]
+async fn token_rate_limit_reconciled_in_memory() {
+    token_rate_limit_reconciled_impl(
+        ai_gateway::config::rate_limit::store_enabled_for_test_in_memory(),
+        false,
+    )
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn token_rate_limit_reconciled_for_streams_in_memory() {
+    token_rate_limit_reconciled_impl(
+        ai_gateway::config::rate_limit::store_enabled_for_test_in_memory(),
+        true,
+    )
+    .await;
+}
+
+#[cfg(feature = "redis-testing")]
+#[tokio::test]
+#[serial_test::serial]
+async fn token_rate_limit_reconciled_redis() {
+    token_rate_limit_reconciled_impl(
+        ai_gateway::config::rate_limit::store_enabled_for_test_redis(),
+        false,
+    )
+    .await;
+}
+
+#[cfg(feature = "redis-testing")]
+#[tokio::test]
+#[serial_test::serial]
+async fn token_rate_limit_reconciled_for_streams_redis() {
+    token_rate_limit_reconciled_impl(
+        ai_gateway::config::rate_limit::store_enabled_for_test_redis(),
+        true,
+    )
+    .await;
+}
+
 async fn rate_limit_capacity_enforced_impl(
     rate_limit_store: RateLimitStore,
     rate_limit_config: GlobalRateLimitConfig,
@@ -221,6 +266,260 @@ async fn rate_limit_per_user_isolation_impl(
     tokio::time::sleep(std::time::Duration::from_millis(10)).await;
 }
 
+/// Each response uses 300 tokens, far more than the prompt tokens that are
+/// reserved for the request, so a limit of 1000 tokens allows 4 requests
+/// only if the usage is reconciled once each response completes.
+async fn token_rate_limit_reconciled_impl(
+    rate_limit_store: RateLimitStore,
+    stream: bool,
+) {
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::Auth;
+    config.global.rate_limit = Some(GlobalRateLimitConfig {
+        limits: Some(LimitsConfig {
+            per_api_key: Some(GcraConfig {
+                capacity: NonZeroU32::new(100).unwrap(),
+                refill_frequency: Duration::from_secs(1),
+            }),
+            per_api_key_tokens: Some(GcraConfig {
+                capacity: NonZeroU32::new(1000).unwrap(),
+                refill_frequency: Duration::from_secs(60 * 60),
+            }),
+            per_org: None,
+            per_model: None,
+            per_router_total: None,
+        }),
+        cleanup_interval: Duration::from_secs(60),
+    });
+    config.rate_limit_store = rate_limit_store;
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+
+    // a new key for each run, since Redis keeps the limit across runs
+    let auth = format!("sk-helicone-{}", Uuid::new_v4());
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .with_auth_keys(vec![Key {
+            key_hash: hash_key(&auth),
+            owner_id: Uuid::new_v4().to_string(),
+        }])
+        .build()
+        .await;
+
+    let usage = json!({
+        "prompt_tokens": 100,
+        "completion_tokens": 200,
+        "total_tokens": 300
+    });
+    let response = if stream {
+        let chunks = [
+            json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion.chunk",
+                "created": 1_741_569_952,
+                "model": "gpt-4o-mini",
+                "choices": [{
+                    "index": 0,
+                    "delta": { "role": "assistant", "content": "Hello!" },
+                    "finish_reason": "stop"
+                }]
+            }),
+            json!({
+                "id": "chatcmpl-1",
+                "object": "chat.completion.chunk",
+                "created": 1_741_569_952,
+                "model": "gpt-4o-mini",
+                "choices": [],
+                "usage": usage
+            }),
+        ];
+        let body: String = chunks
+            .iter()
+            .map(|chunk| format!("data: {chunk}\n\n"))
+            .chain(std::iter::once("data: [DONE]\n\n".to_string()))
+            .collect();
+        ResponseTemplate::new(200)
+            .insert_header("content-type", "text/event-stream")
+            .set_body_string(body)
+    } else {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "id": "chatcmpl-1",
+            "object": "chat.completion",
+            "created": 1_741_569_952,
+            "model": "gpt-4o-mini",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "Hello!" },
+                "finish_reason": "stop"
+            }],
+            "usage": usage
+        }))
+    };
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/chat/completions"))
+        .respond_with(response)
+        .expect(4)
+        .mount(&harness.mock.openai_mock.http_server)
+        .await;
+
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ],
+        "stream": stream
+    }))
+    .unwrap();
+    let reserved = u64::try_from(body_bytes.len() / 4).unwrap();
+    let auth_header = format!("Bearer {auth}");
+
+    for i in 0..4 {
+        let request = Request::builder()
+            .method(Method::POST)
+            .header("authorization", &auth_header)
+            .uri("http://router.helicone.com/router/default/chat/completions")
+            .body(axum_core::body::Body::from(body_bytes.clone()))
+            .unwrap();
+        let response = harness.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "request {i}");
+        let remaining: u64 = response
+            .headers()
+            .get("x-ratelimit-remaining-tokens")
+            .expect("remaining tokens header should be present")
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        // the previous requests were charged for their usage rather than
+        // their reservation, give or take a token that has been refilled
+        // since
+        let expected = 1000 - 300 * i - reserved;
+        assert!(
+            (expected..=expected + 1).contains(&remaining),
+            "request {i}: expected {expected} remaining tokens, got \
+             {remaining}"
+        );
+        let _body = response.into_body().collect().await.unwrap();
+    }
+
+    let request = Request::builder()
+        .method(Method::POST)
+        .header("authorization", &auth_header)
+        .uri("http://router.helicone.com/router/default/chat/completions")
+        .body(axum_core::body::Body::from(body_bytes))
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let _body = response.into_body().collect().await.unwrap();
+
+    harness.mock.verify().await;
+}
+
+/// Limits every scope, with the given one being the most restrictive.
+fn scoped_limits(most_restrictive: &str) -> LimitsConfig {
+    let gcra = |scope: &str| {
+        let capacity = if scope == most_restrictive { 2 } else { 5 };
+        Some(GcraConfig {
+            capacity: NonZeroU32::new(capacity).unwrap(),
+            refill_frequency: Duration::from_secs(60 * 60),
+        })
+    };
+    LimitsConfig {
+        per_api_key: gcra("per-api-key"),
+        per_api_key_tokens: None,
+        per_org: gcra("per-org"),
+        per_model: gcra("per-model"),
+        per_router_total: gcra("per-router-total"),
+    }
+}
+
+/// The scope's limit of 2 requests must be enforced and reported.
+async fn assert_scope_enforced(limits: LimitsConfig, scope: &str) {
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::Auth;
+    config.global.rate_limit = Some(GlobalRateLimitConfig {
+        limits: Some(limits),
+        cleanup_interval: Duration::from_secs(60),
+    });
+    config.rate_limit_store = RateLimitStore::InMemory;
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:openai:chat_completion", 2.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let auth = format!("sk-helicone-{}", Uuid::new_v4());
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .with_auth_keys(vec![Key {
+            key_hash: hash_key(&auth),
+            owner_id: Uuid::new_v4().to_string(),
+        }])
+        .build()
+        .await;
+    let auth_header = format!("Bearer {auth}");
+
+    for remaining in ["1", "0"] {
+        let response = make_chat_request(&mut harness, &auth_header).await;
+        assert_eq!(response.status(), StatusCode::OK, "{scope}");
+        assert_eq!(response.headers()["x-ratelimit-scope"], scope);
+        assert_eq!(
+            response.headers()["x-ratelimit-remaining"],
+            remaining,
+            "{scope}"
+        );
+        let _body = response.into_body().collect().await.unwrap();
+    }
+    let response = make_chat_request(&mut harness, &auth_header).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS, "{scope}");
+    let _body = response.into_body().collect().await.unwrap();
+
+    harness.mock.verify().await;
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn every_scope_is_enforced_and_most_restrictive_is_reported() {
+    for scope in ["per-api-key", "per-org", "per-model", "per-router-total"] {
+        assert_scope_enforced(scoped_limits(scope), scope).await;
+    }
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn scopes_are_enforced_without_a_per_api_key_limit() {
+    let gcra = GcraConfig {
+        capacity: NonZeroU32::new(2).unwrap(),
+        refill_frequency: Duration::from_secs(60 * 60),
+    };
+    assert_scope_enforced(
+        LimitsConfig {
+            per_org: Some(gcra.clone()),
+            ..Default::default()
+        },
+        "per-org",
+    )
+    .await;
+    assert_scope_enforced(
+        LimitsConfig {
+            per_model: Some(gcra),
+            ..Default::default()
+        },
+        "per-model",
+    )
+    .await;
+}
+
 #[tokio::test]
 #[serial_test::serial]
 async fn rate_limit_disabled() {
@@ -21,10 +21,14 @@ use tower::Service;
 
 fn create_test_limits(capacity: u32, duration_ms: u64) -> LimitsConfig {
     LimitsConfig {
-        per_api_key: GcraConfig {
+        per_api_key: Some(GcraConfig {
             capacity: capacity.try_into().unwrap(),
             refill_frequency: Duration::from_millis(duration_ms),
-        },
+        }),
+        per_api_key_tokens: None,
+        per_org: None,
+        per_model: None,
+        per_router_total: None,
     }
 }
 
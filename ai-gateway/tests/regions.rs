@@ -0,0 +1,210 @@
+use std::{collections::HashMap, time::Duration};
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner},
+        helicone::HeliconeFeatures,
+        monitor::{GracePeriod, HealthMonitorConfig},
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use opentelemetry_sdk::metrics::{
+    InMemoryMetricExporter, SdkMeterProvider, data::Sum,
+};
+use rust_decimal::Decimal;
+use serde_json::json;
+use stubr::wiremock_rs::{Mock, MockServer, ResponseTemplate, matchers};
+use tower::Service;
+
+/// East is slower than the default RTT of west, so west is picked by
+/// latency until it is unhealthy.
+async fn east_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/chat/completions"))
+        .and(matchers::header("authorization", "Bearer sk-east-us"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({
+                    "id": "chatcmpl-east",
+                    "object": "chat.completion",
+                    "created": 1_741_569_952,
+                    "model": "gpt-4o-mini",
+                    "choices": [{
+                        "index": 0,
+                        "message": {
+                            "role": "assistant",
+                            "content": "Hello from east"
+                        },
+                        "finish_reason": "stop"
+                    }],
+                    "usage": {
+                        "prompt_tokens": 10,
+                        "completion_tokens": 5,
+                        "total_tokens": 15
+                    }
+                }))
+                .set_delay(Duration::from_millis(50)),
+        )
+        .mount(&server)
+        .await;
+    server
+}
+
+async fn west_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/v1/chat/completions"))
+        // regions without their own key use the provider's key
+        .and(matchers::header("authorization", "Bearer sk-..."))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn regions_config(east: &MockServer, west: &MockServer) -> Config {
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::None;
+    config.discover.monitor.health = HealthMonitorConfig::ErrorRatio {
+        ratio: Decimal::try_from(0.5).unwrap(),
+        window: Duration::from_secs(60),
+        buckets: 10,
+        interval: Duration::from_secs(60),
+        grace_period: GracePeriod::Requests { min_requests: 2 },
+    };
+    config
+        .providers
+        .get_mut(&InferenceProvider::OpenAI)
+        .unwrap()
+        .regions = serde_json::from_value(json!({
+        "east-us": { "base-url": east.uri() },
+        "west-us": { "base-url": west.uri() },
+    }))
+    .unwrap();
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Latency {
+                    providers: nes![InferenceProvider::OpenAI],
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    config
+}
+
+async fn chat_request(harness: &mut Harness) -> StatusCode {
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ]
+    }))
+    .unwrap();
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/chat/completions")
+        .body(axum_core::body::Body::from(body_bytes))
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    let status = response.status();
+    let _body = response.into_body().collect().await.unwrap();
+    status
+}
+
+async fn request_count(server: &MockServer) -> usize {
+    server.received_requests().await.unwrap_or_default().len()
+}
+
+fn in_memory_metrics() -> (SdkMeterProvider, InMemoryMetricExporter) {
+    let exporter = InMemoryMetricExporter::default();
+    let provider = SdkMeterProvider::builder()
+        .with_periodic_exporter(exporter.clone())
+        .build();
+    opentelemetry::global::set_meter_provider(provider.clone());
+    (provider, exporter)
+}
+
+fn region_response_count(
+    provider: &SdkMeterProvider,
+    exporter: &InMemoryMetricExporter,
+    region: &str,
+) -> u64 {
+    provider.force_flush().unwrap();
+    let metrics = exporter.get_finished_metrics().unwrap();
+    // counters are cumulative, so only the latest export is needed
+    metrics
+        .last()
+        .into_iter()
+        .flat_map(|resource| &resource.scope_metrics)
+        .flat_map(|scope| &scope.metrics)
+        .filter(|metric| metric.name == "response_count")
+        .filter_map(|metric| metric.data.as_any().downcast_ref::<Sum<u64>>())
+        .flat_map(|sum| &sum.data_points)
+        .filter(|point| {
+            point.attributes.iter().any(|attribute| {
+                attribute.key.as_str() == "region"
+                    && attribute.value.as_str() == region
+            })
+        })
+        .map(|point| point.value)
+        .sum()
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn unhealthy_region_is_skipped() {
+    // SAFETY: tests that read provider keys from the environment run
+    // serially
+    unsafe {
+        std::env::set_var("OPENAI_API_KEY_EAST_US", "sk-east-us");
+    }
+    let (meter_provider, exporter) = in_memory_metrics();
+    let east = east_server().await;
+    let west = west_server().await;
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([(
+            "success:openai:chat_completion",
+            0.into(),
+        )]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(regions_config(&east, &west))
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+
+    // west is unhealthy once it has failed the grace period's requests
+    for _ in 0..20 {
+        if request_count(&west).await >= 2 {
+            break;
+        }
+        chat_request(&mut harness).await;
+    }
+    assert_eq!(request_count(&west).await, 2);
+
+    let east_requests = request_count(&east).await;
+    for _ in 0..5 {
+        assert_eq!(chat_request(&mut harness).await, StatusCode::OK);
+    }
+    assert_eq!(request_count(&west).await, 2);
+    assert_eq!(request_count(&east).await, east_requests + 5);
+    assert!(region_response_count(&meter_provider, &exporter, "east-us") >= 5);
+
+    // the provider's own base url isn't used for requests
+    harness.mock.verify().await;
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner, BalanceTarget},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+
+async fn harness(provider: InferenceProvider, stub: &'static str) -> Harness {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're not testing authentication
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Weighted {
+                    providers: nes![BalanceTarget {
+                        provider,
+                        weight: Decimal::from(1),
+                    }],
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            (stub, 1.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await
+}
+
+async fn create_response(
+    harness: &mut Harness,
+    uri: &str,
+    model: &str,
+) -> serde_json::Value {
+    let request_body = axum_core::body::Body::from(
+        serde_json::to_vec(&json!({
+            "model": model,
+            "instructions": "You are a helpful assistant.",
+            "input": "Hello, world!"
+        }))
+        .unwrap(),
+    );
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(request_body)
+        .unwrap();
+    let response = harness.call(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    serde_json::from_slice(&body).unwrap()
+}
+
+fn output_text(response: &serde_json::Value) -> &str {
+    assert_eq!(response["object"], "response");
+    assert_eq!(response["output"][0]["type"], "message");
+    response["output"][0]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn openai_responses() {
+    let mut harness =
+        harness(InferenceProvider::OpenAI, "success:openai:responses").await;
+    let response = create_response(
+        &mut harness,
+        "http://router.helicone.com/router/default/responses",
+        "openai/gpt-4o-mini",
+    )
+    .await;
+    assert_eq!(output_text(&response), "Hi! How can I help you today?");
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn responses_mapped_to_anthropic_messages() {
+    let mut harness =
+        harness(InferenceProvider::Anthropic, "success:anthropic:messages")
+            .await;
+    let response = create_response(
+        &mut harness,
+        "http://router.helicone.com/router/default/responses",
+        "openai/gpt-4o-mini",
+    )
+    .await;
+    assert_eq!(output_text(&response), "Hi! My name is Claude.");
+    assert_eq!(response["usage"]["input_tokens"], 2095);
+}
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn unified_api_responses() {
+    let mut harness =
+        harness(InferenceProvider::Anthropic, "success:anthropic:messages")
+            .await;
+    let response = create_response(
+        &mut harness,
+        "http://router.helicone.com/ai/responses",
+        "anthropic/claude-sonnet-4-0",
+    )
+    .await;
+    assert_eq!(output_text(&response), "Hi! My name is Claude.");
+}
@@ -0,0 +1,112 @@
+use std::{collections::HashMap, time::Duration};
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::BalanceConfig,
+        helicone::HeliconeFeatures,
+        retry::{RetryConfig, Strategy},
+        router::{RouterConfig, RouterConfigs},
+    },
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::router::RouterId,
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use serde_json::json;
+use tower::Service;
+
+fn config_with_retries(load_balance: BalanceConfig, max_retries: u8) -> Config {
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're testing retries
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance,
+            retries: Some(RetryConfig {
+                enabled: true,
+                max_retries,
+                strategy: Strategy::Exponential {
+                    base: Duration::from_millis(1),
+                    max: Duration::from_millis(5),
+                },
+            }),
+            ..Default::default()
+        },
+    )]));
+    config
+}
+
+fn chat_request() -> Request<axum_core::body::Body> {
+    let request_body = axum_core::body::Body::from(
+        serde_json::to_vec(&json!({
+            "model": "openai/gpt-4o-mini",
+            "messages": [
+                {
+                    "role": "user",
+                    "content": "Hello, world!"
+                }
+            ]
+        }))
+        .unwrap(),
+    );
+    Request::builder()
+        .method(Method::POST)
+        .uri("http://router.helicone.com/router/default/chat/completions")
+        .body(request_body)
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn upstream_errors_are_retried_up_to_max_retries() {
+    let config = config_with_retries(BalanceConfig::anthropic_chat(), 2);
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            // the initial attempt plus two retries
+            ("error:anthropic:messages", 3.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+
+    let response = harness.call(chat_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        response.headers().get("helicone-retry-attempts").unwrap(),
+        "2"
+    );
+    let _response_body = response.into_body().collect().await.unwrap();
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn successful_requests_are_not_retried() {
+    let config = config_with_retries(BalanceConfig::openai_chat(), 2);
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            ("success:openai:chat_completion", 1.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+
+    let response = harness.call(chat_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("helicone-retry-attempts").unwrap(),
+        "0"
+    );
+    let _response_body = response.into_body().collect().await.unwrap();
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        helicone::HeliconeFeatures,
+        spend::{BudgetConfig, BudgetPeriod, SpendConfig},
+    },
+    control_plane::types::{Key, hash_key},
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+use uuid::Uuid;
+
+/// Each `openai/gpt-4o-mini` stub response uses 19 prompt and 10 completion
+/// tokens, which costs $0.00000885, or 9 micro-dollars once rounded, so the
+/// budget of $0.000015 is spent by the second request, and its soft limit by
+/// the first.
+fn budget_config() -> Config {
+    let mut config = Config::test_default();
+    config.helicone.features = HeliconeFeatures::Auth;
+    config.global.rate_limit = None;
+    config.global.spend = Some(SpendConfig {
+        per_api_key: Some(BudgetConfig {
+            limit: Decimal::new(15, 6),
+            period: BudgetPeriod::Daily,
+            soft_limit: Some(Decimal::new(5, 1)),
+        }),
+        per_org: None,
+    });
+    config
+}
+
+/// Returns the harness along with the authorization header of a new key, so
+/// that each run has its own budget.
+async fn harness(
+    stubs: HashMap<&'static str, stubr::wiremock_rs::Times>,
+) -> (Harness, String) {
+    let mock_args = MockArgs::builder().stubs(stubs).build();
+    let auth = format!("sk-helicone-{}", Uuid::new_v4());
+    let harness = Harness::builder()
+        .with_config(budget_config())
+        .with_mock_args(mock_args)
+        .with_auth_keys(vec![Key {
+            key_hash: hash_key(&auth),
+            owner_id: Uuid::new_v4().to_string(),
+        }])
+        .build()
+        .await;
+    (harness, format!("Bearer {auth}"))
+}
+
+async fn make_chat_request(
+    harness: &mut Harness,
+    auth_header: &str,
+    cache_control: Option<&str>,
+) -> http::Response<
+    tower_http::body::UnsyncBoxBody<
+        bytes::Bytes,
+        Box<dyn std::error::Error + Send + Sync + 'static>,
+    >,
+> {
+    let body_bytes = serde_json::to_vec(&json!({
+        "model": "openai/gpt-4o-mini",
+        "messages": [
+            {
+                "role": "user",
+                "content": "Hello, world!"
+            }
+        ]
+    }))
+    .unwrap();
+
+    let mut builder = Request::builder()
+        .method(Method::POST)
+        .header("authorization", auth_header)
+        .uri("http://router.helicone.com/router/default/chat/completions");
+    if let Some(cache_control) = cache_control {
+        builder = builder.header("cache-control", cache_control);
+    }
+    let request = builder
+        .body(axum_core::body::Body::from(body_bytes))
+        .unwrap();
+    harness.call(request).await.unwrap()
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn exhausted_budget_is_rejected_after_soft_limit_warning() {
+    let (mut harness, auth_header) = harness(HashMap::from([
+        ("success:openai:chat_completion", 2.into()),
+        ("success:minio:upload_request", 0.into()),
+        ("success:jawn:log_request", 0.into()),
+    ]))
+    .await;
+
+    let response = make_chat_request(&mut harness, &auth_header, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("x-budget-warning").is_none());
+    // the spend is accrued once the body has been read
+    let _body = response.into_body().collect().await.unwrap();
+
+    let response = make_chat_request(&mut harness, &auth_header, None).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let warning = response.headers()["x-budget-warning"].to_str().unwrap();
+    assert!(warning.starts_with("daily per-api-key budget is 60% spent"));
+    let _body = response.into_body().collect().await.unwrap();
+
+    let response = make_chat_request(&mut harness, &auth_header, None).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["error"]["code"], "insufficient_quota");
+
+    harness.mock.verify().await;
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn cache_hits_are_not_charged() {
+    let (mut harness, auth_header) = harness(HashMap::from([
+        ("success:openai:chat_completion_cacheable", 1.into()),
+        ("success:minio:upload_request", 0.into()),
+        ("success:jawn:log_request", 0.into()),
+    ]))
+    .await;
+
+    for cache in ["MISS", "HIT", "HIT"] {
+        let response =
+            make_chat_request(&mut harness, &auth_header, Some("max-age=3600"))
+                .await;
+        assert_eq!(response.status(), StatusCode::OK, "{cache}");
+        assert_eq!(response.headers()["helicone-cache"], cache);
+        let _body = response.into_body().collect().await.unwrap();
+    }
+
+    harness.mock.verify().await;
+}
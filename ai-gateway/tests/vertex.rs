@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use ai_gateway::{
+    config::{
+        Config,
+        balance::{BalanceConfig, BalanceConfigInner, BalanceTarget},
+        helicone::HeliconeFeatures,
+        router::{RouterConfig, RouterConfigs},
+    },
+    endpoints::EndpointType,
+    tests::{TestDefault, harness::Harness, mock::MockArgs},
+    types::{provider::InferenceProvider, router::RouterId},
+};
+use http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use nonempty_collections::nes;
+use rust_decimal::Decimal;
+use serde_json::json;
+use tower::Service;
+
+#[tokio::test]
+#[serial_test::serial(default_mock)]
+async fn vertex_access_token_is_cached() {
+    let key_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/vertex-service-account.json"
+    );
+    // SAFETY: tests sharing the default mock run serially
+    unsafe {
+        std::env::set_var("GOOGLE_APPLICATION_CREDENTIALS", key_path);
+    }
+    let mut config = Config::test_default();
+    // Disable auth for this test since we're not testing authentication
+    config.helicone.features = HeliconeFeatures::None;
+    config.routers = RouterConfigs::new(HashMap::from([(
+        RouterId::Default,
+        RouterConfig {
+            load_balance: BalanceConfig::from(HashMap::from([(
+                EndpointType::Chat,
+                BalanceConfigInner::Weighted {
+                    providers: nes![BalanceTarget {
+                        provider: InferenceProvider::GoogleVertex,
+                        weight: Decimal::from(1),
+                    }],
+                },
+            )])),
+            ..Default::default()
+        },
+    )]));
+    let mock_args = MockArgs::builder()
+        .stubs(HashMap::from([
+            // the token is minted once and reused for the second request
+            ("success:vertex:token", 1.into()),
+            ("success:vertex:chat_completion", 2.into()),
+            ("success:minio:upload_request", 0.into()),
+            ("success:jawn:log_request", 0.into()),
+        ]))
+        .build();
+    let mut harness = Harness::builder()
+        .with_config(config)
+        .with_mock_args(mock_args)
+        .build()
+        .await;
+
+    for _ in 0..2 {
+        let request_body = axum_core::body::Body::from(
+            serde_json::to_vec(&json!({
+                "model": "openai/gpt-4o-mini",
+                "messages": [
+                    {
+                        "role": "user",
+                        "content": "Hello, world!"
+                    }
+                ]
+            }))
+            .unwrap(),
+        );
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("http://router.helicone.com/router/default/chat/completions")
+            .body(request_body)
+            .unwrap();
+        let response = harness.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body["choices"][0]["message"]["content"],
+            "Hello! How can I assist you today?"
+        );
+    }
+}
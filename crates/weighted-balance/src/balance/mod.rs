@@ -218,6 +218,48 @@ where
             }
         }
     }
+
+    /// Samples another ready service by weight if the one at `index` is
+    /// excluded, keeping `index` if every ready service is.
+    fn allowed_index(
+        &mut self,
+        index: usize,
+        exclude: impl Fn(&D::Key) -> bool,
+    ) -> usize {
+        let key =
+            |idx| self.services.get_ready_index(idx).expect("invalid index").0;
+        if !exclude(key(index)) {
+            return index;
+        }
+        let allowed = (0..self.services.ready_len())
+            .filter(|idx| !exclude(key(*idx)))
+            .collect::<Vec<_>>();
+        if allowed.is_empty() {
+            return index;
+        }
+        rand::seq::index::sample_weighted(
+            &mut self.rng,
+            allowed.len(),
+            |idx| key(allowed[idx]).weight(),
+            1,
+        )
+        .map_or(index, |sample| allowed[sample.index(0)])
+    }
+
+    /// Like [`Service::call`], except the request isn't sent to a service
+    /// whose key is excluded, unless every ready service is.
+    pub fn call_excluding(
+        &mut self,
+        request: Req,
+        exclude: impl Fn(&D::Key) -> bool,
+    ) -> <Self as Service<Req>>::Future {
+        tracing::trace!("WeightedBalance::call_excluding");
+        let index = self.ready_index.take().expect("called before ready");
+        let index = self.allowed_index(index, exclude);
+        self.services
+            .call_ready_index(index, request)
+            .map_err(Into::into)
+    }
 }
 
 impl<D, Req> Service<Req> for WeightedBalance<D, Req>
@@ -290,3 +332,73 @@ where
             .map_err(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use futures::{future, stream};
+    use tower::{ServiceExt, service_fn};
+
+    use super::*;
+    use crate::weight::Weight;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Key(u32);
+
+    impl HasWeight for Key {
+        fn weight(&self) -> Weight {
+            Weight::UNIT
+        }
+    }
+
+    fn discover(
+        keys: &[u32],
+    ) -> impl Discover<
+        Key = Key,
+        Service = impl Service<(), Response = u32, Error = Infallible>,
+        Error = Infallible,
+    > + Unpin {
+        let changes = keys
+            .iter()
+            .map(|key| {
+                let key = *key;
+                let service = service_fn(move |()| {
+                    future::ready(Ok::<_, Infallible>(key))
+                });
+                Ok::<_, Infallible>(Change::Insert(Key(key), service))
+            })
+            .collect::<Vec<_>>();
+        stream::iter(changes)
+    }
+
+    #[tokio::test]
+    async fn excluded_keys_are_skipped() {
+        let mut balance: WeightedBalance<_, ()> =
+            WeightedBalance::new(discover(&[1, 2, 3]));
+        for _ in 0..20 {
+            let key = balance
+                .ready()
+                .await
+                .unwrap()
+                .call_excluding((), |key| key.0 != 3)
+                .await
+                .unwrap();
+            assert_eq!(key, 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn falls_back_when_every_key_is_excluded() {
+        let mut balance: WeightedBalance<_, ()> =
+            WeightedBalance::new(discover(&[1, 2]));
+        let key = balance
+            .ready()
+            .await
+            .unwrap()
+            .call_excluding((), |_| true)
+            .await
+            .unwrap();
+        assert!([1, 2].contains(&key));
+    }
+}
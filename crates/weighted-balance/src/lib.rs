@@ -1,2 +1,3 @@
 pub mod balance;
+pub mod p2c;
 pub mod weight;
@@ -0,0 +1,389 @@
+//! Copyright (c) 2019 Tower Contributors
+//!
+//! Permission is hereby granted, free of charge, to any
+//! person obtaining a copy of this software and associated
+//! documentation files (the "Software"), to deal in the
+//! Software without restriction, including without
+//! limitation the rights to use, copy, modify, merge,
+//! publish, distribute, sublicense, and/or sell copies of
+//! the Software, and to permit persons to whom the Software
+//! is furnished to do so, subject to the following
+//! conditions:
+//!
+//! The above copyright notice and this permission notice
+//! shall be included in all copies or substantial portions
+//! of the Software.
+//!
+//! THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+//! ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+//! TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+//! PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+//! SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+//! CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+//! OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+//! IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+//! DEALINGS IN THE SOFTWARE.
+//!
+//! The power of two choices balancer from `tower`, which can also skip
+//! excluded services for individual requests.
+use std::{
+    fmt,
+    hash::Hash,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{
+    future::{self, TryFutureExt},
+    ready,
+};
+use rand::{SeedableRng, rngs::SmallRng};
+use tower::{
+    Service,
+    discover::{Change, Discover},
+    load::Load,
+    ready_cache::{ReadyCache, error::Failed},
+};
+use tracing::{debug, trace};
+
+use crate::balance::Error;
+
+/// Distributes requests to the less loaded of two randomly chosen ready
+/// services, like [`tower::balance::p2c::Balance`].
+///
+/// Like [`WeightedBalance`], this requires that the [`Discover`] you use is
+/// [`Unpin`] in order to implement [`Service`].
+///
+/// [`WeightedBalance`]: crate::balance::WeightedBalance
+pub struct P2cBalance<D, Req>
+where
+    D: Discover,
+    D::Key: Hash,
+{
+    discover: D,
+
+    services: ReadyCache<D::Key, D::Service, Req>,
+    ready_index: Option<usize>,
+
+    rng: SmallRng,
+
+    _req: PhantomData<Req>,
+}
+
+impl<D: Discover, Req> fmt::Debug for P2cBalance<D, Req>
+where
+    D: fmt::Debug,
+    D::Key: Hash + fmt::Debug,
+    D::Service: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("P2cBalance")
+            .field("discover", &self.discover)
+            .field("services", &self.services)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<D, Req> P2cBalance<D, Req>
+where
+    D: Discover,
+    D::Key: Hash,
+    D::Service: Service<Req>,
+    <D::Service as Service<Req>>::Error: Into<tower::BoxError>,
+{
+    pub fn new(discover: D) -> Self {
+        tracing::trace!("P2cBalance::new");
+        Self {
+            rng: SmallRng::from_rng(&mut rand::rng()),
+            discover,
+            services: ReadyCache::default(),
+            ready_index: None,
+
+            _req: PhantomData,
+        }
+    }
+
+    /// Returns the number of endpoints currently tracked by the balancer.
+    pub fn len(&self) -> usize {
+        self.services.len()
+    }
+
+    /// Returns whether or not the balancer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.services.is_empty()
+    }
+}
+
+impl<D, Req> P2cBalance<D, Req>
+where
+    D: Discover + Unpin,
+    D::Key: Hash + Clone,
+    D::Error: Into<tower::BoxError>,
+    D::Service: Service<Req> + Load,
+    <D::Service as Load>::Metric: fmt::Debug,
+    <D::Service as Service<Req>>::Error: Into<tower::BoxError>,
+{
+    /// Polls `discover` for updates, adding new items to `not_ready`.
+    ///
+    /// Removals may alter the order of either `ready` or `not_ready`.
+    fn update_pending_from_discover(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<(), Error>>> {
+        debug!("updating from discover");
+        loop {
+            match ready!(Pin::new(&mut self.discover).poll_discover(cx))
+                .transpose()
+                .map_err(|e| Error::Discover(e.into()))?
+            {
+                None => return Poll::Ready(None),
+                Some(Change::Remove(key)) => {
+                    trace!("remove");
+                    self.services.evict(&key);
+                }
+                Some(Change::Insert(key, svc)) => {
+                    trace!("insert");
+                    // If this service already existed in the set, it will be
+                    // replaced as the new one becomes ready.
+                    self.services.push(key, svc);
+                }
+            }
+        }
+    }
+
+    fn promote_pending_to_ready(&mut self, cx: &mut Context<'_>) {
+        loop {
+            match self.services.poll_pending(cx) {
+                Poll::Ready(Ok(())) => {
+                    // There are no remaining pending services.
+                    debug_assert_eq!(self.services.pending_len(), 0);
+                    break;
+                }
+                Poll::Pending => {
+                    // None of the pending services are ready.
+                    debug_assert!(self.services.pending_len() > 0);
+                    break;
+                }
+                Poll::Ready(Err(error)) => {
+                    // An individual service was lost; continue processing
+                    // pending services.
+                    debug!(%error, "dropping failed endpoint");
+                }
+            }
+        }
+        trace!(
+            ready = %self.services.ready_len(),
+            pending = %self.services.pending_len(),
+            "poll_unready"
+        );
+    }
+
+    /// The less loaded of two services sampled from `len` candidates, where
+    /// `candidate` maps each to the index of a ready service.
+    fn p2c_index(
+        &mut self,
+        len: usize,
+        candidate: impl Fn(usize) -> usize,
+    ) -> Option<usize> {
+        match len {
+            0 => None,
+            1 => Some(candidate(0)),
+            len => {
+                let sample = rand::seq::index::sample(&mut self.rng, len, 2);
+                let aidx = candidate(sample.index(0));
+                let bidx = candidate(sample.index(1));
+
+                let aload = self.ready_index_load(aidx);
+                let bload = self.ready_index_load(bidx);
+                let chosen = if aload <= bload { aidx } else { bidx };
+
+                trace!(
+                    a.index = aidx,
+                    a.load = ?aload,
+                    b.index = bidx,
+                    b.load = ?bload,
+                    chosen = if chosen == aidx { "a" } else { "b" },
+                    "p2c",
+                );
+                Some(chosen)
+            }
+        }
+    }
+
+    fn ready_index_load(&self, index: usize) -> <D::Service as Load>::Metric {
+        let (_, svc) =
+            self.services.get_ready_index(index).expect("invalid index");
+        svc.load()
+    }
+
+    /// Chooses another ready service if the one at `index` is excluded,
+    /// keeping `index` if every ready service is.
+    fn allowed_index(
+        &mut self,
+        index: usize,
+        exclude: impl Fn(&D::Key) -> bool,
+    ) -> usize {
+        let key =
+            |idx| self.services.get_ready_index(idx).expect("invalid index").0;
+        if !exclude(key(index)) {
+            return index;
+        }
+        let allowed = (0..self.services.ready_len())
+            .filter(|idx| !exclude(key(*idx)))
+            .collect::<Vec<_>>();
+        self.p2c_index(allowed.len(), |idx| allowed[idx])
+            .unwrap_or(index)
+    }
+
+    /// Like [`Service::call`], except the request isn't sent to a service
+    /// whose key is excluded, unless every ready service is.
+    pub fn call_excluding(
+        &mut self,
+        request: Req,
+        exclude: impl Fn(&D::Key) -> bool,
+    ) -> <Self as Service<Req>>::Future {
+        tracing::trace!("P2cBalance::call_excluding");
+        let index = self.ready_index.take().expect("called before ready");
+        let index = self.allowed_index(index, exclude);
+        self.services
+            .call_ready_index(index, request)
+            .map_err(Into::into)
+    }
+}
+
+impl<D, Req> Service<Req> for P2cBalance<D, Req>
+where
+    D: Discover + Unpin,
+    D::Key: Hash + Clone,
+    D::Error: Into<tower::BoxError>,
+    D::Service: Service<Req> + Load,
+    <D::Service as Load>::Metric: fmt::Debug,
+    <D::Service as Service<Req>>::Error: Into<tower::BoxError>,
+{
+    type Response = <D::Service as Service<Req>>::Response;
+    type Error = tower::BoxError;
+    type Future = future::MapErr<
+        <D::Service as Service<Req>>::Future,
+        fn(<D::Service as Service<Req>>::Error) -> tower::BoxError,
+    >;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        tracing::trace!("P2cBalance::poll_ready");
+        // `ready_index` may have already been set by a prior invocation. These
+        // updates cannot disturb the order of existing ready services.
+        let _ = self.update_pending_from_discover(cx)?;
+        self.promote_pending_to_ready(cx);
+
+        loop {
+            // If a service has already been selected, ensure that it is ready.
+            // This ensures that the underlying service is ready immediately
+            // before a request is dispatched to it (i.e. in the same task
+            // invocation). If, e.g., a failure detector has changed the state
+            // of the service, it may be evicted from the ready set so that
+            // another service can be selected.
+            if let Some(index) = self.ready_index.take() {
+                match self.services.check_ready_index(cx, index) {
+                    Ok(true) => {
+                        // The service remains ready.
+                        self.ready_index = Some(index);
+                        return Poll::Ready(Ok(()));
+                    }
+                    Ok(false) => {
+                        // The service is no longer ready. Try to find a new
+                        // one.
+                        trace!("ready service became unavailable");
+                    }
+                    Err(Failed(_, error)) => {
+                        // The ready endpoint failed, so log the error and try
+                        // to find a new one.
+                        debug!(%error, "endpoint failed");
+                    }
+                }
+            }
+
+            self.ready_index =
+                self.p2c_index(self.services.ready_len(), |idx| idx);
+            if self.ready_index.is_none() {
+                debug_assert_eq!(self.services.ready_len(), 0);
+                // We have previously registered interest in updates from
+                // discover and pending services.
+                return Poll::Pending;
+            }
+        }
+    }
+
+    fn call(&mut self, request: Req) -> Self::Future {
+        tracing::trace!("P2cBalance::call");
+        let index = self.ready_index.take().expect("called before ready");
+        self.services
+            .call_ready_index(index, request)
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use futures::{future, stream};
+    use tower::{ServiceExt, load::Constant, service_fn};
+
+    use super::*;
+
+    fn discover(
+        keys: &[u32],
+    ) -> impl Discover<
+        Key = u32,
+        Service = impl Service<(), Response = u32, Error = Infallible>
+                  + Load<Metric = i32>,
+        Error = Infallible,
+    > + Unpin {
+        let changes = keys
+            .iter()
+            .map(|key| {
+                let key = *key;
+                let service = service_fn(move |()| {
+                    future::ready(Ok::<_, Infallible>(key))
+                });
+                Ok::<_, Infallible>(Change::Insert(
+                    key,
+                    Constant::new(service, 0),
+                ))
+            })
+            .collect::<Vec<_>>();
+        stream::iter(changes)
+    }
+
+    #[tokio::test]
+    async fn excluded_keys_are_skipped() {
+        let mut balance: P2cBalance<_, ()> =
+            P2cBalance::new(discover(&[1, 2, 3]));
+        for _ in 0..20 {
+            let key = balance
+                .ready()
+                .await
+                .unwrap()
+                .call_excluding((), |key| *key != 3)
+                .await
+                .unwrap();
+            assert_eq!(key, 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn falls_back_when_every_key_is_excluded() {
+        let mut balance: P2cBalance<_, ()> = P2cBalance::new(discover(&[1, 2]));
+        let key = balance
+            .ready()
+            .await
+            .unwrap()
+            .call_excluding((), |_| true)
+            .await
+            .unwrap();
+        assert!([1, 2].contains(&key));
+    }
+}